@@ -1,8 +1,12 @@
 //! Shared utilities for API transports.
 
-use reqwest::Client;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use std::time::Duration;
 
+use super::retry::RetryPolicy;
+use crate::errors::VestaboardError;
+
 /// Default timeout for Vestaboard API requests (10 seconds)
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
 
@@ -15,3 +19,134 @@ pub fn create_client() -> Client {
     .build()
     .expect("Failed to build HTTP client")
 }
+
+/// Upper bound on how long we'll wait for a single `Retry-After`, so a bad header can't stall the app
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Delay used when a 429 response has no usable `Retry-After` header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Parses a `Retry-After` header value, which is either a number of seconds or an HTTP date.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+  let value = value.trim();
+  if let Ok(seconds) = value.parse::<u64>() {
+    return Some(Duration::from_secs(seconds));
+  }
+  let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+  let wait = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+  Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Sends a request, retrying up to `policy.attempts` times in all.
+///
+/// Connection failures, timeouts, and 5xx responses are retried after the policy's backoff.
+/// A 429 Too Many Requests is retried after the server's `Retry-After` (capped at
+/// `max_retry_after`). `build` is called once per attempt since a `RequestBuilder` can't be
+/// reused. When attempts run out the last response or error is returned so the caller can
+/// report it.
+pub async fn send_with_retry<F>(
+  build: F,
+  policy: &RetryPolicy,
+  max_retry_after: Duration,
+) -> Result<Response, reqwest::Error>
+where
+  F: Fn() -> RequestBuilder,
+{
+  let mut attempt = 1;
+  loop {
+    let result = build().send().await;
+    let delay = match &result {
+      _ if attempt >= policy.attempts => return result,
+      Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_retry_after)
+        .unwrap_or(DEFAULT_RETRY_AFTER)
+        .min(max_retry_after),
+      Ok(response) if response.status().is_server_error() => policy.delay(attempt),
+      Err(e) if e.is_connect() || e.is_timeout() => policy.delay(attempt),
+      _ => return result,
+    };
+    match &result {
+      Ok(response) => {
+        log::warn!("HTTP {} (attempt {}/{}), retrying in {:?}", response.status(), attempt, policy.attempts, delay)
+      },
+      Err(e) => log::warn!("Attempt {}/{} failed ({}), retrying in {:?}", attempt, policy.attempts, e, delay),
+    }
+    tokio::time::sleep(delay).await;
+    attempt += 1;
+  }
+}
+
+/// Reads a response body, checking the status first so error pages never reach a JSON parser.
+///
+/// `context` names the service for error messages (e.g. "Weather API").
+pub async fn read_checked(response: Response, context: &str) -> Result<String, VestaboardError> {
+  let status = response.status().as_u16();
+  let body = response
+    .text()
+    .await
+    .map_err(|e| VestaboardError::reqwest_error(e, &format!("reading {} response", context)))?;
+  check_status(status, body, context)
+}
+
+/// Longest piece of an error response body written to the log at error level
+pub const MAX_LOGGED_BODY_CHARS: usize = 200;
+
+/// The start of a response body on one line, for logging: error pages can be large HTML
+/// documents, and some echo the request back.
+pub fn body_excerpt(body: &str) -> String {
+  let body = body.split_whitespace().collect::<Vec<_>>().join(" ");
+  if body.chars().count() <= MAX_LOGGED_BODY_CHARS {
+    return body;
+  }
+  let kept: String = body.chars().take(MAX_LOGGED_BODY_CHARS).collect();
+  format!("{}...", kept)
+}
+
+/// Returns the body on 2xx, or an `ApiError` carrying the status code.
+///
+/// JSON error bodies shaped like `{"error": {"message": ...}}` keep their message; anything else
+/// (such as an HTML error page) is replaced with the status reason.
+pub fn check_status(status: u16, body: String, context: &str) -> Result<String, VestaboardError> {
+  if (200..300).contains(&status) {
+    return Ok(body);
+  }
+
+  log::error!("{} returned HTTP {}: {}", context, status, body_excerpt(&body));
+  log::debug!("Full {} error response: {}", context, body);
+  let message = match status {
+    429 => format!("{} rate limited, try again later", context),
+    502..=504 => format!("{} temporarily unavailable", context),
+    _ => {
+      let detail = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| json["error"]["message"].as_str().map(|s| s.to_string()))
+        .or_else(|| {
+          StatusCode::from_u16(status)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .map(|s| s.to_string())
+        })
+        .unwrap_or_else(|| "Unexpected response".to_string());
+      format!("{} error: {}", context, detail)
+    },
+  };
+  Err(VestaboardError::api_error(Some(status), &message))
+}
+
+/// Reads a 6x22 grid of character codes from JSON, as the board APIs return the current message.
+///
+/// Returns None if the value isn't exactly 6 rows of 22 codes.
+pub fn grid_from_json(value: &serde_json::Value) -> Option<[[u8; 22]; 6]> {
+  let rows = value.as_array().filter(|rows| rows.len() == 6)?;
+  let mut grid = [[0u8; 22]; 6];
+  for (row, codes) in grid.iter_mut().zip(rows) {
+    let codes = codes.as_array().filter(|codes| codes.len() == 22)?;
+    for (cell, code) in row.iter_mut().zip(codes) {
+      *cell = u8::try_from(code.as_u64()?).ok()?;
+    }
+  }
+  Some(grid)
+}
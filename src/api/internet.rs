@@ -5,13 +5,20 @@
 
 use crate::cli_display::{ print_error, print_progress, print_success };
 use crate::errors::VestaboardError;
+use crate::secrets::secret;
 use dotenv::dotenv;
 use once_cell::sync::Lazy;
 use reqwest::Client;
-use serde_json::json;
-use std::env;
+use serde_json::{ json, Value };
 
-use super::common::create_client;
+use super::common::{
+  create_client,
+  grid_from_json,
+  read_checked,
+  send_with_retry,
+  MAX_RETRY_AFTER,
+};
+use super::retry::NETWORK_RETRY;
 
 /// Vestaboard Read/Write API endpoint
 const INTERNET_API_URL: &str = "https://rw.vestaboard.com/";
@@ -40,16 +47,12 @@ impl InternetTransport {
     dotenv().ok();
 
     // Get the API key, returning a helpful error if not set or empty
-    let api_key = env
-      ::var("INTERNET_API_KEY")
-      .ok()
-      .filter(|s| !s.is_empty())
-      .ok_or_else(|| {
-        VestaboardError::config_error(
-          "INTERNET_API_KEY",
-          "Environment variable not set. Set it with: export INTERNET_API_KEY=your-key (or add to .env file)."
-        )
-      })?;
+    let api_key = secret("INTERNET_API_KEY").ok_or_else(|| {
+      VestaboardError::config_error(
+        "INTERNET_API_KEY",
+        "Environment variable not set. Set it with: export INTERNET_API_KEY=your-key (or add to .env file, or set INTERNET_API_KEY_FILE to a file holding it)."
+      )
+    })?;
 
     Ok(Self { api_key })
   }
@@ -65,71 +68,87 @@ impl InternetTransport {
     log::debug!("Sending internet API request to {}", INTERNET_API_URL);
     log::trace!("Request body: {:?}", body);
 
-    let res = client
-      .post(INTERNET_API_URL)
-      .header("X-Vestaboard-Read-Write-Key", &self.api_key)
-      .json(&body)
-      .send().await;
-
-    let duration = start_time.elapsed();
+    // Connection failures and 5xx responses are retried with backoff, and 429s after their
+    // Retry-After, all within send_with_retry
+    let res = async {
+      let response = send_with_retry(
+        || client
+          .post(INTERNET_API_URL)
+          .header("X-Vestaboard-Read-Write-Key", &self.api_key)
+          .json(&body),
+        &NETWORK_RETRY,
+        MAX_RETRY_AFTER
+      ).await
+        .map_err(|e| VestaboardError::reqwest_error(e, "Vestaboard"))?;
+
+      let status = response.status();
+      log::info!("API response received: {} in {:?}", status, start_time.elapsed());
+
+      // Get response body for all cases
+      let response_body = response
+        .text().await
+        .unwrap_or_else(|_| "Unable to read response".to_string());
+
+      if status.is_success() || status.as_u16() == 304 {
+        Ok(status.as_u16())
+      } else if status.as_u16() == 429 {
+        log::error!("Rate limited by Vestaboard API after {} attempts", NETWORK_RETRY.attempts);
+        Err(VestaboardError::api_error(Some(429), "Rate limited by Vestaboard API, try again later"))
+      } else {
+        log::error!("API error response: {}", response_body);
+        Err(VestaboardError::api_error(Some(status.as_u16()), &response_body))
+      }
+    }.await;
 
     match res {
-      Ok(response) => {
-        let status = response.status();
-        log::info!("API response received: {} in {:?}", status, duration);
-
-        // Get response body for all cases
-        let response_body = response
-          .text().await
-          .unwrap_or_else(|_| "Unable to read response".to_string());
-
-        if status.is_success() {
-          print_success("Sent to Vestaboard");
-          Ok(())
-        } else if status.as_u16() == 304 {
-          // 304 Not Modified - the internet API already has this message
-          // (This compares against the last message sent via internet API, not what's currently displayed)
-          print_success("Message unchanged (already sent via internet API)");
-          Ok(())
-        } else {
-          log::error!("API error response: {}", response_body);
-          print_error(&format!("Vestaboard error: HTTP {} - {}", status, response_body));
-          Err(VestaboardError::api_error(Some(status.as_u16()), &response_body))
-        }
+      Ok(304) => {
+        // 304 Not Modified - the internet API already has this message
+        // (This compares against the last message sent via internet API, not what's currently displayed)
+        print_success("Message unchanged (already sent via internet API)");
+        Ok(())
+      }
+      Ok(_) => {
+        print_success("Sent to Vestaboard");
+        Ok(())
       }
-      Err(e) => {
-        log::error!("API request failed after {:?}: {}", duration, e);
-        let error = VestaboardError::reqwest_error(e, "Vestaboard");
-        print_error(&error.to_user_message());
+      Err(error) => {
+        log::error!("API request failed after {:?}: {}", start_time.elapsed(), error);
+        match &error {
+          VestaboardError::ApiError { code: Some(429), .. } => {
+            print_error("Vestaboard error: rate limited, try again later");
+          }
+          VestaboardError::ApiError { code: Some(code), message } => {
+            print_error(&format!("Vestaboard error: HTTP {} - {}", code, message));
+          }
+          _ => print_error(&error.to_user_message()),
+        }
         Err(error)
       }
     }
   }
 
-  /// Get the current message displayed on the Vestaboard via internet.
+  /// Get the character codes currently displayed on the Vestaboard via internet.
   ///
-  /// Note: This method is kept for future features but is not yet fully implemented.
-  /// The return type should eventually return the actual message data.
-  pub async fn get_message(&self) -> Result<(), VestaboardError> {
+  /// Returns None if the response doesn't contain a 6x22 grid.
+  pub async fn get_message(&self) -> Result<Option<[[u8; 22]; 6]>, VestaboardError> {
     let client = &*INTERNET_CLIENT;
 
     log::debug!("Getting message from internet API at {}", INTERNET_API_URL);
 
-    let res = client
+    let response = client
       .get(INTERNET_API_URL)
       .header("X-Vestaboard-Read-Write-Key", &self.api_key)
-      .send().await;
-
-    match res {
-      Ok(response) => {
-        log::debug!("Response: {:?}", response);
-        Ok(())
-      }
-      Err(e) => {
-        let error = VestaboardError::reqwest_error(e, "Vestaboard");
-        print_error(&error.to_user_message());
-        Err(error)
-      }
-    }
+      .send().await
+      .map_err(|e| VestaboardError::reqwest_error(e, "Vestaboard"))?;
+    let body = read_checked(response, "Vestaboard").await?;
+    let json: Value = serde_json::from_str(&body)
+      .map_err(|e| VestaboardError::json_error(e, "reading current message"))?;
+
+    // The Read/Write API returns the grid as a JSON string: {"currentMessage": {"layout": "[[...]]"}}
+    let layout = json["currentMessage"]["layout"]
+      .as_str()
+      .and_then(|layout| serde_json::from_str::<Value>(layout).ok())
+      .unwrap_or(Value::Null);
+    Ok(grid_from_json(&layout))
   }
 }
@@ -4,13 +4,15 @@
 
 use crate::cli_display::{ print_error, print_progress, print_success };
 use crate::errors::VestaboardError;
+use crate::secrets::secret;
 use dotenv::dotenv;
 use once_cell::sync::Lazy;
 use reqwest::Client;
-use serde_json::json;
+use serde_json::{ json, Value };
 use std::env;
 
-use super::common::create_client;
+use super::common::{ create_client, grid_from_json, read_checked };
+use super::retry::NETWORK_RETRY;
 
 /// Shared HTTP client for local API requests.
 /// Uses connection pooling for better performance with repeated requests.
@@ -37,16 +39,12 @@ impl LocalTransport {
     dotenv().ok();
 
     // Get the API key, returning a helpful error if not set or empty
-    let api_key = env
-      ::var("LOCAL_API_KEY")
-      .ok()
-      .filter(|s| !s.is_empty())
-      .ok_or_else(|| {
-        VestaboardError::config_error(
-          "LOCAL_API_KEY",
-          "Environment variable not set. Set it with: export LOCAL_API_KEY=your-key (or add to .env file)."
-        )
-      })?;
+    let api_key = secret("LOCAL_API_KEY").ok_or_else(|| {
+      VestaboardError::config_error(
+        "LOCAL_API_KEY",
+        "Environment variable not set. Set it with: export LOCAL_API_KEY=your-key (or add to .env file, or set LOCAL_API_KEY_FILE to a file holding it)."
+      )
+    })?;
 
     let ip_address = env
       ::var("IP_ADDRESS")
@@ -74,63 +72,66 @@ impl LocalTransport {
     log::debug!("Sending local API request to {}", url);
     log::trace!("Request body: {:?}", body);
 
-    let res = client
-      .post(&url)
-      .header("X-Vestaboard-Local-Api-Key", &self.api_key)
-      .json(&body)
-      .send().await;
+    // Connection failures and 5xx responses are retried with backoff
+    let res = NETWORK_RETRY.run(|| async {
+      let response = client
+        .post(&url)
+        .header("X-Vestaboard-Local-Api-Key", &self.api_key)
+        .json(&body)
+        .send().await
+        .map_err(|e| VestaboardError::reqwest_error(e, "Vestaboard"))?;
 
-    let duration = start_time.elapsed();
+      let status = response.status();
+      log::info!("API response received: {} in {:?}", status, start_time.elapsed());
+
+      if status.is_success() {
+        return Ok(());
+      }
+      let response_body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unable to read response".to_string());
+      log::error!("API error response: {}", response_body);
+      Err(VestaboardError::api_error(Some(status.as_u16()), &response_body))
+    }).await;
 
     match res {
-      Ok(response) => {
-        let status = response.status();
-        log::info!("API response received: {} in {:?}", status, duration);
-
-        if status.is_success() {
-          print_success("Sent to Vestaboard");
-          Ok(())
-        } else {
-          let response_body = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unable to read response".to_string());
-          log::error!("API error response: {}", response_body);
-          print_error(&format!("Vestaboard error: HTTP {} - {}", status, response_body));
-          Err(VestaboardError::api_error(Some(status.as_u16()), &response_body))
-        }
+      Ok(()) => {
+        print_success("Sent to Vestaboard");
+        Ok(())
       }
-      Err(e) => {
-        log::error!("API request failed after {:?}: {}", duration, e);
-        let error = VestaboardError::reqwest_error(e, "Vestaboard");
-        print_error(&error.to_user_message());
+      Err(error) => {
+        log::error!("API request failed after {:?}: {}", start_time.elapsed(), error);
+        match &error {
+          VestaboardError::ApiError { code: Some(code), message } => {
+            print_error(&format!("Vestaboard error: HTTP {} - {}", code, message));
+          }
+          _ => print_error(&error.to_user_message()),
+        }
         Err(error)
       }
     }
   }
 
-  /// Get the current message displayed on the Vestaboard via local network.
+  /// Get the character codes currently displayed on the Vestaboard via local network.
   ///
-  /// Note: This method is kept for future features but is not yet fully implemented.
-  /// The return type should eventually return the actual message data.
-  pub async fn get_message(&self) -> Result<(), VestaboardError> {
+  /// Returns None if the response doesn't contain a 6x22 grid.
+  pub async fn get_message(&self) -> Result<Option<[[u8; 22]; 6]>, VestaboardError> {
     let client = &*LOCAL_CLIENT;
     let url = format!("http://{}:7000/local-api/message", &self.ip_address);
 
     log::debug!("Getting message from local API at {}", url);
 
-    let res = client.get(&url).header("X-Vestaboard-Local-Api-Key", &self.api_key).send().await;
-
-    match res {
-      Ok(response) => {
-        log::debug!("Response: {:?}", response);
-        Ok(())
-      }
-      Err(e) => {
-        let error = VestaboardError::reqwest_error(e, "Vestaboard");
-        print_error(&error.to_user_message());
-        Err(error)
-      }
-    }
+    let response = client
+      .get(&url)
+      .header("X-Vestaboard-Local-Api-Key", &self.api_key)
+      .send().await
+      .map_err(|e| VestaboardError::reqwest_error(e, "Vestaboard"))?;
+    let body = read_checked(response, "Vestaboard").await?;
+    let json: Value = serde_json::from_str(&body)
+      .map_err(|e| VestaboardError::json_error(e, "reading current message"))?;
+
+    // The local API wraps the grid as {"message": [[...]]}
+    Ok(grid_from_json(&json["message"]))
   }
 }
@@ -0,0 +1,72 @@
+//! In-memory transport for tests.
+//!
+//! Records every grid it is asked to send instead of talking to a Vestaboard,
+//! so tests can assert on what (and how often) a runner sends.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use tempfile::TempDir;
+
+use crate::errors::VestaboardError;
+
+/// Test transport that records sent character codes.
+///
+/// Clones share the same record, so a test can keep a handle while the
+/// transport itself is owned by the code under test. Each mock also has its own
+/// history file in a temporary directory, removed when the last clone is dropped.
+#[derive(Debug, Clone)]
+pub struct MockTransport {
+  sent: Arc<Mutex<Vec<[[u8; 22]; 6]>>>,
+  fail_status: Option<u16>,
+  history_dir: Arc<TempDir>,
+}
+
+impl Default for MockTransport {
+  fn default() -> Self {
+    Self {
+      sent: Arc::default(),
+      fail_status: None,
+      history_dir: Arc::new(TempDir::new().expect("Failed to create mock history directory")),
+    }
+  }
+}
+
+impl MockTransport {
+  /// Creates a mock transport with no recorded sends.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Creates a mock transport whose sends fail with the given HTTP status.
+  pub fn failing(status: u16) -> Self {
+    Self {
+      fail_status: Some(status),
+      ..Self::default()
+    }
+  }
+
+  /// Record the codes as sent, or fail if the mock was built with `failing`.
+  pub async fn send_codes(&self, codes: [[u8; 22]; 6]) -> Result<(), VestaboardError> {
+    if let Some(status) = self.fail_status {
+      return Err(VestaboardError::api_error(Some(status), "mock transport failure"));
+    }
+    self.sent.lock().unwrap().push(codes);
+    Ok(())
+  }
+
+  /// The last grid sent plays the part of the board; None until something is sent.
+  pub async fn get_message(&self) -> Result<Option<[[u8; 22]; 6]>, VestaboardError> {
+    Ok(self.sent.lock().unwrap().last().copied())
+  }
+
+  /// History file that messages sent through this mock are recorded in.
+  pub fn history_path(&self) -> PathBuf {
+    self.history_dir.path().join("history.json")
+  }
+
+  /// All grids sent so far, oldest first.
+  pub fn sent_codes(&self) -> Vec<[[u8; 22]; 6]> {
+    self.sent.lock().unwrap().clone()
+  }
+}
@@ -13,12 +13,19 @@
 pub mod common;
 pub mod internet;
 pub mod local;
+#[cfg(test)]
+pub mod mock;
+pub mod retry;
 
+use crate::config::Config;
 use crate::errors::VestaboardError;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 pub use internet::InternetTransport;
 pub use local::LocalTransport;
+#[cfg(test)]
+pub use mock::MockTransport;
 
 /// Transport type for configuration and CLI selection.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,6 +47,9 @@ pub enum Transport {
   Local(LocalTransport),
   /// Internet transport via Read/Write API
   Internet(InternetTransport),
+  /// In-memory transport that records sends (tests only)
+  #[cfg(test)]
+  Mock(MockTransport),
 }
 
 impl Transport {
@@ -62,26 +72,41 @@ impl Transport {
     match self {
       Transport::Local(t) => t.send_codes(codes).await,
       Transport::Internet(t) => t.send_codes(codes).await,
+      #[cfg(test)]
+      Transport::Mock(t) => t.send_codes(codes).await,
     }
   }
 
-  /// Get the current message displayed on the Vestaboard.
+  /// Get the character codes currently displayed on the Vestaboard.
   ///
-  /// Note: This method is kept for future features but is not yet fully implemented.
-  /// The return type should eventually return the actual message data.
-  pub async fn get_message(&self) -> Result<(), VestaboardError> {
+  /// Returns None when the board's reply has no grid we can read.
+  pub async fn get_message(&self) -> Result<Option<[[u8; 22]; 6]>, VestaboardError> {
     log::debug!("Getting message via {} transport", self.name());
     match self {
       Transport::Local(t) => t.get_message().await,
       Transport::Internet(t) => t.get_message().await,
+      #[cfg(test)]
+      Transport::Mock(t) => t.get_message().await,
     }
   }
 
+  /// History file that messages sent through this transport are recorded in: the
+  /// configured one, or a mock's own temporary file so tests leave `data/` alone.
+  pub fn history_path(&self, config: &Config) -> PathBuf {
+    #[cfg(test)]
+    if let Transport::Mock(t) = self {
+      return t.history_path();
+    }
+    config.get_history_file_path()
+  }
+
   /// Get the name of this transport for logging.
   pub fn name(&self) -> &'static str {
     match self {
       Transport::Local(_) => "local",
       Transport::Internet(_) => "internet",
+      #[cfg(test)]
+      Transport::Mock(_) => "mock",
     }
   }
 }
@@ -91,6 +116,8 @@ impl std::fmt::Debug for Transport {
     match self {
       Transport::Local(_) => write!(f, "Transport::Local"),
       Transport::Internet(_) => write!(f, "Transport::Internet"),
+      #[cfg(test)]
+      Transport::Mock(_) => write!(f, "Transport::Mock"),
     }
   }
 }
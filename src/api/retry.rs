@@ -0,0 +1,99 @@
+//! Retry with exponential backoff for network calls.
+//!
+//! `RetryPolicy` wraps a whole operation (send and read) and retries transient failures such
+//! as connection errors, timeouts, and 5xx responses. `send_with_retry` in `common` applies a
+//! policy to a single request and also waits out `Retry-After` on 429 responses; use one or
+//! the other for a call, never both, so retries don't multiply.
+
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::errors::VestaboardError;
+
+/// Retry settings for network calls: total attempts and the backoff between them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+  /// Total attempts, including the first (1 means no retries)
+  pub attempts: u32,
+  /// Delay before the first retry; doubles for each retry after that
+  pub base_delay: Duration,
+  /// Upper bound on any single delay
+  pub max_delay: Duration,
+  /// Randomize each delay to between half and all of its value, so clients don't retry in lockstep
+  pub jitter: bool,
+}
+
+/// Policy for Vestaboard transports and widget APIs: up to 3 attempts, 500ms then 1s apart
+pub const NETWORK_RETRY: RetryPolicy = RetryPolicy {
+  attempts: 3,
+  base_delay: Duration::from_millis(500),
+  max_delay: Duration::from_secs(5),
+  jitter: true,
+};
+
+impl RetryPolicy {
+  /// Delay before retry number `retry` (1-based), without jitter: `base_delay * 2^(retry - 1)`,
+  /// capped at `max_delay`.
+  pub fn backoff(&self, retry: u32) -> Duration {
+    let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+    self.base_delay.saturating_mul(factor).min(self.max_delay)
+  }
+
+  /// Delay before retry number `retry`, with jitter if the policy has it.
+  pub(crate) fn delay(&self, retry: u32) -> Duration {
+    let delay = self.backoff(retry);
+    if self.jitter {
+      delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    } else {
+      delay
+    }
+  }
+
+  /// Run `operation` until it succeeds, fails with an error that isn't retryable, or runs out
+  /// of attempts. The last error is returned as-is.
+  pub async fn run<T, F, Fut>(&self, operation: F) -> Result<T, VestaboardError>
+  where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, VestaboardError>>,
+  {
+    self.run_with_sleep(operation, tokio::time::sleep).await
+  }
+
+  /// Like `run`, but waits between attempts with `sleep` so tests can skip real delays.
+  pub async fn run_with_sleep<T, F, Fut, S, SleepFut>(&self, mut operation: F, sleep: S) -> Result<T, VestaboardError>
+  where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, VestaboardError>>,
+    S: Fn(Duration) -> SleepFut,
+    SleepFut: Future<Output = ()>,
+  {
+    let mut attempt = 1;
+    loop {
+      match operation().await {
+        Ok(value) => return Ok(value),
+        Err(e) if attempt < self.attempts && is_retryable(&e) => {
+          let delay = self.delay(attempt);
+          log::warn!("Attempt {}/{} failed ({}), retrying in {:?}", attempt, self.attempts, e, delay);
+          sleep(delay).await;
+          attempt += 1;
+        },
+        Err(e) => return Err(e),
+      }
+    }
+  }
+}
+
+/// Whether an error is likely transient: a connection failure, a timeout, or a 5xx response.
+///
+/// Configuration, input, and 4xx errors fail the same way every time, so they aren't retried.
+/// A 429 is left out too: it's only retried by `send_with_retry`, which waits out `Retry-After`.
+pub fn is_retryable(error: &VestaboardError) -> bool {
+  match error {
+    VestaboardError::ReqwestError { source, .. } => {
+      source.is_connect() || source.is_timeout() || source.status().is_some_and(|status| status.is_server_error())
+    },
+    VestaboardError::ApiError { code: Some(code), .. } => (500..600).contains(code),
+    _ => false,
+  }
+}
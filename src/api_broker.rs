@@ -1,15 +1,44 @@
+use chrono::Local;
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
+use crate::api::common::{create_client, read_checked};
 use crate::api::Transport;
-use crate::cli_display::{print_error, print_message, print_progress};
+use crate::cli_display::{
+  print_error, print_message, print_message_diff, print_progress, print_text_grid, print_verbose, print_warning,
+};
+use crate::config::Config;
 use crate::errors::VestaboardError;
+use crate::history;
+use crate::hooks::{run_hook, HookEvent};
+use crate::night_mode::apply_night_mode;
+use crate::quiet_hours::{quiet_hours_action, QuietHoursAction};
+use crate::runner::common::PAUSED_INDICATOR_SOURCE;
+use crate::widgets::widget_utils::{emoji_to_color_tiles, is_blank_message, BoardSize, Theme, BLANK_PLACEHOLDER};
 
 #[derive(Debug)]
 pub enum MessageDestination {
   Vestaboard,
   Console,
   ConsoleWithTitle(String),
+  /// Preview with the tiles that differ from the board's current message marked
+  ConsoleDiff,
+  /// Plain text grid padded to the board's width, for piping
+  ConsoleGrid,
+  /// POST the message as JSON to this URL instead of the board
+  Webhook(String),
+}
+
+/// JSON body posted to a webhook: the widget that produced the message, its lines,
+/// and the character codes the board would show.
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload {
+  pub source: String,
+  pub message: Vec<String>,
+  pub codes: [[u8; 22]; 6],
 }
 
 static CHARACTER_CODES: Lazy<HashMap<char, u8>> = Lazy::new(|| {
@@ -83,18 +112,44 @@ static CHARACTER_CODES: Lazy<HashMap<char, u8>> = Lazy::new(|| {
   characters.iter().cloned().collect()
 });
 
+/// Translate a line of text into Vestaboard character codes, one per character.
+///
+/// Unsupported characters become blank tiles (0) so the rest of the line stays in place;
+/// validation normally rejects them before a message gets this far.
 pub fn to_codes(message: &str) -> Vec<u8> {
-  let mut codes = Vec::new();
-
-  for c in message.chars() {
-    if let Some(&code) = CHARACTER_CODES.get(&c) {
-      codes.push(code);
-    }
-    // Note: Invalid characters should have been caught during validation
-    // If we reach here with invalid chars, it's a programming error
-  }
+  message
+    .chars()
+    .map(|c| match CHARACTER_CODES.get(&c) {
+      Some(&code) => code,
+      None => {
+        log::warn!("No character code for {:?}, sending a blank tile", c);
+        0
+      },
+    })
+    .collect()
+}
 
+/// Render a code grid as 6 lines of 22 right-aligned codes, for debugging the translation
+pub fn format_code_grid(codes: &[[u8; 22]; 6]) -> String {
   codes
+    .iter()
+    .map(|row| {
+      row
+        .iter()
+        .map(|code| format!("{:>2}", code))
+        .collect::<Vec<_>>()
+        .join(" ")
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// The (row, column) of every tile that differs between two grids, in reading order
+pub fn changed_tiles(current: &[[u8; 22]; 6], next: &[[u8; 22]; 6]) -> Vec<(usize, usize)> {
+  (0..6)
+    .flat_map(|row| (0..22).map(move |col| (row, col)))
+    .filter(|&(row, col)| current[row][col] != next[row][col])
+    .collect()
 }
 
 /// Converts message lines to Vestaboard codes array for testing
@@ -132,17 +187,116 @@ pub async fn display_message(message: Vec<String>, transport: &Transport) -> Res
   log::debug!("Message content: {:?}", message);
 
   let codes = message_to_codes(message);
-  log::debug!("Converted message to character codes");
+  let grid = format_code_grid(&codes);
+  log::debug!("Converted message to character codes:\n{}", grid);
+  print_verbose("Character codes:");
+  grid.lines().for_each(print_verbose);
+
+  // Neither Vestaboard API accepts individual tiles, so every send is the whole grid; the
+  // board itself only flips the tiles whose character changes.
+  transport.send_codes(codes).await
+}
+
+/// Highest character code in `CHARACTER_CODES` (black); raw code grids may not go above it
+pub const MAX_CHARACTER_CODE: u8 = 70;
+
+/// Parse a grid of raw character codes: a JSON array of 6 rows of 22 numbers from 0 to
+/// `MAX_CHARACTER_CODE`, e.g. `[[0, 8, 9, 0, ...], ...]`.
+pub fn parse_code_grid(json: &str) -> Result<[[u8; 22]; 6], VestaboardError> {
+  let rows: Vec<Vec<i64>> = serde_json::from_str(json).map_err(|e| {
+    VestaboardError::validation_error(&format!("Codes must be a JSON array of 6 rows of 22 numbers: {}", e))
+  })?;
+  if rows.len() != 6 {
+    return Err(VestaboardError::validation_error(&format!("Expected 6 rows of codes, got {}", rows.len())));
+  }
 
+  let mut codes = [[0u8; 22]; 6];
+  for (row, values) in rows.iter().enumerate() {
+    if values.len() != 22 {
+      return Err(VestaboardError::validation_error(&format!(
+        "Row {} has {} codes, expected 22",
+        row + 1,
+        values.len()
+      )));
+    }
+    for (col, &value) in values.iter().enumerate() {
+      codes[row][col] = u8::try_from(value)
+        .ok()
+        .filter(|code| *code <= MAX_CHARACTER_CODE)
+        .ok_or_else(|| {
+          VestaboardError::validation_error(&format!(
+            "Code {} at row {}, column {} is out of range (0-{})",
+            value,
+            row + 1,
+            col + 1,
+            MAX_CHARACTER_CODE
+          ))
+        })?;
+    }
+  }
+  Ok(codes)
+}
+
+/// Read a code grid from a JSON file, or from `source` itself when it isn't a file.
+pub fn read_code_grid(source: &str) -> Result<[[u8; 22]; 6], VestaboardError> {
+  let path = Path::new(source);
+  let json = if path.is_file() {
+    fs::read_to_string(path).map_err(|e| VestaboardError::io_error(e, &format!("reading codes file {}", source)))?
+  } else {
+    source.to_string()
+  };
+  parse_code_grid(&json)
+}
+
+/// Send a raw code grid straight to the board for debugging. Nothing is formatted or
+/// validated beyond the grid itself, and night mode, quiet hours, and history are skipped.
+pub async fn send_code_grid(source: &str, transport: &Transport) -> Result<(), VestaboardError> {
+  let codes = read_code_grid(source)?;
+  log::info!("Sending raw character codes");
+  log::debug!("Raw codes: {:?}", codes);
   transport.send_codes(codes).await
 }
 
+/// JSON for a code grid, one row per line, in the shape `parse_code_grid` reads
+pub fn code_grid_json(codes: &[[u8; 22]; 6]) -> String {
+  let rows = codes
+    .iter()
+    .map(|row| format!("  {}", serde_json::to_string(&row[..]).unwrap_or_default()))
+    .collect::<Vec<_>>()
+    .join(",\n");
+  format!("[\n{}\n]\n", rows)
+}
+
+/// Save the board's current grid to `path` as JSON that `vbl send-codes` can send back.
+pub async fn snapshot_board(path: &Path, transport: &Transport) -> Result<[[u8; 22]; 6], VestaboardError> {
+  let codes = transport.get_message().await?.ok_or_else(|| {
+    VestaboardError::api_error(None, &format!("The {} API didn't return the board's current grid", transport.name()))
+  })?;
+  fs::write(path, code_grid_json(&codes))
+    .map_err(|e| VestaboardError::io_error(e, &format!("writing snapshot to {}", path.display())))?;
+  log::info!("Saved board snapshot to {}", path.display());
+  Ok(codes)
+}
+
 /// Checks if a character is valid for Vestaboard display
 /// This is the single source of truth for valid characters
 pub fn is_valid_character(c: char) -> bool {
   CHARACTER_CODES.contains_key(&c)
 }
 
+/// Characters in `message` the board can't show, each listed once in the order they first
+/// appear. Line breaks are skipped since they separate lines rather than take a tile, and
+/// colored-square emoji are allowed since text messages turn them into color tiles.
+pub fn unsupported_chars(message: &str) -> Vec<char> {
+  let mut chars = Vec::new();
+  for c in emoji_to_color_tiles(message).chars() {
+    if c != '\n' && c != '\r' && !is_valid_character(c) && !chars.contains(&c) {
+      chars.push(c);
+    }
+  }
+  chars
+}
+
 /// Gets all valid characters as a formatted string for error messages
 pub fn get_valid_characters_description() -> String {
   "a-z, 0-9, space, punctuation (!@#$()-+&=;:'\"%,./?), D (degree), and color codes (ROYGBVWK)".to_string()
@@ -176,15 +330,68 @@ pub fn validate_message_content(message: &[String]) -> Result<(), VestaboardErro
   Ok(())
 }
 
+/// Validates that a message fits a board of the given size: rows past the last one must be
+/// blank, and no line may be wider than the board (trailing spaces don't count).
+pub fn validate_message_size(message: &[String], size: BoardSize) -> Result<(), VestaboardError> {
+  let too_big = |detail: String| {
+    VestaboardError::validation_error(&format!("Message doesn't fit a {}x{} board: {}", size.cols, size.rows, detail))
+  };
+
+  if let Some(row) = message.iter().skip(size.rows).position(|line| !line.trim().is_empty()) {
+    return Err(too_big(format!("row {} is past the bottom", size.rows + row + 1)));
+  }
+  for (row, line) in message.iter().enumerate() {
+    let width = line.trim_end().chars().count();
+    if width > size.cols {
+      return Err(too_big(format!("row {} is {} tiles wide", row + 1, width)));
+    }
+  }
+  Ok(())
+}
+
+/// POST a message to a webhook, treating any non-2xx response as an error.
+///
+/// The post is tried once: a POST isn't idempotent, and a receiver that timed out or
+/// answered 5xx may already have handled it.
+pub async fn post_to_webhook(url: &str, message: &[String], source: &str) -> Result<(), VestaboardError> {
+  let payload = WebhookPayload {
+    source: source.to_string(),
+    message: message.to_vec(),
+    codes: message_to_codes(message.to_vec()),
+  };
+  log::debug!("Posting {} message to webhook {}", source, url);
+
+  let response = create_client()
+    .post(url)
+    .json(&payload)
+    .send()
+    .await
+    .map_err(|e| VestaboardError::reqwest_error(e, "posting to webhook"))?;
+  read_checked(response, "Webhook").await?;
+
+  log::info!("Posted {} message to webhook", source);
+  Ok(())
+}
+
+/// Validate a message and send it to its destination, with the board size and the settings
+/// below taken from `config`.
+///
+/// During quiet hours, messages to a board are replaced by a single clear and then dropped.
+/// Messages sent to a board are dimmed during the configured `night_mode` window, recorded
+/// undimmed in the history file under `source` (the widget name), and mirrored as shown to
+/// the configured `webhook_url` if one is set. The paused indicator is sent but not recorded.
 pub async fn handle_message(
   message: Vec<String>,
+  source: &str,
   destination: MessageDestination,
   transport: &Transport,
+  config: &Config,
 ) -> Result<(), VestaboardError> {
   log::debug!("Handling message for destination: {:?}", destination);
 
-  // Validate the message content
-  match validate_message_content(&message) {
+  // Validate the message content and that it fits the configured board
+  let board_size = config.get_board_size();
+  match validate_message_content(&message).and_then(|_| validate_message_size(&message, board_size)) {
     Ok(_) => {
       log::debug!("Message validation successful");
     },
@@ -195,9 +402,62 @@ pub async fn handle_message(
     },
   }
 
+  // A blank board looks broken; clear is the one widget meant to produce one
+  let message = if source != "clear" && is_blank_message(&message) {
+    log::warn!("{} rendered a blank message", source);
+    print_warning(&format!("{} rendered a blank message", source));
+    let live = matches!(destination, MessageDestination::Vestaboard);
+    if live && config.get_blank_placeholder() {
+      Theme::from_config(config).status_message(BLANK_PLACEHOLDER, board_size)
+    } else {
+      message
+    }
+  } else {
+    message
+  };
+
   match destination {
     MessageDestination::Vestaboard => {
-      display_message(message, transport).await?;
+      let history_path = transport.history_path(config);
+      let cleared = history::quiet_hours_cleared(&history_path);
+      match quiet_hours_action(config.get_quiet_hours().as_ref(), Local::now().time(), cleared) {
+        QuietHoursAction::Send => {},
+        QuietHoursAction::Clear => {
+          log::info!("Quiet hours began, clearing the board instead of sending {}", source);
+          print_progress("Quiet hours: clearing the board");
+          display_message(vec![String::new()], transport).await?;
+          history::record_quiet_hours_clear(&history_path);
+          return Ok(());
+        },
+        QuietHoursAction::Suppress => {
+          log::info!("Quiet hours, not sending {}", source);
+          print_progress(&format!("Quiet hours: {} not sent", source));
+          return Ok(());
+        },
+      }
+
+      let night_mode = config.get_night_mode();
+      let shown = apply_night_mode(message.clone(), night_mode.as_ref(), Local::now().time());
+      if let Err(e) = display_message(shown.clone(), transport).await {
+        run_hook(config, source, HookEvent::Failure);
+        return Err(e);
+      }
+      // Record the message as rendered, so `vbl repeat` in the daytime isn't dimmed. The paused
+      // indicator isn't content, so `vbl repeat` shouldn't bring it back at all.
+      if source != PAUSED_INDICATOR_SOURCE {
+        history::record_to(&history_path, source, &message, config.get_history_size());
+      }
+      run_hook(config, source, HookEvent::Success);
+      if let Some(url) = config.get_webhook_url() {
+        // The board already has the message; a failed mirror shouldn't fail the send
+        if let Err(e) = post_to_webhook(&url, &shown, source).await {
+          log::warn!("Failed to mirror message to webhook: {}", e);
+          print_warning(&format!("Webhook mirror failed: {}", e.to_user_message()));
+        }
+      }
+    },
+    MessageDestination::Webhook(url) => {
+      post_to_webhook(&url, &message, source).await?;
     },
     MessageDestination::Console => {
       print_progress("Displaying message preview:");
@@ -207,6 +467,25 @@ pub async fn handle_message(
       print_progress("Displaying message preview:");
       print_message(message, &title);
     },
+    MessageDestination::ConsoleGrid => {
+      print_text_grid(&message);
+    },
+    MessageDestination::ConsoleDiff => match transport.get_message().await {
+      Ok(Some(current)) => {
+        let changed = changed_tiles(&current, &message_to_codes(message.clone()));
+        print_progress(&format!("Displaying message preview ({} tiles change):", changed.len()));
+        print_message_diff(message, "", &changed);
+      },
+      result => {
+        // Previewing shouldn't fail just because the board couldn't be read
+        if let Err(e) = result {
+          log::warn!("Failed to read the current board: {}", e);
+        }
+        print_warning("Couldn't read the current board, showing a plain preview");
+        print_progress("Displaying message preview:");
+        print_message(message, "");
+      },
+    },
   }
 
   Ok(())
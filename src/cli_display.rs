@@ -5,12 +5,25 @@ use std::sync::atomic::{AtomicBool, Ordering};
 static QUIET_MODE: AtomicBool = AtomicBool::new(false);
 static VERBOSE_MODE: AtomicBool = AtomicBool::new(false);
 static TTY_DETECTED: AtomicBool = AtomicBool::new(false);
+static NO_COLOR_MODE: AtomicBool = AtomicBool::new(false);
 
 /// Initialize output control settings
-pub fn init_output_control(quiet: bool, verbose: bool) {
+pub fn init_output_control(quiet: bool, verbose: bool, no_color: bool) {
   QUIET_MODE.store(quiet, Ordering::Relaxed);
   VERBOSE_MODE.store(verbose, Ordering::Relaxed);
   TTY_DETECTED.store(std::io::stdout().is_terminal(), Ordering::Relaxed);
+  let no_color_env = std::env::var_os("NO_COLOR");
+  NO_COLOR_MODE.store(color_disabled(no_color, no_color_env.as_deref()), Ordering::Relaxed);
+}
+
+/// Check if color was turned off with `--no-color` or a non-empty NO_COLOR (see https://no-color.org)
+pub fn color_disabled(no_color_flag: bool, no_color_env: Option<&std::ffi::OsStr>) -> bool {
+  no_color_flag || no_color_env.is_some_and(|value| !value.is_empty())
+}
+
+/// Decide whether to colorize: stdout must be a terminal and color must not be turned off
+pub fn should_colorize(tty: bool, color_disabled: bool) -> bool {
+  tty && !color_disabled
 }
 
 /// Check if we should print progress messages
@@ -23,6 +36,28 @@ fn should_print_verbose() -> bool {
   VERBOSE_MODE.load(Ordering::Relaxed) && !QUIET_MODE.load(Ordering::Relaxed)
 }
 
+/// Check if output should be colorized (only when stdout is a terminal and color is not turned off)
+pub fn colors_enabled() -> bool {
+  should_colorize(TTY_DETECTED.load(Ordering::Relaxed), NO_COLOR_MODE.load(Ordering::Relaxed))
+}
+
+/// Wrap text in an ANSI color code (e.g. "33" for yellow)
+pub fn colorize(text: &str, ansi_code: &str) -> String {
+  format!("\x1b[{}m{}\x1b[0m", ansi_code, text)
+}
+
+/// Truncate text to a column width (in characters), ending with an ellipsis if cut
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+  if text.chars().count() <= width {
+    return text.to_string();
+  }
+  if width <= 3 {
+    return ".".repeat(width);
+  }
+  let kept: String = text.chars().take(width - 3).collect();
+  format!("{}...", kept)
+}
+
 /// Truncate long messages with ellipsis
 fn truncate_message(msg: &str, max_len: usize) -> String {
   if msg.len() <= max_len {
@@ -78,6 +113,66 @@ pub fn print_warning(msg: &str) {
 }
 
 pub fn print_message(message: Vec<String>, title: &str) -> Vec<String> {
+  let output = format_preview(&message, title, !NO_COLOR_MODE.load(Ordering::Relaxed));
+  output.iter().for_each(|line| println!("{}", line));
+  output
+}
+
+/// Print the preview with the `changed` tiles (row, column) marked; see `format_diff_preview`
+pub fn print_message_diff(message: Vec<String>, title: &str, changed: &[(usize, usize)]) -> Vec<String> {
+  let output = format_diff_preview(&message, title, !NO_COLOR_MODE.load(Ordering::Relaxed), changed);
+  output.iter().for_each(|line| println!("{}", line));
+  output
+}
+
+/// Print the message as a plain text grid; see `format_text_grid`
+pub fn print_text_grid(message: &[String]) -> Vec<String> {
+  let output = format_text_grid(message);
+  output.iter().for_each(|line| println!("{}", line));
+  output
+}
+
+/// Build a plain text grid of the message: every row padded to the board's 22 columns and
+/// framed with `|`, with the characters as-is (no colors or tile symbols) so the output can be
+/// piped and its spacing checked
+pub fn format_text_grid(message: &[String]) -> Vec<String> {
+  let border = format!("+{}+", "-".repeat(22));
+  let mut output = vec![border.clone()];
+  for row in 0..6 {
+    let line: String = message
+      .get(row)
+      .map(String::as_str)
+      .unwrap_or("")
+      .chars()
+      .take(22)
+      .collect();
+    output.push(format!("|{:<22}|", line));
+  }
+  output.push(border);
+  output
+}
+
+/// Render one board character for a preview: a colored square for a color tile (or its letter
+/// without colors), and a degree sign for `D`
+fn preview_tile(c: char, colors: bool) -> String {
+  const SOLID_SQUARE: char = '\u{2588}';
+  match c {
+    'D' => "°".to_string(),
+    'R' | 'O' | 'Y' | 'G' | 'B' | 'V' | 'W' | 'K' if !colors => c.to_string(),
+    'R' => format!("\x1b[{}m{}\x1b[0m", "31", SOLID_SQUARE),
+    'O' => format!("\x1b[{}m{}\x1b[0m", "38:5:208", SOLID_SQUARE),
+    'Y' => format!("\x1b[{}m{}\x1b[0m", "33", SOLID_SQUARE),
+    'G' => format!("\x1b[{}m{}\x1b[0m", "32", SOLID_SQUARE),
+    'B' => format!("\x1b[{}m{}\x1b[0m", "34", SOLID_SQUARE),
+    'V' => format!("\x1b[{}m{}\x1b[0m", "35", SOLID_SQUARE),
+    'W' => format!("\x1b[{}m{}\x1b[0m", "37", SOLID_SQUARE),
+    'K' => format!("\x1b[{}m{}\x1b[0m", "30", SOLID_SQUARE),
+    _ => c.to_string(),
+  }
+}
+
+/// The title (or a default one) and top border a preview starts with
+fn preview_header(title: &str) -> Vec<String> {
   let mut output = Vec::new();
   if title == "" {
     output.push("Vestaboard Display:".to_string());
@@ -85,24 +180,15 @@ pub fn print_message(message: Vec<String>, title: &str) -> Vec<String> {
     output.push(format!("{}", title));
   }
   output.push("|----------------------|".to_string());
+  output
+}
+
+/// Build the ASCII preview of a message; without colors, color tiles show as their letter
+pub fn format_preview(message: &[String], title: &str, colors: bool) -> Vec<String> {
+  let mut output = preview_header(title);
   message.iter().take(6).for_each(|line| {
     let padded_line = format!("{:<22}", line);
-    const SOLID_SQUARE: char = '\u{2588}';
-    let modified_line = padded_line
-      .chars()
-      .map(|c| match c {
-        'D' => "°".to_string(),
-        'R' => format!("\x1b[{}m{}\x1b[0m", "31", SOLID_SQUARE),
-        'O' => format!("\x1b[{}m{}\x1b[0m", "38:5:208", SOLID_SQUARE),
-        'Y' => format!("\x1b[{}m{}\x1b[0m", "33", SOLID_SQUARE),
-        'G' => format!("\x1b[{}m{}\x1b[0m", "32", SOLID_SQUARE),
-        'B' => format!("\x1b[{}m{}\x1b[0m", "34", SOLID_SQUARE),
-        'V' => format!("\x1b[{}m{}\x1b[0m", "35", SOLID_SQUARE),
-        'W' => format!("\x1b[{}m{}\x1b[0m", "37", SOLID_SQUARE),
-        'K' => format!("\x1b[{}m{}\x1b[0m", "30", SOLID_SQUARE),
-        _ => c.to_string(),
-      })
-      .collect::<String>();
+    let modified_line = padded_line.chars().map(|c| preview_tile(c, colors)).collect::<String>();
     output.push(format!("|{}|", modified_line));
   });
   // Make sure display matches the Vestaboard.
@@ -111,6 +197,36 @@ pub fn print_message(message: Vec<String>, title: &str) -> Vec<String> {
     output.push("|                      |".to_string());
   }
   output.push("|----------------------|".to_string());
-  output.iter().for_each(|line| println!("{}", line));
+  output
+}
+
+/// Build the preview with the `changed` tiles (row, column) marked: in inverse video with colors,
+/// or by a line of `^` under each changed row without them
+pub fn format_diff_preview(message: &[String], title: &str, colors: bool, changed: &[(usize, usize)]) -> Vec<String> {
+  let mut output = preview_header(title);
+  // Draw all 6 rows, since a marker line can follow any of them and a change can be on a blank row
+  for row in 0..6 {
+    let line = message.get(row).map(String::as_str).unwrap_or("");
+    let padded_line = format!("{:<22}", line);
+    let modified_line = padded_line
+      .chars()
+      .enumerate()
+      .map(|(col, c)| {
+        if colors && changed.contains(&(row, col)) {
+          format!("\x1b[7m{}\x1b[0m", preview_tile(c, colors))
+        } else {
+          preview_tile(c, colors)
+        }
+      })
+      .collect::<String>();
+    output.push(format!("|{}|", modified_line));
+    if !colors && changed.iter().any(|&(r, _)| r == row) {
+      let markers = (0..22)
+        .map(|col| if changed.contains(&(row, col)) { '^' } else { ' ' })
+        .collect::<String>();
+      output.push(format!(" {}", markers.trim_end()));
+    }
+  }
+  output.push("|----------------------|".to_string());
   output
 }
@@ -12,6 +12,29 @@ pub struct FileArgs {
   pub name: PathBuf,
 }
 
+#[derive(Args, Debug)]
+pub struct RandomArgs {
+  #[arg(required = true, help = "Widgets to choose from (e.g. weather sat-word jokes)")]
+  pub choices: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ForexArgs {
+  #[arg(required = true, help = "Currency to convert from (e.g. USD)")]
+  pub from: String,
+  #[arg(required = true, help = "Currency to convert to (e.g. EUR)")]
+  pub to: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ColorTestArgs {
+  #[arg(
+    required = true,
+    help = "Color to fill the board with (red, orange, yellow, green, blue, violet, white, black)"
+  )]
+  pub color: String,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum WidgetCommand {
   #[command(name = "text", about = "Display a text message")]
@@ -20,20 +43,79 @@ pub enum WidgetCommand {
   File(FileArgs),
   #[command(name = "weather", about = "Display the weather")]
   Weather,
+  #[command(name = "airquality", about = "Display the air quality index")]
+  AirQuality,
+  #[command(name = "forex", about = "Display a currency exchange rate")]
+  Forex(ForexArgs),
   #[command(name = "jokes", about = "Display a random joke")]
   Jokes,
   #[command(name = "clear", about = "Clear the Vestaboard")]
   Clear,
+  #[command(
+    name = "colortest",
+    about = "Fill the board with one color to check how that tile looks",
+    after_help = "Examples:\n  vbl show colortest red\n  vbl show colortest blue"
+  )]
+  ColorTest(ColorTestArgs),
   #[command(name = "sat-word", about = "Display a random SAT word")]
   SATWord,
+  #[command(name = "onthisday", about = "Display a notable event from this day in history")]
+  OnThisDay,
+  #[command(name = "random", about = "Display a randomly chosen widget")]
+  Random(RandomArgs),
 }
 
 #[derive(Args, Debug)]
 pub struct ShowArgs {
   #[command(subcommand)]
-  pub widget_command: WidgetCommand,
+  pub widget_command: Option<WidgetCommand>,
+  #[arg(long, value_name = "NAME", help = "Run any registered widget by name instead of a widget subcommand")]
+  pub widget: Option<String>,
+  #[arg(
+    long,
+    value_name = "JSON",
+    requires = "widget",
+    help = "Input for --widget as JSON (plain text is used as a string)"
+  )]
+  pub input: Option<String>,
+  #[arg(
+    long,
+    value_name = "PATH",
+    requires = "widget",
+    conflicts_with = "input",
+    help = "Read the input for --widget from a JSON file"
+  )]
+  pub input_file: Option<PathBuf>,
   #[arg(short = 'd', long = "dry-run", help = "Preview message without updating Vestaboard")]
   pub dry_run: bool,
+  #[arg(
+    long,
+    requires = "dry_run",
+    help = "With --dry-run, mark the tiles that differ from what the board shows now"
+  )]
+  pub diff: bool,
+  #[arg(
+    long,
+    requires = "dry_run",
+    conflicts_with = "diff",
+    help = "With --dry-run, print a plain grid with every row padded to 22 columns (no colors)"
+  )]
+  pub grid: bool,
+  #[arg(long, value_name = "URL", help = "POST the message to this webhook instead of the Vestaboard")]
+  pub webhook: Option<String>,
+  #[arg(
+    long = "for",
+    value_name = "DURATION",
+    conflicts_with_all = ["dry_run", "webhook"],
+    help = "Show the message for this long (e.g. 30s, 5m), then put the previous message back"
+  )]
+  pub for_duration: Option<String>,
+  #[arg(
+    long,
+    requires = "for_duration",
+    help = "With --for, clear the board afterwards instead of restoring"
+  )]
+  pub then_clear: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -41,13 +123,25 @@ pub enum PlaylistArgs {
   #[command(
     name = "add",
     about = "Add a widget to the playlist",
-    after_help = "Examples:\n  vbl playlist add weather\n  vbl playlist add text \"Hello world\"\n  vbl playlist add sat-word"
+    after_help = "Examples:\n  vbl playlist add weather\n  vbl playlist add text \"Hello world\"\n  vbl playlist add sat-word\n  vbl playlist add forex usd eur\n  vbl playlist add random weather sat-word jokes\n  vbl playlist add --at 0 text \"First up\"\n  vbl playlist add --input-file rows.json kv"
   )]
   Add {
-    #[clap(help = "The widget to add (weather, text, sat-word, jokes, clear)", required = true)]
+    #[clap(
+      help = "The widget to add (weather, airquality, forex, text, sat-word, onthisday, jokes, clear, random)",
+      required = true
+    )]
     widget: String,
-    #[clap(help = "Widget input (required for text widget)")]
+    #[clap(help = "Widget input (required for text, forex, and random widgets)")]
     input: Vec<String>,
+    #[arg(
+      long,
+      value_name = "PATH",
+      conflicts_with = "input",
+      help = "Read the widget input from a JSON file instead of the command line"
+    )]
+    input_file: Option<PathBuf>,
+    #[arg(long, help = "Insert at this 0-based position instead of appending")]
+    at: Option<usize>,
   },
   #[command(name = "list", about = "List all playlist items")]
   List,
@@ -56,19 +150,59 @@ pub enum PlaylistArgs {
     #[clap(help = "The ID of the playlist item to remove", required = true)]
     id: String,
   },
+  #[command(
+    name = "edit",
+    about = "Change a playlist item's input, keeping its ID and position",
+    after_help = "Examples:\n  vbl playlist edit abc1 --input \"Hello world\""
+  )]
+  Edit {
+    #[clap(help = "The ID of the playlist item to edit", required = true)]
+    id: String,
+    #[arg(long, num_args = 1.., required = true, help = "New widget input")]
+    input: Vec<String>,
+  },
   #[command(name = "clear", about = "Remove all playlist items")]
   Clear,
+  #[command(
+    name = "import",
+    about = "Merge items from a playlist JSON file; items whose ID is already in the playlist replace it",
+    after_help = "Examples:\n  vbl playlist import playlist.json\n  vbl playlist import --keep-existing playlist.json"
+  )]
+  Import {
+    #[clap(help = "Playlist JSON file to import (as written by vbl playlist export)", required = true)]
+    file: PathBuf,
+    #[arg(long = "keep-existing", help = "Keep existing items when IDs collide instead of replacing them")]
+    keep_existing: bool,
+  },
   #[command(name = "interval", about = "Show or set the rotation interval in seconds (minimum 60)")]
   Interval {
     #[clap(help = "Interval in seconds between items (omit to show current)")]
     seconds: Option<u64>,
   },
+  #[command(
+    name = "export",
+    about = "Print the playlist as JSON, or as commands that recreate it",
+    after_help = "Examples:\n  vbl playlist export\n  vbl playlist export --as-commands > playlist.sh"
+  )]
+  Export {
+    #[arg(long = "as-commands", help = "Emit vbl playlist commands instead of JSON")]
+    as_commands: bool,
+  },
   #[command(name = "preview", about = "Preview all playlist items without sending to Vestaboard")]
   Preview,
+  #[command(
+    name = "show",
+    about = "Preview one playlist item without sending to Vestaboard",
+    after_help = "Examples:\n  vbl playlist show abc1"
+  )]
+  Show {
+    #[clap(help = "The ID of the playlist item to preview", required = true)]
+    id: String,
+  },
   #[command(
     name = "run",
     about = "Run the playlist, rotating through items at the set interval",
-    after_help = "Examples:\n  vbl playlist run\n  vbl playlist run --resume\n  vbl playlist run --once\n  vbl playlist run --index 2\n  vbl playlist run --id abc1\n  vbl playlist run --dry-run"
+    after_help = "Examples:\n  vbl playlist run\n  vbl playlist run --resume\n  vbl playlist run --once\n  vbl playlist run --index 2\n  vbl playlist run --id abc1\n  vbl playlist run --dry-run\n  vbl playlist run --tui\n  vbl playlist run --once --shuffle\n  vbl playlist run --tag morning\n  vbl playlist run --interval 60"
   )]
   Run {
     #[arg(long, help = "Run through playlist once and exit")]
@@ -81,44 +215,194 @@ pub enum PlaylistArgs {
     id: Option<String>,
     #[arg(short = 'd', long = "dry-run", help = "Preview mode - show messages without sending to Vestaboard")]
     dry_run: bool,
+    #[arg(long, help = "Show a full-screen dashboard instead of line output (requires a terminal)")]
+    tui: bool,
+    #[arg(long, help = "Rotate backward through the playlist (starts at the last item)")]
+    reverse: bool,
+    #[arg(
+      long,
+      help = "Play items in a random order, shuffled once at startup (with --once, a single randomized pass)",
+      conflicts_with_all = ["resume", "index", "id", "reverse"]
+    )]
+    shuffle: bool,
+    #[arg(
+      long,
+      value_name = "TAG",
+      help = "Only run items tagged with TAG (set in each item's \"tags\" list in the playlist file)",
+      conflicts_with = "resume"
+    )]
+    tag: Option<String>,
+    #[arg(
+      long,
+      value_name = "SECONDS",
+      help = "Seconds between items for this run only (minimum 60; the saved interval is unchanged)"
+    )]
+    interval: Option<u64>,
   },
 }
 
 #[derive(Subcommand, Debug)]
 pub enum ScheduleArgs {
-  #[command(name = "list", about = "List all scheduled messages")]
-  List,
+  #[command(
+    name = "list",
+    about = "List all scheduled messages",
+    after_help = "Examples:\n  vbl schedule list\n  vbl schedule list --from 2025-05-01 --to 2025-05-07\n  vbl schedule list --from \"2025-05-01 08:00:00\""
+  )]
+  List {
+    #[arg(long, help = "Only show tasks at or after this time (YYYY-MM-DD or YYYY-MM-DD HH:MM:SS)")]
+    from: Option<String>,
+    #[arg(long, help = "Only show tasks at or before this time (YYYY-MM-DD or YYYY-MM-DD HH:MM:SS)")]
+    to: Option<String>,
+  },
+  #[command(name = "next", about = "Show the next upcoming scheduled message")]
+  Next,
   #[command(
     name = "add",
     about = "Add a new scheduled message.  Message must be in lowercase letters.",
     arg_required_else_help = true,
-    after_help = "Example:\n  vbl schedule add \"2025-05-01 08:30:30\" text \"Don\\'t panic!\"\n  vbl schedule add \"2025-05-01 20:00:30\" weather"
+    after_help = "Example:\n  vbl schedule add \"2025-05-01 08:30:30\" text \"Don\\'t panic!\"\n  vbl schedule add \"2025-05-01 20:00:30\" weather\n  vbl schedule add --repeat weekdays \"2025-05-01 07:00:00\" weather\n  vbl schedule add --show-now \"2025-05-01 18:00:00\" text \"Dinner at six\""
   )]
   Add {
     #[clap(help = "The time to (YYYY-MM-DD HH:MM:SS) in military time.", required = true)]
     time: String,
-    #[clap(help = "The widget to use (text, file, weather, sat-word).", required = true)]
+    #[clap(
+      help = "The widget to use (text, file, weather, airquality, forex, sat-word, onthisday).",
+      required = true
+    )]
     widget: String,
     #[clap(help = "Widget input (optional).  To use quotes use \\' or \\\".")]
     input: Vec<String>,
+    #[arg(
+      long,
+      value_name = "PATH",
+      conflicts_with = "input",
+      help = "Read the widget input from a JSON file instead of the command line"
+    )]
+    input_file: Option<PathBuf>,
+    #[arg(
+      long,
+      value_name = "SPEC",
+      help = "Repeat at the same time: daily, weekdays, weekends, or days like \"mon,wed,fri\""
+    )]
+    repeat: Option<String>,
+    #[arg(long = "show-now", help = "Also show the widget on the Vestaboard right away")]
+    show_now: bool,
   },
   #[command(name = "remove", about = "Remove a scheduled message by ID.  Run vbl schdule list to see the ID's")]
   Remove {
     #[clap(help = "The ID of the scheduled task", required = true)]
     id: String,
   },
-  #[command(name = "clear", about = "Clear all scheduled messages")]
-  Clear,
-  #[command(name = "preview", about = "Preview the schedule without updating the Vestaboard")]
-  Preview,
+  #[command(
+    name = "reschedule",
+    alias = "move",
+    about = "Move a scheduled message to a new time, keeping its ID and widget",
+    after_help = "Examples:\n  vbl schedule reschedule abc1 \"2025-05-01 09:00:00\"\n  vbl schedule move abc1 \"2025-05-02 18:30:00\""
+  )]
+  Reschedule {
+    #[clap(help = "The ID of the scheduled task", required = true)]
+    id: String,
+    #[clap(help = "The new time (YYYY-MM-DD HH:MM:SS) in military time.", required = true)]
+    time: String,
+  },
+  #[command(
+    name = "clear",
+    about = "Clear all scheduled messages, or only those before a time",
+    after_help = "Examples:\n  vbl schedule clear\n  vbl schedule clear --before\n  vbl schedule clear --before 2025-05-01"
+  )]
+  Clear {
+    #[arg(
+      long,
+      value_name = "TIME",
+      num_args = 0..=1,
+      default_missing_value = "now",
+      help = "Only remove one-shot tasks before this time (\"YYYY-MM-DD HH:MM:SS\" or a date; default now)"
+    )]
+    before: Option<String>,
+  },
+  #[command(
+    name = "dedupe",
+    about = "Remove duplicate tasks (same widget, input, and time), keeping the first",
+    after_help = "Examples:\n  vbl schedule dedupe\n  vbl schedule dedupe --window 5m"
+  )]
+  Dedupe {
+    #[arg(
+      long,
+      value_name = "DURATION",
+      help = "Also treat tasks this close together as duplicates (e.g. 90s, 5m)"
+    )]
+    window: Option<String>,
+  },
+  #[command(
+    name = "import",
+    about = "Add a text task for each upcoming event in an iCalendar (.ics) file, skipping events already imported",
+    after_help = "Examples:\n  vbl schedule import calendar.ics"
+  )]
+  Import {
+    #[clap(help = "The .ics file to import", required = true)]
+    file: PathBuf,
+  },
+  #[command(
+    name = "export",
+    about = "Print the schedule as JSON, or as commands that recreate it",
+    after_help = "Examples:\n  vbl schedule export\n  vbl schedule export --as-commands > schedule.sh"
+  )]
+  Export {
+    #[arg(long = "as-commands", help = "Emit vbl schedule add commands instead of JSON")]
+    as_commands: bool,
+  },
+  #[command(
+    name = "preview",
+    about = "Preview the schedule without updating the Vestaboard",
+    after_help = "Examples:\n  vbl schedule preview\n  vbl schedule preview --count 3"
+  )]
+  Preview {
+    #[arg(long, value_name = "N", help = "Only preview the next N upcoming tasks")]
+    count: Option<usize>,
+  },
   #[command(
     name = "run",
     about = "Run the schedule, executing tasks at their scheduled times",
-    after_help = "Examples:\n  vbl schedule run\n  vbl schedule run --dry-run"
+    after_help = "Examples:\n  vbl schedule run\n  vbl schedule run --dry-run\n  vbl schedule run --catch-up\n  vbl schedule run --simulate 2025-05-01 2025-05-07"
   )]
   Run {
     #[arg(short = 'd', long = "dry-run", help = "Preview mode - show messages without sending to Vestaboard")]
     dry_run: bool,
+    #[arg(
+      long = "catch-up",
+      help = "Fire tasks missed shortly before startup (within catch_up_minutes) once instead of skipping them"
+    )]
+    catch_up: bool,
+    #[arg(
+      long,
+      num_args = 2,
+      value_names = ["FROM", "TO"],
+      conflicts_with_all = ["dry_run", "catch_up"],
+      help = "List when tasks would fire between two times (YYYY-MM-DD or YYYY-MM-DD HH:MM:SS), repeats included, without running or sending anything"
+    )]
+    simulate: Option<Vec<String>>,
+  },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryArgs {
+  #[command(name = "show", about = "Re-render a past message to the console (1 is the most recent)")]
+  Show {
+    #[clap(help = "Entry number from vbl history", required = true)]
+    n: usize,
+  },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WidgetArgs {
+  #[command(
+    name = "schema",
+    about = "Print a JSON description of a widget's input fields",
+    after_help = "Examples:\n  vbl widget schema forex\n  vbl widget schema text"
+  )]
+  Schema {
+    #[clap(help = "The widget to describe (e.g. forex, kv, random)", required = true)]
+    name: String,
   },
 }
 
@@ -126,7 +410,7 @@ pub enum ScheduleArgs {
 pub enum Command {
   #[command(
     about = "Show a message on the Vestaboard",
-    after_help = "Examples:\n  vbl show text \"Hello World\"\n  vbl show --dry-run weather\n  vbl show file message.txt"
+    after_help = "Examples:\n  vbl show text \"Hello World\"\n  vbl show --dry-run weather\n  vbl show file message.txt\n  vbl show --widget forex --input '{\"from\": \"usd\", \"to\": \"eur\"}'"
   )]
   Show(ShowArgs),
   #[command(
@@ -145,6 +429,93 @@ pub enum Command {
     #[command(subcommand)]
     action: PlaylistArgs,
   },
+  #[command(about = "Show playlist state and display statistics")]
+  Status,
+  #[command(name = "preview-all", about = "Preview every widget with sample input without sending to Vestaboard")]
+  PreviewAll,
+  #[command(about = "Send a test pattern to verify the Vestaboard connection")]
+  Test,
+  #[command(
+    about = "Check for common setup problems (config, env vars, data directory, lock file, connection)",
+    after_help = "Examples:\n  vbl doctor\n  vbl doctor --skip-connection\n  vbl --internet doctor"
+  )]
+  Doctor {
+    #[arg(long, help = "Don't send the test pattern to the Vestaboard")]
+    skip_connection: bool,
+  },
+  #[command(
+    about = "Count down on the Vestaboard, then show a label",
+    after_help = "Examples:\n  vbl timer 25m \"break time\"\n  vbl timer 1h30m\n  vbl timer --dry-run 90s"
+  )]
+  Timer {
+    #[clap(help = "How long to count down (e.g. 25m, 90s, 1h30m)", required = true)]
+    duration: String,
+    #[clap(help = "Label to show while counting and when time is up")]
+    label: Vec<String>,
+    #[arg(short = 'd', long = "dry-run", help = "Preview mode - show frames without sending to Vestaboard")]
+    dry_run: bool,
+  },
+  #[command(about = "Re-send the last message sent to the Vestaboard (e.g. after the board was cleared)")]
+  Repeat,
+  #[command(
+    about = "Show the splash, then run the playlist with scheduled tasks taking over when due",
+    after_help = "Examples:\n  vbl daemon\n  vbl daemon --dry-run\n  vbl daemon --once"
+  )]
+  Daemon {
+    #[arg(short = 'd', long = "dry-run", help = "Preview mode - show messages without sending to Vestaboard")]
+    dry_run: bool,
+    #[arg(
+      long,
+      help = "Fire due scheduled tasks (catching up on recently missed ones) and any due within a minute, then exit"
+    )]
+    once: bool,
+  },
+  #[command(
+    name = "send-codes",
+    about = "Send a 6x22 grid of raw character codes (0-70) to the Vestaboard, for debugging",
+    after_help = "Examples:\n  vbl send-codes codes.json\n  vbl send-codes '[[63,0,0,...],...]'"
+  )]
+  SendCodes {
+    #[clap(help = "Path to a JSON file with the grid, or the JSON itself", required = true)]
+    codes: String,
+  },
+  #[command(
+    about = "Save the board's current grid as a JSON file that send-codes can show again",
+    after_help = "Examples:\n  vbl snapshot board.json\n  vbl send-codes board.json"
+  )]
+  Snapshot {
+    #[clap(help = "File to write the grid of character codes to", required = true)]
+    path: PathBuf,
+  },
+  #[command(about = "Describe widgets for building tools around vbl")]
+  Widget {
+    #[command(subcommand)]
+    action: WidgetArgs,
+  },
+  #[command(
+    about = "Check that every character in a message can be shown on the Vestaboard",
+    after_help = "Examples:\n  vbl lint \"see you at 5pm!\"\n  vbl lint \"Café €5\""
+  )]
+  Lint {
+    #[clap(help = "The message to check", required = true)]
+    message: String,
+  },
+  #[command(
+    about = "Remove the runner lock file left behind by a crashed playlist, schedule, or daemon",
+    after_help = "Examples:\n  vbl unlock\n  vbl unlock --force"
+  )]
+  Unlock {
+    #[arg(long, help = "Remove the lock even if the process holding it is still running")]
+    force: bool,
+  },
+  #[command(
+    about = "List messages recently sent to the Vestaboard",
+    after_help = "Examples:\n  vbl history\n  vbl history show 1"
+  )]
+  History {
+    #[command(subcommand)]
+    action: Option<HistoryArgs>,
+  },
 }
 
 #[derive(Parser, Debug)]
@@ -165,6 +536,13 @@ pub struct Cli {
   #[arg(short = 'v', long = "verbose", global = true, help = "Show detailed progress information")]
   pub verbose: bool,
 
+  #[arg(
+    long = "no-color",
+    global = true,
+    help = "Disable colored output (also honors the NO_COLOR environment variable)"
+  )]
+  pub no_color: bool,
+
   #[arg(long, global = true, help = "Use internet API instead of local network")]
   pub internet: bool,
 }
@@ -1,10 +1,19 @@
 use crate::api::TransportType;
 use crate::cli_display::{print_error, print_progress, print_success};
+use crate::datetime::TimeWindow;
 use crate::errors::VestaboardError;
+use crate::locale::Locale;
+use crate::widgets::weather::{parse_weather_lines, Units, WeatherCache, WeatherLine};
+use crate::widgets::widget_utils::{
+  color_tile, BoardSize, DEFAULT_ACCENT_COLOR, HEADER_PLACEHOLDER, MAX_MESSAGE_HEIGHT, MAX_MESSAGE_LENGTH,
+  STANDARD_BOARD,
+};
+use chrono::NaiveTime;
 use log::LevelFilter;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 // Configuration file and default paths
 pub const CONFIG_FILE_PATH: &str = "data/vblconfig.toml";
@@ -15,19 +24,124 @@ pub const DEFAULT_SCHEDULE_FILE_PATH: &str = "data/schedule.json";
 pub const DEFAULT_SCHEDULE_BACKUP_PATH: &str = "data/schedule_backup.json";
 pub const DEFAULT_PLAYLIST_FILE_PATH: &str = "data/playlist.json";
 pub const DEFAULT_RUNTIME_STATE_PATH: &str = "data/runtime_state.json";
+pub const DEFAULT_SCHEDULE_STATE_PATH: &str = "data/schedule_state.json";
 pub const DEFAULT_LOCK_FILE_PATH: &str = "data/vestaboard.lock";
+pub const DEFAULT_WEATHER_LOCATION: &str = "austin";
+pub const DEFAULT_HISTORY_FILE_PATH: &str = "data/history.json";
+pub const DEFAULT_DISPLAY_QUEUE_PATH: &str = "data/display_queue.json";
+pub const DEFAULT_HISTORY_SIZE: usize = 50;
+pub const DEFAULT_ERROR_ACCENT_COLOR: &str = "red";
+pub const DEFAULT_THEME_ALIGNMENT: &str = "center";
+pub const DEFAULT_FOREX_API_URL: &str = "https://v6.exchangerate-api.com/v6/{key}/pair/{from}/{to}";
+pub const DEFAULT_LOG_MAX_BYTES: u64 = 5 * 1024 * 1024;
+pub const DEFAULT_LOG_MAX_FILES: usize = 5;
+pub const DEFAULT_WEATHER_CACHE_PATH: &str = "data/weather_cache.json";
+pub const DEFAULT_WEATHER_STALE_MINUTES: u64 = 10;
+pub const DEFAULT_CATCH_UP_MINUTES: u64 = 15;
+pub const DEFAULT_ICS_ALL_DAY_TIME: &str = "08:00";
+pub const DEFAULT_ON_THIS_DAY_FILE_PATH: &str = "./src/widgets/on_this_day/events.txt";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
   pub log_level: String,
   pub log_file_path: String,
   pub console_log_level: Option<String>,
+  /// Rotate the log file once it reaches this size
+  pub log_max_bytes: Option<u64>,
+  /// How many rotated log files to keep; older ones are deleted
+  pub log_max_files: Option<usize>,
+  /// Gzip rotated log files (needs the log-compression feature)
+  pub log_compress: Option<bool>,
   pub schedule_file_path: Option<String>,
   pub schedule_backup_path: Option<String>,
   pub check_interval_seconds: Option<u64>,
+  /// How far back (in minutes) `vbl schedule run --catch-up` fires tasks missed before startup
+  pub catch_up_minutes: Option<u64>,
   pub playlist_file_path: Option<String>,
   pub runtime_state_path: Option<String>,
+  /// Where executed schedule task IDs are recorded, so restarts don't re-fire them
+  pub schedule_state_path: Option<String>,
   pub lock_file_path: Option<String>,
+  /// Where recently sent messages are logged for `vbl history`
+  pub history_file_path: Option<String>,
+  /// How many sent messages to keep in the history file
+  pub history_size: Option<usize>,
+  /// Where `vbl show` queues messages waiting for their turn on the board
+  pub display_queue_path: Option<String>,
+  /// Skip schedule tasks whose display matches the task just before them
+  pub skip_duplicate_renders: Option<bool>,
+  /// Move a schedule or playlist file that fails to parse aside and start with an empty one
+  pub recover_corrupt_files: Option<bool>,
+  /// Show a setup hint on the board instead of an error tile when a widget's API key is missing
+  pub friendly_missing_keys: Option<bool>,
+  /// Color of the tile row under error headers (red, orange, yellow, green, blue, violet, white, black)
+  pub error_accent_color: Option<String>,
+  /// Header template for error messages; `{header}` is replaced by the message's header (e.g. "!! {header} !!")
+  pub theme_header: Option<String>,
+  /// Alignment of error and status message lines (left, center, right)
+  pub theme_alignment: Option<String>,
+  /// Show "paused" on the board's bottom row while the playlist is paused
+  pub pause_indicator: Option<bool>,
+  /// Retry a playlist item whose widget failed after its error has been up this many seconds
+  pub error_display_seconds: Option<u64>,
+  /// Randomly shorten or lengthen each playlist interval by up to this many seconds
+  pub interval_jitter_seconds: Option<u64>,
+  /// While this file exists, `vbl daemon` sends nothing (a maintenance window)
+  pub maintenance_file_path: Option<String>,
+  /// Show "down for maintenance" once when a maintenance window begins
+  pub maintenance_message: Option<bool>,
+  /// Send a short placeholder instead of a blank message (except from the clear widget)
+  pub blank_placeholder: Option<bool>,
+  /// Have the playlist and schedule runners skip a blank render instead of sending it
+  pub skip_blank: Option<bool>,
+  /// Abbreviate text widget messages that spill a few characters onto an extra line
+  pub fit_text: Option<bool>,
+  /// File of `word=abbreviation` lines that fit mode tries before its built-in short forms
+  pub abbreviations_file_path: Option<String>,
+  /// Language for day and month names in text placeholders (en, es, fr, de, or pt)
+  pub locale: Option<String>,
+  /// Tiles across the board (at most 22; smaller for non-standard displays)
+  pub board_cols: Option<usize>,
+  /// Tiles down the board (at most 6)
+  pub board_rows: Option<usize>,
+  /// Location passed to weatherapi.com for the weather and airquality widgets
+  pub weather_location: Option<String>,
+  /// Reuse a weather response for this many seconds instead of calling the API (0 turns caching off)
+  pub weather_cache_seconds: Option<u64>,
+  /// File the weather cache is kept in
+  pub weather_cache_path: Option<String>,
+  /// Show the age of cached weather once it is this many minutes old
+  pub weather_stale_minutes: Option<u64>,
+  /// Lines the weather widget shows, in order (e.g. ["time", "temps", "condition", "wind"])
+  pub weather_lines: Option<Vec<String>>,
+  /// Events dataset for the onthisday widget (lines of `MM-DD text`)
+  pub on_this_day_file_path: Option<String>,
+  /// Pair conversion URL for the forex widget, with `{key}`, `{from}` and `{to}` placeholders
+  pub forex_api_url: Option<String>,
+  /// Weather units (imperial, metric, or both); when unset, chosen by the location's country
+  pub units: Option<Units>,
+  /// Widget the schedule runner shows when no task is near (e.g. "sat-word")
+  pub idle_widget: Option<String>,
+  /// Input for the idle widget
+  pub idle_input: Option<serde_json::Value>,
+  /// Widget `vbl daemon` shows at startup before the playlist begins (e.g. "text")
+  pub splash_widget: Option<String>,
+  /// Input for the splash widget
+  pub splash_input: Option<serde_json::Value>,
+  /// Time of day (HH:MM) that all-day calendar events are scheduled at by `vbl schedule import`
+  pub ics_all_day_time: Option<String>,
+  /// Local time window (e.g. "22:00-07:00") during which board messages are dimmed
+  pub night_mode: Option<String>,
+  /// Start of the daily quiet hours (HH:MM), when the board is kept blank
+  pub quiet_start: Option<String>,
+  /// End of the daily quiet hours (HH:MM); before `quiet_start` wraps past midnight
+  pub quiet_end: Option<String>,
+  /// Also POST every message sent to the board to this URL (e.g. to mirror it in chat)
+  pub webhook_url: Option<String>,
+  /// Command run after a message is sent to the board, with the widget and `success` as arguments
+  pub on_success: Option<String>,
+  /// Command run when sending to the board fails, with the widget and `failure` as arguments
+  pub on_failure: Option<String>,
   /// Default transport for API communication (local or internet)
   pub transport: Option<TransportType>,
 }
@@ -38,12 +152,57 @@ impl Default for Config {
       log_level: DEFAULT_LOG_LEVEL.to_string(),
       log_file_path: DEFAULT_LOG_FILE_PATH.to_string(),
       console_log_level: Some(DEFAULT_CONSOLE_LOG_LEVEL.to_string()),
+      log_max_bytes: Some(DEFAULT_LOG_MAX_BYTES),
+      log_max_files: Some(DEFAULT_LOG_MAX_FILES),
+      log_compress: Some(false),
       schedule_file_path: Some(DEFAULT_SCHEDULE_FILE_PATH.to_string()),
       schedule_backup_path: Some(DEFAULT_SCHEDULE_BACKUP_PATH.to_string()),
       check_interval_seconds: Some(3),
+      catch_up_minutes: Some(DEFAULT_CATCH_UP_MINUTES),
       playlist_file_path: Some(DEFAULT_PLAYLIST_FILE_PATH.to_string()),
       runtime_state_path: Some(DEFAULT_RUNTIME_STATE_PATH.to_string()),
+      schedule_state_path: Some(DEFAULT_SCHEDULE_STATE_PATH.to_string()),
       lock_file_path: Some(DEFAULT_LOCK_FILE_PATH.to_string()),
+      history_file_path: Some(DEFAULT_HISTORY_FILE_PATH.to_string()),
+      display_queue_path: Some(DEFAULT_DISPLAY_QUEUE_PATH.to_string()),
+      history_size: Some(DEFAULT_HISTORY_SIZE),
+      skip_duplicate_renders: Some(false),
+      recover_corrupt_files: Some(false),
+      friendly_missing_keys: Some(false),
+      fit_text: Some(false),
+      abbreviations_file_path: None,
+      locale: None,
+      board_cols: Some(MAX_MESSAGE_LENGTH),
+      board_rows: Some(MAX_MESSAGE_HEIGHT),
+      pause_indicator: Some(false),
+      error_display_seconds: None,
+      interval_jitter_seconds: Some(0),
+      maintenance_file_path: None,
+      maintenance_message: Some(false),
+      blank_placeholder: Some(false),
+      skip_blank: Some(false),
+      error_accent_color: Some(DEFAULT_ERROR_ACCENT_COLOR.to_string()),
+      theme_header: Some(HEADER_PLACEHOLDER.to_string()),
+      theme_alignment: Some(DEFAULT_THEME_ALIGNMENT.to_string()),
+      weather_location: Some(DEFAULT_WEATHER_LOCATION.to_string()),
+      weather_cache_seconds: Some(0),
+      weather_cache_path: Some(DEFAULT_WEATHER_CACHE_PATH.to_string()),
+      weather_stale_minutes: Some(DEFAULT_WEATHER_STALE_MINUTES),
+      weather_lines: None,
+      on_this_day_file_path: Some(DEFAULT_ON_THIS_DAY_FILE_PATH.to_string()),
+      forex_api_url: Some(DEFAULT_FOREX_API_URL.to_string()),
+      units: None, // Picked from the weather location's country
+      idle_widget: None,
+      idle_input: None,
+      splash_widget: None,
+      splash_input: None,
+      ics_all_day_time: Some(DEFAULT_ICS_ALL_DAY_TIME.to_string()),
+      night_mode: None,
+      quiet_start: None,
+      quiet_end: None,
+      webhook_url: None,
+      on_success: None,
+      on_failure: None,
       transport: None, // Defaults to Local via get_transport()
     }
   }
@@ -60,6 +219,14 @@ impl Config {
     Self::load_internal(false)
   }
 
+  /// Load the config file if there is one, without creating a default one when it's missing
+  pub fn load_existing() -> Result<Option<Self>, VestaboardError> {
+    if !Path::new(CONFIG_FILE_PATH).exists() {
+      return Ok(None);
+    }
+    Self::load_internal(false).map(Some)
+  }
+
   fn load_internal(show_messages: bool) -> Result<Self, VestaboardError> {
     let config_path = PathBuf::from(CONFIG_FILE_PATH);
 
@@ -150,6 +317,18 @@ impl Config {
     PathBuf::from(&self.log_file_path)
   }
 
+  pub fn get_log_max_bytes(&self) -> u64 {
+    self.log_max_bytes.unwrap_or(DEFAULT_LOG_MAX_BYTES)
+  }
+
+  pub fn get_log_max_files(&self) -> usize {
+    self.log_max_files.unwrap_or(DEFAULT_LOG_MAX_FILES)
+  }
+
+  pub fn get_log_compress(&self) -> bool {
+    self.log_compress.unwrap_or(false)
+  }
+
   pub fn get_schedule_file_path(&self) -> PathBuf {
     PathBuf::from(self.schedule_file_path.as_deref().unwrap_or(DEFAULT_SCHEDULE_FILE_PATH))
   }
@@ -167,6 +346,11 @@ impl Config {
     self.check_interval_seconds.unwrap_or(3)
   }
 
+  /// Window for `vbl schedule run --catch-up`.
+  pub fn get_catch_up_window(&self) -> chrono::Duration {
+    chrono::Duration::minutes(self.catch_up_minutes.unwrap_or(DEFAULT_CATCH_UP_MINUTES) as i64)
+  }
+
   pub fn get_playlist_file_path(&self) -> PathBuf {
     PathBuf::from(self.playlist_file_path.as_deref().unwrap_or(DEFAULT_PLAYLIST_FILE_PATH))
   }
@@ -175,12 +359,305 @@ impl Config {
     PathBuf::from(self.runtime_state_path.as_deref().unwrap_or(DEFAULT_RUNTIME_STATE_PATH))
   }
 
+  pub fn get_schedule_state_path(&self) -> PathBuf {
+    PathBuf::from(
+      self
+        .schedule_state_path
+        .as_deref()
+        .unwrap_or(DEFAULT_SCHEDULE_STATE_PATH),
+    )
+  }
+
   pub fn get_lock_file_path(&self) -> PathBuf {
     PathBuf::from(self.lock_file_path.as_deref().unwrap_or(DEFAULT_LOCK_FILE_PATH))
   }
 
+  pub fn get_history_file_path(&self) -> PathBuf {
+    PathBuf::from(self.history_file_path.as_deref().unwrap_or(DEFAULT_HISTORY_FILE_PATH))
+  }
+
+  pub fn get_display_queue_path(&self) -> PathBuf {
+    PathBuf::from(self.display_queue_path.as_deref().unwrap_or(DEFAULT_DISPLAY_QUEUE_PATH))
+  }
+
+  /// Held by the `vbl show` that is sending the queued messages, next to the queue file
+  pub fn get_display_queue_lock_path(&self) -> PathBuf {
+    self.get_display_queue_path().with_extension("lock")
+  }
+
+  pub fn get_history_size(&self) -> usize {
+    self.history_size.unwrap_or(DEFAULT_HISTORY_SIZE)
+  }
+
+  pub fn get_skip_duplicate_renders(&self) -> bool {
+    self.skip_duplicate_renders.unwrap_or(false)
+  }
+
+  pub fn get_recover_corrupt_files(&self) -> bool {
+    self.recover_corrupt_files.unwrap_or(false)
+  }
+
+  pub fn get_friendly_missing_keys(&self) -> bool {
+    self.friendly_missing_keys.unwrap_or(false)
+  }
+
+  pub fn get_pause_indicator(&self) -> bool {
+    self.pause_indicator.unwrap_or(false)
+  }
+
+  /// How long a playlist error stays up before the item is retried; `None` (or 0) keeps it
+  /// up for the whole interval.
+  pub fn get_error_display(&self) -> Option<Duration> {
+    self
+      .error_display_seconds
+      .filter(|seconds| *seconds > 0)
+      .map(Duration::from_secs)
+  }
+
+  /// Seconds each playlist interval may randomly run shorter or longer; 0 keeps it fixed.
+  pub fn get_interval_jitter_seconds(&self) -> u64 {
+    self.interval_jitter_seconds.unwrap_or(0)
+  }
+
+  /// Directories vbl saves its config, log, schedule, playlist, state, history, display
+  /// queue, and lock files in, each listed once.
+  pub fn get_data_dirs(&self) -> Vec<PathBuf> {
+    let files = [
+      PathBuf::from(CONFIG_FILE_PATH),
+      self.get_log_file_path(),
+      self.get_schedule_file_path(),
+      self.get_playlist_file_path(),
+      self.get_runtime_state_path(),
+      self.get_schedule_state_path(),
+      self.get_lock_file_path(),
+      self.get_history_file_path(),
+      self.get_display_queue_path(),
+    ];
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for file in files {
+      let dir = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+      };
+      if !dirs.contains(&dir) {
+        dirs.push(dir);
+      }
+    }
+    dirs
+  }
+
+  /// Check up front that every data directory can be written to, so a command on read-only
+  /// media fails with one clear error instead of partway through saving.
+  pub fn check_data_dirs_writable(&self) -> Result<(), VestaboardError> {
+    self.get_data_dirs().iter().try_for_each(|dir| ensure_writable(dir))
+  }
+
+  pub fn get_maintenance_file_path(&self) -> Option<PathBuf> {
+    self
+      .maintenance_file_path
+      .as_deref()
+      .filter(|path| !path.trim().is_empty())
+      .map(PathBuf::from)
+  }
+
+  pub fn get_maintenance_message(&self) -> bool {
+    self.maintenance_message.unwrap_or(false)
+  }
+
+  pub fn get_blank_placeholder(&self) -> bool {
+    self.blank_placeholder.unwrap_or(false)
+  }
+
+  pub fn get_skip_blank(&self) -> bool {
+    self.skip_blank.unwrap_or(false)
+  }
+
+  pub fn get_fit_text(&self) -> bool {
+    self.fit_text.unwrap_or(false)
+  }
+
+  pub fn get_abbreviations_file_path(&self) -> Option<PathBuf> {
+    self.abbreviations_file_path.as_deref().map(PathBuf::from)
+  }
+
+  /// Locale for day and month names; English when unset or not supported
+  pub fn get_locale(&self) -> Locale {
+    self.locale.as_deref().map(Locale::parse_or_english).unwrap_or_default()
+  }
+
+  /// Board geometry from `board_cols` and `board_rows`. A size of zero or larger than the
+  /// standard 22x6 board can't be sent, so it is logged and the standard size is used.
+  pub fn get_board_size(&self) -> BoardSize {
+    let cols = self.board_cols.unwrap_or(MAX_MESSAGE_LENGTH);
+    let rows = self.board_rows.unwrap_or(MAX_MESSAGE_HEIGHT);
+    if !(1..=MAX_MESSAGE_LENGTH).contains(&cols) || !(1..=MAX_MESSAGE_HEIGHT).contains(&rows) {
+      log::warn!("Invalid board size {}x{}, using {}x{}", cols, rows, MAX_MESSAGE_LENGTH, MAX_MESSAGE_HEIGHT);
+      return STANDARD_BOARD;
+    }
+    BoardSize { cols, rows }
+  }
+
+  pub fn get_on_this_day_file_path(&self) -> PathBuf {
+    PathBuf::from(
+      self
+        .on_this_day_file_path
+        .as_deref()
+        .unwrap_or(DEFAULT_ON_THIS_DAY_FILE_PATH),
+    )
+  }
+
+  /// Color tile for the row under error headers; unknown colors fall back to red.
+  pub fn get_error_accent_color(&self) -> char {
+    let name = self.error_accent_color.as_deref().unwrap_or(DEFAULT_ERROR_ACCENT_COLOR);
+    color_tile(name).unwrap_or_else(|| {
+      log::warn!("Unknown error_accent_color '{}', using red", name);
+      DEFAULT_ACCENT_COLOR
+    })
+  }
+
+  pub fn get_forex_api_url(&self) -> String {
+    self
+      .forex_api_url
+      .clone()
+      .unwrap_or_else(|| DEFAULT_FOREX_API_URL.to_string())
+  }
+
+  /// Explicit weather units, or None to go by the location's country.
+  pub fn get_units(&self) -> Option<Units> {
+    self.units
+  }
+
+  pub fn get_weather_location(&self) -> String {
+    self
+      .weather_location
+      .clone()
+      .unwrap_or_else(|| DEFAULT_WEATHER_LOCATION.to_string())
+  }
+
+  /// Weather cache settings, or `None` if caching is off.
+  pub fn get_weather_cache(&self) -> Option<WeatherCache> {
+    let seconds = self.weather_cache_seconds.unwrap_or(0);
+    if seconds == 0 {
+      return None;
+    }
+    let stale_minutes = self.weather_stale_minutes.unwrap_or(DEFAULT_WEATHER_STALE_MINUTES);
+    Some(WeatherCache {
+      path: PathBuf::from(self.weather_cache_path.as_deref().unwrap_or(DEFAULT_WEATHER_CACHE_PATH)),
+      max_age: chrono::Duration::seconds(seconds as i64),
+      stale_after: chrono::Duration::minutes(stale_minutes as i64),
+    })
+  }
+
+  /// The weather layout from `weather_lines`, or None for the standard view (or if a name is unknown).
+  pub fn get_weather_lines(&self) -> Option<Vec<WeatherLine>> {
+    let names = self.weather_lines.as_ref()?;
+    parse_weather_lines(names)
+      .map_err(|e| log::warn!("Ignoring weather_lines: {}", e))
+      .ok()
+  }
+
+  /// Idle widget and its input, if an idle widget is configured.
+  pub fn get_idle(&self) -> Option<(String, serde_json::Value)> {
+    let widget = self.idle_widget.as_deref()?.trim().to_lowercase();
+    if widget.is_empty() {
+      return None;
+    }
+    Some((widget, self.idle_input.clone().unwrap_or(serde_json::Value::Null)))
+  }
+
+  /// Splash widget and its input, if a splash widget is configured.
+  pub fn get_splash(&self) -> Option<(String, serde_json::Value)> {
+    let widget = self.splash_widget.as_deref()?.trim().to_lowercase();
+    if widget.is_empty() {
+      return None;
+    }
+    Some((widget, self.splash_input.clone().unwrap_or(serde_json::Value::Null)))
+  }
+
+  /// Time of day for all-day calendar events, falling back to the default if unset or invalid.
+  pub fn get_ics_all_day_time(&self) -> NaiveTime {
+    let default = NaiveTime::parse_from_str(DEFAULT_ICS_ALL_DAY_TIME, "%H:%M").expect("default time is valid");
+    match self
+      .ics_all_day_time
+      .as_deref()
+      .map(|time| NaiveTime::parse_from_str(time.trim(), "%H:%M"))
+    {
+      Some(Ok(time)) => time,
+      Some(Err(_)) => {
+        log::warn!("Invalid ics_all_day_time, using {}", DEFAULT_ICS_ALL_DAY_TIME);
+        default
+      },
+      None => default,
+    }
+  }
+
+  /// Night mode window, if one is configured. An invalid window is logged and ignored.
+  pub fn get_night_mode(&self) -> Option<TimeWindow> {
+    let spec = self.night_mode.as_deref()?;
+    match TimeWindow::parse(spec) {
+      Ok(window) => Some(window),
+      Err(e) => {
+        log::warn!("Ignoring night_mode: {}", e);
+        None
+      },
+    }
+  }
+
+  /// Quiet hours window from `quiet_start` and `quiet_end`, or None when neither is set.
+  /// Setting only one of them is an error.
+  pub fn parse_quiet_hours(&self) -> Result<Option<TimeWindow>, String> {
+    match (self.quiet_start.as_deref(), self.quiet_end.as_deref()) {
+      (None, None) => Ok(None),
+      (Some(start), Some(end)) => TimeWindow::parse(&format!("{}-{}", start, end)).map(Some),
+      _ => Err("Both quiet_start and quiet_end must be set".to_string()),
+    }
+  }
+
+  /// Quiet hours window, if one is configured. An invalid window is logged and ignored.
+  pub fn get_quiet_hours(&self) -> Option<TimeWindow> {
+    self.parse_quiet_hours().unwrap_or_else(|e| {
+      log::warn!("Ignoring quiet hours: {}", e);
+      None
+    })
+  }
+
+  /// Webhook that mirrors board messages, if one is configured.
+  pub fn get_webhook_url(&self) -> Option<String> {
+    self.webhook_url.clone().filter(|url| !url.trim().is_empty())
+  }
+
+  /// Hook command for successful sends, if one is configured.
+  pub fn get_on_success(&self) -> Option<String> {
+    self.on_success.clone().filter(|command| !command.trim().is_empty())
+  }
+
+  /// Hook command for failed sends, if one is configured.
+  pub fn get_on_failure(&self) -> Option<String> {
+    self.on_failure.clone().filter(|command| !command.trim().is_empty())
+  }
+
   /// Get the configured transport type, defaulting to Local if not specified.
   pub fn get_transport(&self) -> TransportType {
     self.transport.unwrap_or_default()
   }
 }
+
+/// Make sure files can be created in `dir` (creating it if needed) by writing and removing
+/// a probe file.
+pub fn ensure_writable(dir: &Path) -> Result<(), VestaboardError> {
+  let probe = dir.join(".vbl_write_probe");
+  fs::create_dir_all(dir)
+    .and_then(|_| fs::write(&probe, b"ok"))
+    .and_then(|_| fs::remove_file(&probe))
+    .map_err(|e| {
+      log::error!("Data directory {} is not writable: {}", dir.display(), e);
+      VestaboardError::io_error(
+        e,
+        &format!(
+          "{} is not writable. Run vbl from a directory you own, fix its permissions, or point the *_path settings in {} somewhere writable",
+          dir.display(),
+          CONFIG_FILE_PATH
+        ),
+      )
+    })
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 
 pub fn datetime_to_utc(time_str: &str) -> Result<DateTime<Utc>, String> {
   let naive_datetime = NaiveDateTime::parse_from_str(time_str, "%Y-%m-%d %H:%M:%S")
@@ -11,6 +11,80 @@ pub fn datetime_to_utc(time_str: &str) -> Result<DateTime<Utc>, String> {
   Ok(local_datetime.with_timezone(&Utc))
 }
 
+/// Parse a range bound for filtering: either a full `YYYY-MM-DD HH:MM:SS` or just a date.
+///
+/// A bare date covers the whole day, so it becomes midnight for a start bound and
+/// 23:59:59 for an end bound.
+pub fn range_bound_to_utc(bound: &str, end_of_day: bool) -> Result<DateTime<Utc>, String> {
+  if NaiveDate::parse_from_str(bound, "%Y-%m-%d").is_ok() {
+    let time = if end_of_day { "23:59:59" } else { "00:00:00" };
+    return datetime_to_utc(&format!("{} {}", bound, time));
+  }
+  datetime_to_utc(bound)
+}
+
+/// Longest duration `parse_duration` accepts (a year), so adding one to the current time
+/// can't overflow
+pub const MAX_DURATION_SECS: u64 = 366 * 24 * 60 * 60;
+
+/// Parse a length of time such as `25m`, `90s`, `1h`, or `1h30m`. A bare number is minutes.
+pub fn parse_duration(text: &str) -> Result<std::time::Duration, String> {
+  let invalid = || format!("Invalid duration '{}'. Use e.g. 25m, 90s, or 1h30m.", text);
+  let too_long = || format!("Duration '{}' is too long.", text);
+  let text = text.trim().to_lowercase();
+
+  if let Ok(minutes) = text.parse::<u64>() {
+    return if minutes > 0 {
+      minutes
+        .checked_mul(60)
+        .filter(|&seconds| seconds <= MAX_DURATION_SECS)
+        .map(std::time::Duration::from_secs)
+        .ok_or_else(too_long)
+    } else {
+      Err(invalid())
+    };
+  }
+
+  let mut total: u64 = 0;
+  let mut digits = String::new();
+  for c in text.chars() {
+    if c.is_ascii_digit() {
+      digits.push(c);
+      continue;
+    }
+    let value: u64 = digits.parse().map_err(|_| invalid())?;
+    let seconds = match c {
+      'h' => value.checked_mul(3600),
+      'm' => value.checked_mul(60),
+      's' => Some(value),
+      _ => return Err(invalid()),
+    };
+    total = seconds
+      .and_then(|seconds| total.checked_add(seconds))
+      .ok_or_else(too_long)?;
+    digits.clear();
+  }
+
+  if !digits.is_empty() || total == 0 {
+    return Err(invalid());
+  }
+  if total > MAX_DURATION_SECS {
+    return Err(too_long());
+  }
+  Ok(std::time::Duration::from_secs(total))
+}
+
+/// Format a duration as `m:ss` (or `h:mm:ss` for an hour or more).
+pub fn format_countdown(remaining: std::time::Duration) -> String {
+  let total = remaining.as_secs();
+  let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+  if hours > 0 {
+    format!("{}:{:02}:{:02}", hours, minutes, seconds)
+  } else {
+    format!("{}:{:02}", minutes, seconds)
+  }
+}
+
 pub fn datetime_to_local(dt: DateTime<Utc>) -> String {
   let local_time = dt.with_timezone(&Local::now().timezone());
   let formatted_time = local_time.format("%Y.%m.%d %I:%M %p").to_string();
@@ -20,3 +94,31 @@ pub fn datetime_to_local(dt: DateTime<Utc>) -> String {
 pub fn is_or_before(dt1: DateTime<Utc>, dt2: DateTime<Utc>) -> bool {
   dt1 <= dt2
 }
+
+/// A daily window of local time such as `22:00-07:00`. A window whose end is before
+/// its start wraps past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeWindow {
+  pub start: NaiveTime,
+  pub end: NaiveTime,
+}
+
+impl TimeWindow {
+  /// Parse `HH:MM-HH:MM`.
+  pub fn parse(spec: &str) -> Result<Self, String> {
+    let invalid = || format!("Invalid time window '{}'. Please use HH:MM-HH:MM (e.g. 22:00-07:00).", spec);
+    let (start, end) = spec.split_once('-').ok_or_else(invalid)?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| invalid())?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").map_err(|_| invalid())?;
+    Ok(Self { start, end })
+  }
+
+  /// Whether `time` falls in the window. The start is inclusive and the end exclusive.
+  pub fn contains(&self, time: NaiveTime) -> bool {
+    if self.start <= self.end {
+      self.start <= time && time < self.end
+    } else {
+      time >= self.start || time < self.end
+    }
+  }
+}
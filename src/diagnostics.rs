@@ -0,0 +1,359 @@
+//! Connectivity check for `vbl test` and setup checks for `vbl doctor`.
+//!
+//! Sends a fixed pattern through the configured transport and turns any failure
+//! into a message that says what to fix (missing env var, unreachable board,
+//! rejected key) rather than a raw HTTP error.
+
+use std::fs;
+use std::path::Path;
+
+use crate::api::{Transport, TransportType};
+use crate::api_broker::message_to_codes;
+use crate::cli_display::{colorize, colors_enabled, print_error, print_progress, print_success};
+use crate::config::{ensure_writable, Config, CONFIG_FILE_PATH};
+use crate::datetime::TimeWindow;
+use crate::errors::VestaboardError;
+use crate::runner::lock::{lock_status, LockStatus};
+use crate::secrets::secret;
+use crate::widgets::resolver::WIDGET_NAMES;
+use crate::widgets::weather::parse_weather_lines;
+use crate::widgets::widget_utils::{center_line, color_tile, Alignment};
+
+/// Color bar used on the top and bottom rows of the test pattern
+const COLOR_BAR: &str = "RRROOOYYYGGGBBBVVVWWWK";
+
+/// Build the test pattern: color bars framing a short centered message.
+pub fn test_pattern() -> Vec<String> {
+  vec![
+    COLOR_BAR.to_string(),
+    String::new(),
+    center_line("vestaboard local".to_string()),
+    center_line("connection test".to_string()),
+    String::new(),
+    COLOR_BAR.to_string(),
+  ]
+}
+
+/// Name of the environment variable holding the API key for a transport.
+fn api_key_var(transport_name: &str) -> &'static str {
+  match transport_name {
+    "internet" => "INTERNET_API_KEY",
+    _ => "LOCAL_API_KEY",
+  }
+}
+
+/// Explain a connection test failure in terms of what the user should check.
+pub fn diagnose(error: &VestaboardError, transport_name: &str) -> String {
+  let status = match error {
+    VestaboardError::ApiError { code, .. } => *code,
+    VestaboardError::ReqwestError { source, .. } => source.status().map(|s| s.as_u16()),
+    _ => None,
+  };
+
+  match (error, status) {
+    (VestaboardError::ConfigError { field, .. }, _) => {
+      format!("{} is not set. Export it or add it to your .env file.", field)
+    },
+    (_, Some(401 | 403)) => {
+      format!("The Vestaboard rejected the API key. Check that {} is correct.", api_key_var(transport_name))
+    },
+    (_, Some(429)) => "Rate limited by the Vestaboard API. Wait a minute and try again.".to_string(),
+    (VestaboardError::ReqwestError { source, .. }, _) if source.is_connect() || source.is_timeout() => {
+      if transport_name == "internet" {
+        "Could not reach the Vestaboard API. Check your internet connection.".to_string()
+      } else {
+        "Could not connect to the Vestaboard. Check IP_ADDRESS and that the board is on the same network.".to_string()
+      }
+    },
+    _ => error.to_user_message(),
+  }
+}
+
+/// Send the test pattern through a transport.
+pub async fn send_test_pattern(transport: &Transport) -> Result<(), VestaboardError> {
+  transport.send_codes(message_to_codes(test_pattern())).await
+}
+
+/// Run the connection test for a transport type and return the process exit code.
+pub async fn run_connection_test(transport_type: TransportType) -> i32 {
+  let transport = match Transport::new(transport_type) {
+    Ok(t) => t,
+    Err(e) => {
+      log::error!("Connection test could not create transport: {}", e);
+      let name = match transport_type {
+        TransportType::Local => "local",
+        TransportType::Internet => "internet",
+      };
+      print_error(&diagnose(&e, name));
+      return 1;
+    },
+  };
+
+  print_progress(&format!("Testing {} connection...", transport.name()));
+  match send_test_pattern(&transport).await {
+    Ok(()) => {
+      log::info!("Connection test succeeded via {} transport", transport.name());
+      print_success("Connection OK: test pattern sent to Vestaboard");
+      0
+    },
+    Err(e) => {
+      log::error!("Connection test failed: {}", e);
+      print_error(&diagnose(&e, transport.name()));
+      1
+    },
+  }
+}
+
+/// Outcome of a single `vbl doctor` check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+  Pass,
+  /// Something worth fixing that doesn't stop vbl from working
+  Warn,
+  /// Something that stops vbl from working
+  Fail,
+}
+
+/// A `vbl doctor` check result, with a hint on how to fix anything that didn't pass
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+  pub name: &'static str,
+  pub status: CheckStatus,
+  pub detail: String,
+  pub hint: Option<String>,
+}
+
+impl CheckResult {
+  fn pass(name: &'static str, detail: &str) -> Self {
+    Self {
+      name,
+      status: CheckStatus::Pass,
+      detail: detail.to_string(),
+      hint: None,
+    }
+  }
+
+  fn warn(name: &'static str, detail: &str, hint: &str) -> Self {
+    Self {
+      name,
+      status: CheckStatus::Warn,
+      detail: detail.to_string(),
+      hint: Some(hint.to_string()),
+    }
+  }
+
+  fn fail(name: &'static str, detail: &str, hint: &str) -> Self {
+    Self {
+      name,
+      status: CheckStatus::Fail,
+      detail: detail.to_string(),
+      hint: Some(hint.to_string()),
+    }
+  }
+}
+
+/// Check that the config file parses and its values make sense.
+pub fn check_config(path: &Path) -> CheckResult {
+  const NAME: &str = "config";
+  if !path.exists() {
+    return CheckResult::warn(
+      NAME,
+      &format!("{} not found", path.display()),
+      "A default config is created on first run; nothing to do unless you expected one.",
+    );
+  }
+
+  let content = match fs::read_to_string(path) {
+    Ok(content) => content,
+    Err(e) => {
+      return CheckResult::fail(
+        NAME,
+        &format!("Cannot read {}: {}", path.display(), e),
+        "Check the file's permissions.",
+      )
+    },
+  };
+  let config: Config = match toml::from_str(&content) {
+    Ok(config) => config,
+    Err(e) => {
+      return CheckResult::fail(
+        NAME,
+        &format!("Invalid config format: {}", e.message()),
+        &format!("Fix the value above, or delete {} to recreate the defaults.", path.display()),
+      )
+    },
+  };
+
+  let mut problems = Vec::new();
+  if let Some(Err(e)) = config.night_mode.as_deref().map(TimeWindow::parse) {
+    problems.push(format!("night_mode: {}", e));
+  }
+  if let Err(e) = config.parse_quiet_hours() {
+    problems.push(format!("quiet hours: {}", e));
+  }
+  if let Some(widget) = config.idle_widget.as_deref() {
+    if !WIDGET_NAMES.contains(&widget.trim().to_lowercase().as_str()) {
+      problems.push(format!("idle_widget: unknown widget '{}'", widget));
+    }
+  }
+  if let Some(color) = config.error_accent_color.as_deref() {
+    if color_tile(color).is_none() {
+      problems.push(format!("error_accent_color: unknown color '{}'", color));
+    }
+  }
+  if let Some(alignment) = config.theme_alignment.as_deref() {
+    if Alignment::parse(alignment).is_none() {
+      problems.push(format!("theme_alignment: unknown alignment '{}'", alignment));
+    }
+  }
+  if let Some(Err(e)) = config.weather_lines.as_deref().map(parse_weather_lines) {
+    problems.push(format!("weather_lines: {}", e));
+  }
+
+  if problems.is_empty() {
+    CheckResult::pass(NAME, &format!("{} is valid", path.display()))
+  } else {
+    CheckResult::warn(NAME, &problems.join("; "), "These settings are ignored or fall back to defaults until fixed.")
+  }
+}
+
+/// Check that the environment variables the transport needs are set.
+pub fn check_transport_env(transport_type: TransportType, env: &impl Fn(&str) -> Option<String>) -> CheckResult {
+  const NAME: &str = "transport";
+  let (name, required): (&str, &[&str]) = match transport_type {
+    TransportType::Local => ("local", &["LOCAL_API_KEY", "IP_ADDRESS"]),
+    TransportType::Internet => ("internet", &["INTERNET_API_KEY"]),
+  };
+  let missing: Vec<&str> = required.iter().copied().filter(|var| env(var).is_none()).collect();
+
+  if missing.is_empty() {
+    CheckResult::pass(NAME, &format!("{} transport variables are set", name))
+  } else {
+    CheckResult::fail(
+      NAME,
+      &format!("{} transport needs {}", name, missing.join(" and ")),
+      "Export them or add them to your .env file.",
+    )
+  }
+}
+
+/// Check that the weather API key used by the weather and airquality widgets is set.
+pub fn check_weather_key(env: &impl Fn(&str) -> Option<String>) -> CheckResult {
+  const NAME: &str = "weather key";
+  if env("WEATHER_API_KEY").is_some() {
+    CheckResult::pass(NAME, "WEATHER_API_KEY is set")
+  } else {
+    CheckResult::warn(
+      NAME,
+      "WEATHER_API_KEY is not set",
+      "The weather and airquality widgets need a key from weatherapi.com in your .env file.",
+    )
+  }
+}
+
+/// Check that vbl can create files in its data directory.
+pub fn check_data_dir(dir: &Path) -> CheckResult {
+  const NAME: &str = "data directory";
+  match ensure_writable(dir) {
+    Ok(()) => CheckResult::pass(NAME, &format!("{} is writable", dir.display())),
+    Err(VestaboardError::IOError { source, .. }) => CheckResult::fail(
+      NAME,
+      &format!("Cannot write to {}: {}", dir.display(), source),
+      "Run vbl from a directory you own, or fix the permissions on the data directory.",
+    ),
+    Err(e) => CheckResult::fail(NAME, &e.to_user_message(), "Check the data directory."),
+  }
+}
+
+/// Check the runner lock file for a running or stale playlist/schedule runner.
+pub fn check_lock_file(path: &Path) -> CheckResult {
+  const NAME: &str = "lock file";
+  match lock_status(path) {
+    LockStatus::Free => CheckResult::pass(NAME, "No runner is holding the lock"),
+    LockStatus::Held { mode, pid } => CheckResult::warn(
+      NAME,
+      &format!("{} runner is running (PID {})", mode, pid),
+      "Stop it before starting another playlist or schedule run.",
+    ),
+    LockStatus::Stale => CheckResult::warn(
+      NAME,
+      &format!("{} is left over from a runner that is no longer running", path.display()),
+      "It is taken over automatically on the next run, or you can remove it with vbl unlock.",
+    ),
+  }
+}
+
+/// Run every offline check against the current environment.
+pub fn run_checks(transport_type: TransportType, env: &impl Fn(&str) -> Option<String>) -> Vec<CheckResult> {
+  let config_path = Path::new(CONFIG_FILE_PATH);
+  // Loading normally would create a missing config, hiding it from `check_config`
+  let config = Config::load_existing().ok().flatten().unwrap_or_default();
+  vec![
+    check_config(config_path),
+    check_data_dir(config_path.parent().unwrap_or(Path::new("."))),
+    check_transport_env(transport_type, env),
+    check_weather_key(env),
+    check_lock_file(&config.get_lock_file_path()),
+  ]
+}
+
+/// Exit code for a set of check results: 1 if any check failed, otherwise 0.
+pub fn doctor_exit_code(results: &[CheckResult]) -> i32 {
+  if results.iter().any(|result| result.status == CheckStatus::Fail) {
+    1
+  } else {
+    0
+  }
+}
+
+/// Print one line per check, with its hint indented underneath.
+fn print_report(results: &[CheckResult]) {
+  for result in results {
+    let (label, color) = match result.status {
+      CheckStatus::Pass => ("PASS", "32"),
+      CheckStatus::Warn => ("WARN", "33"),
+      CheckStatus::Fail => ("FAIL", "31"),
+    };
+    let label = if colors_enabled() {
+      colorize(label, color)
+    } else {
+      label.to_string()
+    };
+    println!("[{}] {}: {}", label, result.name, result.detail);
+    if let Some(hint) = &result.hint {
+      println!("       {}", hint);
+    }
+  }
+}
+
+/// Run `vbl doctor`: the setup checks, then (unless skipped or the transport is
+/// misconfigured) the connection test. Returns the process exit code.
+pub async fn run_doctor(transport_type: TransportType, skip_connection: bool) -> i32 {
+  let mut results = run_checks(transport_type, &secret);
+
+  let transport_ok = results
+    .iter()
+    .all(|result| result.name != "transport" || result.status == CheckStatus::Pass);
+  if !skip_connection && transport_ok {
+    let connection = match Transport::new(transport_type) {
+      Ok(transport) => {
+        print_progress(&format!("Testing {} connection...", transport.name()));
+        match send_test_pattern(&transport).await {
+          Ok(()) => CheckResult::pass("connection", "Test pattern sent to Vestaboard"),
+          Err(e) => CheckResult::fail("connection", &e.to_user_message(), &diagnose(&e, transport.name())),
+        }
+      },
+      Err(e) => CheckResult::fail("connection", &e.to_user_message(), &e.to_user_message()),
+    };
+    results.push(connection);
+  }
+
+  print_report(&results);
+  let exit_code = doctor_exit_code(&results);
+  if exit_code == 0 {
+    print_success("No critical problems found");
+  } else {
+    print_error("Critical problems found; fix the FAIL items above");
+  }
+  exit_code
+}
@@ -0,0 +1,235 @@
+//! Display queue for `vbl show`.
+//!
+//! Shows started at nearly the same time (e.g. from scripts) would otherwise race to the
+//! board, and the second would replace the first right away. Instead, each show adds its
+//! rendered message to an on-disk FIFO queue. Whichever show holds the queue lock sends the
+//! queued messages in order, `QUEUE_SEND_GAP` apart; the others wait until theirs is sent.
+//!
+//! Every change to the queue file happens under a short-lived file lock, so shows adding
+//! messages and the show sending them never overwrite each other. The sending show records
+//! how each send went, and a waiting show reports that result as its own.
+
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::Utc;
+use nanoid::nanoid;
+
+use crate::api::Transport;
+use crate::api_broker::{handle_message, MessageDestination};
+use crate::cli_display::print_progress;
+use crate::config::Config;
+use crate::errors::VestaboardError;
+use crate::runner::lock::InstanceLock;
+use crate::runtime_state::{DisplayQueue, QueueOutcome, QueuedMessage};
+use crate::scheduler::{CUSTOM_ALPHABET, ID_LENGTH};
+
+/// Time between queued messages, so each stays up long enough to be read
+pub const QUEUE_SEND_GAP: Duration = Duration::from_secs(15);
+
+/// How often a waiting show checks whether its message has been sent
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often a show waiting for the queue file lock tries again
+const FILE_LOCK_RETRY: Duration = Duration::from_millis(10);
+
+/// A queue file lock older than this was left by a show that exited mid-edit
+const FILE_LOCK_STALE: Duration = Duration::from_secs(5);
+
+/// Send outcomes no show has read after this many minutes are dropped
+const OUTCOME_KEEP_MINUTES: i64 = 10;
+
+/// Exclusive lock on the queue file, held only while it is read and rewritten.
+///
+/// The lock file is created with `create_new`, so only one process can hold it at a time.
+struct QueueFileLock {
+  path: Option<PathBuf>,
+}
+
+impl QueueFileLock {
+  async fn acquire(queue_path: &Path) -> Self {
+    let path = queue_path.with_extension("json.lock");
+    if let Some(parent) = path.parent() {
+      let _ = fs::create_dir_all(parent);
+    }
+    loop {
+      match OpenOptions::new().write(true).create_new(true).open(&path) {
+        Ok(_) => return Self { path: Some(path) },
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+          let age = fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+          if age.is_some_and(|age| age > FILE_LOCK_STALE) {
+            log::warn!("Removing stale display queue lock {}", path.display());
+            let _ = fs::remove_file(&path);
+          } else {
+            tokio::time::sleep(FILE_LOCK_RETRY).await;
+          }
+        },
+        Err(e) => {
+          log::warn!("Cannot lock the display queue, editing it unlocked: {}", e);
+          return Self { path: None };
+        },
+      }
+    }
+  }
+}
+
+impl Drop for QueueFileLock {
+  fn drop(&mut self) {
+    if let Some(path) = &self.path {
+      if let Err(e) = fs::remove_file(path) {
+        log::warn!("Cannot remove display queue lock: {}", e);
+      }
+    }
+  }
+}
+
+/// Load the queue at `path`, change it with `edit`, and save it, holding the queue file
+/// lock throughout.
+async fn update_queue<T>(path: &Path, edit: impl FnOnce(&mut DisplayQueue) -> T) -> T {
+  let _lock = QueueFileLock::acquire(path).await;
+  let mut queue = DisplayQueue::load(path);
+  let result = edit(&mut queue);
+  queue.save(path);
+  result
+}
+
+/// Add a rendered message to the end of the queue at `path`, returning its ID.
+pub async fn enqueue(path: &Path, widget: &str, message: Vec<String>) -> String {
+  let id = nanoid!(ID_LENGTH, CUSTOM_ALPHABET);
+  let waiting = update_queue(path, |queue| {
+    queue.entries.push(QueuedMessage {
+      id: id.clone(),
+      widget: widget.to_string(),
+      message,
+      queued_at: Utc::now(),
+    });
+    queue.entries.len()
+  })
+  .await;
+  log::debug!("Queued {} message {} ({} waiting)", widget, id, waiting);
+  id
+}
+
+/// Take the oldest message off the queue at `path`.
+async fn dequeue(path: &Path) -> Option<QueuedMessage> {
+  update_queue(path, DisplayQueue::pop_front).await
+}
+
+/// Record how sending message `id` went, for the show waiting on it.
+async fn record_outcome(path: &Path, id: &str, result: &Result<(), VestaboardError>) {
+  update_queue(path, |queue| {
+    let now = Utc::now();
+    let cutoff = now - chrono::Duration::minutes(OUTCOME_KEEP_MINUTES);
+    queue.outcomes.retain(|outcome| outcome.finished_at > cutoff);
+    queue.outcomes.push(QueueOutcome {
+      id: id.to_string(),
+      error: result.as_ref().err().map(|e| e.to_user_message()),
+      finished_at: now,
+    });
+  })
+  .await;
+}
+
+/// The result of sending message `id`, once a show has sent it. The outcome is removed
+/// from the queue file.
+pub async fn take_outcome(path: &Path, id: &str) -> Option<Result<(), VestaboardError>> {
+  update_queue(path, |queue| queue.take_outcome(id))
+    .await
+    .map(|outcome| match outcome.error {
+      None => Ok(()),
+      Some(message) => Err(VestaboardError::other(&message)),
+    })
+}
+
+/// Send every queued message to the board in order, waiting `gap` (with `sleep`) between
+/// sends. Messages queued while draining are sent too.
+///
+/// A failed send is logged and the rest are still sent; each entry's ID is returned with
+/// the result of sending it, and the result is also recorded in the queue file for the
+/// show waiting on it.
+pub async fn drain_queue<S, SleepFut>(
+  path: &Path,
+  transport: &Transport,
+  config: &Config,
+  gap: Duration,
+  sleep: S,
+) -> Vec<(String, Result<(), VestaboardError>)>
+where
+  S: Fn(Duration) -> SleepFut,
+  SleepFut: Future<Output = ()>,
+{
+  let mut results = Vec::new();
+  while let Some(entry) = dequeue(path).await {
+    if !results.is_empty() {
+      log::debug!("Waiting {:?} before the next queued message", gap);
+      sleep(gap).await;
+    }
+    log::info!("Sending queued {} message {}", entry.widget, entry.id);
+    let result = handle_message(entry.message, &entry.widget, MessageDestination::Vestaboard, transport, config).await;
+    if let Err(e) = &result {
+      log::error!("Failed to send queued message {}: {}", entry.id, e);
+    }
+    record_outcome(path, &entry.id, &result).await;
+    results.push((entry.id, result));
+  }
+  results
+}
+
+/// Send a message to the board through the configured display queue, returning once it has
+/// been sent.
+///
+/// If no other show is sending, this one sends the queue (including its own message).
+/// Otherwise it waits until the sending show has sent its message, and returns that send's
+/// result.
+pub async fn show_queued(
+  widget: &str,
+  message: Vec<String>,
+  transport: &Transport,
+  config: &Config,
+) -> Result<(), VestaboardError> {
+  let queue_path = config.get_display_queue_path();
+  show_queued_at(&queue_path, &config.get_display_queue_lock_path(), widget, message, transport, config).await
+}
+
+/// `show_queued` with the queue file at `queue_path` and the sending lock at `lock_path`.
+pub async fn show_queued_at(
+  queue_path: &Path,
+  lock_path: &PathBuf,
+  widget: &str,
+  message: Vec<String>,
+  transport: &Transport,
+  config: &Config,
+) -> Result<(), VestaboardError> {
+  let id = enqueue(queue_path, widget, message).await;
+
+  let mut waiting = false;
+  loop {
+    // Another show took it off the queue and sent it
+    if let Some(result) = take_outcome(queue_path, &id).await {
+      return result;
+    }
+    match InstanceLock::acquire_at("display queue", lock_path) {
+      Ok(_lock) => {
+        drain_queue(queue_path, transport, config, QUEUE_SEND_GAP, tokio::time::sleep).await;
+        return take_outcome(queue_path, &id).await.unwrap_or_else(|| {
+          log::error!("Queued message {} left the queue without being sent", id);
+          Err(VestaboardError::other("The queued message was not sent"))
+        });
+      },
+      Err(e) => {
+        if !waiting {
+          log::info!("Display queue is busy ({}), waiting for message {}", e, id);
+          print_progress("Another message is being shown, waiting for its turn...");
+          waiting = true;
+        }
+        tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+      },
+    }
+  }
+}
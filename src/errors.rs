@@ -185,6 +185,32 @@ impl VestaboardError {
   }
 }
 
+/// Exit code for failures without a more specific class (widget, schedule, input, validation)
+pub const EXIT_GENERAL: i32 = 1;
+/// Exit code for configuration errors (config file, environment variables)
+pub const EXIT_CONFIG: i32 = 2;
+/// Exit code for network and Vestaboard API errors
+pub const EXIT_NETWORK: i32 = 3;
+/// Exit code for file read/write and file format errors
+pub const EXIT_IO: i32 = 4;
+/// Exit code when another runner holds the instance lock
+pub const EXIT_LOCK: i32 = 5;
+
+/// Process exit code for an error, so scripts can tell failure classes apart.
+pub fn exit_code_for(error: &VestaboardError) -> i32 {
+  match error {
+    VestaboardError::ConfigError { .. } => EXIT_CONFIG,
+    VestaboardError::ReqwestError { .. } | VestaboardError::ApiError { .. } => EXIT_NETWORK,
+    VestaboardError::IOError { .. } | VestaboardError::JsonError { .. } => EXIT_IO,
+    VestaboardError::LockError { .. } => EXIT_LOCK,
+    VestaboardError::WidgetError { .. }
+    | VestaboardError::ScheduleError { .. }
+    | VestaboardError::InputError { .. }
+    | VestaboardError::ValidationError { .. }
+    | VestaboardError::Other { .. } => EXIT_GENERAL,
+  }
+}
+
 // From implementations for automatic conversion
 impl From<std::io::Error> for VestaboardError {
   fn from(error: std::io::Error) -> Self {
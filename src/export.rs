@@ -0,0 +1,178 @@
+use chrono::Local;
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::errors::VestaboardError;
+use crate::playlist::{OnError, Playlist, PlaylistItem};
+use crate::scheduler::{load_schedule_readonly, Schedule};
+use crate::widgets::resolver::input_optional;
+
+/// Quote an argument for a POSIX shell, leaving plain words untouched
+pub fn shell_quote(arg: &str) -> String {
+  let plain = !arg.is_empty()
+    && arg
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | ',' | ':' | '@' | '%' | '+'));
+  if plain {
+    return arg.to_string();
+  }
+  format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Convert widget arguments from `add` into the stored input JSON.
+///
+/// Returns None when the widget is not addable from the command line or the arguments
+/// don't fit it (e.g. forex without exactly two currencies).
+pub fn args_to_input(widget: &str, args: &[String]) -> Option<Value> {
+  match widget {
+    "text" | "file" if !args.is_empty() => Some(json!(args.join(" "))),
+    "forex" if args.len() == 2 => Some(json!({ "from": args[0], "to": args[1] })),
+    "random" if !args.is_empty() => Some(json!({ "choices": args })),
+    _ if input_optional(widget) => Some(json!(null)),
+    _ => None,
+  }
+}
+
+/// Convert stored input JSON back into `add` arguments, the reverse of `args_to_input`.
+///
+/// Returns None for inputs that only exist in the JSON files (e.g. a weather mode or kv rows).
+pub fn input_to_args(widget: &str, input: &Value) -> Option<Vec<String>> {
+  let args = match input {
+    Value::Null => vec![],
+    Value::String(text) if !text.is_empty() => vec![text.clone()],
+    Value::Object(map) => match widget {
+      "forex" if map.len() == 2 => vec![
+        map.get("from")?.as_str()?.to_string(),
+        map.get("to")?.as_str()?.to_string(),
+      ],
+      "random" if map.len() == 1 => map
+        .get("choices")?
+        .as_array()?
+        .iter()
+        .map(|choice| choice.as_str().map(str::to_string))
+        .collect::<Option<Vec<_>>>()?,
+      _ => return None,
+    },
+    _ => return None,
+  };
+  // Only accept what `add` would store for these arguments
+  (args_to_input(widget, &args).as_ref() == Some(input)).then_some(args)
+}
+
+/// Join a command and its widget arguments, adding `--` so an argument like `-5` isn't read as a flag
+fn command_line(mut words: Vec<String>, args: &[String]) -> String {
+  if args.iter().any(|arg| arg.starts_with('-')) {
+    words.push("--".to_string());
+  }
+  words.extend(args.iter().map(|arg| shell_quote(arg)));
+  words.join(" ")
+}
+
+/// Build the `vbl schedule add` commands that recreate a schedule.
+///
+/// Times are written in local time, as `schedule add` expects. Tasks that can't be
+/// expressed as a command are kept as comments so nothing is dropped silently.
+pub fn schedule_commands(schedule: &Schedule) -> Vec<String> {
+  let mut lines = vec!["#!/bin/sh".to_string(), "# Recreate the vbl schedule".to_string()];
+  for task in &schedule.tasks {
+    let Some(args) = input_to_args(&task.widget, &task.input) else {
+      lines.push(format!("# skipped {}: {} input {} has no command form", task.id, task.widget, task.input));
+      continue;
+    };
+    let mut words = vec!["vbl".to_string(), "schedule".to_string(), "add".to_string()];
+    if let Some(recurrence) = &task.recurrence {
+      words.push("--repeat".to_string());
+      words.push(shell_quote(&recurrence.to_string()));
+    }
+    let local_time = task.time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string();
+    words.push(shell_quote(&local_time));
+    words.push(task.widget.clone());
+    lines.push(command_line(words, &args));
+  }
+  lines
+}
+
+/// Item settings that `vbl playlist add` has no flag for, as they appear in the playlist file
+fn file_only_settings(item: &PlaylistItem) -> Vec<String> {
+  let mut settings = Vec::new();
+  if item.on_error == OnError::Skip {
+    settings.push(r#""on_error": "skip""#.to_string());
+  }
+  if item.pinned_first {
+    settings.push(r#""pinned_first": true"#.to_string());
+  }
+  if !item.tags.is_empty() {
+    settings.push(format!(r#""tags": {}"#, json!(item.tags)));
+  }
+  settings
+}
+
+/// Build the `vbl playlist` commands that recreate a playlist in order, including its interval.
+///
+/// Settings the commands can't express (`on_error`, `pinned_first`, `tags`) follow their
+/// item's command as a comment, so they can be restored in the playlist file.
+pub fn playlist_commands(playlist: &Playlist) -> Vec<String> {
+  let mut lines = vec![
+    "#!/bin/sh".to_string(),
+    "# Recreate the vbl playlist (items are appended; run vbl playlist clear first to replace)".to_string(),
+    format!("vbl playlist interval {}", playlist.interval_seconds),
+  ];
+  for item in &playlist.items {
+    let Some(args) = input_to_args(&item.widget, &item.input) else {
+      lines.push(format!("# skipped {}: {} input {} has no command form", item.id, item.widget, item.input));
+      continue;
+    };
+    let words = vec![
+      "vbl".to_string(),
+      "playlist".to_string(),
+      "add".to_string(),
+      item.widget.clone(),
+    ];
+    lines.push(command_line(words, &args));
+    let settings = file_only_settings(item);
+    if !settings.is_empty() {
+      lines.push(format!(
+        "# {} also set {}, which playlist add can't; edit the playlist file",
+        item.id,
+        settings.join(", ")
+      ));
+    }
+  }
+  lines
+}
+
+/// Print the schedule as JSON, or as `vbl schedule add` commands with `as_commands`
+pub fn export_schedule(as_commands: bool) -> Result<(), VestaboardError> {
+  let config = Config::load_silent()?;
+  let schedule = load_schedule_readonly(&config.get_schedule_file_path())?;
+  log::info!("Exporting {} scheduled tasks", schedule.tasks.len());
+
+  if as_commands {
+    schedule_commands(&schedule)
+      .iter()
+      .for_each(|line| println!("{}", line));
+  } else {
+    let json =
+      serde_json::to_string_pretty(&schedule).map_err(|e| VestaboardError::json_error(e, "serializing schedule"))?;
+    println!("{}", json);
+  }
+  Ok(())
+}
+
+/// Print the playlist as JSON, or as `vbl playlist` commands with `as_commands`
+pub fn export_playlist(as_commands: bool) -> Result<(), VestaboardError> {
+  let config = Config::load_silent()?;
+  let playlist = Playlist::load_silent(&config.get_playlist_file_path())?;
+  log::info!("Exporting {} playlist items", playlist.len());
+
+  if as_commands {
+    playlist_commands(&playlist)
+      .iter()
+      .for_each(|line| println!("{}", line));
+  } else {
+    let json =
+      serde_json::to_string_pretty(&playlist).map_err(|e| VestaboardError::json_error(e, "serializing playlist"))?;
+    println!("{}", json);
+  }
+  Ok(())
+}
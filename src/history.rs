@@ -0,0 +1,164 @@
+//! History of messages sent to the Vestaboard.
+//!
+//! Every message that `handle_message` sends to a board is appended to a rolling
+//! history file, so recent displays can be audited (`vbl history`) and re-rendered
+//! to the console (`vbl history show <n>`) or re-sent to the board (`vbl repeat`).
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+use crate::api::Transport;
+use crate::api_broker::{display_message, handle_message, MessageDestination};
+use crate::cli_display::{print_error, print_message, print_success};
+use crate::config::Config;
+use crate::errors::VestaboardError;
+use crate::runtime_state::{HistoryEntry, MessageHistory};
+
+/// Append a sent message to the history file at `path`, keeping at most `max_entries`.
+pub fn record_to(path: &Path, widget: &str, message: &[String], max_entries: usize) {
+  let mut history = MessageHistory::load(path);
+  history.push(
+    HistoryEntry {
+      time: Utc::now(),
+      widget: widget.to_string(),
+      message: message.to_vec(),
+    },
+    max_entries,
+  );
+  history.save(path);
+  log::debug!("Recorded {} message in history", widget);
+}
+
+/// Summary line for an entry: its number, local time, widget, and first non-blank line.
+pub fn format_history_line(n: usize, entry: &HistoryEntry) -> String {
+  let local_time = entry.time.with_timezone(&chrono::Local);
+  let preview = entry
+    .message
+    .iter()
+    .map(|line| line.trim())
+    .find(|line| !line.is_empty())
+    .unwrap_or("(blank)");
+  format!("{:>3}  {}  {:<12} {}", n, local_time.format("%Y.%m.%d %I:%M %p"), entry.widget, preview)
+}
+
+/// List recent history entries, most recent first.
+pub fn list_history() {
+  let config = Config::load_silent().unwrap_or_default();
+  let history = MessageHistory::load(&config.get_history_file_path());
+
+  if history.entries.is_empty() {
+    println!("No messages sent yet.");
+    return;
+  }
+
+  for (i, entry) in history.entries.iter().rev().enumerate() {
+    println!("{}", format_history_line(i + 1, entry));
+  }
+}
+
+/// Re-render the `n`th most recent history entry to the console.
+pub fn show_history_entry(n: usize) -> Result<(), VestaboardError> {
+  let config = Config::load_silent().unwrap_or_default();
+  let history = MessageHistory::load(&config.get_history_file_path());
+
+  match history.recent(n) {
+    Some(entry) => {
+      print_message(entry.message.clone(), &format_history_line(n, entry));
+      Ok(())
+    },
+    None => {
+      let error = VestaboardError::validation_error(&format!(
+        "No history entry {} (history has {} entries)",
+        n,
+        history.entries.len()
+      ));
+      print_error(&error.to_user_message());
+      Err(error)
+    },
+  }
+}
+
+/// Note in the history file at `path` that the board was blanked for quiet hours. The blank
+/// board isn't an entry, so `vbl repeat` and `vbl undo` go back to the last real message.
+pub fn record_quiet_hours_clear(path: &Path) {
+  let mut history = MessageHistory::load(path);
+  history.quiet_hours_cleared = Some(Utc::now());
+  history.save(path);
+}
+
+/// Whether the board was blanked for quiet hours and nothing has been recorded since
+pub fn quiet_hours_cleared(path: &Path) -> bool {
+  MessageHistory::load(path).quiet_hours_cleared.is_some()
+}
+
+/// The most recent entry in the history file at `path`: what the board is showing now.
+pub fn latest_entry(path: &Path) -> Option<HistoryEntry> {
+  MessageHistory::load(path).recent(1).cloned()
+}
+
+/// Wait `delay`, then put `previous` back on the board, or clear the board when there is
+/// nothing to go back to (`vbl show --for`). The revert goes through `handle_message`, so
+/// quiet hours and night mode apply to it like any other message.
+///
+/// `cancelled` is polled while waiting (e.g. for Ctrl+C); once it returns true the board
+/// is reverted right away instead of waiting out the delay.
+pub async fn revert_after(
+  delay: Duration,
+  previous: Option<HistoryEntry>,
+  transport: &Transport,
+  config: &Config,
+  cancelled: impl Fn() -> bool,
+) -> Result<(), VestaboardError> {
+  let end = Instant::now()
+    .checked_add(delay)
+    .ok_or_else(|| VestaboardError::validation_error("Revert delay is too long"))?;
+  while Instant::now() < end && !cancelled() {
+    tokio::time::sleep(
+      end
+        .saturating_duration_since(Instant::now())
+        .min(Duration::from_millis(100)),
+    )
+    .await;
+  }
+
+  match previous {
+    Some(entry) => {
+      log::info!("Reverting board to previous {} message", entry.widget);
+      handle_message(entry.message, &entry.widget, MessageDestination::Vestaboard, transport, config).await
+    },
+    None => {
+      log::info!("No previous message, clearing board");
+      handle_message(vec![String::new()], "clear", MessageDestination::Vestaboard, transport, config).await
+    },
+  }
+}
+
+/// Re-send the most recent message in the history file at `path` to the board,
+/// returning the widget that produced it.
+pub async fn repeat_from(path: &Path, transport: &Transport) -> Result<String, VestaboardError> {
+  let history = MessageHistory::load(path);
+  let entry = history
+    .recent(1)
+    .ok_or_else(|| VestaboardError::validation_error("No messages sent yet, so there is nothing to repeat."))?;
+
+  log::info!("Repeating last {} message from {}", entry.widget, entry.time);
+  display_message(entry.message.clone(), transport).await?;
+  Ok(entry.widget.clone())
+}
+
+/// Re-send the last message in the configured history file to the board.
+pub async fn repeat_last(transport: &Transport) -> Result<(), VestaboardError> {
+  let config = Config::load_silent().unwrap_or_default();
+  match repeat_from(&config.get_history_file_path(), transport).await {
+    Ok(widget) => {
+      print_success(&format!("Re-sent last {} message", widget));
+      Ok(())
+    },
+    Err(e) => {
+      print_error(&e.to_user_message());
+      Err(e)
+    },
+  }
+}
@@ -0,0 +1,62 @@
+//! External commands run after a message is sent to the board.
+//!
+//! `on_success` and `on_failure` in the config name a command that is started with the widget
+//! name and `success` or `failure` appended as arguments. Hooks run in the background: a hook
+//! that can't be started is logged, and nothing waits for it to finish.
+
+use std::fmt;
+
+use tokio::process::{Child, Command};
+
+use crate::config::Config;
+use crate::errors::VestaboardError;
+
+/// Outcome of a send, passed to hooks as their last argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+  Success,
+  Failure,
+}
+
+impl fmt::Display for HookEvent {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HookEvent::Success => write!(f, "success"),
+      HookEvent::Failure => write!(f, "failure"),
+    }
+  }
+}
+
+/// Start `command` with `widget` and `event` appended to its arguments.
+///
+/// The command is split on whitespace (no shell quoting), so `notify.sh --quiet` runs
+/// `notify.sh` with `--quiet weather success`.
+pub fn spawn_hook(command: &str, widget: &str, event: HookEvent) -> Result<Child, VestaboardError> {
+  let mut words = command.split_whitespace();
+  let program = words
+    .next()
+    .ok_or_else(|| VestaboardError::config_error("hook", "command is empty"))?;
+  Command::new(program)
+    .args(words)
+    .arg(widget)
+    .arg(event.to_string())
+    .spawn()
+    .map_err(|e| VestaboardError::io_error(e, &format!("starting hook {}", program)))
+}
+
+/// Run the configured hook for `event`, if there is one, without waiting for it.
+///
+/// A hook that fails to start only logs a warning so it never interrupts sending.
+pub fn run_hook(config: &Config, widget: &str, event: HookEvent) {
+  let command = match event {
+    HookEvent::Success => config.get_on_success(),
+    HookEvent::Failure => config.get_on_failure(),
+  };
+  let Some(command) = command else {
+    return;
+  };
+  match spawn_hook(&command, widget, event) {
+    Ok(_) => log::debug!("Started {} hook for {}: {}", event, widget, command),
+    Err(e) => log::warn!("Failed to run {} hook: {}", event, e),
+  }
+}
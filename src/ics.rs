@@ -0,0 +1,149 @@
+//! Minimal iCalendar (.ics) reader for `vbl schedule import`.
+//!
+//! Only each VEVENT's UID, SUMMARY, and DTSTART are read; every other property and
+//! component is ignored. Times with a TZID are read as local time, and recurring
+//! events (RRULE) import only their first occurrence.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde_json::Value;
+
+use crate::errors::VestaboardError;
+use crate::scheduler::ScheduledTask;
+
+/// A calendar event: what it's called and when it starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IcsEvent {
+  /// The event's unique ID, when the calendar gives one
+  pub uid: Option<String>,
+  pub summary: String,
+  pub start: DateTime<Utc>,
+}
+
+/// Join folded lines (continuations start with a space or tab) into logical lines.
+fn unfold(content: &str) -> Vec<String> {
+  let mut lines: Vec<String> = Vec::new();
+  for line in content.lines() {
+    match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+      (Some(rest), Some(last)) => last.push_str(rest),
+      _ => lines.push(line.to_string()),
+    }
+  }
+  lines
+}
+
+/// Undo iCalendar text escaping (`\,` `\;` `\n` `\\`).
+fn unescape(value: &str) -> String {
+  let mut text = String::with_capacity(value.len());
+  let mut chars = value.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      text.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('n' | 'N') => text.push(' '),
+      Some(other) => text.push(other),
+      None => {},
+    }
+  }
+  text
+}
+
+/// Convert a local date and time to UTC, taking the earlier time when it's ambiguous.
+fn local_to_utc(naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+  Local
+    .from_local_datetime(&naive)
+    .earliest()
+    .map(|time| time.with_timezone(&Utc))
+}
+
+/// Parse a DTSTART value. Dates (all-day events) start at `all_day_time`, times ending
+/// in `Z` are UTC, and all other times are local.
+fn parse_start(params: &str, value: &str, all_day_time: NaiveTime) -> Option<DateTime<Utc>> {
+  let value = value.trim();
+  let is_date = params.split(';').any(|param| match param.split_once('=') {
+    Some((key, kind)) => key.trim().eq_ignore_ascii_case("VALUE") && kind.trim().eq_ignore_ascii_case("DATE"),
+    None => false,
+  });
+  if is_date || value.len() == 8 {
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+    return local_to_utc(date.and_time(all_day_time));
+  }
+  if let Some(utc) = value.strip_suffix('Z') {
+    let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+    return Some(Utc.from_utc_datetime(&naive));
+  }
+  local_to_utc(NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?)
+}
+
+/// Read the events from an iCalendar file's contents.
+///
+/// Events without a summary or with a start time that can't be read are skipped.
+/// Content that isn't a calendar at all is an error.
+pub fn parse_ics(content: &str, all_day_time: NaiveTime) -> Result<Vec<IcsEvent>, VestaboardError> {
+  let lines = unfold(content);
+  if !lines
+    .iter()
+    .any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+  {
+    return Err(VestaboardError::validation_error("Not an iCalendar file (no BEGIN:VCALENDAR)."));
+  }
+
+  let mut events = Vec::new();
+  // Components we're inside; only properties directly in a VEVENT are read
+  let mut components: Vec<String> = Vec::new();
+  let mut uid: Option<String> = None;
+  let mut summary: Option<String> = None;
+  let mut start: Option<DateTime<Utc>> = None;
+
+  for line in &lines {
+    let Some((key, value)) = line.split_once(':') else {
+      continue;
+    };
+    let (name, params) = key.split_once(';').unwrap_or((key, ""));
+    let name = name.trim().to_uppercase();
+
+    match name.as_str() {
+      "BEGIN" => {
+        let component = value.trim().to_uppercase();
+        if component == "VEVENT" {
+          uid = None;
+          summary = None;
+          start = None;
+        }
+        components.push(component);
+      },
+      "END" => {
+        let ended = components.pop();
+        if ended.as_deref() == Some("VEVENT") {
+          match (summary.take(), start.take()) {
+            (Some(summary), Some(start)) => events.push(IcsEvent {
+              uid: uid.take(),
+              summary,
+              start,
+            }),
+            _ => log::warn!("Skipping calendar event without a summary or readable start time"),
+          }
+        }
+      },
+      _ if components.last().map(String::as_str) != Some("VEVENT") => {},
+      "UID" => uid = Some(value.trim().to_string()).filter(|s| !s.is_empty()),
+      "SUMMARY" => summary = Some(unescape(value).trim().to_string()).filter(|s| !s.is_empty()),
+      "DTSTART" => start = parse_start(params, value, all_day_time),
+      _ => {},
+    }
+  }
+
+  Ok(events)
+}
+
+/// Turn events into text tasks that show each event's summary at its start time.
+pub fn events_to_tasks(events: &[IcsEvent]) -> Vec<ScheduledTask> {
+  events
+    .iter()
+    .map(|event| ScheduledTask {
+      uid: event.uid.clone(),
+      ..ScheduledTask::new(event.start, "text".to_string(), Value::String(event.summary.to_lowercase()))
+    })
+    .collect()
+}
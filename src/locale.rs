@@ -0,0 +1,102 @@
+//! Day and month names for the `locale` setting.
+//!
+//! The board has no accented letters, so names are spelled without them (e.g. `miercoles`,
+//! `fevrier`, `maerz`). Unknown locales fall back to English.
+
+use chrono::{DateTime, Datelike, TimeZone, Weekday};
+
+/// Language used for day and month names on the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+  #[default]
+  English,
+  Spanish,
+  French,
+  German,
+  Portuguese,
+}
+
+/// Locale codes accepted by `Locale::parse`, in the order they're listed in messages
+pub const LOCALE_NAMES: &[&str] = &["en", "es", "fr", "de", "pt"];
+
+impl Locale {
+  /// Parse a locale code such as `es`, `es-MX`, or `fr_FR` by its language (case-insensitive).
+  pub fn parse(code: &str) -> Option<Self> {
+    let language = code.trim().split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    match language.as_str() {
+      "en" => Some(Locale::English),
+      "es" => Some(Locale::Spanish),
+      "fr" => Some(Locale::French),
+      "de" => Some(Locale::German),
+      "pt" => Some(Locale::Portuguese),
+      _ => None,
+    }
+  }
+
+  /// Like `parse`, but English for a code that isn't supported
+  pub fn parse_or_english(code: &str) -> Self {
+    Self::parse(code).unwrap_or_else(|| {
+      log::warn!("Unknown locale '{}', using English. Supported: {}", code, LOCALE_NAMES.join(", "));
+      Locale::English
+    })
+  }
+
+  /// Full weekday name, lowercase (e.g. `monday`, `lunes`)
+  pub fn weekday_name(self, weekday: Weekday) -> &'static str {
+    let names: [&str; 7] = match self {
+      Locale::English => [
+        "monday",
+        "tuesday",
+        "wednesday",
+        "thursday",
+        "friday",
+        "saturday",
+        "sunday",
+      ],
+      Locale::Spanish => ["lunes", "martes", "miercoles", "jueves", "viernes", "sabado", "domingo"],
+      Locale::French => ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+      Locale::German => [
+        "montag",
+        "dienstag",
+        "mittwoch",
+        "donnerstag",
+        "freitag",
+        "samstag",
+        "sonntag",
+      ],
+      Locale::Portuguese => ["segunda", "terca", "quarta", "quinta", "sexta", "sabado", "domingo"],
+    };
+    names[weekday.num_days_from_monday() as usize]
+  }
+
+  /// Short month name, lowercase (e.g. `may`, `dic`); `month` is 1-12
+  pub fn month_abbrev(self, month: u32) -> &'static str {
+    let names: [&str; 12] = match self {
+      Locale::English => [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+      ],
+      Locale::Spanish => [
+        "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+      ],
+      Locale::French => [
+        "janv", "fevr", "mars", "avr", "mai", "juin", "juil", "aout", "sept", "oct", "nov", "dec",
+      ],
+      Locale::German => [
+        "jan", "feb", "maerz", "apr", "mai", "juni", "juli", "aug", "sep", "okt", "nov", "dez",
+      ],
+      Locale::Portuguese => [
+        "jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez",
+      ],
+    };
+    names[(month.clamp(1, 12) - 1) as usize]
+  }
+
+  /// Month and day in the locale's usual order (e.g. `may 5`, `5 mai`)
+  pub fn format_date<Tz: TimeZone>(self, date: &DateTime<Tz>) -> String {
+    let month = self.month_abbrev(date.month());
+    match self {
+      Locale::English => format!("{} {}", month, date.day()),
+      _ => format!("{} {}", date.day(), month),
+    }
+  }
+}
@@ -1,31 +1,153 @@
 use crate::config::Config;
 use crate::errors::VestaboardError;
 use env_logger::{Builder, Target};
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-pub fn init_logging() -> Result<(), VestaboardError> {
-  let config = Config::load_silent()?;
+/// Path of the `n`th rotated log file (1 is the most recent), with `.gz` if compressed
+pub fn rotated_log_path(path: &Path, n: usize, compressed: bool) -> PathBuf {
+  let mut name = path.as_os_str().to_owned();
+  name.push(format!(".{}", n));
+  if compressed {
+    name.push(".gz");
+  }
+  PathBuf::from(name)
+}
 
-  // Ensure log directory exists
-  let log_file_path = config.get_log_file_path();
-  if let Some(parent) = log_file_path.parent() {
-    std::fs::create_dir_all(parent).map_err(|e| VestaboardError::io_error(e, "creating log directory"))?;
+/// Remove a file, treating a missing file as already removed
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+  match fs::remove_file(path) {
+    Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+    _ => Ok(()),
   }
+}
 
-  // Create file logger
-  let log_file = OpenOptions::new()
-    .create(true)
-    .append(true)
-    .open(&log_file_path)
-    .map_err(|e| VestaboardError::io_error(e, "opening log file"))?;
+/// Gzip `path` to `path.gz` and remove the original
+#[cfg(feature = "log-compression")]
+fn compress_file(path: &Path) -> io::Result<()> {
+  use flate2::{write::GzEncoder, Compression};
 
-  let mut builder = Builder::new();
+  let mut gz_name = path.as_os_str().to_owned();
+  gz_name.push(".gz");
+  let mut encoder = GzEncoder::new(fs::File::create(PathBuf::from(gz_name))?, Compression::default());
+  io::copy(&mut fs::File::open(path)?, &mut encoder)?;
+  encoder.finish()?;
+  fs::remove_file(path)
+}
+
+/// Without the `log-compression` feature, rotated logs are kept uncompressed. This runs
+/// inside the log writer, so it mustn't log; `init_logging` warns about it once instead.
+#[cfg(not(feature = "log-compression"))]
+fn compress_file(_path: &Path) -> io::Result<()> {
+  Ok(())
+}
+
+/// Rotate the log file: `log` becomes `log.1`, `log.1` becomes `log.2`, and so on,
+/// keeping at most `max_files` rotated files (the oldest are deleted). With `compress`,
+/// the newly rotated file is gzipped to `log.1.gz`.
+pub fn rotate_logs(path: &Path, max_files: usize, compress: bool) -> io::Result<()> {
+  // Drop the file that would be pushed past max_files, and any left over from a larger max_files
+  let first_dropped = max_files.max(1);
+  for n in first_dropped.. {
+    let (plain, compressed) = (rotated_log_path(path, n, false), rotated_log_path(path, n, true));
+    if n > first_dropped && !plain.exists() && !compressed.exists() {
+      break;
+    }
+    remove_if_exists(&plain)?;
+    remove_if_exists(&compressed)?;
+  }
+
+  if max_files == 0 {
+    return remove_if_exists(path);
+  }
+
+  for n in (1..max_files).rev() {
+    for compressed in [false, true] {
+      let from = rotated_log_path(path, n, compressed);
+      if from.exists() {
+        fs::rename(&from, rotated_log_path(path, n + 1, compressed))?;
+      }
+    }
+  }
+
+  let newest = rotated_log_path(path, 1, false);
+  fs::rename(path, &newest)?;
+  if compress {
+    compress_file(&newest)?;
+  }
+  Ok(())
+}
+
+/// Rotate the log file if it has grown past `max_bytes`.
+pub fn rotate_if_needed(path: &Path, max_bytes: u64, max_files: usize, compress: bool) -> io::Result<bool> {
+  match fs::metadata(path) {
+    Ok(metadata) if metadata.len() >= max_bytes => rotate_logs(path, max_files, compress).map(|_| true),
+    _ => Ok(false),
+  }
+}
+
+/// Log file that rotates itself before a write once it has grown past `max_bytes`, so a
+/// long-running daemon doesn't keep appending to one file.
+pub struct RotatingLogFile {
+  path: PathBuf,
+  file: fs::File,
+  len: u64,
+  max_bytes: u64,
+  max_files: usize,
+  compress: bool,
+}
+
+impl RotatingLogFile {
+  /// Open `path` for appending, creating it if needed
+  pub fn open(path: &Path, max_bytes: u64, max_files: usize, compress: bool) -> io::Result<Self> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let len = file.metadata()?.len();
+    Ok(Self {
+      path: path.to_path_buf(),
+      file,
+      len,
+      max_bytes,
+      max_files,
+      compress,
+    })
+  }
+
+  /// Rotate the current file away and start a new, empty one
+  fn rotate(&mut self) -> io::Result<()> {
+    self.file.flush()?;
+    rotate_logs(&self.path, self.max_files, self.compress)?;
+    self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+    self.len = 0;
+    Ok(())
+  }
+}
+
+impl Write for RotatingLogFile {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    if self.len >= self.max_bytes {
+      // Losing rotation shouldn't lose the log line too; try again after another max_bytes
+      if let Err(e) = self.rotate() {
+        eprintln!("Warning: could not rotate log file: {}", e);
+        self.len = 0;
+      }
+    }
+    let written = self.file.write(buf)?;
+    self.len += written as u64;
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.file.flush()
+  }
+}
 
-  // Configure file logging
+/// Logger that writes formatted records to `log_file` at `level` and above
+pub fn file_logger(log_file: RotatingLogFile, level: log::LevelFilter) -> Builder {
+  let mut builder = Builder::new();
   builder
     .target(Target::Pipe(Box::new(log_file)))
-    .filter_level(config.get_log_level())
+    .filter_level(level)
     .format(|buf, record| {
       writeln!(
         buf,
@@ -37,9 +159,36 @@ pub fn init_logging() -> Result<(), VestaboardError> {
         record.args()
       )
     });
+  builder
+}
+
+pub fn init_logging() -> Result<(), VestaboardError> {
+  let config = Config::load_silent()?;
+
+  // Ensure log directory exists
+  let log_file_path = config.get_log_file_path();
+  if let Some(parent) = log_file_path.parent() {
+    std::fs::create_dir_all(parent).map_err(|e| VestaboardError::io_error(e, "creating log directory"))?;
+  }
+
+  // Start a fresh file once the current one is too big (best-effort; the logger isn't up yet)
+  if let Err(e) =
+    rotate_if_needed(&log_file_path, config.get_log_max_bytes(), config.get_log_max_files(), config.get_log_compress())
+  {
+    eprintln!("Warning: could not rotate log file: {}", e);
+  }
+
+  // Create file logger, which keeps rotating while a daemon runs
+  let log_file = RotatingLogFile::open(
+    &log_file_path,
+    config.get_log_max_bytes(),
+    config.get_log_max_files(),
+    config.get_log_compress(),
+  )
+  .map_err(|e| VestaboardError::io_error(e, "opening log file"))?;
 
   // Initialize the logger
-  builder
+  file_logger(log_file, config.get_log_level())
     .try_init()
     .map_err(|e| VestaboardError::other(&format!("Failed to initialize logger: {}", e)))?;
 
@@ -47,6 +196,9 @@ pub fn init_logging() -> Result<(), VestaboardError> {
   setup_console_logging(&config)?;
 
   log::info!("Logging initialized - file: {}, level: {}", log_file_path.display(), config.log_level);
+  if config.get_log_compress() && !cfg!(feature = "log-compression") {
+    log::warn!("log_compress is set, but vbl was built without the log-compression feature");
+  }
 
   Ok(())
 }
@@ -4,59 +4,216 @@ mod cli_display;
 mod cli_setup;
 mod config;
 mod datetime;
+mod diagnostics;
+mod display_queue;
 mod errors;
+mod export;
+mod history;
+mod hooks;
+mod ics;
+mod locale;
 mod logging;
+mod night_mode;
 mod playlist;
 mod process_control;
+mod quiet_hours;
+mod recovery;
+mod recurrence;
 mod runner;
 mod runtime_state;
 mod scheduler;
+mod secrets;
 mod widgets;
 
 use api::{Transport, TransportType};
 use api_broker::{handle_message, MessageDestination};
 use config::Config;
 use cli_display::{init_output_control, print_error, print_progress, print_success};
-use cli_setup::{Cli, Command, PlaylistArgs, ScheduleArgs, WidgetCommand};
-use datetime::datetime_to_utc;
-use errors::VestaboardError;
+use cli_setup::{Cli, Command, HistoryArgs, PlaylistArgs, ScheduleArgs, ShowArgs, WidgetArgs, WidgetCommand};
+use datetime::{datetime_to_utc, parse_duration, range_bound_to_utc};
+use errors::{exit_code_for, VestaboardError};
+use process_control::ProcessController;
+use runner::lock::UnlockOutcome;
 use scheduler::{
-  add_task_to_schedule, clear_schedule, list_schedule, preview_schedule, remove_task_from_schedule, run_schedule,
+  add_task_to, add_task_to_schedule, clear_schedule, clear_schedule_before, dedupe_schedule, import_ics_to_schedule,
+  list_schedule, preview_schedule, remove_task_from_schedule, reschedule_task, run_schedule, show_next_task,
 };
+use std::path::Path;
 use std::process;
-use widgets::resolver::execute_widget;
-use widgets::widget_utils::error_to_display_message;
+use widgets::resolver::{
+  error_display, execute_widget, preview_all_widgets, resolve_inline_widget, resolve_input_file, widget_input_schema,
+};
 
 use clap::Parser;
 use serde_json::json;
 
-async fn process_widget_command(
-  widget_command: &WidgetCommand,
-  dry_run: bool,
-  transport: &Transport,
-) -> Result<(), VestaboardError> {
+/// Widget name and JSON input for a `vbl show` widget subcommand
+fn widget_command_input(widget_command: &WidgetCommand) -> (String, serde_json::Value) {
   let (widget_name, input_value) = match widget_command {
     WidgetCommand::Text(args) => ("text", json!(&args.message)),
     WidgetCommand::File(args) => ("file", json!(args.name.to_string_lossy())),
     WidgetCommand::Weather => ("weather", json!(null)),
+    WidgetCommand::AirQuality => ("airquality", json!(null)),
+    WidgetCommand::Forex(args) => ("forex", json!({ "from": args.from, "to": args.to })),
     WidgetCommand::Jokes => ("jokes", json!(null)),
     WidgetCommand::SATWord => ("sat-word", json!(null)),
+    WidgetCommand::OnThisDay => ("onthisday", json!(null)),
     WidgetCommand::Clear => ("clear", json!(null)),
+    WidgetCommand::ColorTest(args) => ("colortest", json!(args.color)),
+    WidgetCommand::Random(args) => ("random", json!({ "choices": args.choices })),
+  };
+  (widget_name.to_string(), input_value)
+}
+
+/// Widget name and input for `vbl show`: either a widget subcommand or `--widget`/`--input`
+fn show_widget_input(show_args: &ShowArgs) -> Result<(String, serde_json::Value), VestaboardError> {
+  match (&show_args.widget_command, &show_args.widget) {
+    (Some(widget_command), None) => Ok(widget_command_input(widget_command)),
+    (None, Some(name)) => match &show_args.input_file {
+      Some(path) => resolve_input_file(name, path),
+      None => resolve_inline_widget(name, show_args.input.as_deref()),
+    },
+    (Some(_), Some(_)) => {
+      Err(VestaboardError::validation_error("Use either a widget subcommand or --widget, not both."))
+    },
+    (None, None) => Err(VestaboardError::validation_error(
+      "Choose a widget: a subcommand such as 'vbl show weather', or --widget NAME.",
+    )),
+  }
+}
+
+/// Widget name and input from `--input-file` for `schedule add` and `playlist add`, checked
+/// against the widget's schema and rendered to the console. Exits on an invalid file.
+async fn validated_input_file(
+  widget: &str,
+  path: &Path,
+  transport: &Transport,
+  config: &Config,
+) -> (String, serde_json::Value) {
+  let (widget, input) = match resolve_input_file(widget, path) {
+    Ok(resolved) => resolved,
+    Err(e) => {
+      log::error!("Invalid input file {}: {}", path.display(), e);
+      print_error(&e.to_user_message());
+      process::exit(exit_code_for(&e));
+    },
+  };
+  print_progress("Validating...");
+  if let Err(e) = process_widget_command(&widget, &input, MessageDestination::Console, transport, config).await {
+    log::error!("Widget validation failed: {}", e);
+    print_error(&e.to_user_message());
+    process::exit(exit_code_for(&e));
+  }
+  (widget, input)
+}
+
+/// Widget name and input from the widget arguments of `schedule add` and `playlist add`,
+/// checked by rendering the widget to the console. Exits on missing or invalid arguments.
+async fn validated_widget_args(
+  widget: &str,
+  input: &[String],
+  transport: &Transport,
+  config: &Config,
+) -> (String, serde_json::Value) {
+  let widget_lower = widget.to_lowercase();
+  // Convert the widget args to a WidgetCommand for validation
+  let widget_command = match widget_lower.as_str() {
+    "weather" => WidgetCommand::Weather,
+    "airquality" => WidgetCommand::AirQuality,
+    "sat-word" => WidgetCommand::SATWord,
+    "onthisday" => WidgetCommand::OnThisDay,
+    "jokes" => WidgetCommand::Jokes,
+    "clear" => WidgetCommand::Clear,
+    "text" => {
+      if !input.is_empty() {
+        WidgetCommand::Text(cli_setup::TextArgs {
+          message: input.join(" "),
+        })
+      } else {
+        print_error("Input is required for text widgets.");
+        process::exit(1);
+      }
+    },
+    "file" => {
+      if !input.is_empty() {
+        WidgetCommand::File(cli_setup::FileArgs {
+          name: std::path::PathBuf::from(input.join(" ")),
+        })
+      } else {
+        print_error("Input is required for file widgets.");
+        process::exit(1);
+      }
+    },
+    "forex" => {
+      if input.len() == 2 {
+        WidgetCommand::Forex(cli_setup::ForexArgs {
+          from: input[0].clone(),
+          to: input[1].clone(),
+        })
+      } else {
+        print_error("From and to currencies are required for forex widgets (e.g. usd eur).");
+        process::exit(1);
+      }
+    },
+    "random" => {
+      if !input.is_empty() {
+        WidgetCommand::Random(cli_setup::RandomArgs {
+          choices: input.to_vec(),
+        })
+      } else {
+        print_error("Choices are required for random widgets.");
+        process::exit(1);
+      }
+    },
+    _ => {
+      print_error(&format!(
+        "Unsupported widget type: {}. Supported: weather, airquality, forex, text, sat-word, onthisday, jokes, clear, file, random",
+        widget
+      ));
+      process::exit(1);
+    },
   };
 
+  // Validate the widget can produce a valid message (dry-run mode - don't send to Vestaboard)
+  print_progress("Validating...");
+  let (widget_name, input_value) = widget_command_input(&widget_command);
+  if let Err(e) =
+    process_widget_command(&widget_name, &input_value, MessageDestination::Console, transport, config).await
+  {
+    log::error!("Widget validation failed: {}", e);
+    print_error(&e.to_user_message());
+    process::exit(exit_code_for(&e));
+  }
+  log::debug!("Widget validation successful");
+
+  // Convert back to the format stored in the schedule and playlist
+  let Some(input_json) = export::args_to_input(&widget_lower, input) else {
+    log::error!("Unsupported widget type: {}", widget_lower);
+    print_error(&format!("Unsupported widget type: {}", widget_lower));
+    process::exit(1);
+  };
+  (widget_lower, input_json)
+}
+
+async fn process_widget_command(
+  widget_name: &str,
+  input_value: &serde_json::Value,
+  destination: MessageDestination,
+  transport: &Transport,
+  config: &Config,
+) -> Result<(), VestaboardError> {
   // In dry-run mode, handle errors by converting them to display messages
-  let message = match execute_widget(widget_name, &input_value).await {
+  let message = match execute_widget(widget_name, input_value, config).await {
     Ok(message) => message,
-    Err(e) => error_to_display_message(&e),
+    Err(e) => error_display(&e, config),
   };
 
-  let destination = if dry_run {
-    MessageDestination::Console
-  } else {
-    MessageDestination::Vestaboard
+  // Ad-hoc shows to the board take turns, so one sent right after another doesn't replace it at once
+  let result = match destination {
+    MessageDestination::Vestaboard => display_queue::show_queued(widget_name, message, transport, config).await,
+    destination => handle_message(message, widget_name, destination, transport, config).await,
   };
-
-  match handle_message(message.clone(), destination, transport).await {
+  match result {
     Ok(_) => Ok(()),
     Err(e) => {
       log::error!("Failed to handle message: {}", e);
@@ -66,9 +223,182 @@ async fn process_widget_command(
   }
 }
 
+/// Add a task to the schedule, then show its widget right away (`vbl schedule add --show-now`).
+/// The task is saved first so a failed display doesn't lose it.
+async fn schedule_and_show(
+  time: chrono::DateTime<chrono::Utc>,
+  widget: &str,
+  input: serde_json::Value,
+  repeat: Option<&str>,
+  destination: MessageDestination,
+  transport: &Transport,
+  config: &Config,
+) -> Result<String, VestaboardError> {
+  let task_id = add_task_to(&config.get_schedule_file_path(), time, widget.to_string(), input.clone(), repeat)
+    .inspect_err(|e| print_error(&e.to_user_message()))?;
+  print_success(&format!("Task scheduled (ID: {})", task_id));
+  process_widget_command(widget, &input, destination, transport, config).await?;
+  Ok(task_id)
+}
+
+/// Wait out `vbl show --for`, then put the previous message back (or clear the board).
+/// Ctrl+C skips the rest of the wait and reverts right away.
+async fn revert_board(
+  delay: std::time::Duration,
+  previous: Option<runtime_state::HistoryEntry>,
+  transport: &Transport,
+  config: &Config,
+) -> Result<(), VestaboardError> {
+  let process_controller = ProcessController::new();
+  process_controller.setup_signal_handler()?;
+
+  let then = if previous.is_some() {
+    "restoring the previous message"
+  } else {
+    "clearing the board"
+  };
+  print_progress(&format!("Showing for {}s, then {} (Ctrl+C to revert now)...", delay.as_secs(), then));
+  history::revert_after(delay, previous, transport, config, || process_controller.should_shutdown()).await?;
+  print_success("Board reverted");
+  Ok(())
+}
+
+/// Parse an optional `--from`/`--to` bound, exiting with an error message if it's invalid.
+fn parse_range_bound(bound: Option<&str>, end_of_day: bool) -> Option<chrono::DateTime<chrono::Utc>> {
+  let bound = bound?;
+  match range_bound_to_utc(bound, end_of_day) {
+    Ok(dt) => Some(dt),
+    Err(e) => {
+      log::error!("Invalid range bound '{}': {}", bound, e);
+      print_error(&format!("Invalid datetime format: {}", e));
+      process::exit(1);
+    },
+  }
+}
+
+/// Remove the runner lock file, reporting what was removed. Returns the exit code.
+fn unlock_command(config: &Config, force: bool) -> i32 {
+  let path = config.get_lock_file_path();
+  match runner::lock::unlock(&path, force) {
+    Ok(UnlockOutcome::NoLock) => {
+      print_success(&format!("No lock file at {}", path.display()));
+      0
+    },
+    Ok(UnlockOutcome::RemovedStale) => {
+      print_success(&format!("Removed stale lock file {}", path.display()));
+      0
+    },
+    Ok(UnlockOutcome::RemovedLive { mode, pid }) => {
+      print_success(&format!("Removed lock held by {} (PID {}), which is still running", mode, pid));
+      0
+    },
+    Err(e) => {
+      log::error!("Failed to unlock: {}", e);
+      print_error(&e.to_user_message());
+      exit_code_for(&e)
+    },
+  }
+}
+
+/// Print a widget's input schema as JSON. Returns the exit code.
+fn print_widget_schema(name: &str) -> i32 {
+  let schema = widget_input_schema(&name.trim().to_lowercase()).and_then(|schema| {
+    serde_json::to_string_pretty(&schema).map_err(|e| VestaboardError::json_error(e, "serializing widget schema"))
+  });
+  match schema {
+    Ok(json) => {
+      println!("{}", json);
+      0
+    },
+    Err(e) => {
+      log::error!("Failed to describe widget {}: {}", name, e);
+      print_error(&e.to_user_message());
+      exit_code_for(&e)
+    },
+  }
+}
+
+/// Report characters in `message` that the board can't show. Returns the exit code.
+fn lint_message(message: &str) -> i32 {
+  let unsupported = api_broker::unsupported_chars(message);
+  if unsupported.is_empty() {
+    print_success("Every character can be shown on the Vestaboard");
+    return 0;
+  }
+  log::info!("Message has unsupported characters: {:?}", unsupported);
+  print_error(&format!(
+    "The Vestaboard can't show: {}. Valid characters are: {}.",
+    unsupported
+      .iter()
+      .map(|c| format!("'{}'", c))
+      .collect::<Vec<_>>()
+      .join(", "),
+    api_broker::get_valid_characters_description()
+  ));
+  1
+}
+
+/// The transport to use: `--internet` takes priority over the configured one.
+fn transport_type(internet: bool, config: &Config) -> TransportType {
+  if internet {
+    TransportType::Internet
+  } else {
+    config.get_transport()
+  }
+}
+
+/// Whether a command saves the schedule, playlist, runtime state, history, display queue, or
+/// runner lock, and so needs writable data directories.
+fn writes_data(command: &Command) -> bool {
+  match command {
+    Command::Show(args) => !args.dry_run,
+    Command::Schedule { action } => !matches!(
+      action,
+      ScheduleArgs::List { .. }
+        | ScheduleArgs::Next
+        | ScheduleArgs::Export { .. }
+        | ScheduleArgs::Preview { .. }
+        | ScheduleArgs::Run { simulate: Some(_), .. }
+    ),
+    Command::Playlist { action } => !matches!(
+      action,
+      PlaylistArgs::List
+        | PlaylistArgs::Export { .. }
+        | PlaylistArgs::Preview
+        | PlaylistArgs::Show { .. }
+        | PlaylistArgs::Interval { seconds: None }
+    ),
+    Command::Daemon { .. } | Command::Timer { .. } | Command::Repeat => true,
+    _ => false,
+  }
+}
+
 #[tokio::main]
 async fn main() {
-  // Initialize logging first
+  // Read .env once, before anything looks up an API key
+  dotenv::dotenv().ok();
+
+  let cli = Cli::parse();
+
+  // Initialize output control (quiet, verbose, TTY detection)
+  init_output_control(cli.quiet, cli.verbose, cli.no_color);
+
+  // Fail early with one clear error rather than partway through saving. This runs before
+  // logging and config loading, which create the log and config files themselves.
+  if writes_data(&cli.command) {
+    let paths = Config::load_existing().ok().flatten().unwrap_or_default();
+    if let Err(e) = paths.check_data_dirs_writable() {
+      print_error(&e.to_user_message());
+      process::exit(exit_code_for(&e));
+    }
+  }
+
+  // Doctor reports a missing config, so it runs before logging and config loading create one
+  if let Command::Doctor { skip_connection } = cli.command {
+    let config = Config::load_existing().ok().flatten().unwrap_or_default();
+    process::exit(diagnostics::run_doctor(transport_type(cli.internet, &config), skip_connection).await);
+  }
+
   if let Err(e) = logging::init_logging() {
     eprintln!("Failed to initialize logging: {}", e);
     // Continue without logging rather than failing completely
@@ -76,18 +406,36 @@ async fn main() {
 
   log::info!("Vestaboard Local starting up");
 
-  let cli = Cli::parse();
-
-  // Initialize output control (quiet, verbose, TTY detection)
-  init_output_control(cli.quiet, cli.verbose);
-
   // Determine transport type: CLI flag takes priority over config
   let config = Config::load_silent().unwrap_or_default();
-  let transport_type = if cli.internet {
-    TransportType::Internet
-  } else {
-    config.get_transport()
-  };
+  let transport_type = transport_type(cli.internet, &config);
+
+  // The connection test creates its own transport so it can diagnose creation failures
+  if let Command::Test = cli.command {
+    log::info!("Running connection test");
+    process::exit(diagnostics::run_connection_test(transport_type).await);
+  }
+
+  // Unlocking only touches the lock file, so it works without a transport
+  if let Command::Unlock { force } = cli.command {
+    log::info!("Unlocking - force: {}", force);
+    process::exit(unlock_command(&config, force));
+  }
+
+  // Linting only checks the message locally
+  if let Command::Lint { message } = &cli.command {
+    log::info!("Linting message: {}", message);
+    process::exit(lint_message(message));
+  }
+
+  // Widget descriptions don't talk to the board either
+  if let Command::Widget {
+    action: WidgetArgs::Schema { name },
+  } = &cli.command
+  {
+    log::info!("Printing input schema for widget {}", name);
+    process::exit(print_widget_schema(name));
+  }
 
   // Create transport (exit early if it fails)
   let transport = match Transport::new(transport_type) {
@@ -95,28 +443,72 @@ async fn main() {
     Err(e) => {
       log::error!("Failed to create transport: {}", e);
       print_error(&e.to_user_message());
-      process::exit(1);
+      process::exit(exit_code_for(&e));
     },
   };
 
   let exit_code = match cli.command {
     Command::Show(show_args) => {
-      log::info!("Processing show command with dry_run: {}", show_args.dry_run);
+      log::info!("Processing show command with dry_run: {}, webhook: {:?}", show_args.dry_run, show_args.webhook);
 
-      match process_widget_command(&show_args.widget_command, show_args.dry_run, &transport).await {
+      let destination = match (show_args.dry_run, show_args.webhook.clone()) {
+        (true, _) if show_args.diff => MessageDestination::ConsoleDiff,
+        (true, _) if show_args.grid => MessageDestination::ConsoleGrid,
+        (true, _) => MessageDestination::Console,
+        (false, Some(url)) => MessageDestination::Webhook(url),
+        (false, None) => MessageDestination::Vestaboard,
+      };
+      let revert_delay = show_args
+        .for_duration
+        .as_deref()
+        .map(parse_duration)
+        .transpose()
+        .map_err(|e| VestaboardError::validation_error(&e));
+      let result = match (revert_delay, show_widget_input(&show_args)) {
+        (Ok(revert_delay), Ok((widget_name, input_value))) => {
+          // Capture what's on the board before it's replaced, so --for can put it back
+          let previous = match show_args.then_clear {
+            true => None,
+            false => revert_delay.and_then(|_| history::latest_entry(&transport.history_path(&config))),
+          };
+          match process_widget_command(&widget_name, &input_value, destination, &transport, &config).await {
+            Ok(_) => match revert_delay {
+              Some(delay) => revert_board(delay, previous, &transport, &config).await,
+              None => Ok(()),
+            },
+            Err(e) => Err(e),
+          }
+        },
+        (Err(e), _) | (_, Err(e)) => Err(e),
+      };
+      match result {
         Ok(_) => 0,
         Err(e) => {
           log::error!("Failed to process widget command: {}", e);
           print_error(&e.to_user_message());
-          1
+          exit_code_for(&e)
         },
       }
     },
     Command::Schedule { action } => {
       log::info!("Processing schedule command");
       match action {
-        ScheduleArgs::Add { time, widget, input } => {
-          log::info!("Adding scheduled task - time: {}, widget: {}, input: {:?}", time, widget, input);
+        ScheduleArgs::Add {
+          time,
+          widget,
+          input,
+          input_file,
+          repeat,
+          show_now,
+        } => {
+          log::info!(
+            "Adding scheduled task - time: {}, widget: {}, input: {:?}, repeat: {:?}, show_now: {}",
+            time,
+            widget,
+            input,
+            repeat,
+            show_now
+          );
           let datetime_utc = match datetime_to_utc(&time) {
             Ok(dt) => {
               log::debug!("Parsed datetime: {}", dt);
@@ -132,76 +524,53 @@ async fn main() {
             },
           };
 
-          // Convert the schedule widget args to a WidgetCommand for validation
-          let widget_command = match widget.to_lowercase().as_str() {
-            "weather" => WidgetCommand::Weather,
-            "sat-word" => WidgetCommand::SATWord,
-            "jokes" => WidgetCommand::Jokes,
-            "clear" => WidgetCommand::Clear,
-            "text" => {
-              if !input.is_empty() {
-                WidgetCommand::Text(cli_setup::TextArgs {
-                  message: input.join(" "),
-                })
-              } else {
-                print_error("Input is required for text widgets.");
-                process::exit(1);
-              }
-            },
-            "file" => {
-              if !input.is_empty() {
-                WidgetCommand::File(cli_setup::FileArgs {
-                  name: std::path::PathBuf::from(input.join(" ")),
-                })
-              } else {
-                print_error("Input is required for file widgets.");
-                process::exit(1);
-              }
-            },
-            _ => {
-              print_error(&format!("Unsupported widget type: {}", widget));
-              process::exit(1);
-            },
-          };
-
-          // Validate the widget can produce a valid message (dry-run mode - don't send to Vestaboard)
-          print_progress("Validating...");
-          if let Err(e) = process_widget_command(&widget_command, true, &transport).await {
-            log::error!("Scheduled widget validation failed: {}", e);
-            print_error(&e.to_user_message());
-            process::exit(1);
+          // Reject a bad repeat spec before validating the widget
+          if let Some(spec) = &repeat {
+            if let Err(e) = recurrence::Recurrence::parse(spec) {
+              print_error(&e.to_user_message());
+              process::exit(exit_code_for(&e));
+            }
           }
 
-          log::debug!("Scheduled widget validation successful");
-
-          // Convert back to the format expected by the scheduler
-          let input_json: serde_json::Value;
-          let widget_lower = widget.to_lowercase();
-          match widget_lower.as_str() {
-            "weather" | "sat-word" | "jokes" | "clear" => {
-              input_json = json!(null);
-            },
-            "text" | "file" => {
-              input_json = serde_json::to_value(input.join(" ")).unwrap();
-            },
-            _ => {
-              log::error!("Unsupported widget type: {}", widget_lower);
-              print_error(&format!("Unsupported widget type: {}", widget_lower));
-              process::exit(1);
-            },
-          }
+          let (widget_lower, input_json) = match &input_file {
+            Some(path) => validated_input_file(&widget, path, &transport, &config).await,
+            None => validated_widget_args(&widget, &input, &transport, &config).await,
+          };
 
-          match add_task_to_schedule(datetime_utc, widget_lower, input_json) {
-            Ok(task_id) => {
-              log::info!("Successfully added task {} to schedule", task_id);
-              print_success(&format!("Task scheduled (ID: {})", task_id));
-              0
-            },
-            Err(e) => {
-              log::error!("Failed to add task to schedule: {}", e);
-              print_error(&e.to_user_message());
-              1
-            },
+          if show_now {
+            match schedule_and_show(
+              datetime_utc,
+              &widget_lower,
+              input_json,
+              repeat.as_deref(),
+              MessageDestination::Vestaboard,
+              &transport,
+              &config,
+            )
+            .await
+            {
+              Ok(task_id) => {
+                log::info!("Added task {} to schedule and showed it", task_id);
+                0
+              },
+              Err(e) => {
+                log::error!("Failed to schedule and show task: {}", e);
+                exit_code_for(&e)
+              },
+            }
+          } else {
+            match add_task_to_schedule(datetime_utc, widget_lower, input_json, repeat.as_deref()) {
+              Ok(task_id) => {
+                log::info!("Successfully added task {} to schedule", task_id);
+                print_success(&format!("Task scheduled (ID: {})", task_id));
+                0
+              },
+              Err(e) => {
+                log::error!("Failed to add task to schedule: {}", e);
+                print_error(&e.to_user_message());
+                exit_code_for(&e)
+              },
+            }
           }
         },
         ScheduleArgs::Remove { id } => {
@@ -211,13 +580,34 @@ async fn main() {
             Err(e) => {
               log::error!("Failed to remove task: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
+            },
+          }
+        },
+        ScheduleArgs::Reschedule { id, time } => {
+          log::info!("Rescheduling task {} to {}", id, time);
+          let new_time = match datetime_to_utc(&time) {
+            Ok(dt) => dt,
+            Err(e) => {
+              log::error!("Invalid datetime format '{}': {}", time, e);
+              print_error(&format!("Invalid datetime format: {}", e));
+              process::exit(1);
+            },
+          };
+          match reschedule_task(&id, new_time) {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Failed to reschedule task: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
             },
           }
         },
-        ScheduleArgs::List => {
-          log::info!("Listing scheduled tasks");
-          match list_schedule() {
+        ScheduleArgs::List { from, to } => {
+          log::info!("Listing scheduled tasks - from: {:?}, to: {:?}", from, to);
+          let from = parse_range_bound(from.as_deref(), false);
+          let to = parse_range_bound(to.as_deref(), true);
+          match list_schedule(from, to) {
             Ok(_) => {
               log::debug!("Listed tasks successfully");
               0
@@ -225,11 +615,37 @@ async fn main() {
             Err(e) => {
               log::error!("Failed to list tasks: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
+            },
+          }
+        },
+        ScheduleArgs::Next => {
+          log::info!("Showing next scheduled task");
+          match show_next_task() {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Failed to show next task: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
+            },
+          }
+        },
+        ScheduleArgs::Clear { before: Some(before) } => {
+          let time = match before.as_str() {
+            "now" => chrono::Utc::now(),
+            bound => parse_range_bound(Some(bound), false).expect("bound is present"),
+          };
+          log::info!("Clearing scheduled tasks before {}", time);
+          match clear_schedule_before(time) {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Failed to clear old tasks: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
             },
           }
         },
-        ScheduleArgs::Clear => {
+        ScheduleArgs::Clear { before: None } => {
           log::info!("Clearing all scheduled tasks");
           match clear_schedule() {
             Ok(_) => {
@@ -239,23 +655,79 @@ async fn main() {
             Err(e) => {
               log::error!("Failed to clear schedule: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
             },
           }
         },
-        ScheduleArgs::Preview => {
-          log::info!("Running schedule preview");
-          preview_schedule(&transport).await;
+        ScheduleArgs::Dedupe { window } => {
+          let window = match window.as_deref().map(parse_duration).transpose() {
+            Ok(window) => window.unwrap_or_default(),
+            Err(e) => {
+              print_error(&e);
+              process::exit(1);
+            },
+          };
+          log::info!("Deduplicating schedule - window: {:?}", window);
+          let window = chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX);
+          match dedupe_schedule(window) {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Failed to dedupe schedule: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
+            },
+          }
+        },
+        ScheduleArgs::Import { file } => {
+          log::info!("Importing calendar file {}", file.display());
+          match import_ics_to_schedule(&file) {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Failed to import calendar: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
+            },
+          }
+        },
+        ScheduleArgs::Export { as_commands } => {
+          log::info!("Exporting schedule - as_commands: {}", as_commands);
+          match export::export_schedule(as_commands) {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Failed to export schedule: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
+            },
+          }
+        },
+        ScheduleArgs::Preview { count } => {
+          log::info!("Running schedule preview - count: {:?}", count);
+          preview_schedule(count, &transport).await;
           0
         },
-        ScheduleArgs::Run { dry_run } => {
-          log::info!("Running schedule - dry_run: {}", dry_run);
-          match run_schedule(dry_run, &transport).await {
+        ScheduleArgs::Run {
+          simulate: Some(bounds), ..
+        } => {
+          log::info!("Simulating schedule between {} and {}", bounds[0], bounds[1]);
+          let from = parse_range_bound(Some(&bounds[0]), false).expect("bound is set");
+          let to = parse_range_bound(Some(&bounds[1]), true).expect("bound is set");
+          match scheduler::print_schedule_simulation(from, to) {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Schedule simulation failed: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
+            },
+          }
+        },
+        ScheduleArgs::Run { dry_run, catch_up, .. } => {
+          log::info!("Running schedule - dry_run: {}, catch_up: {}", dry_run, catch_up);
+          match run_schedule(dry_run, catch_up, &transport).await {
             Ok(_) => 0,
             Err(e) => {
               log::error!("Schedule run failed: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
             },
           }
         },
@@ -264,59 +736,20 @@ async fn main() {
     Command::Playlist { action } => {
       log::info!("Processing playlist command");
       match action {
-        PlaylistArgs::Add { widget, input } => {
-          log::info!("Adding playlist item - widget: {}, input: {:?}", widget, input);
-
-          // Validate widget type and build input
-          let widget_lower = widget.to_lowercase();
-          let input_json = match widget_lower.as_str() {
-            "weather" | "sat-word" | "jokes" | "clear" => json!(null),
-            "text" => {
-              if input.is_empty() {
-                print_error("Input is required for text widgets.");
-                process::exit(1);
-              }
-              json!(input.join(" "))
-            },
-            "file" => {
-              if input.is_empty() {
-                print_error("Input is required for file widgets.");
-                process::exit(1);
-              }
-              json!(input.join(" "))
-            },
-            _ => {
-              print_error(&format!(
-                "Unsupported widget type: {}. Supported: weather, text, sat-word, jokes, clear, file",
-                widget
-              ));
-              process::exit(1);
-            },
-          };
+        PlaylistArgs::Add {
+          widget,
+          input,
+          input_file,
+          at,
+        } => {
+          log::info!("Adding playlist item - widget: {}, input: {:?}, at: {:?}", widget, input, at);
 
-          // Validate the widget can produce a valid message (dry-run mode)
-          let widget_command = match widget_lower.as_str() {
-            "weather" => WidgetCommand::Weather,
-            "sat-word" => WidgetCommand::SATWord,
-            "jokes" => WidgetCommand::Jokes,
-            "clear" => WidgetCommand::Clear,
-            "text" => WidgetCommand::Text(cli_setup::TextArgs {
-              message: input.join(" "),
-            }),
-            "file" => WidgetCommand::File(cli_setup::FileArgs {
-              name: std::path::PathBuf::from(input.join(" ")),
-            }),
-            _ => unreachable!(), // Already handled above
+          let (widget_lower, input_json) = match &input_file {
+            Some(path) => validated_input_file(&widget, path, &transport, &config).await,
+            None => validated_widget_args(&widget, &input, &transport, &config).await,
           };
 
-          print_progress("Validating widget...");
-          if let Err(e) = process_widget_command(&widget_command, true, &transport).await {
-            log::error!("Widget validation failed: {}", e);
-            print_error(&e.to_user_message());
-            process::exit(1);
-          }
-
-          match playlist::add_item_to_playlist(&widget_lower, input_json) {
+          match playlist::add_item_to_playlist(&widget_lower, input_json, at) {
             Ok(item_id) => {
               log::info!("Successfully added item {} to playlist", item_id);
               print_success(&format!("Added {} to playlist (ID: {})", widget_lower, item_id));
@@ -325,7 +758,7 @@ async fn main() {
             Err(e) => {
               log::error!("Failed to add item to playlist: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
             },
           }
         },
@@ -336,7 +769,7 @@ async fn main() {
             Err(e) => {
               log::error!("Failed to list playlist: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
             },
           }
         },
@@ -350,7 +783,21 @@ async fn main() {
             Err(e) => {
               log::error!("Failed to remove item: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
+            },
+          }
+        },
+        PlaylistArgs::Edit { id, input } => {
+          log::info!("Editing playlist item {} - input: {:?}", id, input);
+          match playlist::edit_item_in_playlist(&id, &input) {
+            Ok(_) => {
+              print_success(&format!("Updated item {}", id));
+              0
+            },
+            Err(e) => {
+              log::error!("Failed to edit item: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
             },
           }
         },
@@ -364,7 +811,24 @@ async fn main() {
             Err(e) => {
               log::error!("Failed to clear playlist: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
+            },
+          }
+        },
+        PlaylistArgs::Import { file, keep_existing } => {
+          log::info!("Importing playlist from {} (keep existing: {})", file.display(), keep_existing);
+          match playlist::import_playlist(&file, keep_existing) {
+            Ok(counts) => {
+              print_success(&format!(
+                "Imported playlist: {} replaced, {} added, {} skipped",
+                counts.replaced, counts.added, counts.skipped
+              ));
+              0
+            },
+            Err(e) => {
+              log::error!("Failed to import playlist: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
             },
           }
         },
@@ -379,7 +843,7 @@ async fn main() {
               Err(e) => {
                 log::error!("Failed to set interval: {}", e);
                 print_error(&e.to_user_message());
-                1
+                exit_code_for(&e)
               },
             }
           },
@@ -390,42 +854,197 @@ async fn main() {
               Err(e) => {
                 log::error!("Failed to get interval: {}", e);
                 print_error(&e.to_user_message());
-                1
+                exit_code_for(&e)
               },
             }
           },
         },
+        PlaylistArgs::Export { as_commands } => {
+          log::info!("Exporting playlist - as_commands: {}", as_commands);
+          match export::export_playlist(as_commands) {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Failed to export playlist: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
+            },
+          }
+        },
         PlaylistArgs::Preview => {
           log::info!("Previewing playlist");
           playlist::preview_playlist(&transport).await;
           0
         },
+        PlaylistArgs::Show { id } => {
+          log::info!("Previewing playlist item: {}", id);
+          match playlist::show_playlist_item(&id, &transport).await {
+            Ok(_) => 0,
+            Err(e) => {
+              log::error!("Failed to preview item: {}", e);
+              print_error(&e.to_user_message());
+              exit_code_for(&e)
+            },
+          }
+        },
         PlaylistArgs::Run {
           once,
           resume,
           index,
           id,
           dry_run,
+          tui,
+          reverse,
+          shuffle,
+          tag,
+          interval,
         } => {
           log::info!(
-            "Running playlist - once: {}, resume: {}, index: {:?}, id: {:?}, dry_run: {}",
+            "Running playlist - once: {}, resume: {}, index: {:?}, id: {:?}, dry_run: {}, tui: {}, reverse: {}, shuffle: {}, tag: {:?}, interval: {:?}",
             once,
             resume,
             index,
             id,
-            dry_run
+            dry_run,
+            tui,
+            reverse,
+            shuffle,
+            tag,
+            interval
           );
-          match playlist::run_playlist(once, resume, index, id, dry_run, &transport).await {
+          let options = playlist::PlaylistRunOptions {
+            once,
+            resume,
+            start_index: index,
+            start_id: id,
+            dry_run,
+            tui,
+            reverse,
+            shuffle,
+            tag,
+            interval,
+          };
+          match playlist::run_playlist(options, &transport).await {
             Ok(_) => 0,
             Err(e) => {
               log::error!("Playlist run failed: {}", e);
               print_error(&e.to_user_message());
-              1
+              exit_code_for(&e)
             },
           }
         },
       }
     },
+    Command::Status => {
+      log::info!("Showing runtime status");
+      playlist::show_status();
+      0
+    },
+    Command::PreviewAll => {
+      log::info!("Previewing all widgets");
+      preview_all_widgets(&transport, &config).await;
+      0
+    },
+    Command::SendCodes { codes } => {
+      log::info!("Sending raw codes");
+      match api_broker::send_code_grid(&codes, &transport).await {
+        Ok(_) => {
+          print_success("Sent codes to the Vestaboard");
+          0
+        },
+        Err(e) => {
+          log::error!("Failed to send raw codes: {}", e);
+          print_error(&e.to_user_message());
+          exit_code_for(&e)
+        },
+      }
+    },
+    Command::Snapshot { path } => {
+      log::info!("Saving board snapshot to {}", path.display());
+      match api_broker::snapshot_board(&path, &transport).await {
+        Ok(_) => {
+          print_success(&format!("Saved the board to {}", path.display()));
+          0
+        },
+        Err(e) => {
+          log::error!("Failed to save board snapshot: {}", e);
+          print_error(&e.to_user_message());
+          exit_code_for(&e)
+        },
+      }
+    },
+    Command::Repeat => {
+      log::info!("Repeating last message");
+      match history::repeat_last(&transport).await {
+        Ok(_) => 0,
+        Err(e) => {
+          log::error!("Failed to repeat last message: {}", e);
+          exit_code_for(&e)
+        },
+      }
+    },
+    Command::History { action: None } => {
+      log::info!("Listing message history");
+      history::list_history();
+      0
+    },
+    Command::History {
+      action: Some(HistoryArgs::Show { n }),
+    } => {
+      log::info!("Showing history entry {}", n);
+      match history::show_history_entry(n) {
+        Ok(_) => 0,
+        Err(e) => {
+          log::error!("Failed to show history entry {}: {}", n, e);
+          exit_code_for(&e)
+        },
+      }
+    },
+    Command::Daemon { dry_run, once } => {
+      log::info!("Running daemon - dry_run: {}, once: {}", dry_run, once);
+      let result = match once {
+        true => runner::combined_runner::run_daemon_once(dry_run, &transport).await,
+        false => runner::combined_runner::run_daemon(dry_run, &transport).await,
+      };
+      match result {
+        Ok(_) => 0,
+        Err(e) => {
+          log::error!("Daemon failed: {}", e);
+          print_error(&e.to_user_message());
+          exit_code_for(&e)
+        },
+      }
+    },
+    Command::Timer {
+      duration,
+      label,
+      dry_run,
+    } => {
+      let label = if label.is_empty() {
+        runner::timer::DEFAULT_TIMER_LABEL.to_string()
+      } else {
+        label.join(" ")
+      };
+      log::info!("Running timer - duration: {}, label: {}, dry_run: {}", duration, label, dry_run);
+      match runner::timer::parse_timer_duration(&duration) {
+        Ok(length) => match runner::timer::run_timer(length, &label, dry_run, &transport, &config).await {
+          Ok(_) => 0,
+          Err(e) => {
+            log::error!("Timer failed: {}", e);
+            print_error(&e.to_user_message());
+            exit_code_for(&e)
+          },
+        },
+        Err(e) => {
+          print_error(&e.to_user_message());
+          exit_code_for(&e)
+        },
+      }
+    },
+    Command::Test => unreachable!("connection test is handled before transport creation"),
+    Command::Doctor { .. } => unreachable!("doctor is handled before transport creation"),
+    Command::Unlock { .. } => unreachable!("unlock is handled before transport creation"),
+    Command::Lint { .. } => unreachable!("lint is handled before transport creation"),
+    Command::Widget { .. } => unreachable!("widget commands are handled before transport creation"),
   };
 
   process::exit(exit_code);
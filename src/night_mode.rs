@@ -0,0 +1,49 @@
+//! Night mode: a dimmer rendering of messages during a configured time window.
+//!
+//! The board has no brightness control, so dimming swaps the brightest color tiles for
+//! quieter ones and blanks every other color tile. Text is left alone.
+
+use chrono::NaiveTime;
+
+use crate::datetime::TimeWindow;
+
+/// Tile substitutions applied at night (bright color, quieter color)
+const DIM_COLORS: [(char, char); 2] = [('W', 'B'), ('Y', 'O')];
+
+/// Color tile characters
+const COLOR_TILES: &str = "ROYGBVWK";
+
+/// Dim a message: white and yellow tiles become blue and orange, and color tiles on
+/// alternate cells (a checkerboard) are blanked so solid bars show at half density.
+pub fn dim_message(message: &[String]) -> Vec<String> {
+  message
+    .iter()
+    .enumerate()
+    .map(|(row, line)| {
+      line
+        .chars()
+        .enumerate()
+        .map(|(col, c)| {
+          if !COLOR_TILES.contains(c) {
+            return c;
+          }
+          if (row + col) % 2 == 1 {
+            return ' ';
+          }
+          DIM_COLORS
+            .iter()
+            .find(|(bright, _)| *bright == c)
+            .map_or(c, |(_, quiet)| *quiet)
+        })
+        .collect()
+    })
+    .collect()
+}
+
+/// Dim the message if `now` falls in the night window; otherwise return it unchanged.
+pub fn apply_night_mode(message: Vec<String>, window: Option<&TimeWindow>, now: NaiveTime) -> Vec<String> {
+  match window {
+    Some(window) if window.contains(now) => dim_message(&message),
+    _ => message,
+  }
+}
@@ -6,6 +6,7 @@
 use std::path::Path;
 
 use nanoid::nanoid;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -18,6 +19,23 @@ fn generate_item_id() -> String {
   nanoid!(ID_LENGTH, CUSTOM_ALPHABET)
 }
 
+/// What the playlist runner does when an item's widget fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnError {
+  /// Show the error on the board until the next item
+  #[default]
+  Show,
+  /// Move straight on to the next item without sending anything
+  Skip,
+}
+
+impl OnError {
+  fn is_show(&self) -> bool {
+    *self == OnError::Show
+  }
+}
+
 /// A single item in a playlist
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaylistItem {
@@ -28,6 +46,15 @@ pub struct PlaylistItem {
   pub widget: String,
   /// Widget-specific input (varies by widget type)
   pub input: Value,
+  /// What to do when the widget fails (left out of the file when it's `show`)
+  #[serde(default, skip_serializing_if = "OnError::is_show")]
+  pub on_error: OnError,
+  /// Start here when a run resumes from saved state, instead of at the saved index
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub pinned_first: bool,
+  /// Labels for `vbl playlist run --tag` (e.g. "morning")
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub tags: Vec<String>,
 }
 
 impl PlaylistItem {
@@ -40,6 +67,9 @@ impl PlaylistItem {
       id: generate_item_id(),
       widget,
       input,
+      on_error: OnError::Show,
+      pinned_first: false,
+      tags: Vec::new(),
     }
   }
 
@@ -55,9 +85,29 @@ impl PlaylistItem {
   }
 }
 
+/// How many items a playlist import replaced, added, and skipped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeCounts {
+  pub replaced: usize,
+  pub added: usize,
+  pub skipped: usize,
+}
+
 /// Minimum allowed interval between playlist item rotations (60 seconds)
 pub const MIN_INTERVAL_SECONDS: u64 = 60;
 
+/// An interval moved up to `jitter_seconds` earlier or later at random, so boards running
+/// the same playlist don't all send in the same second. A jittered interval is never shorter
+/// than `MIN_INTERVAL_SECONDS`.
+pub fn jittered_interval<R: Rng + ?Sized>(interval_seconds: u64, jitter_seconds: u64, rng: &mut R) -> u64 {
+  if jitter_seconds == 0 {
+    return interval_seconds;
+  }
+  let earliest = interval_seconds.saturating_sub(jitter_seconds);
+  let latest = interval_seconds.saturating_add(jitter_seconds);
+  rng.gen_range(earliest..=latest).max(MIN_INTERVAL_SECONDS)
+}
+
 /// Default interval between playlist item rotations (5 minutes)
 fn default_interval() -> u64 {
   300
@@ -100,6 +150,16 @@ impl Playlist {
     id
   }
 
+  /// Insert an item at the given index and return its ID
+  ///
+  /// Indexes past the end are clamped, so the item is appended.
+  pub fn insert_item(&mut self, index: usize, item: PlaylistItem) -> String {
+    let id = item.id.clone();
+    let index = index.min(self.items.len());
+    self.items.insert(index, item);
+    id
+  }
+
   /// Remove an item by ID, returns true if item was found and removed
   pub fn remove_item(&mut self, id: &str) -> bool {
     let len_before = self.items.len();
@@ -107,6 +167,18 @@ impl Playlist {
     self.items.len() < len_before
   }
 
+  /// Replace an item's input in place (keeping its ID and position).
+  /// Returns true if the item was found and updated.
+  pub fn update_item_input(&mut self, id: &str, input: Value) -> bool {
+    match self.items.iter_mut().find(|item| item.id == id) {
+      Some(item) => {
+        item.input = input;
+        true
+      },
+      None => false,
+    }
+  }
+
   /// Clear all items from the playlist
   pub fn clear(&mut self) {
     self.items.clear();
@@ -137,6 +209,41 @@ impl Playlist {
     self.items.iter().position(|item| item.id == id)
   }
 
+  /// A copy holding only the items tagged `tag` (case-insensitive), in their original order
+  pub fn with_tag(&self, tag: &str) -> Playlist {
+    Playlist {
+      interval_seconds: self.interval_seconds,
+      items: self
+        .items
+        .iter()
+        .filter(|item| item.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        .cloned()
+        .collect(),
+    }
+  }
+
+  /// Merge `imported` items into this playlist by ID, leaving the interval as it is.
+  ///
+  /// Items with a new ID are appended. For an ID already in the playlist, the imported item
+  /// replaces the existing one in place, or with `keep_existing` is skipped.
+  pub fn merge(&mut self, imported: Playlist, keep_existing: bool) -> MergeCounts {
+    let mut counts = MergeCounts::default();
+    for item in imported.items {
+      match self.find_index_by_id(&item.id) {
+        Some(_) if keep_existing => counts.skipped += 1,
+        Some(index) => {
+          self.items[index] = item;
+          counts.replaced += 1;
+        },
+        None => {
+          self.items.push(item);
+          counts.added += 1;
+        },
+      }
+    }
+    counts
+  }
+
   /// Validate that the interval is at least MIN_INTERVAL_SECONDS
   pub fn validate_interval(&self) -> Result<(), VestaboardError> {
     if self.interval_seconds < MIN_INTERVAL_SECONDS {
@@ -252,18 +359,21 @@ impl Playlist {
 
 // --- CLI functions (following scheduler.rs pattern) ---
 
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
 use std::time::Duration;
 
 use crate::api::Transport;
 use crate::api_broker::{handle_message, MessageDestination};
 use crate::config::Config;
 use crate::process_control::ProcessController;
+use crate::recovery::recover_corrupt;
 use crate::runner::keyboard::{InputSource, KeyboardListener};
 use crate::runner::lock::InstanceLock;
 use crate::runner::playlist_runner::PlaylistRunner;
-use crate::runner::{ControlFlow, Runner};
-use crate::widgets::resolver::execute_widget;
-use crate::widgets::widget_utils::error_to_display_message;
+use crate::runner::{tui, ControlFlow, Runner};
+use crate::runtime_state::RuntimeState;
+use crate::widgets::resolver::{error_display, execute_widget, input_from_args, validate_widget_input};
 
 /// Get the default playlist file path from config
 fn get_playlist_path() -> std::path::PathBuf {
@@ -273,11 +383,16 @@ fn get_playlist_path() -> std::path::PathBuf {
 }
 
 /// Add an item to the playlist and save
-pub fn add_item_to_playlist(widget: &str, input: Value) -> Result<String, VestaboardError> {
+///
+/// Appends by default; `at` inserts at a 0-based position instead (clamped to the end).
+pub fn add_item_to_playlist(widget: &str, input: Value, at: Option<usize>) -> Result<String, VestaboardError> {
   let path = get_playlist_path();
   let mut playlist = Playlist::load_silent(&path)?;
 
-  let id = playlist.add_widget(widget, input);
+  let id = match at {
+    Some(index) => playlist.insert_item(index, PlaylistItem::new(widget.to_string(), input)),
+    None => playlist.add_widget(widget, input),
+  };
   playlist.save_silent(&path)?;
 
   log::info!("Added item {} ({}) to playlist", id, widget);
@@ -299,7 +414,12 @@ pub fn list_playlist() -> Result<(), VestaboardError> {
   println!();
 
   for (index, item) in playlist.items.iter().enumerate() {
-    println!("  {}. [{}] {}{}", index + 1, item.id, item.widget, item.format_input());
+    let tags = if item.tags.is_empty() {
+      String::new()
+    } else {
+      format!(" #{}", item.tags.join(" #"))
+    };
+    println!("  {}. [{}] {}{}{}", index + 1, item.id, item.widget, item.format_input(), tags);
   }
 
   println!();
@@ -320,6 +440,54 @@ pub fn remove_item_from_playlist(id: &str) -> Result<(), VestaboardError> {
   Ok(())
 }
 
+/// Change an existing item's input, validating it against the item's widget before saving
+pub fn edit_item_in_playlist(id: &str, args: &[String]) -> Result<(), VestaboardError> {
+  let path = get_playlist_path();
+  let mut playlist = Playlist::load_silent(&path)?;
+
+  let widget = playlist
+    .get_item(id)
+    .map(|item| item.widget.clone())
+    .ok_or_else(|| VestaboardError::validation_error(&format!("Item '{}' not found in playlist", id)))?;
+
+  let input = input_from_args(&widget, args);
+  validate_widget_input(&widget, &input)?;
+
+  playlist.update_item_input(id, input);
+  playlist.save_silent(&path)?;
+
+  log::info!("Updated input for playlist item {} ({})", id, widget);
+  Ok(())
+}
+
+/// Merge the items of a playlist JSON file (as written by `vbl playlist export`) into the
+/// saved playlist; see `Playlist::merge`. Every imported item's input is checked first, so a
+/// bad item leaves the playlist unchanged.
+pub fn import_playlist(file: &Path, keep_existing: bool) -> Result<MergeCounts, VestaboardError> {
+  let content = std::fs::read_to_string(file)
+    .map_err(|e| VestaboardError::io_error(e, &format!("reading playlist from {}", file.display())))?;
+  let imported: Playlist = serde_json::from_str(&content)
+    .map_err(|e| VestaboardError::json_error(e, &format!("parsing playlist from {}", file.display())))?;
+  for item in &imported.items {
+    validate_widget_input(&item.widget, &item.input)
+      .map_err(|e| VestaboardError::validation_error(&format!("Item '{}': {}", item.id, e.to_user_message())))?;
+  }
+
+  let path = get_playlist_path();
+  let mut playlist = Playlist::load_silent(&path)?;
+  let counts = playlist.merge(imported, keep_existing);
+  playlist.save_silent(&path)?;
+
+  log::info!(
+    "Imported playlist from {}: {} replaced, {} added, {} skipped",
+    file.display(),
+    counts.replaced,
+    counts.added,
+    counts.skipped
+  );
+  Ok(counts)
+}
+
 /// Clear all items from the playlist
 pub fn clear_playlist() -> Result<(), VestaboardError> {
   let path = get_playlist_path();
@@ -363,7 +531,8 @@ pub fn set_playlist_interval(seconds: u64) -> Result<(), VestaboardError> {
 
 /// Preview all items in the playlist (dry-run mode)
 pub async fn preview_playlist(transport: &Transport) {
-  let path = get_playlist_path();
+  let config = Config::load_silent().unwrap_or_default();
+  let path = config.get_playlist_file_path();
   let playlist = match Playlist::load_silent(&path) {
     Ok(p) => p,
     Err(e) => {
@@ -384,16 +553,16 @@ pub async fn preview_playlist(transport: &Transport) {
     println!("--- Item {} of {}: {}{} ---", index + 1, playlist.len(), item.widget, item.format_input());
 
     // Execute widget and show preview
-    let message = match execute_widget(&item.widget, &item.input).await {
+    let message = match execute_widget(&item.widget, &item.input, &config).await {
       Ok(msg) => msg,
       Err(e) => {
         println!("  Error: {}", e.to_user_message());
-        error_to_display_message(&e)
+        error_display(&e, &config)
       },
     };
 
     // Display to console (dry-run)
-    if let Err(e) = handle_message(message, MessageDestination::Console, &transport).await {
+    if let Err(e) = handle_message(message, &item.widget, MessageDestination::Console, &transport, &config).await {
       println!("  Display error: {}", e.to_user_message());
     }
 
@@ -403,40 +572,175 @@ pub async fn preview_playlist(transport: &Transport) {
   println!("Preview complete.");
 }
 
+/// Dry-run render one playlist item to the console, titled with its ID and widget, returning
+/// the rendered message
+pub async fn preview_item(
+  playlist: &Playlist,
+  id: &str,
+  transport: &Transport,
+  config: &Config,
+) -> Result<Vec<String>, VestaboardError> {
+  let item = playlist
+    .get_item(id)
+    .ok_or_else(|| VestaboardError::validation_error(&format!("Item '{}' not found in playlist", id)))?;
+
+  let message = match execute_widget(&item.widget, &item.input, config).await {
+    Ok(msg) => msg,
+    Err(e) => {
+      println!("  Error: {}", e.to_user_message());
+      error_display(&e, config)
+    },
+  };
+
+  let title = format!("{}: {}{}", item.id, item.widget, item.format_input());
+  let destination = MessageDestination::ConsoleWithTitle(title);
+  handle_message(message.clone(), &item.widget, destination, transport, config).await?;
+  Ok(message)
+}
+
+/// Preview a single item from the saved playlist (dry-run mode)
+pub async fn show_playlist_item(id: &str, transport: &Transport) -> Result<(), VestaboardError> {
+  let config = Config::load_silent().unwrap_or_default();
+  let playlist = Playlist::load_silent(&config.get_playlist_file_path())?;
+  preview_item(&playlist, id, transport, &config).await.map(|_| ())
+}
+
+/// Show the saved playlist state and display statistics
+pub fn show_status() {
+  let config = Config::load_silent().unwrap_or_default();
+  let state = RuntimeState::load(&config.get_runtime_state_path());
+
+  println!("Playlist state: {:?}", state.playlist_state);
+  println!("Current index: {}", state.playlist_index);
+  match state.last_shown_time {
+    Some(time) => {
+      let local_time = time.with_timezone(&chrono::Local);
+      println!("Last shown: {}", local_time.format("%Y.%m.%d %I:%M %p"));
+    },
+    None => println!("Last shown: never"),
+  }
+  println!();
+
+  println!("Items displayed: {}", state.stats.total_displayed);
+  for (widget, count) in &state.stats.widget_counts {
+    println!("  {}: {}", widget, count);
+  }
+  if let Some(error) = &state.stats.last_error {
+    println!("Last error: {}", error);
+  }
+}
+
+/// How often `wait_for_items` checks the playlist file
+pub const WAIT_FOR_ITEMS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wait until the playlist at `path` has items, checking every `WAIT_FOR_ITEMS_INTERVAL`.
+///
+/// Used after a corrupt playlist was moved aside, so a long-running command keeps going
+/// instead of exiting. Returns None if shutdown is requested first.
+pub async fn wait_for_items(path: &Path, controller: &ProcessController) -> Option<Playlist> {
+  println!("Playlist is empty. Waiting for items to be added with: vbl playlist add <widget>");
+  loop {
+    match Playlist::load_silent(path) {
+      Ok(playlist) if !playlist.is_empty() => {
+        log::info!("Playlist now has {} items, starting", playlist.len());
+        return Some(playlist);
+      },
+      Ok(_) => {},
+      Err(e) => log::warn!("Still waiting for a readable playlist: {}", e),
+    }
+    if controller.should_shutdown() {
+      return None;
+    }
+    tokio::time::sleep(WAIT_FOR_ITEMS_INTERVAL).await;
+  }
+}
+
+/// Options for `run_playlist`, mirroring the `playlist run` flags.
+#[derive(Debug, Clone, Default)]
+pub struct PlaylistRunOptions {
+  /// Run through the playlist once and exit
+  pub once: bool,
+  /// Resume from the last saved position
+  pub resume: bool,
+  /// Starting index (0-based)
+  pub start_index: Option<usize>,
+  /// Starting item ID
+  pub start_id: Option<String>,
+  /// Display to console instead of Vestaboard
+  pub dry_run: bool,
+  /// Show a full-screen dashboard (falls back to line output without a TTY)
+  pub tui: bool,
+  /// Rotate backward, starting from the last item
+  pub reverse: bool,
+  /// Play items in a random order, shuffled once at startup
+  pub shuffle: bool,
+  /// Only run the items with this tag
+  pub tag: Option<String>,
+  /// Seconds between items for this run, overriding the saved interval
+  pub interval: Option<u64>,
+}
+
 /// Run the playlist with interactive controls.
 ///
 /// # Arguments
-/// * `once` - If true, run through playlist once and exit
-/// * `resume` - If true, resume from last saved position
-/// * `start_index` - Optional starting index (0-based)
-/// * `start_id` - Optional starting item ID
-/// * `dry_run` - If true, display to console instead of Vestaboard
+/// * `options` - How to run the playlist (see `PlaylistRunOptions`)
 /// * `transport` - The transport to use for API communication
-pub async fn run_playlist(
-  once: bool,
-  resume: bool,
-  start_index: Option<usize>,
-  start_id: Option<String>,
-  dry_run: bool,
-  transport: &Transport,
-) -> Result<(), VestaboardError> {
+pub async fn run_playlist(options: PlaylistRunOptions, transport: &Transport) -> Result<(), VestaboardError> {
+  let PlaylistRunOptions {
+    once,
+    resume,
+    start_index,
+    start_id,
+    dry_run,
+    tui,
+    reverse,
+    shuffle,
+    tag,
+    interval,
+  } = options;
   let playlist_path = get_playlist_path();
   let config = Config::load_silent().unwrap_or_default();
   let state_path = config.get_runtime_state_path();
 
+  // Setup Ctrl+C handler
+  let process_controller = ProcessController::new();
+  process_controller.setup_signal_handler()?;
+
   // Load playlist
-  let playlist = Playlist::load_silent(&playlist_path)?;
+  let loaded = Playlist::load_silent(&playlist_path);
+  let recovered = loaded.is_err();
+  let playlist = match recover_corrupt(&playlist_path, "playlist", loaded, config.get_recover_corrupt_files())? {
+    // The corrupt playlist was moved aside; keep running until items are added again
+    playlist if playlist.is_empty() && recovered => match wait_for_items(&playlist_path, &process_controller).await {
+      Some(playlist) => playlist,
+      None => return Ok(()),
+    },
+    playlist => playlist,
+  };
 
   if playlist.is_empty() {
     println!("Playlist is empty. Add items with: vbl playlist add <widget>");
     return Ok(());
   }
 
+  let playlist = match tag {
+    Some(tag) => {
+      let tagged = playlist.with_tag(&tag);
+      if tagged.is_empty() {
+        println!("No playlist items are tagged '{}'.", tag);
+        return Ok(());
+      }
+      log::info!("Running {} of {} playlist items tagged '{}'", tagged.len(), playlist.len(), tag);
+      tagged
+    },
+    None => playlist,
+  };
+
   // Acquire exclusive lock
-  let _lock = InstanceLock::acquire("playlist")?;
+  let _lock = InstanceLock::acquire("playlist", &config)?;
 
   // Create runner with appropriate starting position
-  let mut runner = match (start_index, start_id) {
+  let runner = match (start_index, start_id) {
     (Some(idx), _) => {
       if idx >= playlist.len() {
         return Err(VestaboardError::validation_error(&format!(
@@ -458,26 +762,46 @@ pub async fn run_playlist(
       PlaylistRunner::restore_from_state(playlist, state_path, once, dry_run, &transport)
     },
     (None, None) => {
-      // Default: start from beginning
-      PlaylistRunner::new(playlist, state_path, 0, once, dry_run, &transport)
+      // Default: start from the beginning, or from the end when rotating backward
+      let first = if reverse { playlist.len() - 1 } else { 0 };
+      PlaylistRunner::new(playlist, state_path, first, once, dry_run, &transport)
     },
   };
+  let mut runner = runner
+    .with_reverse(reverse)
+    .with_shuffle(shuffle, &mut thread_rng())
+    .with_pause_indicator(config.get_pause_indicator())
+    .with_skip_blank(config.get_skip_blank())
+    .with_error_display(config.get_error_display())
+    .with_interval_jitter(config.get_interval_jitter_seconds(), StdRng::from_entropy())
+    .with_config(config.clone())
+    .with_interval(interval)?;
 
   // Setup keyboard listener
   let mut keyboard = KeyboardListener::new()?;
 
-  // Setup Ctrl+C handler
-  let process_controller = ProcessController::new();
-  process_controller.setup_signal_handler()?;
-
   // Show initial help
   println!("Press ? for help, q to quit.");
 
   // Start the runner
   runner.start();
 
+  // Optional dashboard; dropped (restoring the screen) before the final messages
+  let mut dashboard = if tui && tui::is_supported() {
+    Some(tui::Dashboard::enter()?)
+  } else {
+    if tui {
+      log::warn!("--tui requires a terminal, using line output");
+    }
+    None
+  };
+
   // Main loop
   loop {
+    if let Some(dashboard) = dashboard.as_mut() {
+      dashboard.draw(&runner.dashboard_view());
+    }
+
     // Priority 1: Check for shutdown signal (Ctrl+C)
     if process_controller.should_shutdown() {
       log::info!("Shutdown requested, stopping playlist");
@@ -515,6 +839,7 @@ pub async fn run_playlist(
   }
 
   // Cleanup
+  drop(dashboard);
   runner.cleanup();
   print_success("Playlist stopped.");
 
@@ -0,0 +1,37 @@
+//! Quiet hours: the board is kept blank during a configured time window.
+//!
+//! Unlike night mode, which dims messages, quiet hours send nothing at all. The board is
+//! cleared once when the window begins; after that, messages are dropped until it ends.
+
+use chrono::NaiveTime;
+
+use crate::datetime::TimeWindow;
+
+/// What to do with an outgoing board message given the quiet hours window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuietHoursAction {
+  /// Outside quiet hours: send the message as usual
+  Send,
+  /// Quiet hours just began: blank the board instead of sending the message
+  Clear,
+  /// The board was already blanked for quiet hours: drop the message
+  Suppress,
+}
+
+/// Decide what to do with a message sent at `now`.
+///
+/// `cleared` says whether the board was blanked for quiet hours and nothing has been sent
+/// since (kept in the history file), so the board is cleared once per window even across
+/// separate `vbl` runs.
+pub fn quiet_hours_action(window: Option<&TimeWindow>, now: NaiveTime, cleared: bool) -> QuietHoursAction {
+  match window {
+    Some(window) if window.contains(now) => {
+      if cleared {
+        QuietHoursAction::Suppress
+      } else {
+        QuietHoursAction::Clear
+      }
+    },
+    _ => QuietHoursAction::Send,
+  }
+}
@@ -0,0 +1,55 @@
+//! Recovery for data files that no longer parse.
+//!
+//! With `recover_corrupt_files` on, a corrupt schedule or playlist is moved aside as
+//! `<file>.corrupt-<timestamp>` and replaced by an empty one, so the long-running commands
+//! (`vbl daemon`, `vbl schedule run`, `vbl playlist run`) start instead of failing. They then
+//! wait for tasks or items to be added rather than exiting on the empty file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+use crate::cli_display::print_warning;
+use crate::errors::VestaboardError;
+
+/// Where a corrupt file found at `now` is moved: the same path with `.corrupt-<timestamp>` appended
+pub fn corrupt_file_path(path: &Path, now: DateTime<Local>) -> PathBuf {
+  let mut name = path.as_os_str().to_os_string();
+  name.push(format!(".corrupt-{}", now.format("%Y%m%d-%H%M%S")));
+  PathBuf::from(name)
+}
+
+/// Recover from a load that failed to parse `path` (`what` names the file, e.g. "schedule").
+///
+/// With `recover` set, a JSON error moves the file aside and returns an empty default. Any other
+/// result, or any result with `recover` off, is returned unchanged.
+pub fn recover_corrupt<T: Default>(
+  path: &Path,
+  what: &str,
+  loaded: Result<T, VestaboardError>,
+  recover: bool,
+) -> Result<T, VestaboardError> {
+  match loaded {
+    Err(VestaboardError::JsonError { .. }) if recover => {
+      let moved_to = corrupt_file_path(path, Local::now());
+      fs::rename(path, &moved_to)
+        .map_err(|e| VestaboardError::io_error(e, &format!("moving corrupt {} {}", what, path.display())))?;
+      log::error!(
+        "Corrupt {} {} moved to {}; starting with an empty {}",
+        what,
+        path.display(),
+        moved_to.display(),
+        what
+      );
+      print_warning(&format!(
+        "The {} file was corrupt and was moved to {}; starting with an empty {}",
+        what,
+        moved_to.display(),
+        what
+      ));
+      Ok(T::default())
+    },
+    other => other,
+  }
+}
@@ -0,0 +1,125 @@
+//! Weekly recurrence for scheduled tasks.
+//!
+//! A recurring task keeps a single entry in the schedule. After it runs, its time is
+//! moved to the next matching day at the same local time of day, so the runner only
+//! ever has to look at one upcoming time per task.
+
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::VestaboardError;
+
+/// All days of the week, Monday first
+const ALL_DAYS: [Weekday; 7] = [
+  Weekday::Mon,
+  Weekday::Tue,
+  Weekday::Wed,
+  Weekday::Thu,
+  Weekday::Fri,
+  Weekday::Sat,
+  Weekday::Sun,
+];
+
+/// Days of the week a task repeats on. Stored in the schedule file as a spec string
+/// such as `"daily"`, `"weekdays"`, `"weekends"`, or `"mon,wed,fri"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recurrence {
+  /// Matching days, Monday first, without duplicates
+  days: Vec<Weekday>,
+}
+
+impl Recurrence {
+  /// Parse a recurrence spec: `daily`, `weekdays`, `weekends`, or a comma-separated
+  /// list of day names (`mon,wed,fri`). Unknown tokens are rejected.
+  pub fn parse(spec: &str) -> Result<Self, VestaboardError> {
+    let spec = spec.trim().to_lowercase();
+    let mut days = Vec::new();
+
+    for token in spec.split(',').map(str::trim) {
+      let matched: Vec<Weekday> = match token {
+        "daily" => ALL_DAYS.to_vec(),
+        "weekdays" => ALL_DAYS[..5].to_vec(),
+        "weekends" => ALL_DAYS[5..].to_vec(),
+        _ => vec![token.parse::<Weekday>().map_err(|_| {
+          VestaboardError::validation_error(&format!(
+            "Unknown repeat '{}'. Use daily, weekdays, weekends, or days like mon,wed,fri.",
+            token
+          ))
+        })?],
+      };
+      days.extend(matched);
+    }
+
+    Ok(Self::from_days(&days))
+  }
+
+  /// Build a recurrence from a list of days (order and duplicates don't matter).
+  pub fn from_days(days: &[Weekday]) -> Self {
+    Self {
+      days: ALL_DAYS.into_iter().filter(|day| days.contains(day)).collect(),
+    }
+  }
+
+  /// Whether the task runs on this day of the week.
+  pub fn matches(&self, day: Weekday) -> bool {
+    self.days.contains(&day)
+  }
+
+  /// The first occurrence at or after `time`, at `time`'s local time of day.
+  pub fn first_at_or_after(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+    self.search(time, time.with_timezone(&Local).date_naive(), |candidate| candidate >= time)
+  }
+
+  /// The next occurrence after both `time` (the last run) and `now`, at `time`'s
+  /// local time of day. Occurrences missed while nothing was running are skipped.
+  pub fn next_after(&self, time: DateTime<Utc>, now: DateTime<Utc>) -> DateTime<Utc> {
+    let after = time.max(now);
+    self.search(time, after.with_timezone(&Local).date_naive(), |candidate| candidate > after)
+  }
+
+  /// Scan a week (plus a day) from `from` for the first matching day whose occurrence is accepted.
+  fn search(&self, time: DateTime<Utc>, from: NaiveDate, accept: impl Fn(DateTime<Utc>) -> bool) -> DateTime<Utc> {
+    let clock = time.with_timezone(&Local).time();
+    (0..=ALL_DAYS.len() as u64)
+      .filter_map(|offset| from.checked_add_days(Days::new(offset)))
+      .filter(|date| self.matches(date.weekday()))
+      // Days where the time of day falls in a DST gap are skipped
+      .filter_map(|date| date.and_time(clock).and_local_timezone(Local).earliest())
+      .map(|local| local.with_timezone(&Utc))
+      .find(|candidate| accept(*candidate))
+      .unwrap_or(time)
+  }
+}
+
+impl fmt::Display for Recurrence {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.days == ALL_DAYS {
+      return write!(f, "daily");
+    }
+    if self.days == ALL_DAYS[..5] {
+      return write!(f, "weekdays");
+    }
+    if self.days == ALL_DAYS[5..] {
+      return write!(f, "weekends");
+    }
+    let names: Vec<String> = self.days.iter().map(|day| day.to_string().to_lowercase()).collect();
+    write!(f, "{}", names.join(","))
+  }
+}
+
+impl TryFrom<String> for Recurrence {
+  type Error = VestaboardError;
+
+  fn try_from(spec: String) -> Result<Self, Self::Error> {
+    Self::parse(&spec)
+  }
+}
+
+impl From<Recurrence> for String {
+  fn from(recurrence: Recurrence) -> Self {
+    recurrence.to_string()
+  }
+}
@@ -0,0 +1,370 @@
+//! Combined runner for `vbl daemon`.
+//!
+//! Shows an optional splash, then rotates through the playlist while the schedule keeps
+//! running. A due scheduled task always wins: it's sent in place of the next playlist
+//! item and stays up for a full playlist interval, after which the playlist picks up at
+//! the item it would have shown next.
+//!
+//! While the `maintenance_file_path` file exists the daemon keeps running but sends nothing.
+//! Tasks that came due during the window are skipped, and the playlist resumes right away
+//! once the file is removed.
+//!
+//! `vbl daemon --once` runs only the schedule, once, for setups that invoke `vbl` from cron.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::Value;
+
+use crate::api::Transport;
+use crate::cli_display::{print_error, print_progress, print_success};
+use crate::config::Config;
+use crate::errors::VestaboardError;
+use crate::playlist::{wait_for_items, Playlist};
+use crate::process_control::ProcessController;
+use crate::recovery::recover_corrupt;
+use crate::runner::common::{render_widget, send_message};
+use crate::runner::keyboard::{InputSource, KeyboardListener};
+use crate::runner::lock::InstanceLock;
+use crate::runner::playlist_runner::PlaylistRunner;
+use crate::runner::schedule_runner::ScheduleRunner;
+use crate::runner::{ControlFlow, Runner, DAEMON_HELP};
+use crate::scheduler::{load_schedule_silent, Schedule, ScheduleMonitor};
+use crate::widgets::widget_utils::Theme;
+
+/// Shown once when a maintenance window begins, if `maintenance_message` is on
+pub const MAINTENANCE_MESSAGE: &str = "down for maintenance";
+
+/// Runner that composes the schedule and playlist runners.
+pub struct CombinedRunner<'a> {
+  pub(crate) schedule: ScheduleRunner<'a>,
+  pub(crate) playlist: PlaylistRunner<'a>,
+  /// Widget and input shown once at startup, held for one playlist interval
+  splash: Option<(String, Value)>,
+  /// File whose presence marks a maintenance window
+  maintenance_path: Option<PathBuf>,
+  /// Send `MAINTENANCE_MESSAGE` when a maintenance window begins
+  maintenance_message: bool,
+  /// A maintenance window is in progress
+  pub(crate) in_maintenance: bool,
+  /// Config the splash and maintenance message are rendered with
+  config: Config,
+  dry_run: bool,
+  transport: &'a Transport,
+}
+
+impl<'a> CombinedRunner<'a> {
+  /// Create a combined runner.
+  ///
+  /// # Arguments
+  /// * `schedule` - Runner for scheduled tasks, which take precedence
+  /// * `playlist` - Runner for the playlist, which fills the time between tasks
+  /// * `dry_run` - If true, display to console instead of Vestaboard
+  /// * `transport` - The transport to use for sending to Vestaboard
+  pub fn new(
+    schedule: ScheduleRunner<'a>,
+    playlist: PlaylistRunner<'a>,
+    dry_run: bool,
+    transport: &'a Transport,
+  ) -> Self {
+    Self {
+      schedule,
+      playlist,
+      splash: None,
+      maintenance_path: None,
+      maintenance_message: false,
+      in_maintenance: false,
+      config: Config::default(),
+      dry_run,
+      transport,
+    }
+  }
+
+  /// Config the splash and maintenance message are rendered with. Defaults to `Config::default()`.
+  pub fn with_config(mut self, config: Config) -> Self {
+    self.config = config;
+    self
+  }
+
+  /// Show this widget first, before the playlist starts rotating.
+  pub fn with_splash(mut self, splash: Option<(String, Value)>) -> Self {
+    self.splash = splash;
+    self
+  }
+
+  /// Send nothing while `path` exists, optionally showing `MAINTENANCE_MESSAGE` once as
+  /// each maintenance window begins.
+  pub fn with_maintenance(mut self, path: Option<PathBuf>, show_message: bool) -> Self {
+    self.maintenance_path = path;
+    self.maintenance_message = show_message;
+    self
+  }
+
+  /// Check the maintenance file, handling the start or end of a window. Returns whether
+  /// sends should be skipped this iteration.
+  async fn check_maintenance(&mut self) -> bool {
+    let active = maintenance_active(self.maintenance_path.as_deref());
+    match (active, self.in_maintenance) {
+      (true, false) => {
+        log::info!("Maintenance window started, pausing sends");
+        print_progress("Maintenance window started; nothing will be sent until it ends");
+        self.in_maintenance = true;
+        if self.maintenance_message {
+          let message =
+            Theme::from_config(&self.config).status_message(MAINTENANCE_MESSAGE, self.config.get_board_size());
+          let _ = send_message(message, "maintenance", self.dry_run, "Maintenance", self.transport, &self.config).await;
+        }
+      },
+      (false, true) => {
+        log::info!("Maintenance window ended, resuming");
+        print_success("Maintenance window ended, resuming");
+        self.in_maintenance = false;
+        // Tasks that came due during the window are skipped rather than sent late
+        self.schedule.skip_missed_tasks();
+        self.playlist.last_display_time = None;
+      },
+      _ => {},
+    }
+    active
+  }
+
+  /// Reload the schedule (e.g. after the schedule file was edited).
+  pub fn reload_schedule(&mut self, schedule: Schedule) {
+    self.schedule.reload_schedule(schedule);
+  }
+
+  /// Display the splash widget.
+  async fn show_splash(&mut self, widget: &str, input: &Value) {
+    log::info!("Showing splash widget {}", widget);
+    print_progress(&format!("Showing splash {}...", widget));
+    let message = render_widget(widget, input, &self.config).await;
+    let _ = send_message(message, widget, self.dry_run, "Splash", self.transport, &self.config).await;
+  }
+}
+
+impl<'a> Runner for CombinedRunner<'a> {
+  fn start(&mut self) {
+    log::info!("Daemon started");
+    self.schedule.start();
+    self.playlist.start();
+  }
+
+  async fn run_iteration(&mut self) -> Result<ControlFlow, VestaboardError> {
+    if self.check_maintenance().await {
+      return Ok(ControlFlow::Continue);
+    }
+
+    if let Some((widget, input)) = self.splash.take() {
+      self.show_splash(&widget, &input).await;
+      self.playlist.restart_interval();
+      return Ok(ControlFlow::Continue);
+    }
+
+    // The schedule goes first so a due task is never delayed by a playlist item
+    let task_due = self.schedule.has_due_task();
+    self.schedule.run_iteration().await?;
+    if task_due {
+      log::info!("Scheduled task took over, playlist resumes at index {}", self.playlist.current_index());
+      self.playlist.restart_interval();
+      return Ok(ControlFlow::Continue);
+    }
+
+    self.playlist.run_iteration().await
+  }
+
+  fn handle_key(&mut self, key: KeyCode) -> ControlFlow {
+    match key {
+      KeyCode::Char('?') => {
+        println!("\n{}\n", self.help_text());
+        ControlFlow::Continue
+      },
+      _ => self.playlist.handle_key(key),
+    }
+  }
+
+  fn help_text(&self) -> &'static str {
+    DAEMON_HELP
+  }
+
+  fn cleanup(&mut self) {
+    self.playlist.cleanup();
+    self.schedule.cleanup();
+    log::info!("Daemon cleanup complete");
+  }
+}
+
+/// Run the daemon: splash, then the playlist with scheduled tasks taking over when due.
+///
+/// The schedule file is hot-reloaded like `vbl schedule run`; the playlist is read once at startup.
+pub async fn run_daemon(dry_run: bool, transport: &Transport) -> Result<(), VestaboardError> {
+  let config = Config::load_silent().unwrap_or_default();
+  let schedule_path = config.get_schedule_file_path();
+
+  // Setup Ctrl+C handler
+  let process_controller = ProcessController::new();
+  process_controller.setup_signal_handler()?;
+
+  let recover = config.get_recover_corrupt_files();
+  let playlist_path = config.get_playlist_file_path();
+  let loaded = Playlist::load_silent(&playlist_path);
+  let recovered = loaded.is_err();
+  let playlist = match recover_corrupt(&playlist_path, "playlist", loaded, recover)? {
+    // The corrupt playlist was moved aside; keep running until items are added again
+    playlist if playlist.is_empty() && recovered => match wait_for_items(&playlist_path, &process_controller).await {
+      Some(playlist) => playlist,
+      None => return Ok(()),
+    },
+    playlist => playlist,
+  };
+  if playlist.is_empty() {
+    println!("Playlist is empty. Add items with: vbl playlist add <widget>");
+    return Ok(());
+  }
+  let schedule = recover_corrupt(&schedule_path, "schedule", load_schedule_silent(&schedule_path), recover)?;
+
+  // Acquire exclusive lock
+  let _lock = InstanceLock::acquire("daemon", &config)?;
+
+  // Create schedule monitor for hot-reload
+  let mut schedule_monitor = ScheduleMonitor::new(&schedule_path).with_recover_corrupt(recover);
+  schedule_monitor.initialize()?;
+
+  let schedule_runner = ScheduleRunner::new(schedule, dry_run, transport)
+    .with_state_path(config.get_schedule_state_path())
+    .with_schedule_path(schedule_path.clone())
+    .with_skip_duplicates(config.get_skip_duplicate_renders())
+    .with_skip_blank(config.get_skip_blank())
+    .with_error_display(config.get_error_display())
+    .with_config(config.clone());
+  let playlist_runner = PlaylistRunner::new(playlist, config.get_runtime_state_path(), 0, false, dry_run, transport)
+    .with_pause_indicator(config.get_pause_indicator())
+    .with_skip_blank(config.get_skip_blank())
+    .with_error_display(config.get_error_display())
+    .with_interval_jitter(config.get_interval_jitter_seconds(), StdRng::from_entropy())
+    .with_config(config.clone());
+  let mut runner = CombinedRunner::new(schedule_runner, playlist_runner, dry_run, transport)
+    .with_splash(config.get_splash())
+    .with_maintenance(config.get_maintenance_file_path(), config.get_maintenance_message())
+    .with_config(config.clone());
+
+  // Setup keyboard listener
+  let mut keyboard = KeyboardListener::new()?;
+
+  // Show initial help
+  println!("Press ? for help, q to quit.");
+
+  runner.start();
+
+  // Main loop
+  loop {
+    // Priority 1: Check for shutdown signal (Ctrl+C)
+    if process_controller.should_shutdown() {
+      log::info!("Shutdown requested, stopping daemon");
+      println!("\nShutting down...");
+      break;
+    }
+
+    // Priority 2: Check for keyboard input (non-blocking)
+    if let Some(key) = keyboard.try_recv() {
+      if runner.handle_key(key) == ControlFlow::Exit {
+        log::info!("User requested exit via keyboard");
+        break;
+      }
+    }
+
+    // Priority 3: Check for schedule file changes (hot-reload)
+    match schedule_monitor.reload_if_modified() {
+      Ok(true) => {
+        log::info!("Schedule file updated, reloading");
+        runner.reload_schedule(schedule_monitor.get_current_schedule().clone());
+        print_success(&format!("Schedule reloaded ({} tasks)", schedule_monitor.get_current_schedule().tasks.len()));
+      },
+      Ok(false) => {},
+      Err(e) => {
+        log::warn!("Error checking for schedule updates: {}", e);
+      },
+    }
+
+    // Priority 4: Run one iteration of the runner
+    match runner.run_iteration().await {
+      Ok(ControlFlow::Continue) => {},
+      Ok(ControlFlow::Exit) => {
+        log::info!("Runner requested exit");
+        break;
+      },
+      Err(e) => {
+        log::error!("Runner error: {}", e);
+        print_error(&e.to_user_message());
+      },
+    }
+
+    // Small sleep to prevent busy-looping
+    tokio::time::sleep(Duration::from_millis(100)).await;
+  }
+
+  runner.cleanup();
+  print_success("Daemon stopped.");
+
+  Ok(())
+}
+
+/// Whether a maintenance window is in progress: the maintenance file is set and exists.
+pub(crate) fn maintenance_active(path: Option<&Path>) -> bool {
+  path.is_some_and(|path| path.exists())
+}
+
+/// Run the schedule once for `vbl daemon --once`: fire the tasks that are due (catching up on
+/// any missed within `catch_up_minutes`), wait for one coming due within a minute, then exit.
+/// Nothing is sent while a maintenance window is in progress.
+///
+/// Meant for cron-driven setups, so the playlist, splash, and keyboard controls are left out.
+pub async fn run_daemon_once(dry_run: bool, transport: &Transport) -> Result<(), VestaboardError> {
+  let config = Config::load_silent().unwrap_or_default();
+  let schedule_path = config.get_schedule_file_path();
+  let loaded = load_schedule_silent(&schedule_path);
+  let schedule = recover_corrupt(&schedule_path, "schedule", loaded, config.get_recover_corrupt_files())?;
+  if schedule.tasks.is_empty() {
+    println!("Schedule is empty. Add tasks with: vbl schedule add <time> <widget>");
+    return Ok(());
+  }
+  if maintenance_active(config.get_maintenance_file_path().as_deref()) {
+    log::info!("Maintenance window in progress, not running the schedule");
+    println!("Maintenance window in progress, nothing sent.");
+    return Ok(());
+  }
+
+  let _lock = InstanceLock::acquire("daemon", &config)?;
+
+  let mut runner = ScheduleRunner::new(schedule, dry_run, transport)
+    .with_state_path(config.get_schedule_state_path())
+    .with_schedule_path(schedule_path)
+    .with_catch_up(Some(config.get_catch_up_window()))
+    .with_skip_duplicates(config.get_skip_duplicate_renders())
+    .with_skip_blank(config.get_skip_blank())
+    .with_error_display(config.get_error_display())
+    .with_config(config.clone())
+    .with_run_once(true);
+
+  let process_controller = ProcessController::new();
+  process_controller.setup_signal_handler()?;
+
+  runner.start();
+  while !process_controller.should_shutdown() {
+    match runner.run_iteration().await {
+      Ok(ControlFlow::Continue) => {},
+      Ok(ControlFlow::Exit) => break,
+      Err(e) => {
+        log::error!("Runner error: {}", e);
+        print_error(&e.to_user_message());
+      },
+    }
+    tokio::time::sleep(Duration::from_millis(100)).await;
+  }
+
+  runner.cleanup();
+  print_success("Schedule run complete.");
+  Ok(())
+}
@@ -4,45 +4,67 @@ use serde_json::Value;
 
 use crate::api::Transport;
 use crate::api_broker::{handle_message, MessageDestination};
-use crate::cli_display::{print_error, print_success};
+use crate::cli_display::{print_error, print_success, print_warning};
+use crate::config::Config;
 use crate::errors::VestaboardError;
-use crate::widgets::resolver::execute_widget;
-use crate::widgets::widget_utils::error_to_display_message;
+use crate::widgets::resolver::{error_display, execute_widget};
+use crate::widgets::widget_utils::{center_line, is_blank_message, MAX_MESSAGE_HEIGHT};
 
-/// Execute a widget and send the result to the appropriate destination.
-///
-/// This function handles the common pattern of:
-/// 1. Executing a widget to generate a message
-/// 2. Converting errors to display messages (so the board shows something)
-/// 3. Sending to Vestaboard or console based on dry_run mode
-/// 4. Logging success/failure
+/// Text shown on the bottom row while a runner is paused
+pub const PAUSED_INDICATOR: &str = "paused";
+
+/// Source the paused indicator frame is sent under, so it's kept out of the history
+pub const PAUSED_INDICATOR_SOURCE: &str = "paused-indicator";
+
+/// Execute a widget, converting errors to a display message so the board always shows something.
+pub async fn render_widget(widget: &str, input: &Value, config: &Config) -> Vec<String> {
+  match execute_widget(widget, input, config).await {
+    Ok(msg) => msg,
+    Err(e) => widget_error_message(widget, &e, config),
+  }
+}
+
+/// Report a widget failure and build the error message shown in its place.
+pub fn widget_error_message(widget: &str, error: &VestaboardError, config: &Config) -> Vec<String> {
+  log::error!("Widget '{}' failed: {}", widget, error);
+  print_error(&format!("Widget {} failed: {}", widget, error.to_user_message()));
+  error_display(error, config)
+}
+
+/// Send an already-rendered message to Vestaboard, or to the console in dry-run mode.
 ///
-/// # Arguments
-/// * `widget` - The widget type to execute (e.g., "weather", "text")
-/// * `input` - JSON input for the widget
-/// * `dry_run` - If true, display to console instead of Vestaboard
-/// * `label` - A label for logging (e.g., "task abc123", "item weather")
-/// * `transport` - The transport to use for sending to Vestaboard
+/// With `skip_blank` set in `config`, a blank render is skipped instead of sent (see
+/// `send_unless_blank`).
+pub async fn send_message(
+  message: Vec<String>,
+  widget: &str,
+  dry_run: bool,
+  label: &str,
+  transport: &Transport,
+  config: &Config,
+) -> Result<(), VestaboardError> {
+  send_unless_blank(message, widget, dry_run, label, transport, config, config.get_skip_blank()).await
+}
+
+/// Like `send_message`, but takes the `skip_blank` setting directly.
 ///
-/// # Returns
-/// * `Ok(())` - Message was sent successfully
-/// * `Err(VestaboardError)` - Failed to send message (widget errors are handled internally)
-pub async fn execute_and_send(
+/// When `skip_blank` is true, a live send whose message is entirely blank logs a warning and
+/// sends nothing, so a misconfigured widget doesn't leave the board looking dead. The clear
+/// widget is meant to be blank and dry runs always show the render, so neither is skipped.
+pub async fn send_unless_blank(
+  message: Vec<String>,
   widget: &str,
-  input: &Value,
   dry_run: bool,
   label: &str,
   transport: &Transport,
+  config: &Config,
+  skip_blank: bool,
 ) -> Result<(), VestaboardError> {
-  // Execute widget, converting errors to display messages
-  let message = match execute_widget(widget, input).await {
-    Ok(msg) => msg,
-    Err(e) => {
-      log::error!("Widget '{}' failed: {}", widget, e);
-      print_error(&format!("Widget {} failed: {}", widget, e.to_user_message()));
-      error_to_display_message(&e)
-    },
-  };
+  if skip_blank && !dry_run && widget != "clear" && is_blank_message(&message) {
+    log::warn!("{}: {} rendered a blank message, not sending it", label, widget);
+    print_warning(&format!("{}: {} rendered a blank message, skipped", label, widget));
+    return Ok(());
+  }
 
   // Determine destination based on dry_run mode
   let destination = if dry_run {
@@ -52,7 +74,7 @@ pub async fn execute_and_send(
   };
 
   // Send message
-  match handle_message(message, destination, transport).await {
+  match handle_message(message, widget, destination, transport, config).await {
     Ok(_) => {
       log::info!("{} completed successfully", label);
       print_success(&format!("{} completed", label));
@@ -65,3 +87,12 @@ pub async fn execute_and_send(
     },
   }
 }
+
+/// Build the paused frame: the message on the board with its bottom row replaced by
+/// the paused indicator.
+pub fn paused_frame(message: &[String]) -> Vec<String> {
+  let mut frame: Vec<String> = message.iter().take(MAX_MESSAGE_HEIGHT - 1).cloned().collect();
+  frame.resize(MAX_MESSAGE_HEIGHT - 1, String::new());
+  frame.push(center_line(PAUSED_INDICATOR.to_string()));
+  frame
+}
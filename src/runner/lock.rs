@@ -14,11 +14,12 @@
 
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::config::Config;
 use crate::errors::VestaboardError;
 
 /// Data stored in the lock file
@@ -41,16 +42,17 @@ pub struct InstanceLock {
 }
 
 impl InstanceLock {
-  /// Acquire an exclusive lock at the default location.
+  /// Acquire an exclusive lock at the configured `lock_file_path`.
   ///
   /// # Arguments
   /// * `mode` - The mode name ("playlist" or "schedule") for error messages
+  /// * `config` - Loaded config supplying the lock file path
   ///
   /// # Returns
   /// * `Ok(InstanceLock)` - Lock acquired successfully
   /// * `Err(VestaboardError)` - Lock could not be acquired (another instance running)
-  pub fn acquire(mode: &str) -> Result<Self, VestaboardError> {
-    Self::acquire_at(mode, &PathBuf::from("data/vestaboard.lock"))
+  pub fn acquire(mode: &str, config: &Config) -> Result<Self, VestaboardError> {
+    Self::acquire_at(mode, &config.get_lock_file_path())
   }
 
   /// Acquire an exclusive lock at a specific path (useful for testing).
@@ -136,6 +138,66 @@ impl Drop for InstanceLock {
   }
 }
 
+/// What a lock file on disk says about who holds it.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LockStatus {
+  /// No lock file
+  Free,
+  /// Held by a running process
+  Held { mode: String, pid: u32 },
+  /// Left by a process that is no longer running, or unreadable
+  Stale,
+}
+
+/// Inspect a lock file without acquiring it.
+pub fn lock_status(path: &Path) -> LockStatus {
+  if !path.exists() {
+    return LockStatus::Free;
+  }
+  match fs::read_to_string(path)
+    .ok()
+    .and_then(|content| serde_json::from_str::<LockData>(&content).ok())
+  {
+    Some(lock_data) if is_pid_running(lock_data.pid) => LockStatus::Held {
+      mode: lock_data.mode,
+      pid: lock_data.pid,
+    },
+    _ => LockStatus::Stale,
+  }
+}
+
+/// What `unlock` did with a lock file.
+#[derive(Debug, PartialEq, Eq)]
+pub enum UnlockOutcome {
+  /// There was no lock file
+  NoLock,
+  /// Removed a lock left by a process that is no longer running, or an unreadable one
+  RemovedStale,
+  /// Removed a lock held by a running process (only with `force`)
+  RemovedLive { mode: String, pid: u32 },
+}
+
+/// Remove a stale lock file, or one held by a running process when `force` is set.
+///
+/// `acquire` already takes over stale locks; this is for clearing one by hand.
+pub fn unlock(path: &Path, force: bool) -> Result<UnlockOutcome, VestaboardError> {
+  let outcome = match lock_status(path) {
+    LockStatus::Free => return Ok(UnlockOutcome::NoLock),
+    LockStatus::Held { mode, pid } if !force => {
+      return Err(VestaboardError::lock_error(&format!(
+        "{} is still running (PID {}); stop it first, or use --force to remove the lock anyway",
+        mode, pid
+      )));
+    },
+    LockStatus::Held { mode, pid } => UnlockOutcome::RemovedLive { mode, pid },
+    LockStatus::Stale => UnlockOutcome::RemovedStale,
+  };
+
+  fs::remove_file(path).map_err(|e| VestaboardError::lock_error(&format!("Cannot remove lock file: {}", e)))?;
+  log::info!("Removed lock file {} ({:?})", path.display(), outcome);
+  Ok(outcome)
+}
+
 /// Check if a process with the given PID is running.
 ///
 /// This is a cross-platform implementation that attempts to detect if a process exists.
@@ -3,11 +3,14 @@
 //! This module provides shared infrastructure for running playlists and schedules
 //! with keyboard controls, instance locking, and graceful shutdown.
 
+pub mod combined_runner;
 pub mod common;
 pub mod keyboard;
 pub mod lock;
 pub mod playlist_runner;
 pub mod schedule_runner;
+pub mod timer;
+pub mod tui;
 
 use crossterm::event::KeyCode;
 
@@ -28,6 +31,15 @@ Schedule Controls:
   q - Quit
   ? - Show this help";
 
+/// Help text for daemon keyboard controls
+pub const DAEMON_HELP: &str = "\
+Daemon Controls:
+  p - Pause playlist rotation (scheduled tasks still run)
+  r - Resume rotation
+  n - Show next playlist item now
+  q - Quit
+  ? - Show this help";
+
 /// Control flow decision after handling an event
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ControlFlow {
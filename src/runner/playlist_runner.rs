@@ -4,25 +4,35 @@
 //! and widget display.
 
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crossterm::event::KeyCode;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 
 use crate::api::Transport;
 use crate::cli_display::print_progress;
+use crate::config::Config;
 use crate::errors::VestaboardError;
-use crate::playlist::Playlist;
-use crate::runner::common::execute_and_send;
+use crate::playlist::{jittered_interval, OnError, Playlist};
+use crate::runner::common::{paused_frame, send_unless_blank, widget_error_message, PAUSED_INDICATOR_SOURCE};
+use crate::runner::tui::DashboardView;
 use crate::runner::{ControlFlow, Runner, PLAYLIST_HELP};
-use crate::runtime_state::{PlaylistState, RuntimeState};
+use crate::runtime_state::{PlaylistState, PlaylistStats, RuntimeState};
+use crate::widgets::resolver::execute_widget;
 
 /// Playlist runner that handles playlist execution with keyboard controls.
 pub struct PlaylistRunner<'a> {
   playlist: Playlist,
   state: PlaylistState,
   current_index: usize,
+  /// Index of the item most recently displayed (None until the first display)
+  last_shown_index: Option<usize>,
   state_path: PathBuf,
   run_once: bool,
+  /// Rotate backward through the playlist instead of forward
+  reverse: bool,
   cycle_complete: bool,
   /// Time of last display, used for interval timing.
   /// None means ready to display immediately (at startup or after 'n' pressed).
@@ -31,6 +41,30 @@ pub struct PlaylistRunner<'a> {
   paused_at: Option<Instant>,
   dry_run: bool,
   transport: &'a Transport,
+  /// Display counters, carried over from the saved state file
+  stats: PlaylistStats,
+  /// Show a paused indicator on the board while paused
+  pause_indicator: bool,
+  /// Don't send an item that renders blank
+  skip_blank: bool,
+  /// Indicator change (true = show, false = restore) waiting for the next iteration
+  pending_indicator: Option<bool>,
+  /// Message most recently sent for a playlist item, restored on resume
+  last_message: Option<Vec<String>>,
+  /// Items skipped in a row because their widget failed with `on_error: skip`
+  consecutive_skips: usize,
+  /// How long an error frame stays up before the failed item is retried (None = whole interval)
+  error_display: Option<Duration>,
+  /// The current item failed and is retried once the error display time has passed
+  retry_pending: bool,
+  /// Seconds each interval may randomly run shorter or longer (0 = every interval is the same)
+  interval_jitter_seconds: u64,
+  /// Random source for the interval jitter
+  jitter_rng: StdRng,
+  /// Seconds the current item stays up, drawn with jitter each time an interval starts
+  pub(crate) current_interval_seconds: u64,
+  /// Config the widgets are rendered with
+  config: Config,
 }
 
 impl<'a> PlaylistRunner<'a> {
@@ -51,21 +85,40 @@ impl<'a> PlaylistRunner<'a> {
     dry_run: bool,
     transport: &'a Transport,
   ) -> Self {
+    let stats = RuntimeState::load(&state_path).stats;
+    let current_interval_seconds = playlist.interval_seconds;
     Self {
       playlist,
       state: PlaylistState::Stopped,
       current_index: start_index,
+      last_shown_index: None,
       state_path,
       run_once,
+      reverse: false,
       cycle_complete: false,
       last_display_time: None,
       paused_at: None,
       dry_run,
       transport,
+      stats,
+      pause_indicator: false,
+      skip_blank: false,
+      pending_indicator: None,
+      last_message: None,
+      consecutive_skips: 0,
+      error_display: None,
+      retry_pending: false,
+      interval_jitter_seconds: 0,
+      jitter_rng: StdRng::from_entropy(),
+      current_interval_seconds,
+      config: Config::default(),
     }
   }
 
   /// Restore from saved state if available.
+  ///
+  /// A `pinned_first` item overrides the saved index, so the run starts there and then
+  /// continues in order as usual.
   pub fn restore_from_state(
     playlist: Playlist,
     state_path: PathBuf,
@@ -75,10 +128,13 @@ impl<'a> PlaylistRunner<'a> {
   ) -> Self {
     let saved_state = RuntimeState::load(&state_path);
 
-    let start_index = if saved_state.playlist_index < playlist.len() {
-      saved_state.playlist_index
-    } else {
-      0
+    let start_index = match playlist.items.iter().position(|item| item.pinned_first) {
+      Some(pinned) => {
+        log::info!("Starting at pinned item {} instead of saved index {}", pinned, saved_state.playlist_index);
+        pinned
+      },
+      None if saved_state.playlist_index < playlist.len() => saved_state.playlist_index,
+      None => 0,
     };
 
     log::info!("Restored playlist state: index={}", start_index);
@@ -86,6 +142,72 @@ impl<'a> PlaylistRunner<'a> {
     Self::new(playlist, state_path, start_index, run_once, dry_run, transport)
   }
 
+  /// Rotate backward (last item to first) instead of forward.
+  pub fn with_reverse(mut self, reverse: bool) -> Self {
+    self.reverse = reverse;
+    self
+  }
+
+  /// Shuffle the items once and start from the first, so each cycle plays them in the same
+  /// random order and `--once` stops after every item has been shown exactly once. A
+  /// `pinned_first` item still leads. The playlist file is not changed.
+  pub fn with_shuffle<R: Rng + ?Sized>(mut self, shuffle: bool, rng: &mut R) -> Self {
+    if shuffle {
+      self.playlist.items.shuffle(rng);
+      if let Some(pinned) = self.playlist.items.iter().position(|item| item.pinned_first) {
+        self.playlist.items[..=pinned].rotate_right(1);
+      }
+      self.current_index = 0;
+      log::info!("Shuffled playlist order: {:?}", self.playlist.items.iter().map(|item| &item.id).collect::<Vec<_>>());
+    }
+    self
+  }
+
+  /// Override the playlist's rotation interval for this run only; the playlist file is not changed.
+  pub fn with_interval(mut self, interval_seconds: Option<u64>) -> Result<Self, VestaboardError> {
+    if let Some(seconds) = interval_seconds {
+      self.playlist.interval_seconds = seconds;
+      self.playlist.validate_interval()?;
+      self.current_interval_seconds = seconds;
+    }
+    Ok(self)
+  }
+
+  /// Randomly shorten or lengthen each interval by up to `jitter_seconds`, drawing from `rng`,
+  /// so several boards running the same playlist spread out their sends.
+  pub fn with_interval_jitter(mut self, jitter_seconds: u64, rng: StdRng) -> Self {
+    self.interval_jitter_seconds = jitter_seconds;
+    self.jitter_rng = rng;
+    self
+  }
+
+  /// Mark the board as paused while paused, restoring the item on resume.
+  pub fn with_pause_indicator(mut self, pause_indicator: bool) -> Self {
+    self.pause_indicator = pause_indicator;
+    self
+  }
+
+  /// Leave the previous item up instead of sending an item that renders blank (see
+  /// `send_unless_blank`).
+  pub fn with_skip_blank(mut self, skip_blank: bool) -> Self {
+    self.skip_blank = skip_blank;
+    self
+  }
+
+  /// After showing a widget's error for `error_display`, retry that item once instead of
+  /// leaving the error up for the whole interval. If the retry fails too, its error stays up
+  /// for the interval as usual.
+  pub fn with_error_display(mut self, error_display: Option<Duration>) -> Self {
+    self.error_display = error_display;
+    self
+  }
+
+  /// Config the widgets are rendered with. Defaults to `Config::default()`.
+  pub fn with_config(mut self, config: Config) -> Self {
+    self.config = config;
+    self
+  }
+
   /// Get the current index in the playlist.
   pub fn current_index(&self) -> usize {
     self.current_index
@@ -96,6 +218,42 @@ impl<'a> PlaylistRunner<'a> {
     self.state
   }
 
+  /// Get the display counters for this playlist.
+  pub fn stats(&self) -> &PlaylistStats {
+    &self.stats
+  }
+
+  /// Build a snapshot of the runner for the dashboard.
+  pub fn dashboard_view(&self) -> DashboardView {
+    let describe = |index: usize| {
+      self
+        .playlist
+        .get_item_by_index(index)
+        .map(|item| format!("{} [{}]", item.widget, item.id))
+    };
+    let interval = Duration::from_secs(self.current_interval_seconds);
+
+    DashboardView {
+      state: self.state,
+      current: self.last_shown_index.and_then(describe),
+      next: describe(self.current_index),
+      next_index: self.current_index,
+      total: self.playlist.len(),
+      remaining: self
+        .last_display_time
+        .map(|last| interval.saturating_sub(last.elapsed())),
+      dry_run: self.dry_run,
+    }
+  }
+
+  /// Start a fresh interval without advancing, so whatever was just put on the board
+  /// (e.g. a scheduled task) stays up before the next item is shown.
+  pub fn restart_interval(&mut self) {
+    self.last_display_time = Some(Instant::now());
+    self.current_interval_seconds =
+      jittered_interval(self.playlist.interval_seconds, self.interval_jitter_seconds, &mut self.jitter_rng);
+  }
+
   /// Check if the playlist has completed a full cycle (for --once mode).
   pub fn is_complete(&self) -> bool {
     self.cycle_complete
@@ -109,6 +267,9 @@ impl<'a> PlaylistRunner<'a> {
       self.state = PlaylistState::Paused;
       self.paused_at = Some(Instant::now());
       self.save_state();
+      if self.pause_indicator {
+        self.pending_indicator = Some(true);
+      }
       log::info!("Playlist paused at index {}", self.current_index);
       println!("Paused.");
     }
@@ -133,6 +294,9 @@ impl<'a> PlaylistRunner<'a> {
       self.paused_at = None;
 
       self.save_state();
+      if self.pause_indicator {
+        self.pending_indicator = Some(false);
+      }
       log::info!("Playlist resumed from index {}", self.current_index);
       println!("Resumed.");
     }
@@ -170,17 +334,29 @@ impl<'a> PlaylistRunner<'a> {
   ///
   /// Advances the index and logs the action. Does NOT clear the display timer.
   pub fn skip_to_next(&mut self) {
+    self.retry_pending = false;
     self.advance_index();
     log::info!("Skipped to item {}", self.current_index);
     println!("Skipping to next item...");
   }
 
-  /// Advance to the next index (wrapping around).
+  /// Advance to the next index (wrapping around), moving backward in reverse mode.
   fn advance_index(&mut self) {
     if self.playlist.is_empty() {
       return;
     }
 
+    if self.reverse {
+      // Wrapping past the first item completes a reverse cycle
+      if self.current_index == 0 {
+        self.current_index = self.playlist.len() - 1;
+        self.cycle_complete = true;
+      } else {
+        self.current_index -= 1;
+      }
+      return;
+    }
+
     self.current_index = (self.current_index + 1) % self.playlist.len();
 
     // Check if we completed a full cycle
@@ -190,13 +366,14 @@ impl<'a> PlaylistRunner<'a> {
   }
 
   /// Check if it's time to display the next item.
-  fn should_display_next(&self) -> bool {
+  pub(crate) fn should_display_next(&self) -> bool {
     match self.state {
-      PlaylistState::Running => match self.last_display_time {
-        None => true, // First display
-        Some(last) => {
+      PlaylistState::Running => match (self.last_display_time, self.error_display) {
+        (None, _) => true, // First display
+        (Some(last), Some(error_display)) if self.retry_pending => last.elapsed() >= error_display,
+        (Some(last), _) => {
           let elapsed = last.elapsed().as_secs();
-          elapsed >= self.playlist.interval_seconds
+          elapsed >= self.current_interval_seconds
         },
       },
       _ => false,
@@ -209,10 +386,32 @@ impl<'a> PlaylistRunner<'a> {
       playlist_state: self.state,
       playlist_index: self.current_index,
       last_shown_time: Some(chrono::Utc::now()),
+      stats: self.stats.clone(),
     };
     state.save(&self.state_path);
   }
 
+  /// Send the paused indicator, or put the last item back after resuming.
+  async fn update_pause_indicator(&mut self, show: bool) {
+    let message = match (show, &self.last_message) {
+      (true, last) => paused_frame(last.as_deref().unwrap_or_default()),
+      // Nothing to restore, or the next item is due and will replace the indicator anyway
+      (false, None) => return,
+      (false, Some(_)) if self.should_display_next() => return,
+      (false, Some(last)) => last.clone(),
+    };
+    let (source, label) = if show {
+      (PAUSED_INDICATOR_SOURCE, "Paused indicator")
+    } else {
+      ("playlist", "Restore after pause")
+    };
+    if let Err(e) =
+      send_unless_blank(message, source, self.dry_run, label, self.transport, &self.config, self.skip_blank).await
+    {
+      log::warn!("Failed to update paused indicator: {}", e);
+    }
+  }
+
   /// Display the current playlist item.
   async fn display_current_item(&mut self) -> Result<(), VestaboardError> {
     let item = match self.playlist.get_item_by_index(self.current_index) {
@@ -236,11 +435,47 @@ impl<'a> PlaylistRunner<'a> {
     self.save_state();
 
     let label = format!("Item {}", item.widget);
-    // Ignore the result - we want to continue even if sending fails
-    let _ = execute_and_send(&item.widget, &item.input, self.dry_run, &label, self.transport).await;
+    let is_retry = std::mem::take(&mut self.retry_pending);
+    let message = match execute_widget(&item.widget, &item.input, &self.config).await {
+      Ok(message) => message,
+      Err(e) if item.on_error == OnError::Skip => {
+        log::warn!("Widget '{}' failed, skipping item {}: {}", item.widget, item.id, e);
+        print_progress(&format!("{} failed, skipping to the next item", item.widget));
+        self.stats.record_error(&e.to_user_message());
+        self.save_state();
+        // Leave the display timer alone so the next item shows right away. If every item
+        // has been skipped in a row, wait out the interval instead of retrying in a tight loop.
+        self.consecutive_skips += 1;
+        if self.consecutive_skips >= self.playlist.len() {
+          self.consecutive_skips = 0;
+          self.restart_interval();
+        }
+        return Ok(());
+      },
+      Err(e) => {
+        if self.error_display.is_some() && !is_retry {
+          log::info!("Widget '{}' failed, retrying item {} after {:?}", item.widget, item.id, self.error_display);
+          self.retry_pending = true;
+        }
+        widget_error_message(&item.widget, &e, &self.config)
+      },
+    };
+    self.consecutive_skips = 0;
+    self.last_message = Some(message.clone());
+    // Record failures in stats, but continue even if sending fails. Only items that reached
+    // the board count as displayed.
+    match send_unless_blank(message, &item.widget, self.dry_run, &label, self.transport, &self.config, self.skip_blank)
+      .await
+    {
+      Ok(()) if !self.dry_run => self.stats.record_display(&item.widget),
+      Ok(()) => {},
+      Err(e) => self.stats.record_error(&e.to_user_message()),
+    }
+    self.save_state();
 
     // Always update display time to maintain interval timing
-    self.last_display_time = Some(Instant::now());
+    self.restart_interval();
+    self.last_shown_index = Some(self.current_index);
 
     Ok(())
   }
@@ -277,10 +512,17 @@ impl<'a> Runner for PlaylistRunner<'a> {
       return Ok(ControlFlow::Exit);
     }
 
+    if let Some(show) = self.pending_indicator.take() {
+      self.update_pause_indicator(show).await;
+    }
+
     // Only display if running and interval has elapsed
     if self.should_display_next() {
       self.display_current_item().await?;
-      self.advance_index();
+      // A failed item waiting to be retried stays current
+      if !self.retry_pending {
+        self.advance_index();
+      }
     }
 
     Ok(ControlFlow::Continue)
@@ -1,25 +1,66 @@
 //! Schedule runner implementation.
 //!
 //! Handles schedule execution with interactive controls and hot-reload support.
-//! This runner skips past-due tasks and waits for the next upcoming task.
+//! This runner skips tasks missed while it wasn't running (unless catching up) and waits
+//! for the next upcoming task.
 
 use std::collections::HashSet;
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use chrono::Utc;
+use chrono::{Local, Utc};
 use crossterm::event::KeyCode;
+use serde_json::Value;
 
 use crate::api::Transport;
+use crate::api_broker::message_to_codes;
 use crate::cli_display::print_progress;
+use crate::config::Config;
 use crate::errors::VestaboardError;
-use crate::runner::common::execute_and_send;
+use crate::runner::common::{render_widget, send_unless_blank, widget_error_message};
 use crate::runner::{ControlFlow, Runner, SCHEDULE_HELP};
-use crate::scheduler::{Schedule, ScheduledTask};
+use crate::runtime_state::ScheduleState;
+use crate::scheduler::{load_schedule_silent, save_schedule_silent, time_until, Schedule, ScheduledTask};
+use crate::widgets::resolver::execute_widget;
+
+/// Minimum quiet time, both since the last task and until the next, before idle content is shown
+pub const IDLE_MIN_GAP: Duration = Duration::from_secs(30 * 60);
+
+/// How long a one-shot run waits for an upcoming task before exiting
+pub const ONCE_WINDOW: Duration = Duration::from_secs(60);
 
 /// Schedule runner that handles schedule execution with keyboard controls.
 pub struct ScheduleRunner<'a> {
   schedule: Schedule,
   executed_task_ids: HashSet<String>,
+  /// Tasks whose time passed before the runner started, outside the catch-up window
+  missed_task_ids: HashSet<String>,
+  /// How far back a task missed before startup can be and still fire once; None skips them all
+  catch_up: Option<chrono::Duration>,
+  /// Where executed task IDs are persisted; None keeps them in memory only
+  state_path: Option<PathBuf>,
+  /// Schedule file to update when a recurring task moves to its next time
+  schedule_path: Option<PathBuf>,
+  /// Skip a task whose rendered grid matches the previous task in the same pass
+  skip_duplicates: bool,
+  /// Don't send a task or idle widget that renders blank
+  skip_blank: bool,
+  /// Grid sent by the previous task in the current pass of due tasks
+  last_rendered: Option<[[u8; 22]; 6]>,
+  /// Widget and input shown during long gaps between tasks
+  idle: Option<(String, Value)>,
+  /// Whether idle content has been shown since the last task
+  idle_shown: bool,
+  /// When the last task ran, so its content stays up for a while before going idle
+  last_task_at: Option<Instant>,
+  /// For a one-shot run, when to stop waiting for upcoming tasks; None runs until quit
+  once_until: Option<Instant>,
+  /// How long a failed task's error stays up before the task is retried; None never retries
+  error_display: Option<Duration>,
+  /// A task whose widget failed, and when to retry it
+  retry: Option<(ScheduledTask, Instant)>,
+  /// Config the widgets are rendered with
+  config: Config,
   dry_run: bool,
   transport: &'a Transport,
 }
@@ -35,25 +76,182 @@ impl<'a> ScheduleRunner<'a> {
     Self {
       schedule,
       executed_task_ids: HashSet::new(),
+      missed_task_ids: HashSet::new(),
+      catch_up: None,
+      state_path: None,
+      schedule_path: None,
+      skip_duplicates: false,
+      skip_blank: false,
+      last_rendered: None,
+      idle: None,
+      idle_shown: false,
+      last_task_at: None,
+      once_until: None,
+      error_display: None,
+      retry: None,
+      config: Config::default(),
       dry_run,
       transport,
     }
   }
 
-  /// Get the next pending task that is due or in the future.
+  /// Persist executed task IDs to `state_path`, and skip tasks already executed today.
   ///
-  /// Skips past-due tasks and returns the soonest future task that
-  /// hasn't been executed yet.
-  pub fn next_pending_task(&self) -> Option<&ScheduledTask> {
-    let now = Utc::now();
+  /// Without this, restarting the runner would re-fire any past-due task.
+  pub fn with_state_path(mut self, state_path: PathBuf) -> Self {
+    self.state_path = Some(state_path);
+    self.executed_task_ids = self.load_executed_today();
+    log::info!("Loaded {} task(s) already executed today", self.executed_task_ids.len());
+    self
+  }
+
+  /// Save recurring tasks' next times to the schedule file at `schedule_path`.
+  ///
+  /// Without this, a recurring task only moves forward in memory.
+  pub fn with_schedule_path(mut self, schedule_path: PathBuf) -> Self {
+    self.schedule_path = Some(schedule_path);
+    self
+  }
+
+  /// Skip sending a task whose rendered grid equals the previous task's, when several
+  /// tasks come due back to back (e.g. two "clear" tasks), to avoid redundant board updates.
+  pub fn with_skip_duplicates(mut self, skip_duplicates: bool) -> Self {
+    self.skip_duplicates = skip_duplicates;
+    self
+  }
+
+  /// Leave the board as it is instead of sending a task or idle widget that renders blank
+  /// (see `send_unless_blank`).
+  pub fn with_skip_blank(mut self, skip_blank: bool) -> Self {
+    self.skip_blank = skip_blank;
+    self
+  }
+
+  /// Fire tasks missed within `window` before startup (e.g. after a crash or reboot) once,
+  /// instead of skipping them.
+  pub fn with_catch_up(mut self, window: Option<chrono::Duration>) -> Self {
+    self.catch_up = window;
+    self
+  }
 
+  /// Run once: handle the tasks that are due, plus any coming due within `ONCE_WINDOW`, then
+  /// ask to exit instead of waiting for later tasks or showing idle content.
+  pub fn with_run_once(mut self, run_once: bool) -> Self {
+    self.once_until = run_once.then(|| Instant::now() + ONCE_WINDOW);
     self
+  }
+
+  /// After showing a task's widget error for `error_display`, run the task once more instead
+  /// of leaving the error up until the next task. If the retry fails too, its error stays up.
+  pub fn with_error_display(mut self, error_display: Option<Duration>) -> Self {
+    self.error_display = error_display;
+    self
+  }
+
+  /// Config the widgets are rendered with. Defaults to `Config::default()`.
+  pub fn with_config(mut self, config: Config) -> Self {
+    self.config = config;
+    self
+  }
+
+  /// Whether a one-shot run has nothing left to do: no task is due and none comes due in its
+  /// window, and no retry is pending within it.
+  fn once_finished(&self) -> bool {
+    let Some(until) = self.once_until else {
+      return false;
+    };
+    let upcoming = self
+      .time_until_next_task()
+      .is_some_and(|wait| Instant::now() + wait <= until);
+    let retrying = self.retry.as_ref().is_some_and(|(_, at)| *at <= until);
+    !self.has_due_task() && !upcoming && !retrying
+  }
+
+  /// Take the pending retry if its error has been up for `error_display`.
+  fn take_due_retry(&mut self) -> Option<ScheduledTask> {
+    match &self.retry {
+      Some((_, at)) if Instant::now() >= *at => self.retry.take().map(|(task, _)| task),
+      _ => None,
+    }
+  }
+
+  /// Sort out tasks whose time passed without running (before startup, or during a daemon
+  /// maintenance window): those within the catch-up window stay due, recurring tasks move to
+  /// their next time, and the rest are skipped.
+  pub(crate) fn skip_missed_tasks(&mut self) {
+    let now = Utc::now();
+    let cutoff = now - self.catch_up.unwrap_or_else(chrono::Duration::zero);
+    let missed: Vec<ScheduledTask> = self
       .schedule
       .tasks
       .iter()
-      .filter(|task| !self.executed_task_ids.contains(&task.id))
-      .filter(|task| task.time > now)
-      .min_by_key(|task| task.time)
+      .filter(|task| task.time <= now && !self.executed_task_ids.contains(&task.id))
+      .cloned()
+      .collect();
+
+    for task in missed {
+      if task.time >= cutoff {
+        log::info!("Catching up on task {} missed at {}", task.id, task.time);
+      } else if task.recurrence.is_some() {
+        self.reschedule_recurring(&task.id);
+      } else {
+        log::info!("Skipping task {} missed at {}", task.id, task.time);
+        self.missed_task_ids.insert(task.id);
+      }
+    }
+  }
+
+  /// Show this widget during long gaps between tasks. Scheduled tasks always take over
+  /// when they come due.
+  pub fn with_idle(mut self, idle: Option<(String, Value)>) -> Self {
+    self.idle = idle;
+    self
+  }
+
+  /// Whether idle content should go up now: configured, not already shown, the last task
+  /// has been up for `IDLE_MIN_GAP`, and no task is due within `IDLE_MIN_GAP`.
+  pub fn should_show_idle(&self) -> bool {
+    self.idle.is_some()
+      && !self.idle_shown
+      && self.last_task_at.is_none_or(|at| at.elapsed() >= IDLE_MIN_GAP)
+      && self.time_until_next_task().is_none_or(|until| until > IDLE_MIN_GAP)
+  }
+
+  /// Display the idle widget.
+  async fn show_idle(&mut self) {
+    let Some((widget, input)) = self.idle.clone() else {
+      return;
+    };
+    log::info!("No task within {:?}, showing idle widget {}", IDLE_MIN_GAP, widget);
+    print_progress(&format!("Showing idle {}...", widget));
+    let message = render_widget(&widget, &input, &self.config).await;
+    let _ =
+      send_unless_blank(message, &widget, self.dry_run, "Idle", self.transport, &self.config, self.skip_blank).await;
+    self.idle_shown = true;
+  }
+
+  /// Load today's executed task IDs from the state file (empty if not persisting).
+  fn load_executed_today(&self) -> HashSet<String> {
+    match &self.state_path {
+      Some(path) => {
+        let today = Local::now().date_naive();
+        ScheduleState::load(path).executed_on(today).cloned().collect()
+      },
+      None => HashSet::new(),
+    }
+  }
+
+  /// Check whether a task has already been executed.
+  pub fn has_executed(&self, task_id: &str) -> bool {
+    self.executed_task_ids.contains(task_id)
+  }
+
+  /// Get the next pending task that is due or in the future.
+  ///
+  /// Skips past-due tasks and returns the soonest future task that
+  /// hasn't been executed yet.
+  pub fn next_pending_task(&self) -> Option<&ScheduledTask> {
+    self.schedule.next_pending_task(Utc::now(), &self.executed_task_ids)
   }
 
   /// Get the next task that is due for execution (time <= now).
@@ -64,31 +262,57 @@ impl<'a> ScheduleRunner<'a> {
       .schedule
       .tasks
       .iter()
-      .filter(|task| !self.executed_task_ids.contains(&task.id))
+      .filter(|task| !self.executed_task_ids.contains(&task.id) && !self.missed_task_ids.contains(&task.id))
       .filter(|task| task.time <= now)
       .min_by_key(|task| task.time)
   }
 
+  /// Whether a task, or a failed task's retry, is due now and will run on the next iteration.
+  pub fn has_due_task(&self) -> bool {
+    self.next_due_task().is_some() || self.retry.as_ref().is_some_and(|(_, at)| Instant::now() >= *at)
+  }
+
   /// Get the time until the next pending task.
   ///
   /// Returns None if there are no pending tasks.
   pub fn time_until_next_task(&self) -> Option<Duration> {
-    self.next_pending_task().map(|task| {
-      let now = Utc::now();
-      let diff = task.time - now;
-      // Convert to std::time::Duration, handling negative values
-      if diff.num_seconds() > 0 {
-        Duration::from_secs(diff.num_seconds() as u64)
-      } else {
-        Duration::ZERO
-      }
-    })
+    self.next_pending_task().map(|task| time_until(task.time, Utc::now()))
   }
 
-  /// Mark a task as executed.
+  /// Mark a task as executed, persisting it when a state path is set.
   pub fn mark_executed(&mut self, task_id: &str) {
     self.executed_task_ids.insert(task_id.to_string());
     log::debug!("Marked task {} as executed", task_id);
+
+    if let Some(path) = &self.state_path {
+      let today = Local::now().date_naive();
+      let mut state = ScheduleState::load(path);
+      state.prune(today);
+      state.mark_executed(task_id, today);
+      state.save(path);
+    }
+  }
+
+  /// Move a recurring task to its next occurrence, in memory and in the schedule file.
+  ///
+  /// Recurring tasks aren't marked executed: their moved time keeps them from re-firing.
+  pub fn reschedule_recurring(&mut self, task_id: &str) {
+    let now = Utc::now();
+    let Some(next) = self.schedule.advance_recurring(task_id, now) else {
+      return;
+    };
+    log::info!("Recurring task {} moved to {}", task_id, next);
+
+    if let Some(path) = &self.schedule_path {
+      // Update the file as it is now, so edits made since the last reload aren't lost
+      let saved = load_schedule_silent(path).and_then(|mut schedule| {
+        schedule.advance_recurring(task_id, now);
+        save_schedule_silent(&schedule, path)
+      });
+      if let Err(e) = saved {
+        log::error!("Failed to save next time for recurring task {}: {}", task_id, e);
+      }
+    }
   }
 
   /// Check if this runner is in dry-run mode.
@@ -99,21 +323,46 @@ impl<'a> ScheduleRunner<'a> {
   /// Reload the schedule with new data.
   ///
   /// This clears the executed set, allowing tasks to re-run if they
-  /// become due again (e.g., after a schedule file edit).
+  /// become due again (e.g., after a schedule file edit). Tasks persisted
+  /// as executed today stay executed.
   pub fn reload_schedule(&mut self, schedule: Schedule) {
     self.schedule = schedule;
-    self.executed_task_ids.clear();
+    self.executed_task_ids = self.load_executed_today();
     log::info!("Schedule reloaded, executed set cleared");
   }
 
   /// Execute a task and send to Vestaboard (or console in dry-run mode).
-  async fn execute_task(&mut self, task: &ScheduledTask) -> Result<(), VestaboardError> {
+  ///
+  /// If the widget fails, its error is shown in its place, and unless this run is already the
+  /// retry, the task is queued to run again after `error_display`.
+  async fn execute_task(&mut self, task: &ScheduledTask, is_retry: bool) -> Result<(), VestaboardError> {
     log::info!("Executing scheduled task: {} ({})", task.widget, task.id);
     print_progress(&format!("Executing task {} ({})...", task.id, task.widget));
 
     let label = format!("Task {}", task.id);
+    let message = match execute_widget(&task.widget, &task.input, &self.config).await {
+      Ok(message) => message,
+      Err(e) => {
+        if let Some(delay) = self.error_display.filter(|_| !is_retry) {
+          log::info!("Retrying task {} in {:?}", task.id, delay);
+          self.retry = Some((task.clone(), Instant::now() + delay));
+        }
+        widget_error_message(&task.widget, &e, &self.config)
+      },
+    };
+
+    let codes = message_to_codes(message.clone());
+    if self.skip_duplicates && self.last_rendered == Some(codes) {
+      log::info!("Skipping task {} ({}): same display as previous task", task.id, task.widget);
+      print_progress(&format!("Task {} unchanged from previous task, skipped", task.id));
+      return Ok(());
+    }
+    self.last_rendered = Some(codes);
+
     // Ignore the result - we want to continue even if sending fails
-    let _ = execute_and_send(&task.widget, &task.input, self.dry_run, &label, self.transport).await;
+    let _ =
+      send_unless_blank(message, &task.widget, self.dry_run, &label, self.transport, &self.config, self.skip_blank)
+        .await;
 
     Ok(())
   }
@@ -123,6 +372,11 @@ impl<'a> Runner for ScheduleRunner<'a> {
   fn start(&mut self) {
     log::info!("Schedule runner started with {} tasks", self.schedule.tasks.len());
 
+    self.skip_missed_tasks();
+    if !self.missed_task_ids.is_empty() {
+      println!("Skipped {} task(s) missed while not running.", self.missed_task_ids.len());
+    }
+
     let mode = if self.dry_run { "preview" } else { "live" };
     print_progress(&format!("Starting schedule runner ({} tasks, {} mode)...", self.schedule.tasks.len(), mode));
 
@@ -139,8 +393,16 @@ impl<'a> Runner for ScheduleRunner<'a> {
   async fn run_iteration(&mut self) -> Result<ControlFlow, VestaboardError> {
     // Check if any task is due now
     if let Some(task) = self.next_due_task().cloned() {
-      self.execute_task(&task).await?;
-      self.mark_executed(&task.id);
+      // A newly due task replaces whatever error was waiting to be retried
+      self.retry = None;
+      self.execute_task(&task, false).await?;
+      self.last_task_at = Some(Instant::now());
+      self.idle_shown = false;
+      if task.recurrence.is_some() {
+        self.reschedule_recurring(&task.id);
+      } else {
+        self.mark_executed(&task.id);
+      }
 
       // Show next pending task info
       if let Some(next) = self.next_pending_task() {
@@ -150,8 +412,21 @@ impl<'a> Runner for ScheduleRunner<'a> {
       } else {
         println!("No more upcoming tasks.");
       }
+    } else if let Some(task) = self.take_due_retry() {
+      self.execute_task(&task, true).await?;
+      self.last_task_at = Some(Instant::now());
+    } else {
+      // Nothing due: the current pass of back-to-back tasks is over
+      self.last_rendered = None;
+      if self.once_until.is_none() && self.should_show_idle() {
+        self.show_idle().await;
+      }
     }
 
+    if self.once_finished() {
+      log::info!("One-shot schedule run finished");
+      return Ok(ControlFlow::Exit);
+    }
     Ok(ControlFlow::Continue)
   }
 
@@ -0,0 +1,122 @@
+//! Countdown timer for `vbl timer`.
+//!
+//! The board shows the label and the minutes left, redrawn each time the minute
+//! count changes, then the label alone when time is up. Frames are built by pure
+//! functions so they can be tested without a board; `run_timer` is the thin loop
+//! that sends them.
+
+use std::time::{Duration, Instant};
+
+use crate::api::Transport;
+use crate::cli_display::print_success;
+use crate::config::Config;
+use crate::datetime::parse_duration;
+use crate::errors::VestaboardError;
+use crate::process_control::ProcessController;
+use crate::runner::common::send_message;
+use crate::widgets::widget_utils::{center_line, center_message, split_into_lines, MAX_MESSAGE_HEIGHT};
+
+/// Label shown when none is given on the command line
+pub const DEFAULT_TIMER_LABEL: &str = "time is up";
+
+/// Widget name recorded in history for timer frames
+const TIMER_SOURCE: &str = "timer";
+
+/// Parse a timer length such as `25m`, `90s`, `1h`, or `1h30m`. A bare number is minutes.
+pub fn parse_timer_duration(text: &str) -> Result<Duration, VestaboardError> {
+  parse_duration(text).map_err(|_| {
+    VestaboardError::validation_error(&format!("Invalid timer length '{}'. Use e.g. 25m, 90s, or 1h30m.", text))
+  })
+}
+
+/// Whole minutes left, rounded up so the board never shows "0 min" before time is up.
+pub fn minutes_remaining(remaining: Duration) -> u64 {
+  remaining.as_secs().div_ceil(60)
+}
+
+/// Describe the minutes left, e.g. "25 min left" or "1 hr 05 min left".
+pub fn format_minutes_left(minutes: u64) -> String {
+  if minutes >= 60 {
+    format!("{} hr {:02} min left", minutes / 60, minutes % 60)
+  } else {
+    format!("{} min left", minutes)
+  }
+}
+
+/// Most rows the label can wrap to, leaving room for the blank row and the minutes left
+pub const MAX_TIMER_LABEL_LINES: usize = MAX_MESSAGE_HEIGHT - 2;
+
+/// Check that the label fits on the board alongside the minutes left.
+pub fn validate_timer_label(label: &str) -> Result<(), VestaboardError> {
+  if split_into_lines(label).len() > MAX_TIMER_LABEL_LINES {
+    return Err(VestaboardError::validation_error(&format!(
+      "Timer label is too long: it must fit in {} lines of 22 characters",
+      MAX_TIMER_LABEL_LINES
+    )));
+  }
+  Ok(())
+}
+
+/// Build the board frame for the time remaining: the label over the minutes left,
+/// or just the label once the timer reaches zero. Long labels wrap onto several rows.
+pub fn timer_frame(remaining: Duration, label: &str) -> Vec<String> {
+  let label = label.to_lowercase();
+  let mut lines: Vec<String> = split_into_lines(&label).into_iter().map(center_line).collect();
+  let minutes = minutes_remaining(remaining);
+
+  if minutes > 0 {
+    lines.push(String::new());
+    lines.push(center_line(format_minutes_left(minutes)));
+  }
+  center_message(lines, MAX_MESSAGE_HEIGHT)
+}
+
+/// Count down on the board, sending a new frame whenever the minute count changes.
+///
+/// Stops early on Ctrl+C without showing the final frame.
+pub async fn run_timer(
+  duration: Duration,
+  label: &str,
+  dry_run: bool,
+  transport: &Transport,
+  config: &Config,
+) -> Result<(), VestaboardError> {
+  validate_timer_label(label)?;
+  log::info!("Starting {}s timer: {}", duration.as_secs(), label);
+
+  let process_controller = ProcessController::new();
+  process_controller.setup_signal_handler()?;
+
+  let end = Instant::now()
+    .checked_add(duration)
+    .ok_or_else(|| VestaboardError::validation_error("Timer length is too long"))?;
+  let mut last_shown = None;
+
+  loop {
+    if process_controller.should_shutdown() {
+      log::info!("Shutdown requested, stopping timer");
+      println!("\nTimer cancelled.");
+      return Ok(());
+    }
+
+    let remaining = end.saturating_duration_since(Instant::now());
+    let minutes = minutes_remaining(remaining);
+    if last_shown != Some(minutes) {
+      let frame = timer_frame(remaining, label);
+      let frame_label = format!("Timer ({} min left)", minutes);
+      if let Err(e) = send_message(frame, TIMER_SOURCE, dry_run, &frame_label, transport, config).await {
+        // Keep counting; the next minute's frame may get through
+        log::warn!("Failed to send timer frame: {}", e);
+      }
+      last_shown = Some(minutes);
+      if minutes == 0 {
+        break;
+      }
+    }
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+  }
+
+  print_success("Timer finished.");
+  Ok(())
+}
@@ -0,0 +1,120 @@
+//! Full-screen dashboard for the playlist runner.
+//!
+//! The view is built from a `DashboardView` snapshot by pure formatting functions,
+//! so layout can be tested without a terminal. `Dashboard` owns the terminal side:
+//! it switches to the alternate screen and redraws when the frame changes.
+
+use std::io::{stdout, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::execute;
+use crossterm::terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+
+use crate::datetime::format_countdown;
+use crate::errors::VestaboardError;
+use crate::runtime_state::PlaylistState;
+
+/// Width of the divider lines in the dashboard
+const DIVIDER_WIDTH: usize = 40;
+
+/// Redraw at least this often, even if nothing changed, to clear stray output
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Snapshot of playlist runner state used to render the dashboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DashboardView {
+  /// Current playlist execution state
+  pub state: PlaylistState,
+  /// Item currently on the board (widget and ID), if any has been shown yet
+  pub current: Option<String>,
+  /// Item that will be shown next (widget and ID)
+  pub next: Option<String>,
+  /// 0-based position of the next item
+  pub next_index: usize,
+  /// Number of items in the playlist
+  pub total: usize,
+  /// Time left before the next item; None means it will show immediately
+  pub remaining: Option<Duration>,
+  /// Whether messages are going to the console instead of the board
+  pub dry_run: bool,
+}
+
+/// Render the dashboard as lines of text.
+pub fn render_dashboard(view: &DashboardView) -> Vec<String> {
+  let mode = if view.dry_run { "preview" } else { "live" };
+  let state = match view.state {
+    PlaylistState::Running => "Running",
+    PlaylistState::Paused => "Paused",
+    PlaylistState::Stopped => "Stopped",
+  };
+  let next_in = match (view.state, view.remaining) {
+    (PlaylistState::Paused, _) => "paused".to_string(),
+    (_, None) => "now".to_string(),
+    (_, Some(remaining)) => format_countdown(remaining),
+  };
+  let divider = "-".repeat(DIVIDER_WIDTH);
+
+  vec![
+    format!("Vestaboard Playlist ({} mode)", mode),
+    divider.clone(),
+    format!("State:    {}", state),
+    format!("Showing:  {}", view.current.as_deref().unwrap_or("-")),
+    format!("Next:     {} ({}/{})", view.next.as_deref().unwrap_or("-"), view.next_index + 1, view.total),
+    format!("Next in:  {}", next_in),
+    divider,
+    "p pause  r resume  n next  q quit".to_string(),
+  ]
+}
+
+/// Check whether the dashboard can be shown (stdout must be a terminal).
+pub fn is_supported() -> bool {
+  stdout().is_terminal()
+}
+
+/// Terminal dashboard. Restores the normal screen when dropped.
+pub struct Dashboard {
+  last_frame: Vec<String>,
+  last_draw: Option<Instant>,
+}
+
+impl Dashboard {
+  /// Switch to the alternate screen and hide the cursor.
+  pub fn enter() -> Result<Self, VestaboardError> {
+    execute!(stdout(), EnterAlternateScreen, Hide).map_err(|e| VestaboardError::io_error(e, "starting dashboard"))?;
+    log::debug!("Dashboard started");
+    Ok(Self {
+      last_frame: Vec::new(),
+      last_draw: None,
+    })
+  }
+
+  /// Draw the view if it changed or the redraw interval has passed.
+  pub fn draw(&mut self, view: &DashboardView) {
+    let frame = render_dashboard(view);
+    let stale = self.last_draw.is_none_or(|t| t.elapsed() >= REDRAW_INTERVAL);
+    if frame == self.last_frame && !stale {
+      return;
+    }
+
+    let mut out = stdout();
+    if let Err(e) = execute!(out, MoveTo(0, 0), Clear(ClearType::All)) {
+      log::warn!("Failed to clear dashboard: {}", e);
+      return;
+    }
+    for line in &frame {
+      let _ = writeln!(out, "{}", line);
+    }
+    let _ = out.flush();
+
+    self.last_frame = frame;
+    self.last_draw = Some(Instant::now());
+  }
+}
+
+impl Drop for Dashboard {
+  fn drop(&mut self) {
+    let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+    log::debug!("Dashboard closed");
+  }
+}
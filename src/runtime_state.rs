@@ -4,9 +4,11 @@
 //! best-effort: errors during save/load are logged but don't crash the application.
 //! Losing state (resetting to defaults) is preferable to crashing.
 
+use std::collections::BTreeMap;
 use std::path::Path;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 /// The current state of playlist execution
@@ -21,6 +23,31 @@ pub enum PlaylistState {
   Paused,
 }
 
+/// Usage counters for playlist execution, accumulated across runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PlaylistStats {
+  /// Total number of items displayed
+  pub total_displayed: u64,
+  /// Number of displays per widget type
+  pub widget_counts: BTreeMap<String, u64>,
+  /// The most recent error seen while displaying an item
+  pub last_error: Option<String>,
+}
+
+impl PlaylistStats {
+  /// Record that an item for the given widget was displayed
+  pub fn record_display(&mut self, widget: &str) {
+    self.total_displayed += 1;
+    *self.widget_counts.entry(widget.to_string()).or_insert(0) += 1;
+  }
+
+  /// Record the most recent display error
+  pub fn record_error(&mut self, message: &str) {
+    self.last_error = Some(message.to_string());
+  }
+}
+
 /// Persisted runtime state for resuming execution across restarts
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -31,6 +58,8 @@ pub struct RuntimeState {
   pub playlist_index: usize,
   /// When the last item was displayed
   pub last_shown_time: Option<DateTime<Utc>>,
+  /// Display counters (missing in older state files, so defaulted on load)
+  pub stats: PlaylistStats,
 }
 
 impl RuntimeState {
@@ -41,48 +70,14 @@ impl RuntimeState {
   /// and continue. Crashing because of state file corruption would be worse
   /// than losing position.
   pub fn load(path: &Path) -> Self {
-    match std::fs::read_to_string(path) {
-      Ok(content) if !content.trim().is_empty() => serde_json::from_str(&content).unwrap_or_else(|e| {
-        log::warn!("Invalid runtime state JSON, using defaults: {}", e);
-        Self::default()
-      }),
-      Ok(_) => {
-        // Empty file
-        Self::default()
-      },
-      Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-        log::debug!("Runtime state file not found, using defaults");
-        Self::default()
-      },
-      Err(e) => {
-        log::warn!("Cannot read runtime state: {}, using defaults", e);
-        Self::default()
-      },
-    }
+    load_or_default(path, "runtime state")
   }
 
   /// Save state to file. Errors are logged but not propagated.
   ///
   /// State persistence is best-effort - we don't want to crash if we can't save state.
   pub fn save(&self, path: &Path) {
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-      if let Err(e) = std::fs::create_dir_all(parent) {
-        log::warn!("Cannot create state directory: {}", e);
-        return;
-      }
-    }
-
-    match serde_json::to_string_pretty(self) {
-      Ok(content) => {
-        if let Err(e) = std::fs::write(path, content) {
-          log::warn!("Cannot save runtime state: {}", e);
-        }
-      },
-      Err(e) => {
-        log::warn!("Cannot serialize runtime state: {}", e);
-      },
-    }
+    save_best_effort(self, path, "runtime state");
   }
 
   /// Update index and save (convenience method)
@@ -92,3 +87,192 @@ impl RuntimeState {
     self.save(path);
   }
 }
+
+/// Persisted record of schedule tasks already executed, so a restart doesn't re-fire them.
+///
+/// Each task ID maps to the local date it ran on. Entries from earlier days are pruned,
+/// so the file only ever holds today's executions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ScheduleState {
+  /// Task ID -> local date the task was executed
+  pub executed: BTreeMap<String, NaiveDate>,
+}
+
+impl ScheduleState {
+  /// Load state from file, returning defaults on any error (best-effort, like `RuntimeState`).
+  pub fn load(path: &Path) -> Self {
+    load_or_default(path, "schedule state")
+  }
+
+  /// Save state to file. Errors are logged but not propagated.
+  pub fn save(&self, path: &Path) {
+    save_best_effort(self, path, "schedule state");
+  }
+
+  /// Record that a task was executed on the given date.
+  pub fn mark_executed(&mut self, task_id: &str, date: NaiveDate) {
+    self.executed.insert(task_id.to_string(), date);
+  }
+
+  /// Drop executions from before `today`.
+  pub fn prune(&mut self, today: NaiveDate) {
+    self.executed.retain(|_, date| *date >= today);
+  }
+
+  /// IDs of tasks executed on `today`.
+  pub fn executed_on(&self, today: NaiveDate) -> impl Iterator<Item = &String> {
+    self
+      .executed
+      .iter()
+      .filter(move |(_, date)| **date == today)
+      .map(|(id, _)| id)
+  }
+}
+
+/// A message that was sent to the board, kept for `vbl history`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+  /// When the message was sent
+  pub time: DateTime<Utc>,
+  /// Widget that produced the message
+  pub widget: String,
+  /// The lines that were sent
+  pub message: Vec<String>,
+}
+
+/// Rolling log of the most recent messages sent to the board, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct MessageHistory {
+  pub entries: Vec<HistoryEntry>,
+  /// When the board was blanked for quiet hours, until the next message is recorded
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub quiet_hours_cleared: Option<DateTime<Utc>>,
+}
+
+impl MessageHistory {
+  /// Load history from file, returning an empty history on any error.
+  pub fn load(path: &Path) -> Self {
+    load_or_default(path, "message history")
+  }
+
+  /// Save history to file. Errors are logged but not propagated.
+  pub fn save(&self, path: &Path) {
+    save_best_effort(self, path, "message history");
+  }
+
+  /// Append an entry, dropping the oldest entries beyond `max_entries`. The board shows
+  /// the entry now, so it's no longer blank for quiet hours.
+  pub fn push(&mut self, entry: HistoryEntry, max_entries: usize) {
+    self.quiet_hours_cleared = None;
+    self.entries.push(entry);
+    let excess = self.entries.len().saturating_sub(max_entries);
+    self.entries.drain(..excess);
+  }
+
+  /// The `n`th most recent entry (1 is the latest).
+  pub fn recent(&self, n: usize) -> Option<&HistoryEntry> {
+    n.checked_sub(1).and_then(|i| self.entries.iter().rev().nth(i))
+  }
+}
+
+/// A rendered message waiting its turn to be sent to the board.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueuedMessage {
+  /// Identifies the entry so the `vbl show` that queued it can tell when it has been sent
+  pub id: String,
+  /// Widget that produced the message
+  pub widget: String,
+  /// The lines to send
+  pub message: Vec<String>,
+  /// When the message was queued
+  pub queued_at: DateTime<Utc>,
+}
+
+/// How sending a queued message went, kept until the `vbl show` that queued it reads it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueOutcome {
+  /// ID of the queued message
+  pub id: String,
+  /// Why the send failed, or `None` if it was sent
+  pub error: Option<String>,
+  /// When the message was sent (or failed)
+  pub finished_at: DateTime<Utc>,
+}
+
+/// Messages from `vbl show` waiting to be sent to the board, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DisplayQueue {
+  pub entries: Vec<QueuedMessage>,
+  /// Results of messages already taken off the queue, for the shows still waiting on them
+  pub outcomes: Vec<QueueOutcome>,
+}
+
+impl DisplayQueue {
+  /// Load the queue from file, returning an empty queue on any error.
+  pub fn load(path: &Path) -> Self {
+    load_or_default(path, "display queue")
+  }
+
+  /// Save the queue to file. Errors are logged but not propagated.
+  pub fn save(&self, path: &Path) {
+    save_best_effort(self, path, "display queue");
+  }
+
+  /// Take the oldest entry off the queue.
+  pub fn pop_front(&mut self) -> Option<QueuedMessage> {
+    (!self.entries.is_empty()).then(|| self.entries.remove(0))
+  }
+
+  /// Remove and return the outcome recorded for this ID, if it has been sent.
+  pub fn take_outcome(&mut self, id: &str) -> Option<QueueOutcome> {
+    let index = self.outcomes.iter().position(|outcome| outcome.id == id)?;
+    Some(self.outcomes.remove(index))
+  }
+}
+
+/// Read a JSON state file, returning defaults on any error.
+fn load_or_default<T: DeserializeOwned + Default>(path: &Path, label: &str) -> T {
+  match std::fs::read_to_string(path) {
+    Ok(content) if !content.trim().is_empty() => serde_json::from_str(&content).unwrap_or_else(|e| {
+      log::warn!("Invalid {} JSON, using defaults: {}", label, e);
+      T::default()
+    }),
+    Ok(_) => {
+      // Empty file
+      T::default()
+    },
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+      log::debug!("{} file not found, using defaults", label);
+      T::default()
+    },
+    Err(e) => {
+      log::warn!("Cannot read {}: {}, using defaults", label, e);
+      T::default()
+    },
+  }
+}
+
+/// Write a JSON state file, logging (not propagating) any error.
+fn save_best_effort<T: Serialize>(value: &T, path: &Path, label: &str) {
+  // Ensure parent directory exists
+  if let Some(parent) = path.parent() {
+    if let Err(e) = std::fs::create_dir_all(parent) {
+      log::warn!("Cannot create state directory: {}", e);
+      return;
+    }
+  }
+
+  match serde_json::to_string_pretty(value) {
+    Ok(content) => {
+      if let Err(e) = std::fs::write(path, content) {
+        log::warn!("Cannot save {}: {}", label, e);
+      }
+    },
+    Err(e) => {
+      log::warn!("Cannot serialize {}: {}", label, e);
+    },
+  }
+}
@@ -1,19 +1,25 @@
 use std::{
+  collections::HashSet,
   fs,
   path::{Path, PathBuf},
-  time::SystemTime,
+  time::{Duration, SystemTime},
 };
 
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Local, NaiveTime, Utc};
 use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::api::Transport;
-use crate::api_broker::{handle_message, MessageDestination};
-use crate::cli_display::{print_error, print_progress, print_success, print_warning};
-use crate::widgets::resolver::execute_widget;
-use crate::widgets::widget_utils;
+use crate::api_broker::{handle_message, unsupported_chars, MessageDestination};
+use crate::cli_display::{
+  colorize, colors_enabled, print_error, print_progress, print_success, print_warning, truncate_to_width,
+};
+use crate::datetime::{datetime_to_local, format_countdown};
+use crate::ics::{events_to_tasks, parse_ics};
+use crate::recovery::recover_corrupt;
+use crate::recurrence::Recurrence;
+use crate::widgets::resolver::{error_display, execute_widget, validate_widget_input};
 use crate::{config::Config, errors::VestaboardError};
 
 pub const CUSTOM_ALPHABET: &[char] = &[
@@ -33,6 +39,12 @@ pub struct ScheduledTask {
   pub time: DateTime<Utc>,
   pub widget: String,
   pub input: Value,
+  /// Days the task repeats on; after running it moves to the next matching day
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub recurrence: Option<Recurrence>,
+  /// UID of the calendar event the task was imported from, so re-importing doesn't duplicate it
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub uid: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -48,19 +60,40 @@ impl ScheduledTask {
       time,
       widget,
       input,
+      recurrence: None,
+      uid: None,
     }
   }
+
+  /// Make the task repeat, moving its time forward to the first matching day if needed.
+  pub fn with_recurrence(mut self, recurrence: Recurrence) -> Self {
+    self.time = recurrence.first_at_or_after(self.time);
+    self.recurrence = Some(recurrence);
+    self
+  }
+
+  /// Where the task goes in the schedule: by time, then by ID, so tasks at the same time
+  /// always come out in the same order however they were added or saved.
+  pub fn order_key(&self) -> (DateTime<Utc>, &str) {
+    (self.time, &self.id)
+  }
 }
 
 impl Schedule {
+  /// Insert a task in schedule order (see `ScheduledTask::order_key`).
   pub fn add_task(&mut self, task: ScheduledTask) {
     let position = self
       .tasks
       .iter()
-      .position(|t| t.time > task.time)
+      .position(|t| t.order_key() > task.order_key())
       .unwrap_or(self.tasks.len());
     self.tasks.insert(position, task);
   }
+
+  /// Put the tasks in schedule order (see `ScheduledTask::order_key`).
+  pub fn sort(&mut self) {
+    self.tasks.sort_by(|a, b| a.order_key().cmp(&b.order_key()));
+  }
   pub fn remove_task(&mut self, id: &str) -> bool {
     let initial_len = self.tasks.len();
     self.tasks.retain(|task| task.id != id);
@@ -78,9 +111,101 @@ impl Schedule {
   pub fn clear(&mut self) {
     self.tasks.clear();
   }
+  /// Remove one-shot tasks scheduled before `time`, returning how many were removed.
+  /// Recurring tasks are kept whatever their stored time, since they still have runs ahead.
+  pub fn remove_before(&mut self, time: DateTime<Utc>) -> usize {
+    let initial_len = self.tasks.len();
+    self.tasks.retain(|task| task.time >= time || task.recurrence.is_some());
+    initial_len - self.tasks.len()
+  }
   pub fn is_empty(&self) -> bool {
     self.tasks.is_empty()
   }
+
+  /// Remove tasks that repeat an earlier one: same widget, input, and repeat, with times
+  /// at most `window` apart (zero for exact matches). The first of each group in schedule
+  /// order is kept, so of tasks at the same time, the one with the lowest ID stays.
+  ///
+  /// Returns how many tasks were removed.
+  pub fn dedupe(&mut self, window: chrono::Duration) -> usize {
+    let initial_len = self.tasks.len();
+    let mut kept: Vec<ScheduledTask> = Vec::with_capacity(initial_len);
+    for task in self.tasks.drain(..) {
+      let duplicate = kept.iter().any(|earlier| {
+        earlier.widget == task.widget
+          && earlier.input == task.input
+          && earlier.recurrence == task.recurrence
+          && (task.time - earlier.time).abs() <= window
+      });
+      if duplicate {
+        log::debug!("Removing duplicate task {} ({})", task.id, task.widget);
+      } else {
+        kept.push(task);
+      }
+    }
+    self.tasks = kept;
+    initial_len - self.tasks.len()
+  }
+
+  /// Change a task's time, keeping its ID and everything else, and re-sort the schedule.
+  ///
+  /// A repeating task moves to the first matching day at or after `time`, as when it was
+  /// added. Returns the task's new time.
+  pub fn reschedule(&mut self, id: &str, time: DateTime<Utc>) -> Result<DateTime<Utc>, VestaboardError> {
+    let task = self
+      .get_task_mut(id)
+      .ok_or_else(|| VestaboardError::schedule_error("reschedule", &format!("Task {} not found", id)))?;
+    task.time = match &task.recurrence {
+      Some(recurrence) => recurrence.first_at_or_after(time),
+      None => time,
+    };
+    let new_time = task.time;
+    self.sort();
+    Ok(new_time)
+  }
+
+  /// Move a recurring task to its next occurrence after `now`, keeping the schedule sorted.
+  ///
+  /// Returns the new time, or None if the task doesn't exist or doesn't repeat.
+  pub fn advance_recurring(&mut self, id: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let position = self.tasks.iter().position(|task| task.id == id)?;
+    let next = self.tasks[position]
+      .recurrence
+      .as_ref()?
+      .next_after(self.tasks[position].time, now);
+    let mut task = self.tasks.remove(position);
+    task.time = next;
+    self.add_task(task);
+    Some(next)
+  }
+
+  /// The soonest task after `now` that hasn't been executed yet (past-due tasks are skipped).
+  pub fn next_pending_task(&self, now: DateTime<Utc>, executed: &HashSet<String>) -> Option<&ScheduledTask> {
+    self.next_pending_tasks(now, executed, 1).into_iter().next()
+  }
+
+  /// The next `count` tasks after `now` that haven't been executed, soonest first.
+  pub fn next_pending_tasks(
+    &self,
+    now: DateTime<Utc>,
+    executed: &HashSet<String>,
+    count: usize,
+  ) -> Vec<&ScheduledTask> {
+    let mut pending: Vec<&ScheduledTask> = self
+      .tasks
+      .iter()
+      .filter(|task| !executed.contains(&task.id))
+      .filter(|task| task.time > now)
+      .collect();
+    pending.sort_by(|a, b| a.order_key().cmp(&b.order_key()));
+    pending.truncate(count);
+    pending
+  }
+}
+
+/// Time from `now` until `time`, or zero if it has already passed.
+pub fn time_until(time: DateTime<Utc>, now: DateTime<Utc>) -> Duration {
+  (time - now).to_std().unwrap_or(Duration::ZERO)
 }
 
 /// Monitors schedule file for changes and manages schedule reloading
@@ -88,6 +213,8 @@ pub struct ScheduleMonitor {
   schedule_file_path: PathBuf,
   last_modified: Option<SystemTime>,
   current_schedule: Schedule,
+  /// Move a schedule file that no longer parses aside instead of keeping the old schedule
+  recover_corrupt: bool,
 }
 
 impl ScheduleMonitor {
@@ -97,9 +224,17 @@ impl ScheduleMonitor {
       schedule_file_path: schedule_file_path.as_ref().to_path_buf(),
       last_modified: None,
       current_schedule: Schedule::default(),
+      recover_corrupt: false,
     }
   }
 
+  /// With `recover` set, a reload that finds a corrupt schedule file moves it aside and
+  /// continues with an empty schedule (see `recover_corrupt_files`).
+  pub fn with_recover_corrupt(mut self, recover: bool) -> Self {
+    self.recover_corrupt = recover;
+    self
+  }
+
   /// Initialize the monitor by loading the current schedule and tracking modification time
   pub fn initialize(&mut self) -> Result<(), VestaboardError> {
     log::info!("Initializing schedule monitor for: {:?}", self.schedule_file_path);
@@ -149,7 +284,8 @@ impl ScheduleMonitor {
     self.last_modified = Some(self.get_file_mod_time()?);
 
     // Load the schedule silently - caller manages output
-    match load_schedule_silent(&self.schedule_file_path) {
+    let loaded = load_schedule_silent(&self.schedule_file_path);
+    match recover_corrupt(&self.schedule_file_path, "schedule", loaded, self.recover_corrupt) {
       Ok(schedule) => {
         self.current_schedule = schedule;
         log::info!("Schedule reloaded successfully, {} tasks loaded", self.current_schedule.tasks.len());
@@ -241,15 +377,20 @@ fn save_schedule_internal(schedule: &Schedule, path: &PathBuf, silent: bool) ->
 
 #[allow(dead_code)]
 pub fn load_schedule(path: &PathBuf) -> Result<Schedule, VestaboardError> {
-  load_schedule_internal(path, false)
+  load_schedule_internal(path, false, true)
 }
 
 /// Load schedule without printing progress messages (for internal operations)
 pub fn load_schedule_silent(path: &PathBuf) -> Result<Schedule, VestaboardError> {
-  load_schedule_internal(path, true)
+  load_schedule_internal(path, true, true)
 }
 
-fn load_schedule_internal(path: &PathBuf, silent: bool) -> Result<Schedule, VestaboardError> {
+/// Load schedule for reading only: a missing file is an empty schedule, and nothing is written
+pub fn load_schedule_readonly(path: &PathBuf) -> Result<Schedule, VestaboardError> {
+  load_schedule_internal(path, true, false)
+}
+
+fn load_schedule_internal(path: &PathBuf, silent: bool, create_if_missing: bool) -> Result<Schedule, VestaboardError> {
   log::debug!("Loading schedule from {}", path.display());
 
   match fs::read_to_string(&path) {
@@ -260,7 +401,7 @@ fn load_schedule_internal(path: &PathBuf, silent: bool) -> Result<Schedule, Vest
       } else {
         match serde_json::from_str::<Schedule>(&content) {
           Ok(mut schedule) => {
-            schedule.tasks.sort_by_key(|task| task.time);
+            schedule.sort();
             log::info!("Successfully loaded {} tasks from schedule {}", schedule.tasks.len(), path.display());
             Ok(schedule)
           },
@@ -273,6 +414,10 @@ fn load_schedule_internal(path: &PathBuf, silent: bool) -> Result<Schedule, Vest
         }
       }
     },
+    Err(ref e) if e.kind() == std::io::ErrorKind::NotFound && !create_if_missing => {
+      log::info!("Schedule file {} not found, using an empty schedule", path.display());
+      Ok(Schedule::default())
+    },
     Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => {
       log::info!("Schedule file {} not found, creating new schedule", path.display());
       let schedule = Schedule::default();
@@ -298,23 +443,46 @@ fn load_schedule_internal(path: &PathBuf, silent: bool) -> Result<Schedule, Vest
   }
 }
 
-pub fn add_task_to_schedule(time: DateTime<Utc>, widget: String, input: Value) -> Result<String, VestaboardError> {
+/// Add a task to the schedule file, optionally repeating per a spec such as `daily`,
+/// `weekdays`, or `mon,wed,fri`. An invalid spec is rejected before anything is saved.
+pub fn add_task_to_schedule(
+  time: DateTime<Utc>,
+  widget: String,
+  input: Value,
+  repeat: Option<&str>,
+) -> Result<String, VestaboardError> {
+  let config = Config::load_silent()?;
+  add_task_to(&config.get_schedule_file_path(), time, widget, input, repeat)
+}
+
+/// Add a task to the schedule file at `schedule_path`, as `add_task_to_schedule` does.
+pub fn add_task_to(
+  schedule_path: &PathBuf,
+  time: DateTime<Utc>,
+  widget: String,
+  input: Value,
+  repeat: Option<&str>,
+) -> Result<String, VestaboardError> {
   log::info!(
-    "Adding task to schedule - time: {}, widget: {}, input: {}",
+    "Adding task to schedule - time: {}, widget: {}, input: {}, repeat: {:?}",
     time,
     widget,
-    serde_json::to_string(&input).unwrap_or_else(|_| "invalid".to_string())
+    serde_json::to_string(&input).unwrap_or_else(|_| "invalid".to_string()),
+    repeat
   );
 
-  let config = Config::load_silent()?;
-  let schedule_path = config.get_schedule_file_path();
-  let mut schedule = load_schedule_silent(&schedule_path)?;
+  let recurrence = repeat.map(Recurrence::parse).transpose()?;
 
-  let task = ScheduledTask::new(time, widget.clone(), input);
+  let mut schedule = load_schedule_silent(schedule_path)?;
+
+  let mut task = ScheduledTask::new(time, widget.clone(), input);
+  if let Some(recurrence) = recurrence {
+    task = task.with_recurrence(recurrence);
+  }
   let task_id = task.id.clone();
   schedule.add_task(task);
 
-  match save_schedule_silent(&schedule, &schedule_path) {
+  match save_schedule_silent(&schedule, schedule_path) {
     Ok(_) => {
       log::info!("Successfully added task {} for widget '{}'", task_id, widget);
       Ok(task_id)
@@ -358,6 +526,22 @@ pub fn remove_task_from_schedule(id: &str) -> Result<bool, VestaboardError> {
   }
 }
 
+/// Move a task to a new time (`vbl schedule reschedule`), returning its new time.
+pub fn reschedule_task(id: &str, time: DateTime<Utc>) -> Result<DateTime<Utc>, VestaboardError> {
+  log::info!("Rescheduling task {} to {}", id, time);
+
+  let config = Config::load_silent()?;
+  let schedule_path = config.get_schedule_file_path();
+  let mut schedule = load_schedule_silent(&schedule_path)?;
+
+  let new_time = schedule.reschedule(id, time)?;
+  save_schedule_silent(&schedule, &schedule_path)?;
+
+  log::info!("Task {} rescheduled to {}", id, new_time);
+  print_success(&format!("Task {} moved to {}", id, datetime_to_local(new_time)));
+  Ok(new_time)
+}
+
 pub fn clear_schedule() -> Result<usize, VestaboardError> {
   log::info!("Clearing all scheduled tasks");
 
@@ -383,14 +567,177 @@ pub fn clear_schedule() -> Result<usize, VestaboardError> {
   }
 }
 
-pub fn list_schedule() -> Result<(), VestaboardError> {
-  log::debug!("Listing scheduled tasks");
+/// Add a text task for each upcoming event in an iCalendar file, returning how many
+/// were added. Events that have already started are skipped.
+pub fn import_ics_to_schedule(file: &Path) -> Result<usize, VestaboardError> {
+  let config = Config::load_silent()?;
+  import_ics_to(&config.get_schedule_file_path(), file, config.get_ics_all_day_time())
+}
+
+/// Whether `task` is an imported event already in the schedule as `existing`: the same
+/// calendar UID at the same time, or for events without a UID, the same text at the same time.
+fn is_same_event(existing: &ScheduledTask, task: &ScheduledTask) -> bool {
+  existing.time == task.time
+    && match (&existing.uid, &task.uid) {
+      (Some(a), Some(b)) => a == b,
+      (None, None) => existing.widget == task.widget && existing.input == task.input,
+      _ => false,
+    }
+}
+
+/// Check that an imported event's text is valid text input and only uses characters the board can show.
+fn validate_imported_text(input: &Value) -> Result<(), VestaboardError> {
+  validate_widget_input("text", input)?;
+  let unsupported = unsupported_chars(input.as_str().unwrap_or_default());
+  if !unsupported.is_empty() {
+    let chars: Vec<String> = unsupported.iter().map(|c| format!("'{}'", c)).collect();
+    return Err(VestaboardError::validation_error(&format!("The Vestaboard can't show: {}", chars.join(", "))));
+  }
+  Ok(())
+}
+
+/// `import_ics_to_schedule` into the schedule file at `schedule_path`, with all-day events
+/// starting at `all_day_time`.
+///
+/// Events already imported (see `is_same_event`) and events whose summary can't be shown are
+/// skipped too, so importing the same calendar again only adds its new events.
+pub fn import_ics_to(schedule_path: &PathBuf, file: &Path, all_day_time: NaiveTime) -> Result<usize, VestaboardError> {
+  log::info!("Importing calendar events from {}", file.display());
+
+  let content = fs::read_to_string(file).map_err(|e| VestaboardError::io_error(e, "reading calendar file"))?;
+  let events = parse_ics(&content, all_day_time)?;
+
+  let now = Utc::now();
+  let tasks: Vec<ScheduledTask> = events_to_tasks(&events)
+    .into_iter()
+    .filter(|task| task.time > now)
+    .collect();
+  let past = events.len() - tasks.len();
+  if tasks.is_empty() {
+    print_success(&format!("No upcoming events in {} ({} past events skipped)", file.display(), past));
+    return Ok(0);
+  }
+
+  let mut schedule = load_schedule_silent(schedule_path)?;
+  let (mut added, mut duplicates, mut invalid) = (0, 0, 0);
+  for task in tasks {
+    if let Err(e) = validate_imported_text(&task.input) {
+      log::warn!("Skipping calendar event {} at {}: {}", task.input, task.time, e);
+      let summary = task.input.as_str().unwrap_or_default();
+      print_warning(&format!("Skipping event '{}': {}", summary, e.to_user_message()));
+      invalid += 1;
+    } else if schedule.tasks.iter().any(|existing| is_same_event(existing, &task)) {
+      log::debug!("Skipping calendar event {} at {}: already imported", task.input, task.time);
+      duplicates += 1;
+    } else {
+      schedule.add_task(task);
+      added += 1;
+    }
+  }
+  let skipped = format!("{} past, {} already imported, {} invalid events skipped", past, duplicates, invalid);
+  if added == 0 {
+    print_success(&format!("No new events in {} ({})", file.display(), skipped));
+    return Ok(0);
+  }
+
+  match save_schedule_silent(&schedule, schedule_path) {
+    Ok(_) => {
+      log::info!("Imported {} events from {} ({})", added, file.display(), skipped);
+      print_success(&format!("Imported {} events ({})", added, skipped));
+      Ok(added)
+    },
+    Err(e) => {
+      log::error!("Failed to save schedule after importing events: {}", e);
+      print_error(&e.to_user_message());
+      Err(e)
+    },
+  }
+}
+
+/// Remove one-shot tasks scheduled before `time` from the schedule file, returning how many were removed.
+pub fn clear_schedule_before(time: DateTime<Utc>) -> Result<usize, VestaboardError> {
+  log::info!("Removing scheduled tasks before {}", time);
+
+  let config = Config::load_silent()?;
+  let schedule_path = config.get_schedule_file_path();
+  let mut schedule = load_schedule_silent(&schedule_path)?;
+
+  let removed = schedule.remove_before(time);
+  if removed == 0 {
+    print_success(&format!("No tasks before {}", datetime_to_local(time)));
+    return Ok(0);
+  }
+
+  match save_schedule_silent(&schedule, &schedule_path) {
+    Ok(_) => {
+      log::info!("Removed {} tasks before {}", removed, time);
+      print_success(&format!("Removed {} tasks before {}", removed, datetime_to_local(time)));
+      Ok(removed)
+    },
+    Err(e) => {
+      log::error!("Failed to save schedule after removing old tasks: {}", e);
+      print_error(&e.to_user_message());
+      Err(e)
+    },
+  }
+}
+
+/// Remove duplicate tasks (see `Schedule::dedupe`) from the schedule file, returning how many were removed.
+pub fn dedupe_schedule(window: chrono::Duration) -> Result<usize, VestaboardError> {
+  log::info!("Removing duplicate scheduled tasks within {}s", window.num_seconds());
 
   let config = Config::load_silent()?;
   let schedule_path = config.get_schedule_file_path();
-  let schedule = load_schedule_silent(&schedule_path)?;
+  let mut schedule = load_schedule_silent(&schedule_path)?;
+
+  let removed = schedule.dedupe(window);
+  if removed == 0 {
+    print_success("No duplicate tasks found");
+    return Ok(0);
+  }
+
+  match save_schedule_silent(&schedule, &schedule_path) {
+    Ok(_) => {
+      log::info!("Removed {} duplicate tasks", removed);
+      print_success(&format!("Removed {} duplicate tasks", removed));
+      Ok(removed)
+    },
+    Err(e) => {
+      log::error!("Failed to save schedule after removing duplicates: {}", e);
+      print_error(&e.to_user_message());
+      Err(e)
+    },
+  }
+}
+
+/// Keep only tasks whose time falls within `from..=to` (either bound may be open).
+pub fn filter_tasks_by_range(
+  tasks: &[ScheduledTask],
+  from: Option<DateTime<Utc>>,
+  to: Option<DateTime<Utc>>,
+) -> Result<Vec<ScheduledTask>, VestaboardError> {
+  if let (Some(from), Some(to)) = (from, to) {
+    if from > to {
+      return Err(VestaboardError::validation_error("--from must not be after --to"));
+    }
+  }
+
+  Ok(
+    tasks
+      .iter()
+      .filter(|task| from.is_none_or(|from| task.time >= from))
+      .filter(|task| to.is_none_or(|to| task.time <= to))
+      .cloned()
+      .collect(),
+  )
+}
+
+pub fn list_schedule(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Result<(), VestaboardError> {
+  log::debug!("Listing scheduled tasks");
 
-  log::info!("Displaying {} scheduled tasks", schedule.tasks.len());
+  let config = Config::load_silent()?;
+  let schedule_path = config.get_schedule_file_path();
+  let schedule = load_schedule_readonly(&schedule_path)?;
 
   if schedule.tasks.is_empty() {
     log::debug!("No scheduled tasks found");
@@ -398,20 +745,151 @@ pub fn list_schedule() -> Result<(), VestaboardError> {
     return Ok(());
   }
 
-  println!("Scheduled Tasks ({}):", schedule.tasks.len());
-  println!("{:<6} | {:<22} | {:<15} | {}", "ID", "Time (Local)", "Widget", "Input");
-  println!("{:-<80}", ""); // Separator line
-  for task in schedule.tasks {
+  let filtered = from.is_some() || to.is_some();
+  let tasks = filter_tasks_by_range(&schedule.tasks, from, to)?;
+  log::info!("Displaying {} of {} scheduled tasks", tasks.len(), schedule.tasks.len());
+
+  if tasks.is_empty() {
+    println!("No scheduled tasks in that range");
+    return Ok(());
+  }
+
+  if filtered {
+    println!("Scheduled Tasks ({} of {}):", tasks.len(), schedule.tasks.len());
+  } else {
+    println!("Scheduled Tasks ({}):", tasks.len());
+  }
+  for line in format_schedule_table(&tasks, Utc::now(), colors_enabled()) {
+    println!("{}", line);
+  }
+  Ok(())
+}
+
+/// Width of the input column in `vbl schedule list`; longer inputs are truncated
+const INPUT_COLUMN_WIDTH: usize = 28;
+
+/// Tasks due within this window are highlighted in `vbl schedule list`
+const IMMINENT_WINDOW: chrono::Duration = chrono::Duration::hours(1);
+
+/// Format the schedule table shown by `vbl schedule list`.
+///
+/// With `color` set, the header is highlighted and tasks due within the next hour are
+/// shown in yellow. Without it (piped output), the table is plain text.
+pub fn format_schedule_table(tasks: &[ScheduledTask], now: DateTime<Utc>, color: bool) -> Vec<String> {
+  let mut lines = Vec::new();
+
+  let header = format!("{:<6} | {:<22} | {:<15} | {}", "ID", "Time (Local)", "Widget", "Input");
+  lines.push(if color { colorize(&header, "1;36") } else { header });
+  lines.push(format!("{:-<80}", "")); // Separator line
+
+  for task in tasks {
     let local_time = task.time.with_timezone(&Local::now().timezone());
     let formatted_time = local_time.format("%Y.%m.%d %I:%M %p").to_string();
     let input_str = serde_json::to_string(&task.input).unwrap_or_else(|_| "Invalid JSON".to_string());
-    println!("{:<6} | {:<22} | {:<15} | {}", task.id, formatted_time, task.widget, input_str);
+    let row = format!(
+      "{:<6} | {:<22} | {:<15} | {}",
+      task.id,
+      formatted_time,
+      task.widget,
+      truncate_to_width(&input_str, INPUT_COLUMN_WIDTH)
+    );
+
+    let imminent = task.time >= now && task.time - now <= IMMINENT_WINDOW;
+    lines.push(if color && imminent { colorize(&row, "33") } else { row });
+  }
+
+  lines.push(format!("{:-<80}", "")); // Footer separator line
+  lines
+}
+
+/// Describe the next pending task (widget, local time, and countdown) for `vbl schedule next`.
+pub fn format_next_task(schedule: &Schedule, now: DateTime<Utc>) -> String {
+  match schedule.next_pending_task(now, &HashSet::new()) {
+    Some(task) => {
+      let local_time = task.time.with_timezone(&Local::now().timezone());
+      format!(
+        "Next task: {} [{}] at {} (in {})",
+        task.widget,
+        task.id,
+        local_time.format("%Y.%m.%d %I:%M %p"),
+        format_countdown(time_until(task.time, now))
+      )
+    },
+    None => "No upcoming tasks in schedule.".to_string(),
+  }
+}
+
+/// Print the next pending task without starting a runner.
+pub fn show_next_task() -> Result<(), VestaboardError> {
+  let config = Config::load_silent()?;
+  let schedule = load_schedule_readonly(&config.get_schedule_file_path())?;
+  println!("{}", format_next_task(&schedule, Utc::now()));
+  Ok(())
+}
+
+/// When each task would fire between `from` and `to` (inclusive), soonest first.
+///
+/// Repeating tasks fire at each of their occurrences in the range; one-off tasks fire once if
+/// their time is in it. Same-time tasks are ordered by ID, as the runner fires them.
+pub fn simulate_schedule(
+  schedule: &Schedule,
+  from: DateTime<Utc>,
+  to: DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, &ScheduledTask)> {
+  let mut fires = Vec::new();
+  for task in &schedule.tasks {
+    match &task.recurrence {
+      None => {
+        if task.time >= from && task.time <= to {
+          fires.push((task.time, task));
+        }
+      },
+      Some(recurrence) => {
+        let mut time = if task.time >= from {
+          task.time
+        } else {
+          recurrence.next_after(task.time, from - chrono::Duration::seconds(1))
+        };
+        while time >= from && time <= to {
+          fires.push((time, task));
+          let next = recurrence.next_after(time, time);
+          if next <= time {
+            break;
+          }
+          time = next;
+        }
+      },
+    }
+  }
+  fires.sort_by(|(a_time, a), (b_time, b)| (a_time, &a.id).cmp(&(b_time, &b.id)));
+  fires
+}
+
+/// Print the simulated fire log for the saved schedule between `from` and `to`.
+pub fn print_schedule_simulation(from: DateTime<Utc>, to: DateTime<Utc>) -> Result<(), VestaboardError> {
+  if to < from {
+    return Err(VestaboardError::validation_error("The simulation end must not be before its start"));
+  }
+  let config = Config::load_silent()?;
+  let schedule = load_schedule_readonly(&config.get_schedule_file_path())?;
+  let fires = simulate_schedule(&schedule, from, to);
+  log::info!("Simulated {} task firings between {} and {}", fires.len(), from, to);
+
+  println!("Simulating schedule from {} to {}:", datetime_to_local(from), datetime_to_local(to));
+  if fires.is_empty() {
+    println!("No tasks would fire in that range");
+    return Ok(());
+  }
+  for (time, task) in &fires {
+    println!("  {}  {} [{}]", datetime_to_local(*time), task.widget, task.id);
   }
-  println!("{:-<80}", ""); // Footer separator line
+  println!("{} task firings", fires.len());
   Ok(())
 }
 
-pub async fn preview_schedule(transport: &Transport) {
+/// Dry-run render the schedule to the console: every task, or with `count`, only the next
+/// `count` upcoming ones.
+pub async fn preview_schedule(count: Option<usize>, transport: &Transport) {
   log::debug!("Running schedule preview");
 
   let config = match Config::load_silent() {
@@ -422,7 +900,7 @@ pub async fn preview_schedule(transport: &Transport) {
     },
   };
   let schedule_path = config.get_schedule_file_path();
-  let schedule = load_schedule_silent(&schedule_path).unwrap_or_else(|e| {
+  let schedule = load_schedule_readonly(&schedule_path).unwrap_or_else(|e| {
     log::warn!("Failed to load schedule for dry run: {}, using empty schedule", e);
     Schedule::default()
   });
@@ -432,36 +910,69 @@ pub async fn preview_schedule(transport: &Transport) {
     return;
   }
 
-  println!("Previewing {} scheduled tasks:\n", schedule.tasks.len());
+  match count {
+    Some(count) => {
+      if preview_next_tasks(&schedule, count, Utc::now(), transport, &config).await == 0 {
+        println!("No upcoming tasks to preview");
+        return;
+      }
+    },
+    None => {
+      println!("Previewing {} scheduled tasks:\n", schedule.tasks.len());
+      log::info!("Executing dry run for {} scheduled tasks", schedule.tasks.len());
+      for task in schedule.tasks.iter() {
+        preview_task(task, transport, &config).await;
+      }
+    },
+  }
 
-  log::info!("Executing dry run for {} scheduled tasks", schedule.tasks.len());
+  log::info!("Schedule dry run completed");
+  println!("\n✓ Preview complete");
+}
 
-  for task in schedule.tasks.iter() {
-    log::debug!("Processing task {} (widget: {})", task.id, task.widget);
+/// Dry-run render the next `count` tasks after `now`, soonest first, skipping past ones.
+/// Returns how many tasks were rendered.
+pub async fn preview_next_tasks(
+  schedule: &Schedule,
+  count: usize,
+  now: DateTime<Utc>,
+  transport: &Transport,
+  config: &Config,
+) -> usize {
+  let upcoming = schedule.next_pending_tasks(now, &HashSet::new(), count);
+  if !upcoming.is_empty() {
+    println!("Previewing the next {} scheduled tasks:\n", upcoming.len());
+  }
+  log::info!("Executing dry run for the next {} scheduled tasks", upcoming.len());
+  for task in &upcoming {
+    preview_task(task, transport, config).await;
+  }
+  upcoming.len()
+}
 
-    let local_time = task.time.with_timezone(&Local::now().timezone());
-    let formatted_time = local_time.format("%Y.%m.%d %I:%M %p").to_string();
+/// Render one task to the console, titled with its local time.
+async fn preview_task(task: &ScheduledTask, transport: &Transport, config: &Config) {
+  log::debug!("Processing task {} (widget: {})", task.id, task.widget);
 
-    let message = match execute_widget(&task.widget, &task.input).await {
-      Ok(msg) => msg,
-      Err(e) => {
-        log::error!("Failed to execute widget '{}': {}", task.widget, e);
-        widget_utils::error_to_display_message(&e)
-      },
-    };
+  let local_time = task.time.with_timezone(&Local::now().timezone());
+  let formatted_time = local_time.format("%Y.%m.%d %I:%M %p").to_string();
 
-    let destination = MessageDestination::ConsoleWithTitle(formatted_time);
-    match handle_message(message, destination, &transport).await {
-      Ok(_) => {},
-      Err(e) => {
-        log::error!("Failed to handle message for task {}: {}", task.id, e);
-        eprintln!("Error handling message for task {}: {}", task.id, e);
-      },
-    }
-  }
+  let message = match execute_widget(&task.widget, &task.input, config).await {
+    Ok(msg) => msg,
+    Err(e) => {
+      log::error!("Failed to execute widget '{}': {}", task.widget, e);
+      error_display(&e, config)
+    },
+  };
 
-  log::info!("Schedule dry run completed");
-  println!("\n✓ Preview complete");
+  let destination = MessageDestination::ConsoleWithTitle(formatted_time);
+  match handle_message(message, &task.widget, destination, transport, config).await {
+    Ok(_) => {},
+    Err(e) => {
+      log::error!("Failed to handle message for task {}: {}", task.id, e);
+      eprintln!("Error handling message for task {}: {}", task.id, e);
+    },
+  }
 }
 
 /// Run the schedule with interactive controls.
@@ -472,7 +983,7 @@ pub async fn preview_schedule(transport: &Transport) {
 /// # Arguments
 /// * `dry_run` - If true, display to console instead of Vestaboard
 /// * `transport` - The transport to use for API communication
-pub async fn run_schedule(dry_run: bool, transport: &Transport) -> Result<(), VestaboardError> {
+pub async fn run_schedule(dry_run: bool, catch_up: bool, transport: &Transport) -> Result<(), VestaboardError> {
   use crate::process_control::ProcessController;
   use crate::runner::keyboard::{InputSource, KeyboardListener};
   use crate::runner::lock::InstanceLock;
@@ -484,22 +995,34 @@ pub async fn run_schedule(dry_run: bool, transport: &Transport) -> Result<(), Ve
   let schedule_path = config.get_schedule_file_path();
 
   // Load initial schedule
-  let schedule = load_schedule_silent(&schedule_path)?;
+  let loaded = load_schedule_silent(&schedule_path);
+  let recovered = loaded.is_err();
+  let schedule = recover_corrupt(&schedule_path, "schedule", loaded, config.get_recover_corrupt_files())?;
 
-  if schedule.tasks.is_empty() {
+  // After recovering a corrupt file, keep running so tasks added later are picked up by hot-reload
+  if schedule.tasks.is_empty() && !recovered {
     println!("Schedule is empty. Add tasks with: vbl schedule add <time> <widget>");
     return Ok(());
   }
 
   // Acquire exclusive lock
-  let _lock = InstanceLock::acquire("schedule")?;
+  let _lock = InstanceLock::acquire("schedule", &config)?;
 
   // Create schedule monitor for hot-reload
-  let mut schedule_monitor = ScheduleMonitor::new(&schedule_path);
+  let mut schedule_monitor =
+    ScheduleMonitor::new(&schedule_path).with_recover_corrupt(config.get_recover_corrupt_files());
   schedule_monitor.initialize()?;
 
   // Create runner
-  let mut runner = ScheduleRunner::new(schedule, dry_run, &transport);
+  let mut runner = ScheduleRunner::new(schedule, dry_run, &transport)
+    .with_state_path(config.get_schedule_state_path())
+    .with_schedule_path(schedule_path.clone())
+    .with_catch_up(catch_up.then(|| config.get_catch_up_window()))
+    .with_idle(config.get_idle())
+    .with_skip_duplicates(config.get_skip_duplicate_renders())
+    .with_skip_blank(config.get_skip_blank())
+    .with_error_display(config.get_error_display())
+    .with_config(config.clone());
 
   // Setup keyboard listener
   let mut keyboard = KeyboardListener::new()?;
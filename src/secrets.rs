@@ -0,0 +1,27 @@
+//! API keys and other secrets from the environment.
+//!
+//! A secret is read from its environment variable (e.g. `WEATHER_API_KEY`) or, when that
+//! is unset or empty, from the file named by the same variable with `_FILE` appended
+//! (e.g. `WEATHER_API_KEY_FILE=/run/secrets/weather`), as Docker and Kubernetes secrets are
+//! mounted. Call `dotenv()` first so variables from `.env` are seen.
+
+use std::env;
+use std::fs;
+
+/// The value of `name`, or the trimmed contents of the file named by `{name}_FILE`.
+/// Returns `None` if neither gives a non-empty value.
+pub fn secret(name: &str) -> Option<String> {
+  if let Some(value) = env::var(name).ok().filter(|value| !value.is_empty()) {
+    return Some(value);
+  }
+
+  let file_var = format!("{}_FILE", name);
+  let path = env::var(&file_var).ok().filter(|path| !path.is_empty())?;
+  match fs::read_to_string(&path) {
+    Ok(contents) => Some(contents.trim().to_string()).filter(|value| !value.is_empty()),
+    Err(e) => {
+      log::warn!("Could not read {} from {}: {}", file_var, path, e);
+      None
+    },
+  }
+}
@@ -1,8 +1,11 @@
 #[path = "../api_broker.rs"]
 mod api_broker;
+use crate::config::Config;
+use crate::widgets::widget_utils::{BoardSize, STANDARD_BOARD};
 use api_broker::{
-  display_message, get_valid_characters_description, is_valid_character, message_to_codes, to_codes,
-  validate_message_content,
+  changed_tiles, display_message, format_code_grid, get_valid_characters_description, handle_message,
+  is_valid_character, message_to_codes, parse_code_grid, post_to_webhook, read_code_grid, send_code_grid,
+  snapshot_board, to_codes, unsupported_chars, validate_message_content, validate_message_size, MessageDestination,
 };
 
 #[cfg(test)]
@@ -41,6 +44,79 @@ fn test_message_with_colors() {
   assert_eq!(to_codes(message), expected_codes);
 }
 
+#[test]
+fn test_message_with_punctuation() {
+  let message = "!@#$()-+&=;:'\"%,./?";
+  let expected_codes = vec![
+    37, 38, 39, 40, 41, 42, 44, 46, 47, 48, 49, 50, 52, 53, 54, 55, 56, 59, 60,
+  ];
+  assert_eq!(to_codes(message), expected_codes);
+  assert_eq!(to_codes("72D"), vec![33, 28, 62]);
+}
+
+#[test]
+fn test_unsupported_characters_become_blank_tiles() {
+  // Uppercase letters other than the color and degree tiles, emoji, and accents have no code
+  assert_eq!(to_codes("a~b"), vec![1, 0, 2]);
+  assert_eq!(to_codes("héllo"), vec![8, 0, 12, 12, 15]);
+  assert_eq!(to_codes("A😀"), vec![0, 0]);
+
+  // The blank keeps the rest of the row in place
+  let codes = message_to_codes(vec!["x*y".to_string()]);
+  assert_eq!(codes[0][..3], [24, 0, 25]);
+}
+
+#[test]
+fn test_format_code_grid() {
+  let codes = message_to_codes(vec!["hi".to_string(), "R".to_string()]);
+  let grid = format_code_grid(&codes);
+  let lines: Vec<&str> = grid.lines().collect();
+
+  assert_eq!(lines.len(), 6);
+  assert!(lines[0].starts_with(" 8  9  0"));
+  assert!(lines[1].starts_with("63  0"));
+  assert!(lines.iter().all(|line| line.len() == 22 * 3 - 1));
+}
+
+#[test]
+fn test_validate_message_size_fits() {
+  let small = BoardSize { cols: 15, rows: 3 };
+  let message = vec!["  hello world  ".to_string(), "".to_string(), "".to_string()];
+  assert!(validate_message_size(&message, small).is_ok());
+  assert!(validate_message_size(&message, STANDARD_BOARD).is_ok());
+}
+
+#[test]
+fn test_validate_message_size_too_wide() {
+  let small = BoardSize { cols: 15, rows: 3 };
+  let message = vec!["this line is too wide".to_string()];
+  let error = validate_message_size(&message, small).unwrap_err();
+  assert!(error.to_string().contains("15x3"));
+  assert!(error.to_string().contains("21 tiles wide"));
+
+  // Trailing padding doesn't count toward the width
+  let padded = vec![format!("{:<22}", "short line")];
+  assert!(validate_message_size(&padded, small).is_ok());
+}
+
+#[test]
+fn test_validate_message_size_too_many_rows() {
+  let small = BoardSize { cols: 15, rows: 3 };
+  let message = vec!["one", "two", "three", "four"]
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<_>>();
+  let error = validate_message_size(&message, small).unwrap_err();
+  assert!(error.to_string().contains("row 4"));
+
+  // Blank rows past the bottom are allowed
+  let trailing_blank = vec!["one", "two", "three", "", " "]
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<_>>();
+  assert!(validate_message_size(&trailing_blank, small).is_ok());
+}
+
 #[test]
 fn test_is_valid_character_lowercase() {
   assert!(is_valid_character('a'));
@@ -105,6 +181,22 @@ fn test_is_valid_character_invalid() {
   assert!(!is_valid_character('^'));
 }
 
+#[test]
+fn test_unsupported_chars_lists_each_once_in_order() {
+  assert_eq!(unsupported_chars("café €5 ~ é"), vec!['é', '€', '~']);
+  // Capitals other than the color codes have no tile
+  assert_eq!(unsupported_chars("Hello RGB"), vec!['H']);
+  assert_eq!(unsupported_chars("tab\there"), vec!['\t']);
+}
+
+#[test]
+fn test_unsupported_chars_empty_for_clean_input() {
+  assert!(unsupported_chars("").is_empty());
+  assert!(unsupported_chars("see you at 5pm! 72D").is_empty());
+  assert!(unsupported_chars("two\nlines").is_empty());
+  assert!(unsupported_chars("go 🟩 stop 🟥").is_empty());
+}
+
 #[test]
 fn test_get_valid_characters_description() {
   let desc = get_valid_characters_description();
@@ -254,3 +346,270 @@ fn test_display_message() {
   let _ = display_message;
   assert!(true);
 }
+
+mod webhook_tests {
+  use super::*;
+  use crate::api::{MockTransport, Transport};
+  use crate::errors::VestaboardError;
+  use serde_json::json;
+  use wiremock::matchers::{body_json, method, path};
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  #[tokio::test]
+  async fn test_post_to_webhook_sends_message_and_codes() {
+    let mock_server = MockServer::start().await;
+    let message = vec!["hi".to_string()];
+    let expected = json!({
+      "source": "text",
+      "message": ["hi"],
+      "codes": message_to_codes(message.clone()),
+    });
+
+    Mock::given(method("POST"))
+      .and(path("/hook"))
+      .and(body_json(&expected))
+      .respond_with(ResponseTemplate::new(204))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let url = format!("{}/hook", mock_server.uri());
+    post_to_webhook(&url, &message, "text")
+      .await
+      .expect("webhook post should succeed");
+  }
+
+  #[tokio::test]
+  async fn test_post_to_webhook_non_2xx_is_error() {
+    let mock_server = MockServer::start().await;
+    // Not retried: the receiver may have handled the post before failing
+    Mock::given(method("POST"))
+      .respond_with(ResponseTemplate::new(500))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let result = post_to_webhook(&mock_server.uri(), &["hi".to_string()], "text").await;
+    assert!(matches!(result, Err(VestaboardError::ApiError { code: Some(500), .. })));
+  }
+
+  #[tokio::test]
+  async fn test_handle_message_webhook_destination_skips_board() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+      .and(path("/hook"))
+      .respond_with(ResponseTemplate::new(200))
+      .expect(1)
+      .mount(&mock_server)
+      .await;
+
+    let mock = MockTransport::new();
+    let transport = Transport::Mock(mock.clone());
+    let destination = MessageDestination::Webhook(format!("{}/hook", mock_server.uri()));
+    handle_message(vec!["hello".to_string()], "text", destination, &transport, &Config::default())
+      .await
+      .expect("webhook destination should succeed");
+
+    assert!(mock.sent_codes().is_empty());
+  }
+}
+
+mod code_grid_tests {
+  use super::*;
+  use crate::api::{MockTransport, Transport};
+  use crate::errors::VestaboardError;
+  use serde_json::json;
+
+  /// A 6x22 grid with a red tile in the top-left corner and "hi" on the second row
+  fn sample_grid() -> [[u8; 22]; 6] {
+    let mut grid = [[0u8; 22]; 6];
+    grid[0][0] = 63;
+    grid[1][0] = 8;
+    grid[1][1] = 9;
+    grid[5][21] = 70;
+    grid
+  }
+
+  #[test]
+  fn test_parse_code_grid_accepts_valid_grid() {
+    let json = serde_json::to_string(&sample_grid()).unwrap();
+    assert_eq!(parse_code_grid(&json).unwrap(), sample_grid());
+  }
+
+  #[test]
+  fn test_parse_code_grid_rejects_out_of_range_codes() {
+    let mut rows = json!(sample_grid());
+    rows[2][4] = json!(71);
+    let error = parse_code_grid(&rows.to_string()).unwrap_err();
+    assert!(error
+      .to_string()
+      .contains("Code 71 at row 3, column 5 is out of range (0-70)"));
+
+    rows[2][4] = json!(-1);
+    assert!(parse_code_grid(&rows.to_string()).is_err());
+  }
+
+  #[test]
+  fn test_parse_code_grid_rejects_wrong_shape() {
+    let five_rows = json!(vec![vec![0; 22]; 5]).to_string();
+    assert!(parse_code_grid(&five_rows)
+      .unwrap_err()
+      .to_string()
+      .contains("Expected 6 rows"));
+
+    let mut short_row = json!(sample_grid());
+    short_row[3].as_array_mut().unwrap().pop();
+    let error = parse_code_grid(&short_row.to_string()).unwrap_err();
+    assert!(error.to_string().contains("Row 4 has 21 codes, expected 22"));
+
+    assert!(parse_code_grid("not json").is_err());
+    assert!(parse_code_grid(r#"[["a"]]"#).is_err());
+  }
+
+  #[tokio::test]
+  async fn test_snapshot_board_is_readable_by_send_codes() {
+    let mock = MockTransport::new();
+    let transport = Transport::Mock(mock.clone());
+    transport.send_codes(sample_grid()).await.unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("board.json");
+
+    assert_eq!(snapshot_board(&path, &transport).await.unwrap(), sample_grid());
+
+    assert_eq!(read_code_grid(path.to_str().unwrap()).unwrap(), sample_grid());
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 8);
+  }
+
+  #[tokio::test]
+  async fn test_snapshot_board_errors_when_board_unreadable() {
+    let transport = Transport::Mock(MockTransport::new());
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("board.json");
+
+    let error = snapshot_board(&path, &transport).await.unwrap_err();
+
+    assert!(matches!(error, VestaboardError::ApiError { .. }));
+    assert!(!path.exists());
+  }
+
+  #[test]
+  fn test_read_code_grid_from_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("codes.json");
+    std::fs::write(&path, serde_json::to_string(&sample_grid()).unwrap()).unwrap();
+
+    assert_eq!(read_code_grid(path.to_str().unwrap()).unwrap(), sample_grid());
+  }
+
+  #[tokio::test]
+  async fn test_send_code_grid_reaches_transport_unchanged() {
+    let mock = MockTransport::new();
+    let transport = Transport::Mock(mock.clone());
+    let json = serde_json::to_string(&sample_grid()).unwrap();
+
+    send_code_grid(&json, &transport).await.unwrap();
+
+    assert_eq!(mock.sent_codes(), vec![sample_grid()]);
+  }
+
+  #[tokio::test]
+  async fn test_send_code_grid_sends_nothing_when_invalid() {
+    let mock = MockTransport::new();
+    let transport = Transport::Mock(mock.clone());
+
+    assert!(send_code_grid("[[99]]", &transport).await.is_err());
+    assert!(mock.sent_codes().is_empty());
+  }
+}
+
+mod diff_tests {
+  use super::*;
+  use crate::api::{MockTransport, Transport};
+
+  #[test]
+  fn test_changed_tiles_no_change() {
+    let grid = message_to_codes(vec!["same".to_string(), "message".to_string()]);
+    assert!(changed_tiles(&grid, &grid).is_empty());
+  }
+
+  #[test]
+  fn test_changed_tiles_partial_change() {
+    let current = message_to_codes(vec!["hello".to_string(), "world".to_string()]);
+    let next = message_to_codes(vec![
+      "jello".to_string(),
+      "world".to_string(),
+      "".to_string(),
+      "x".to_string(),
+    ]);
+    assert_eq!(changed_tiles(&current, &next), vec![(0, 0), (3, 0)]);
+  }
+
+  #[test]
+  fn test_changed_tiles_full_change() {
+    let current = [[0u8; 22]; 6];
+    let next = [[63u8; 22]; 6];
+    let changed = changed_tiles(&current, &next);
+    assert_eq!(changed.len(), 6 * 22);
+    assert_eq!(changed.first(), Some(&(0, 0)));
+    assert_eq!(changed.last(), Some(&(5, 21)));
+  }
+
+  #[test]
+  fn test_changed_tiles_clock_minute() {
+    let current = message_to_codes(vec!["12:05".to_string(), "tuesday".to_string()]);
+    let next = message_to_codes(vec!["12:06".to_string(), "tuesday".to_string()]);
+    assert_eq!(changed_tiles(&current, &next), vec![(0, 4)]);
+  }
+
+  #[tokio::test]
+  async fn test_display_message_sends_the_whole_grid() {
+    let mock = MockTransport::new();
+    let transport = Transport::Mock(mock.clone());
+    let first = vec!["12:05".to_string(), "tuesday".to_string()];
+    let second = vec!["12:06".to_string(), "tuesday".to_string()];
+
+    display_message(first.clone(), &transport).await.unwrap();
+    display_message(second.clone(), &transport).await.unwrap();
+    display_message(second.clone(), &transport).await.unwrap();
+
+    // No targeted updates and no skipped repeats: each send is the full grid
+    assert_eq!(
+      mock.sent_codes(),
+      vec![
+        message_to_codes(first),
+        message_to_codes(second.clone()),
+        message_to_codes(second)
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_console_diff_previews_without_sending() {
+    let mock = MockTransport::new();
+    let transport = Transport::Mock(mock.clone());
+    let message = vec!["hi".to_string()];
+
+    // Nothing on the board yet: falls back to a plain preview
+    handle_message(message.clone(), "text", MessageDestination::ConsoleDiff, &transport, &Config::default())
+      .await
+      .unwrap();
+    assert!(mock.sent_codes().is_empty());
+
+    transport.send_codes(message_to_codes(message.clone())).await.unwrap();
+    handle_message(message, "text", MessageDestination::ConsoleDiff, &transport, &Config::default())
+      .await
+      .unwrap();
+    assert_eq!(mock.sent_codes().len(), 1);
+  }
+
+  #[tokio::test]
+  async fn test_console_grid_previews_without_sending() {
+    let mock = MockTransport::new();
+    let transport = Transport::Mock(mock.clone());
+
+    handle_message(vec!["hi".to_string()], "text", MessageDestination::ConsoleGrid, &transport, &Config::default())
+      .await
+      .unwrap();
+    assert!(mock.sent_codes().is_empty());
+  }
+}
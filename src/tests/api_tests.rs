@@ -531,3 +531,228 @@ mod wiremock_tests {
     assert_eq!(result.unwrap().status(), 200);
   }
 }
+
+// Tests for 429 rate-limit handling and Retry-After parsing
+#[cfg(test)]
+mod retry_tests {
+  use crate::api::common::{parse_retry_after, send_with_retry};
+  use crate::api::retry::RetryPolicy;
+  use reqwest::Client;
+  use std::time::{Duration, Instant};
+  use wiremock::matchers::method;
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  /// Three attempts with short backoff, so tests of transient failures stay fast
+  const FAST_RETRY: RetryPolicy = RetryPolicy {
+    attempts: 3,
+    base_delay: Duration::from_millis(10),
+    max_delay: Duration::from_millis(10),
+    jitter: false,
+  };
+
+  #[test]
+  fn test_parse_retry_after_seconds() {
+    assert_eq!(parse_retry_after("2"), Some(Duration::from_secs(2)));
+    assert_eq!(parse_retry_after(" 30 "), Some(Duration::from_secs(30)));
+  }
+
+  #[test]
+  fn test_parse_retry_after_past_http_date_is_zero() {
+    assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+  }
+
+  #[test]
+  fn test_parse_retry_after_invalid() {
+    assert_eq!(parse_retry_after("soon"), None);
+    assert_eq!(parse_retry_after(""), None);
+  }
+
+  /// A 429 with `Retry-After: 2` should wait ~2 seconds, then succeed on the next attempt
+  #[tokio::test]
+  async fn test_retry_after_delays_then_succeeds() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "2"))
+      .up_to_n_times(1)
+      .expect(1)
+      .mount(&mock_server).await;
+    Mock::given(method("GET"))
+      .respond_with(ResponseTemplate::new(200))
+      .expect(1)
+      .mount(&mock_server).await;
+
+    let client = Client::new();
+    let start = Instant::now();
+    let response = send_with_retry(|| client.get(mock_server.uri()), &FAST_RETRY, Duration::from_secs(60)).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert!(start.elapsed() >= Duration::from_secs(2), "Should honor Retry-After, waited {:?}", start.elapsed());
+  }
+
+  /// When every attempt is rate limited, the last 429 is returned after max attempts
+  #[tokio::test]
+  async fn test_retry_after_gives_up_after_max_attempts() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "2"))
+      .expect(3)
+      .mount(&mock_server).await;
+
+    let client = Client::new();
+    let start = Instant::now();
+    // Cap the delay so the test stays fast
+    let response = send_with_retry(|| client.get(mock_server.uri()), &FAST_RETRY, Duration::from_millis(50)).await.unwrap();
+
+    assert_eq!(response.status(), 429);
+    assert!(start.elapsed() < Duration::from_secs(2), "Delay should be capped, waited {:?}", start.elapsed());
+  }
+
+  /// A 503 is retried with backoff, then the next attempt succeeds
+  #[tokio::test]
+  async fn test_server_error_is_retried_then_succeeds() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .respond_with(ResponseTemplate::new(503))
+      .up_to_n_times(1)
+      .expect(1)
+      .mount(&mock_server).await;
+    Mock::given(method("GET"))
+      .respond_with(ResponseTemplate::new(200))
+      .expect(1)
+      .mount(&mock_server).await;
+
+    let client = Client::new();
+    let response = send_with_retry(|| client.get(mock_server.uri()), &FAST_RETRY, Duration::from_secs(60)).await.unwrap();
+
+    assert_eq!(response.status(), 200);
+  }
+
+  /// A server that keeps failing gets exactly the policy's number of attempts in all
+  #[tokio::test]
+  async fn test_server_error_gives_up_after_policy_attempts() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .respond_with(ResponseTemplate::new(503))
+      .expect(3)
+      .mount(&mock_server).await;
+
+    let client = Client::new();
+    let response = send_with_retry(|| client.get(mock_server.uri()), &FAST_RETRY, Duration::from_secs(60)).await.unwrap();
+
+    assert_eq!(response.status(), 503);
+  }
+}
+
+// Tests for the shared HTTP response guard
+#[cfg(test)]
+mod response_guard_tests {
+  use crate::api::common::{body_excerpt, check_status, read_checked, MAX_LOGGED_BODY_CHARS};
+  use crate::errors::VestaboardError;
+  use wiremock::matchers::method;
+  use wiremock::{Mock, MockServer, ResponseTemplate};
+
+  #[test]
+  fn test_check_status_200_returns_body() {
+    let result = check_status(200, "{\"ok\":true}".to_string(), "Test API");
+    assert_eq!(result.unwrap(), "{\"ok\":true}");
+  }
+
+  #[test]
+  fn test_check_status_404_html_page_is_api_error() {
+    let result = check_status(404, "<html><body>Not Found</body></html>".to_string(), "Test API");
+    match result {
+      Err(VestaboardError::ApiError { code, message }) => {
+        assert_eq!(code, Some(404));
+        assert_eq!(message, "Test API error: Not Found");
+      },
+      other => panic!("Expected ApiError, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_check_status_502_is_temporarily_unavailable() {
+    let result = check_status(502, "<html>Bad Gateway</html>".to_string(), "Test API");
+    match result {
+      Err(VestaboardError::ApiError { code, message }) => {
+        assert_eq!(code, Some(502));
+        assert!(message.contains("temporarily unavailable"));
+      },
+      other => panic!("Expected ApiError, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_body_excerpt_shortens_large_bodies() {
+    assert_eq!(body_excerpt("<html>\n  <body>Not Found</body>\n</html>"), "<html> <body>Not Found</body> </html>");
+
+    let page = format!("<html>{}</html>", "x".repeat(5000));
+    let excerpt = body_excerpt(&page);
+    assert_eq!(excerpt.chars().count(), MAX_LOGGED_BODY_CHARS + 3);
+    assert!(excerpt.starts_with("<html>xxx"));
+    assert!(excerpt.ends_with("..."));
+  }
+
+  #[test]
+  fn test_check_status_keeps_json_error_message() {
+    let body = r#"{"error":{"code":1006,"message":"No matching location found."}}"#.to_string();
+    let result = check_status(400, body, "Weather API");
+    assert_eq!(
+      result.unwrap_err(),
+      VestaboardError::api_error(Some(400), "Weather API error: No matching location found.")
+    );
+  }
+
+  #[tokio::test]
+  async fn test_read_checked_with_http_responses() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+      .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+      .up_to_n_times(1)
+      .mount(&mock_server).await;
+    Mock::given(method("GET"))
+      .respond_with(ResponseTemplate::new(404).set_body_string("<html>Not Found</html>"))
+      .mount(&mock_server).await;
+
+    let client = reqwest::Client::new();
+
+    let ok = client.get(mock_server.uri()).send().await.unwrap();
+    assert_eq!(read_checked(ok, "Test API").await.unwrap(), "hello");
+
+    let missing = client.get(mock_server.uri()).send().await.unwrap();
+    match read_checked(missing, "Test API").await {
+      Err(VestaboardError::ApiError { code, .. }) => assert_eq!(code, Some(404)),
+      other => panic!("Expected ApiError, got {:?}", other),
+    }
+  }
+}
+
+// Tests for reading the board's current grid
+#[cfg(test)]
+mod read_grid_tests {
+  use crate::api::common::grid_from_json;
+  use serde_json::json;
+
+  #[test]
+  fn test_grid_from_json_reads_6x22_grid() {
+    let mut rows = vec![vec![0; 22]; 6];
+    rows[0][0] = 63;
+    rows[5][21] = 70;
+
+    let grid = grid_from_json(&json!(rows)).expect("valid grid");
+    assert_eq!(grid[0][0], 63);
+    assert_eq!(grid[5][21], 70);
+  }
+
+  #[test]
+  fn test_grid_from_json_rejects_other_shapes() {
+    assert_eq!(grid_from_json(&json!(null)), None);
+    assert_eq!(grid_from_json(&json!(vec![vec![0; 22]; 5])), None);
+    assert_eq!(grid_from_json(&json!(vec![vec![0; 21]; 6])), None);
+    assert_eq!(grid_from_json(&json!(vec![vec![300; 22]; 6])), None);
+  }
+}
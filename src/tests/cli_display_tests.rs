@@ -137,3 +137,82 @@ fn test_print_warning_exists() {
   cli_display::print_warning("Test warning message");
   assert!(true); // Function executed
 }
+
+#[test]
+fn truncate_to_width_test() {
+  assert_eq!(cli_display::truncate_to_width("hello", 10), "hello");
+  assert_eq!(cli_display::truncate_to_width("hello world", 8), "hello...");
+  assert_eq!(cli_display::truncate_to_width("héllo wörld", 8), "héllo...");
+  assert_eq!(cli_display::truncate_to_width("hello", 2), "..");
+}
+
+#[test]
+fn colorize_test() {
+  assert_eq!(cli_display::colorize("hi", "33"), "\x1b[33mhi\x1b[0m");
+}
+
+#[test]
+fn colors_disabled_without_tty_test() {
+  // Output control was never initialized in this module copy, so no TTY is detected
+  assert!(!cli_display::colors_enabled());
+}
+
+#[test]
+fn no_color_flag_disables_colors_with_tty_test() {
+  assert!(cli_display::should_colorize(true, cli_display::color_disabled(false, None)));
+  assert!(!cli_display::should_colorize(true, cli_display::color_disabled(true, None)));
+}
+
+#[test]
+fn no_color_env_disables_colors_with_tty_test() {
+  let set = std::ffi::OsStr::new("1");
+  let empty = std::ffi::OsStr::new("");
+  assert!(!cli_display::should_colorize(true, cli_display::color_disabled(false, Some(set))));
+  // An empty NO_COLOR does not count as set
+  assert!(cli_display::should_colorize(true, cli_display::color_disabled(false, Some(empty))));
+}
+
+#[test]
+fn format_preview_without_colors_test() {
+  let message = vec!["ROYGBVKW".to_string(), "72D".to_string()];
+
+  let output = cli_display::format_preview(&message, "", false);
+
+  assert!(output.iter().all(|line| !line.contains('\x1b')));
+  assert_eq!(output[2], "|ROYGBVKW              |");
+  assert_eq!(output[3], "|72°                   |");
+  assert_eq!(output.len(), 9);
+}
+
+#[test]
+fn format_text_grid_pads_every_row_test() {
+  let message = vec![
+    "ROYGBVKW".to_string(),
+    "72D".to_string(),
+    "abcdefghijklmnopqrstuv".to_string(),
+  ];
+
+  let output = cli_display::print_text_grid(&message);
+
+  assert_eq!(output.len(), 8);
+  assert!(output.iter().all(|line| line.chars().count() == 24));
+  assert_eq!(output[0], "+----------------------+");
+  assert_eq!(output[1], "|ROYGBVKW              |");
+  assert_eq!(output[2], "|72D                   |");
+  assert_eq!(output[3], "|abcdefghijklmnopqrstuv|");
+  assert_eq!(output[6], format!("|{}|", " ".repeat(22)));
+}
+
+#[test]
+fn format_diff_preview_marks_changed_tiles_test() {
+  let message = vec!["hello".to_string(), "world".to_string()];
+
+  let plain = cli_display::format_diff_preview(&message, "", false, &[(1, 0), (1, 4)]);
+  assert_eq!(plain[3], "|world                 |");
+  assert_eq!(plain[4], " ^   ^");
+  assert_eq!(plain.len(), 10);
+
+  let colored = cli_display::print_message_diff(message, "", &[(0, 1)]);
+  assert_eq!(colored[2], "|h\x1b[7me\x1b[0mllo                 |");
+  assert_eq!(colored.len(), 9);
+}
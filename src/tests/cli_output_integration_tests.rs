@@ -8,6 +8,7 @@
 
 #[cfg(test)]
 mod tests {
+  use crate::config::Config;
   use crate::errors::VestaboardError;
   use crate::widgets::resolver::execute_widget;
   use crate::widgets::text::get_text_from_file;
@@ -29,7 +30,7 @@ mod tests {
   async fn test_widget_execution_messages_text() {
     // Test that text widget execution produces expected messages
     // This tests the message sequence: "Creating message from text..." -> success
-    let result = execute_widget("text", &serde_json::json!("hello world")).await;
+    let result = execute_widget("text", &serde_json::json!("hello world"), &Config::default()).await;
 
     // Verify the operation completes (messages would be printed during execution)
     assert!(result.is_ok());
@@ -71,7 +72,7 @@ mod tests {
   #[tokio::test]
   async fn test_widget_execution_messages_unknown_widget() {
     // Test unknown widget type
-    let result = execute_widget("unknown_widget", &serde_json::json!(null)).await;
+    let result = execute_widget("unknown_widget", &serde_json::json!(null), &Config::default()).await;
 
     // Verify error is returned
     assert!(result.is_err());
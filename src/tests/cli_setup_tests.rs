@@ -2,7 +2,10 @@
 mod cli_setup;
 
 use clap::Parser;
-use cli_setup::{Cli, Command, FileArgs, PlaylistArgs, ScheduleArgs, ShowArgs, TextArgs, WidgetCommand};
+use cli_setup::{
+  Cli, Command, FileArgs, ForexArgs, HistoryArgs, PlaylistArgs, RandomArgs, ScheduleArgs, ShowArgs, TextArgs,
+  WidgetArgs, WidgetCommand,
+};
 
 #[cfg(test)]
 #[test]
@@ -17,7 +20,12 @@ fn test_widget_command_variants() {
       WidgetCommand::Weather => {},
       WidgetCommand::Jokes => {},
       WidgetCommand::Clear => {},
+      WidgetCommand::ColorTest(_) => {},
       WidgetCommand::SATWord => {},
+      WidgetCommand::AirQuality => {},
+      WidgetCommand::Forex(_) => {},
+      WidgetCommand::OnThisDay => {},
+      WidgetCommand::Random(_) => {},
     }
   }
 
@@ -30,6 +38,15 @@ fn test_widget_command_variants() {
   assert_widget_command(WidgetCommand::Jokes);
   assert_widget_command(WidgetCommand::Clear);
   assert_widget_command(WidgetCommand::SATWord);
+  assert_widget_command(WidgetCommand::AirQuality);
+  assert_widget_command(WidgetCommand::Forex(ForexArgs {
+    from: String::from("usd"),
+    to: String::from("eur"),
+  }));
+  assert_widget_command(WidgetCommand::OnThisDay);
+  assert_widget_command(WidgetCommand::Random(RandomArgs {
+    choices: vec![String::from("weather")],
+  }));
 }
 
 #[test]
@@ -41,33 +58,71 @@ fn test_command_variants() {
       Command::Schedule { action } => match action {
         ScheduleArgs::Add { .. } => {},
         ScheduleArgs::Remove { .. } => {},
-        ScheduleArgs::List => {},
-        ScheduleArgs::Clear => {},
-        ScheduleArgs::Preview => {},
+        ScheduleArgs::Reschedule { .. } => {},
+        ScheduleArgs::List { .. } => {},
+        ScheduleArgs::Next => {},
+        ScheduleArgs::Clear { .. } => {},
+        ScheduleArgs::Dedupe { .. } => {},
+        ScheduleArgs::Import { .. } => {},
+        ScheduleArgs::Export { .. } => {},
+        ScheduleArgs::Preview { .. } => {},
         ScheduleArgs::Run { .. } => {},
       },
       Command::Playlist { action } => match action {
         PlaylistArgs::Add { .. } => {},
         PlaylistArgs::List => {},
         PlaylistArgs::Remove { .. } => {},
+        PlaylistArgs::Edit { .. } => {},
         PlaylistArgs::Clear => {},
+        PlaylistArgs::Import { .. } => {},
         PlaylistArgs::Interval { .. } => {},
+        PlaylistArgs::Export { .. } => {},
         PlaylistArgs::Preview => {},
+        PlaylistArgs::Show { .. } => {},
         PlaylistArgs::Run { .. } => {},
       },
+      Command::Status => {},
+      Command::Repeat => {},
+      Command::Daemon { .. } => {},
+      Command::Doctor { .. } => {},
+      Command::PreviewAll => {},
+      Command::Test => {},
+      Command::Timer { .. } => {},
+      Command::Unlock { .. } => {},
+      Command::SendCodes { .. } => {},
+      Command::Snapshot { .. } => {},
+      Command::Lint { .. } => {},
+      Command::Widget { action } => match action {
+        WidgetArgs::Schema { .. } => {},
+      },
+      Command::History { action } => match action {
+        None => {},
+        Some(HistoryArgs::Show { .. }) => {},
+      },
     }
   }
 
   // Call the function to ensure all variants are covered
   assert_command(Command::Show(ShowArgs {
-    widget_command: WidgetCommand::Clear,
+    widget_command: Some(WidgetCommand::Clear),
+    widget: None,
+    input: None,
+    input_file: None,
     dry_run: false,
+    diff: false,
+    grid: false,
+    webhook: None,
+    for_duration: None,
+    then_clear: false,
   }));
   assert_command(Command::Schedule {
     action: ScheduleArgs::Add {
       time: "2025-05-01T09:00:00Z".to_string(),
       widget: "Weather".to_string(),
       input: vec!["".to_string()],
+      input_file: None,
+      repeat: None,
+      show_now: false,
     },
   });
   assert_command(Command::Playlist {
@@ -79,10 +134,18 @@ fn test_command_variants() {
 fn test_show_args() {
   // Test arguments for ShowArgs
   let show_args = ShowArgs {
-    widget_command: WidgetCommand::Text(TextArgs {
+    widget_command: Some(WidgetCommand::Text(TextArgs {
       message: String::from("Test message"),
-    }),
+    })),
+    widget: None,
+    input: None,
+    input_file: None,
     dry_run: true,
+    diff: false,
+    grid: false,
+    webhook: None,
+    for_duration: None,
+    then_clear: false,
   };
 
   // Check if the arguments are handled correctly
@@ -96,10 +159,17 @@ fn test_cli_parses_playlist_add_weather() {
   let cli = Cli::parse_from(["vbl", "playlist", "add", "weather"]);
   match cli.command {
     Command::Playlist {
-      action: PlaylistArgs::Add { widget, input },
+      action: PlaylistArgs::Add {
+        widget,
+        input,
+        input_file,
+        at,
+      },
     } => {
       assert_eq!(widget, "weather");
       assert!(input.is_empty());
+      assert_eq!(input_file, None);
+      assert_eq!(at, None);
     },
     _ => panic!("Expected Playlist Add command"),
   }
@@ -110,7 +180,7 @@ fn test_cli_parses_playlist_add_text_with_input() {
   let cli = Cli::parse_from(["vbl", "playlist", "add", "text", "hello", "world"]);
   match cli.command {
     Command::Playlist {
-      action: PlaylistArgs::Add { widget, input },
+      action: PlaylistArgs::Add { widget, input, .. },
     } => {
       assert_eq!(widget, "text");
       assert_eq!(input, vec!["hello", "world"]);
@@ -119,6 +189,21 @@ fn test_cli_parses_playlist_add_text_with_input() {
   }
 }
 
+#[test]
+fn test_cli_parses_playlist_add_with_at() {
+  let cli = Cli::parse_from(["vbl", "playlist", "add", "--at", "2", "text", "hello"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Add { widget, input, at, .. },
+    } => {
+      assert_eq!(widget, "text");
+      assert_eq!(input, vec!["hello"]);
+      assert_eq!(at, Some(2));
+    },
+    _ => panic!("Expected Playlist Add command"),
+  }
+}
+
 #[test]
 fn test_cli_parses_playlist_list() {
   let cli = Cli::parse_from(["vbl", "playlist", "list"]);
@@ -143,6 +228,33 @@ fn test_cli_parses_playlist_remove() {
   }
 }
 
+#[test]
+fn test_cli_parses_playlist_import() {
+  let cli = Cli::parse_from(["vbl", "playlist", "import", "--keep-existing", "playlist.json"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Import { file, keep_existing },
+    } => {
+      assert_eq!(file, std::path::PathBuf::from("playlist.json"));
+      assert!(keep_existing);
+    },
+    _ => panic!("Expected Playlist Import command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_playlist_show() {
+  let cli = Cli::parse_from(["vbl", "playlist", "show", "abc1"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Show { id },
+    } => {
+      assert_eq!(id, "abc1");
+    },
+    _ => panic!("Expected Playlist Show command"),
+  }
+}
+
 #[test]
 fn test_cli_parses_playlist_clear() {
   let cli = Cli::parse_from(["vbl", "playlist", "clear"]);
@@ -196,19 +308,30 @@ fn test_cli_parses_playlist_run_defaults() {
   let cli = Cli::parse_from(["vbl", "playlist", "run"]);
   match cli.command {
     Command::Playlist {
-      action: PlaylistArgs::Run {
-        once,
-        resume,
-        index,
-        id,
-        dry_run,
-      },
+      action:
+        PlaylistArgs::Run {
+          once,
+          resume,
+          index,
+          id,
+          dry_run,
+          tui,
+          reverse,
+          shuffle,
+          tag,
+          interval,
+        },
     } => {
       assert!(!once);
       assert!(!resume);
       assert!(index.is_none());
       assert!(id.is_none());
       assert!(!dry_run);
+      assert!(!tui);
+      assert!(!reverse);
+      assert!(!shuffle);
+      assert!(tag.is_none());
+      assert!(interval.is_none());
     },
     _ => panic!("Expected Playlist Run command"),
   }
@@ -227,6 +350,33 @@ fn test_cli_parses_playlist_run_once() {
   }
 }
 
+#[test]
+fn test_cli_parses_playlist_run_tag() {
+  let cli = Cli::parse_from(["vbl", "playlist", "run", "--tag", "morning"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Run { tag, .. },
+    } => {
+      assert_eq!(tag.as_deref(), Some("morning"));
+    },
+    _ => panic!("Expected Playlist Run command"),
+  }
+  assert!(Cli::try_parse_from(["vbl", "playlist", "run", "--tag", "morning", "--resume"]).is_err());
+}
+
+#[test]
+fn test_cli_parses_playlist_run_interval() {
+  let cli = Cli::parse_from(["vbl", "playlist", "run", "--interval", "90"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Run { interval, .. },
+    } => {
+      assert_eq!(interval, Some(90));
+    },
+    _ => panic!("Expected Playlist Run command"),
+  }
+}
+
 #[test]
 fn test_cli_parses_playlist_run_with_index() {
   let cli = Cli::parse_from(["vbl", "playlist", "run", "--index", "2"]);
@@ -286,13 +436,15 @@ fn test_cli_parses_playlist_run_combined_flags() {
   let cli = Cli::parse_from(["vbl", "playlist", "run", "--once", "--index", "1", "-d"]);
   match cli.command {
     Command::Playlist {
-      action: PlaylistArgs::Run {
-        once,
-        resume,
-        index,
-        id,
-        dry_run,
-      },
+      action:
+        PlaylistArgs::Run {
+          once,
+          resume,
+          index,
+          id,
+          dry_run,
+          ..
+        },
     } => {
       assert!(once);
       assert!(!resume);
@@ -333,12 +485,62 @@ fn test_cli_playlist_run_resume_and_id_mutually_exclusive() {
 
 // --- Schedule run CLI parsing tests ---
 
+#[test]
+fn test_cli_parses_schedule_clear_before() {
+  let cli = Cli::parse_from(["vbl", "schedule", "clear", "--before", "2025-05-01"]);
+  assert!(matches!(
+    cli.command,
+    Command::Schedule { action: ScheduleArgs::Clear { before: Some(ref b) } } if b == "2025-05-01"
+  ));
+
+  let cli = Cli::parse_from(["vbl", "schedule", "clear", "--before"]);
+  assert!(matches!(
+    cli.command,
+    Command::Schedule { action: ScheduleArgs::Clear { before: Some(ref b) } } if b == "now"
+  ));
+
+  let cli = Cli::parse_from(["vbl", "schedule", "clear"]);
+  assert!(matches!(
+    cli.command,
+    Command::Schedule {
+      action: ScheduleArgs::Clear { before: None }
+    }
+  ));
+}
+
+#[test]
+fn test_cli_parses_schedule_reschedule() {
+  for verb in ["reschedule", "move"] {
+    let cli = Cli::parse_from(["vbl", "schedule", verb, "abc1", "2025-05-01 09:00:00"]);
+    match cli.command {
+      Command::Schedule {
+        action: ScheduleArgs::Reschedule { id, time },
+      } => {
+        assert_eq!(id, "abc1");
+        assert_eq!(time, "2025-05-01 09:00:00");
+      },
+      _ => panic!("Expected schedule reschedule"),
+    }
+  }
+}
+
+#[test]
+fn test_cli_parses_schedule_import() {
+  let cli = Cli::parse_from(["vbl", "schedule", "import", "calendar.ics"]);
+  match cli.command {
+    Command::Schedule {
+      action: ScheduleArgs::Import { file },
+    } => assert_eq!(file, std::path::PathBuf::from("calendar.ics")),
+    _ => panic!("Expected schedule import"),
+  }
+}
+
 #[test]
 fn test_cli_parses_schedule_run() {
   let cli = Cli::parse_from(["vbl", "schedule", "run"]);
   match cli.command {
     Command::Schedule {
-      action: ScheduleArgs::Run { dry_run },
+      action: ScheduleArgs::Run { dry_run, .. },
     } => {
       assert!(!dry_run);
     },
@@ -346,12 +548,61 @@ fn test_cli_parses_schedule_run() {
   }
 }
 
+#[test]
+fn test_cli_parses_daemon_once() {
+  let cli = Cli::parse_from(["vbl", "daemon", "--once"]);
+  match cli.command {
+    Command::Daemon { dry_run, once } => {
+      assert!(!dry_run);
+      assert!(once);
+    },
+    _ => panic!("Expected Daemon command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_schedule_run_simulate() {
+  let cli = Cli::parse_from([
+    "vbl",
+    "schedule",
+    "run",
+    "--simulate",
+    "2025-05-01",
+    "2025-05-03 12:00:00",
+  ]);
+  match cli.command {
+    Command::Schedule {
+      action: ScheduleArgs::Run { simulate, dry_run, .. },
+    } => {
+      assert!(!dry_run);
+      assert_eq!(simulate, Some(vec!["2025-05-01".to_string(), "2025-05-03 12:00:00".to_string()]));
+    },
+    _ => panic!("Expected Schedule Run command"),
+  }
+  assert!(Cli::try_parse_from(["vbl", "schedule", "run", "--simulate", "2025-05-01"]).is_err());
+  assert!(Cli::try_parse_from(["vbl", "schedule", "run", "-d", "--simulate", "2025-05-01", "2025-05-02"]).is_err());
+}
+
+#[test]
+fn test_cli_parses_schedule_run_catch_up() {
+  let cli = Cli::parse_from(["vbl", "schedule", "run", "--catch-up"]);
+  match cli.command {
+    Command::Schedule {
+      action: ScheduleArgs::Run { dry_run, catch_up, .. },
+    } => {
+      assert!(!dry_run);
+      assert!(catch_up);
+    },
+    _ => panic!("Expected Schedule Run command"),
+  }
+}
+
 #[test]
 fn test_cli_parses_schedule_run_dry_run() {
   let cli = Cli::parse_from(["vbl", "schedule", "run", "--dry-run"]);
   match cli.command {
     Command::Schedule {
-      action: ScheduleArgs::Run { dry_run },
+      action: ScheduleArgs::Run { dry_run, .. },
     } => {
       assert!(dry_run);
     },
@@ -364,7 +615,7 @@ fn test_cli_parses_schedule_run_dry_run_short() {
   let cli = Cli::parse_from(["vbl", "schedule", "run", "-d"]);
   match cli.command {
     Command::Schedule {
-      action: ScheduleArgs::Run { dry_run },
+      action: ScheduleArgs::Run { dry_run, .. },
     } => {
       assert!(dry_run);
     },
@@ -430,3 +681,425 @@ fn test_cli_parses_playlist_add_with_internet_flag() {
     _ => panic!("Expected Playlist Add command"),
   }
 }
+
+#[test]
+fn test_cli_parses_show_random_with_choices() {
+  let cli = Cli::parse_from(["vbl", "show", "random", "weather", "jokes"]);
+  match cli.command {
+    Command::Show(ShowArgs {
+      widget_command: Some(WidgetCommand::Random(args)),
+      ..
+    }) => {
+      assert_eq!(args.choices, vec!["weather", "jokes"]);
+    },
+    _ => panic!("Expected Show Random command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_show_colortest() {
+  let cli = Cli::parse_from(["vbl", "show", "colortest", "red"]);
+  match cli.command {
+    Command::Show(ShowArgs {
+      widget_command: Some(WidgetCommand::ColorTest(args)),
+      ..
+    }) => {
+      assert_eq!(args.color, "red");
+    },
+    _ => panic!("Expected Show ColorTest command"),
+  }
+  assert!(Cli::try_parse_from(["vbl", "show", "colortest"]).is_err());
+}
+
+#[test]
+fn test_cli_parses_show_inline_widget() {
+  let cli = Cli::parse_from([
+    "vbl",
+    "show",
+    "--widget",
+    "forex",
+    "--input",
+    r#"{"from":"usd","to":"eur"}"#,
+  ]);
+  match cli.command {
+    Command::Show(ShowArgs {
+      widget_command: None,
+      widget: Some(widget),
+      input: Some(input),
+      ..
+    }) => {
+      assert_eq!(widget, "forex");
+      assert_eq!(input, r#"{"from":"usd","to":"eur"}"#);
+    },
+    _ => panic!("Expected Show with --widget"),
+  }
+}
+
+#[test]
+fn test_cli_parses_show_dry_run_diff() {
+  let cli = Cli::parse_from(["vbl", "show", "--dry-run", "--diff", "text", "hello"]);
+  match cli.command {
+    Command::Show(ShowArgs { dry_run, diff, .. }) => {
+      assert!(dry_run);
+      assert!(diff);
+    },
+    _ => panic!("Expected Show command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_show_dry_run_grid() {
+  let cli = Cli::parse_from(["vbl", "show", "-d", "--grid", "text", "hello"]);
+  match cli.command {
+    Command::Show(ShowArgs { dry_run, grid, .. }) => {
+      assert!(dry_run);
+      assert!(grid);
+    },
+    _ => panic!("Expected Show command"),
+  }
+  assert!(Cli::try_parse_from(["vbl", "show", "--grid", "text", "hello"]).is_err());
+  assert!(Cli::try_parse_from(["vbl", "show", "-d", "--grid", "--diff", "text", "hello"]).is_err());
+}
+
+#[test]
+fn test_cli_show_diff_requires_dry_run() {
+  assert!(Cli::try_parse_from(["vbl", "show", "--diff", "text", "hello"]).is_err());
+}
+
+#[test]
+fn test_cli_show_input_requires_widget() {
+  assert!(Cli::try_parse_from(["vbl", "show", "--input", "hello"]).is_err());
+}
+
+#[test]
+fn test_cli_parses_input_file() {
+  use std::path::PathBuf;
+
+  let cli = Cli::parse_from(["vbl", "show", "--widget", "kv", "--input-file", "rows.json"]);
+  match cli.command {
+    Command::Show(ShowArgs { widget, input_file, .. }) => {
+      assert_eq!(widget.as_deref(), Some("kv"));
+      assert_eq!(input_file, Some(PathBuf::from("rows.json")));
+    },
+    _ => panic!("Expected Show command"),
+  }
+
+  let cli = Cli::parse_from(["vbl", "playlist", "add", "--input-file", "rows.json", "kv"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Add { widget, input_file, .. },
+    } => {
+      assert_eq!(widget, "kv");
+      assert_eq!(input_file, Some(PathBuf::from("rows.json")));
+    },
+    _ => panic!("Expected Playlist Add command"),
+  }
+
+  let cli = Cli::parse_from([
+    "vbl",
+    "schedule",
+    "add",
+    "--input-file",
+    "rows.json",
+    "2030-05-01 08:00:00",
+    "kv",
+  ]);
+  match cli.command {
+    Command::Schedule {
+      action: ScheduleArgs::Add { widget, input_file, .. },
+    } => {
+      assert_eq!(widget, "kv");
+      assert_eq!(input_file, Some(PathBuf::from("rows.json")));
+    },
+    _ => panic!("Expected Schedule Add command"),
+  }
+}
+
+#[test]
+fn test_cli_input_file_conflicts_with_input() {
+  assert!(Cli::try_parse_from(["vbl", "show", "--input-file", "in.json"]).is_err());
+  assert!(Cli::try_parse_from([
+    "vbl",
+    "show",
+    "--widget",
+    "text",
+    "--input",
+    "hi",
+    "--input-file",
+    "in.json"
+  ])
+  .is_err());
+  assert!(Cli::try_parse_from(["vbl", "playlist", "add", "--input-file", "in.json", "text", "hi"]).is_err());
+}
+
+#[test]
+fn test_cli_parses_playlist_edit_with_input() {
+  let cli = Cli::parse_from(["vbl", "playlist", "edit", "abc1", "--input", "hello", "world"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Edit { id, input },
+    } => {
+      assert_eq!(id, "abc1");
+      assert_eq!(input, vec!["hello", "world"]);
+    },
+    _ => panic!("Expected Playlist Edit command"),
+  }
+}
+
+#[test]
+fn test_cli_playlist_edit_requires_input() {
+  let result = Cli::try_parse_from(["vbl", "playlist", "edit", "abc1"]);
+  assert!(result.is_err());
+}
+
+#[test]
+fn test_cli_parses_status() {
+  let cli = Cli::parse_from(["vbl", "status"]);
+  assert!(matches!(cli.command, Command::Status));
+}
+
+#[test]
+fn test_cli_parses_doctor() {
+  let cli = Cli::parse_from(["vbl", "doctor"]);
+  assert!(matches!(cli.command, Command::Doctor { skip_connection: false }));
+
+  let cli = Cli::parse_from(["vbl", "doctor", "--skip-connection"]);
+  assert!(matches!(cli.command, Command::Doctor { skip_connection: true }));
+}
+
+#[test]
+fn test_cli_parses_preview_all() {
+  let cli = Cli::parse_from(["vbl", "preview-all"]);
+  assert!(matches!(cli.command, Command::PreviewAll));
+}
+
+#[test]
+fn test_cli_parses_repeat() {
+  let cli = Cli::parse_from(["vbl", "repeat"]);
+  assert!(matches!(cli.command, Command::Repeat));
+}
+
+#[test]
+fn test_cli_parses_widget_schema() {
+  let cli = Cli::parse_from(["vbl", "widget", "schema", "forex"]);
+  match cli.command {
+    Command::Widget {
+      action: WidgetArgs::Schema { name },
+    } => assert_eq!(name, "forex"),
+    _ => panic!("Expected widget schema command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_snapshot() {
+  let cli = Cli::parse_from(["vbl", "snapshot", "board.json"]);
+  match cli.command {
+    Command::Snapshot { path } => assert_eq!(path, std::path::PathBuf::from("board.json")),
+    _ => panic!("Expected snapshot command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_send_codes() {
+  let cli = Cli::parse_from(["vbl", "send-codes", "codes.json"]);
+  match cli.command {
+    Command::SendCodes { codes } => assert_eq!(codes, "codes.json"),
+    _ => panic!("Expected send-codes command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_lint() {
+  let cli = Cli::parse_from(["vbl", "lint", "café €5"]);
+  match cli.command {
+    Command::Lint { message } => assert_eq!(message, "café €5"),
+    _ => panic!("Expected Lint command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_unlock() {
+  let cli = Cli::parse_from(["vbl", "unlock"]);
+  assert!(matches!(cli.command, Command::Unlock { force: false }));
+
+  let cli = Cli::parse_from(["vbl", "unlock", "--force"]);
+  assert!(matches!(cli.command, Command::Unlock { force: true }));
+}
+
+#[test]
+fn test_cli_parses_history() {
+  let cli = Cli::parse_from(["vbl", "history"]);
+  assert!(matches!(cli.command, Command::History { action: None }));
+
+  let cli = Cli::parse_from(["vbl", "history", "show", "3"]);
+  assert!(matches!(
+    cli.command,
+    Command::History {
+      action: Some(HistoryArgs::Show { n: 3 })
+    }
+  ));
+}
+
+#[test]
+fn test_cli_parses_test_command() {
+  let cli = Cli::parse_from(["vbl", "test"]);
+  assert!(matches!(cli.command, Command::Test));
+}
+
+#[test]
+fn test_cli_parses_timer() {
+  let cli = Cli::parse_from(["vbl", "timer", "25m", "break", "time"]);
+  match cli.command {
+    Command::Timer {
+      duration,
+      label,
+      dry_run,
+    } => {
+      assert_eq!(duration, "25m");
+      assert_eq!(label, vec!["break", "time"]);
+      assert!(!dry_run);
+    },
+    _ => panic!("Expected Timer command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_playlist_run_tui() {
+  let cli = Cli::parse_from(["vbl", "playlist", "run", "--tui"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Run { tui, .. },
+    } => assert!(tui),
+    _ => panic!("Expected Playlist Run command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_playlist_run_reverse() {
+  let cli = Cli::parse_from(["vbl", "playlist", "run", "--reverse", "--once"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Run { reverse, once, .. },
+    } => assert!(reverse && once),
+    _ => panic!("Expected Playlist Run command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_playlist_run_once_shuffle() {
+  let cli = Cli::parse_from(["vbl", "playlist", "run", "--once", "--shuffle"]);
+  match cli.command {
+    Command::Playlist {
+      action: PlaylistArgs::Run { shuffle, once, .. },
+    } => assert!(shuffle && once),
+    _ => panic!("Expected Playlist Run command"),
+  }
+  assert!(Cli::try_parse_from(["vbl", "playlist", "run", "--shuffle", "--resume"]).is_err());
+}
+
+#[test]
+fn test_cli_parses_schedule_next() {
+  let cli = Cli::parse_from(["vbl", "schedule", "next"]);
+  assert!(matches!(
+    cli.command,
+    Command::Schedule {
+      action: ScheduleArgs::Next
+    }
+  ));
+}
+
+#[test]
+fn test_cli_parses_schedule_list_with_range() {
+  let cli = Cli::parse_from([
+    "vbl",
+    "schedule",
+    "list",
+    "--from",
+    "2025-05-01",
+    "--to",
+    "2025-05-07 18:00:00",
+  ]);
+  match cli.command {
+    Command::Schedule {
+      action: ScheduleArgs::List { from, to },
+    } => {
+      assert_eq!(from.as_deref(), Some("2025-05-01"));
+      assert_eq!(to.as_deref(), Some("2025-05-07 18:00:00"));
+    },
+    _ => panic!("Expected Schedule List command"),
+  }
+}
+
+#[test]
+fn test_cli_parses_schedule_add_repeat() {
+  let cli = Cli::parse_from([
+    "vbl",
+    "schedule",
+    "add",
+    "--repeat",
+    "mon,wed,fri",
+    "2025-05-01 07:00:00",
+    "weather",
+  ]);
+  match cli.command {
+    Command::Schedule {
+      action: ScheduleArgs::Add { widget, repeat, .. },
+    } => {
+      assert_eq!(widget, "weather");
+      assert_eq!(repeat.as_deref(), Some("mon,wed,fri"));
+    },
+    _ => panic!("Expected schedule add"),
+  }
+}
+
+#[test]
+fn test_cli_parses_show_for() {
+  let cli = Cli::parse_from(["vbl", "show", "--for", "30s", "--then-clear", "text", "hello"]);
+  match cli.command {
+    Command::Show(show_args) => {
+      assert_eq!(show_args.for_duration.as_deref(), Some("30s"));
+      assert!(show_args.then_clear);
+    },
+    _ => panic!("Expected Show command"),
+  }
+}
+
+#[test]
+fn test_cli_show_for_conflicts_with_dry_run() {
+  assert!(Cli::try_parse_from(["vbl", "show", "--for", "30s", "--dry-run", "text", "hello"]).is_err());
+  assert!(Cli::try_parse_from(["vbl", "show", "--then-clear", "text", "hello"]).is_err());
+}
+
+#[test]
+fn test_cli_parses_global_no_color_flag() {
+  let cli = Cli::parse_from(["vbl", "show", "text", "hi", "--no-color"]);
+  assert!(cli.no_color);
+
+  let cli = Cli::parse_from(["vbl", "--no-color", "show", "clear"]);
+  assert!(cli.no_color);
+
+  let cli = Cli::try_parse_from(["vbl", "show", "clear"]).unwrap();
+  assert!(!cli.no_color);
+}
+
+#[test]
+fn test_cli_parses_schedule_add_show_now() {
+  let cli = Cli::parse_from([
+    "vbl",
+    "schedule",
+    "add",
+    "--show-now",
+    "2025-05-01 18:00:00",
+    "text",
+    "dinner",
+  ]);
+  match cli.command {
+    Command::Schedule {
+      action: ScheduleArgs::Add { show_now, input, .. },
+    } => {
+      assert!(show_now);
+      assert_eq!(input, vec!["dinner"]);
+    },
+    _ => panic!("Expected schedule add"),
+  }
+}
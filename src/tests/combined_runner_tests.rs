@@ -0,0 +1,196 @@
+//! Tests for the CombinedRunner used by `vbl daemon`.
+
+use chrono::{Duration, Utc};
+use serde_json::json;
+use serial_test::serial;
+use std::time::Instant;
+use tempfile::tempdir;
+
+use crate::api::{MockTransport, Transport};
+use crate::api_broker::message_to_codes;
+use crate::playlist::{OnError, Playlist, PlaylistItem};
+use crate::runner::combined_runner::{maintenance_active, CombinedRunner, MAINTENANCE_MESSAGE};
+use crate::runner::playlist_runner::PlaylistRunner;
+use crate::runner::schedule_runner::ScheduleRunner;
+use crate::runner::Runner;
+use crate::scheduler::{Schedule, ScheduledTask};
+use crate::widgets::text::get_text;
+use crate::widgets::widget_utils::{format_message, STANDARD_BOARD};
+
+fn create_text_playlist() -> Playlist {
+  let mut playlist = Playlist {
+    interval_seconds: 60,
+    ..Default::default()
+  };
+  for text in ["first", "second", "third"] {
+    playlist.add_item(PlaylistItem {
+      id: text.to_string(),
+      widget: "text".to_string(),
+      input: json!(text),
+      on_error: OnError::Show,
+      pinned_first: false,
+      tags: Vec::new(),
+    });
+  }
+  playlist
+}
+
+fn due_task(text: &str) -> ScheduledTask {
+  ScheduledTask::new(Utc::now() - Duration::seconds(1), "text".to_string(), json!(text))
+}
+
+fn codes(text: &str) -> [[u8; 22]; 6] {
+  message_to_codes(get_text(text, STANDARD_BOARD).unwrap())
+}
+
+/// Pretend the current playlist interval has run out.
+fn expire_interval(runner: &mut CombinedRunner) {
+  runner.playlist.last_display_time = Instant::now().checked_sub(std::time::Duration::from_secs(61));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_due_task_interrupts_playlist_and_playlist_resumes() {
+  let temp_dir = tempdir().unwrap();
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let schedule = ScheduleRunner::new(Schedule::default(), false, &transport);
+  let playlist =
+    PlaylistRunner::new(create_text_playlist(), temp_dir.path().join("state.json"), 0, false, false, &transport);
+  let mut runner = CombinedRunner::new(schedule, playlist, false, &transport);
+  runner.start();
+
+  runner.run_iteration().await.unwrap();
+  assert_eq!(runner.playlist.current_index(), 1);
+
+  // A task comes due before the next item
+  runner.reload_schedule(Schedule {
+    tasks: vec![due_task("meeting")],
+  });
+  expire_interval(&mut runner);
+  runner.run_iteration().await.unwrap();
+  assert_eq!(runner.playlist.current_index(), 1, "Scheduled task should not use up a playlist item");
+
+  // The task stays up for a full interval
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 2);
+
+  expire_interval(&mut runner);
+  runner.run_iteration().await.unwrap();
+
+  let sent = mock.sent_codes();
+  assert_eq!(sent, vec![codes("first"), codes("meeting"), codes("second")]);
+  assert_eq!(runner.playlist.current_index(), 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_splash_shows_before_playlist() {
+  let temp_dir = tempdir().unwrap();
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let schedule = ScheduleRunner::new(Schedule::default(), false, &transport);
+  let playlist =
+    PlaylistRunner::new(create_text_playlist(), temp_dir.path().join("state.json"), 0, false, false, &transport);
+  let mut runner = CombinedRunner::new(schedule, playlist, false, &transport)
+    .with_splash(Some(("text".to_string(), json!("welcome"))));
+  runner.start();
+
+  runner.run_iteration().await.unwrap();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes(), vec![codes("welcome")]);
+
+  expire_interval(&mut runner);
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes(), vec![codes("welcome"), codes("first")]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_due_task_wins_over_due_playlist_item() {
+  let temp_dir = tempdir().unwrap();
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let schedule = ScheduleRunner::new(Schedule::default(), false, &transport);
+  let playlist =
+    PlaylistRunner::new(create_text_playlist(), temp_dir.path().join("state.json"), 0, false, false, &transport);
+  let mut runner = CombinedRunner::new(schedule, playlist, false, &transport);
+  runner.start();
+  runner.reload_schedule(Schedule {
+    tasks: vec![due_task("meeting")],
+  });
+
+  runner.run_iteration().await.unwrap();
+
+  assert_eq!(mock.sent_codes(), vec![codes("meeting")]);
+  assert_eq!(runner.playlist.current_index(), 0);
+}
+
+#[test]
+fn test_maintenance_active_only_while_file_exists() {
+  let temp_dir = tempdir().unwrap();
+  let maintenance_path = temp_dir.path().join("maintenance");
+
+  assert!(!maintenance_active(None));
+  assert!(!maintenance_active(Some(&maintenance_path)));
+  std::fs::write(&maintenance_path, "").unwrap();
+  assert!(maintenance_active(Some(&maintenance_path)));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_maintenance_file_pauses_sends_until_removed() {
+  let temp_dir = tempdir().unwrap();
+  let maintenance_path = temp_dir.path().join("maintenance");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let schedule = ScheduleRunner::new(Schedule::default(), false, &transport);
+  let playlist =
+    PlaylistRunner::new(create_text_playlist(), temp_dir.path().join("state.json"), 0, false, false, &transport);
+  let mut runner =
+    CombinedRunner::new(schedule, playlist, false, &transport).with_maintenance(Some(maintenance_path.clone()), false);
+  runner.start();
+
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes(), vec![codes("first")]);
+
+  std::fs::write(&maintenance_path, "").unwrap();
+  runner.reload_schedule(Schedule {
+    tasks: vec![due_task("meeting")],
+  });
+  expire_interval(&mut runner);
+  runner.run_iteration().await.unwrap();
+  runner.run_iteration().await.unwrap();
+  assert!(runner.in_maintenance);
+  assert_eq!(mock.sent_codes().len(), 1, "nothing is sent during maintenance");
+
+  std::fs::remove_file(&maintenance_path).unwrap();
+  runner.run_iteration().await.unwrap();
+  assert!(!runner.in_maintenance);
+  assert_eq!(
+    mock.sent_codes(),
+    vec![codes("first"), codes("second")],
+    "the playlist resumes right away and the task missed during maintenance is skipped"
+  );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_maintenance_message_is_shown_once() {
+  let temp_dir = tempdir().unwrap();
+  let maintenance_path = temp_dir.path().join("maintenance");
+  std::fs::write(&maintenance_path, "").unwrap();
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let schedule = ScheduleRunner::new(Schedule::default(), false, &transport);
+  let playlist =
+    PlaylistRunner::new(create_text_playlist(), temp_dir.path().join("state.json"), 0, false, false, &transport);
+  let mut runner =
+    CombinedRunner::new(schedule, playlist, false, &transport).with_maintenance(Some(maintenance_path), true);
+  runner.start();
+
+  runner.run_iteration().await.unwrap();
+  runner.run_iteration().await.unwrap();
+
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(format_message(MAINTENANCE_MESSAGE))]);
+}
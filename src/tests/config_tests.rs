@@ -2,9 +2,12 @@
 mod tests {
   use crate::api::TransportType;
   use crate::config::{
-    Config, DEFAULT_CONSOLE_LOG_LEVEL, DEFAULT_LOG_FILE_PATH, DEFAULT_LOG_LEVEL, DEFAULT_SCHEDULE_BACKUP_PATH,
-    DEFAULT_SCHEDULE_FILE_PATH,
+    ensure_writable, Config, DEFAULT_CONSOLE_LOG_LEVEL, DEFAULT_LOG_FILE_PATH, DEFAULT_LOG_LEVEL,
+    DEFAULT_SCHEDULE_BACKUP_PATH, DEFAULT_SCHEDULE_FILE_PATH,
   };
+  use crate::errors::VestaboardError;
+  use crate::widgets::weather::WeatherLine;
+  use crate::widgets::widget_utils::{BoardSize, STANDARD_BOARD};
   use log::LevelFilter;
   use std::path::PathBuf;
 
@@ -31,12 +34,57 @@ mod tests {
       log_level: DEFAULT_LOG_LEVEL.to_string(),
       log_file_path: DEFAULT_LOG_FILE_PATH.to_string(),
       console_log_level: Some(DEFAULT_CONSOLE_LOG_LEVEL.to_string()),
+      log_max_bytes: None,
+      log_max_files: None,
+      log_compress: None,
       schedule_file_path: Some("custom/schedule.json".to_string()),
       schedule_backup_path: Some("custom/backup.json".to_string()),
       check_interval_seconds: Some(5),
+      catch_up_minutes: None,
       playlist_file_path: None,
       runtime_state_path: None,
+      schedule_state_path: None,
       lock_file_path: None,
+      history_file_path: None,
+      history_size: None,
+      display_queue_path: None,
+      skip_duplicate_renders: None,
+      recover_corrupt_files: None,
+      friendly_missing_keys: None,
+      fit_text: None,
+      abbreviations_file_path: None,
+      locale: None,
+      board_cols: None,
+      board_rows: None,
+      idle_widget: None,
+      idle_input: None,
+      splash_widget: None,
+      splash_input: None,
+      ics_all_day_time: None,
+      night_mode: None,
+      quiet_start: None,
+      quiet_end: None,
+      webhook_url: None,
+      on_success: None,
+      on_failure: None,
+      pause_indicator: None,
+      error_display_seconds: None,
+      interval_jitter_seconds: Some(0),
+      maintenance_file_path: None,
+      maintenance_message: None,
+      blank_placeholder: None,
+      skip_blank: None,
+      error_accent_color: None,
+      theme_header: None,
+      theme_alignment: None,
+      weather_location: None,
+      weather_cache_seconds: None,
+      weather_cache_path: None,
+      weather_stale_minutes: None,
+      weather_lines: None,
+      on_this_day_file_path: None,
+      forex_api_url: None,
+      units: None,
       transport: None,
     };
     assert_eq!(custom_config.get_schedule_file_path(), PathBuf::from("custom/schedule.json"));
@@ -47,12 +95,57 @@ mod tests {
       log_level: DEFAULT_LOG_LEVEL.to_string(),
       log_file_path: DEFAULT_LOG_FILE_PATH.to_string(),
       console_log_level: Some(DEFAULT_CONSOLE_LOG_LEVEL.to_string()),
+      log_max_bytes: None,
+      log_max_files: None,
+      log_compress: None,
       schedule_file_path: None,
       schedule_backup_path: None,
       check_interval_seconds: None,
+      catch_up_minutes: None,
       playlist_file_path: None,
       runtime_state_path: None,
+      schedule_state_path: None,
       lock_file_path: None,
+      history_file_path: None,
+      history_size: None,
+      display_queue_path: None,
+      skip_duplicate_renders: None,
+      recover_corrupt_files: None,
+      friendly_missing_keys: None,
+      fit_text: None,
+      abbreviations_file_path: None,
+      locale: None,
+      board_cols: None,
+      board_rows: None,
+      idle_widget: None,
+      idle_input: None,
+      splash_widget: None,
+      splash_input: None,
+      ics_all_day_time: None,
+      night_mode: None,
+      quiet_start: None,
+      quiet_end: None,
+      webhook_url: None,
+      on_success: None,
+      on_failure: None,
+      pause_indicator: None,
+      error_display_seconds: None,
+      interval_jitter_seconds: Some(0),
+      maintenance_file_path: None,
+      maintenance_message: None,
+      blank_placeholder: None,
+      skip_blank: None,
+      error_accent_color: None,
+      theme_header: None,
+      theme_alignment: None,
+      weather_location: None,
+      weather_cache_seconds: None,
+      weather_cache_path: None,
+      weather_stale_minutes: None,
+      weather_lines: None,
+      on_this_day_file_path: None,
+      forex_api_url: None,
+      units: None,
       transport: None,
     };
     assert_eq!(minimal_config.get_schedule_file_path(), PathBuf::from(DEFAULT_SCHEDULE_FILE_PATH));
@@ -123,17 +216,135 @@ mod tests {
       log_level: DEFAULT_LOG_LEVEL.to_string(),
       log_file_path: DEFAULT_LOG_FILE_PATH.to_string(),
       console_log_level: None,
+      log_max_bytes: None,
+      log_max_files: None,
+      log_compress: None,
       schedule_file_path: None,
       schedule_backup_path: None,
       check_interval_seconds: None,
+      catch_up_minutes: None,
       playlist_file_path: None,
       runtime_state_path: None,
+      schedule_state_path: None,
       lock_file_path: None,
+      history_file_path: None,
+      history_size: None,
+      display_queue_path: None,
+      skip_duplicate_renders: None,
+      recover_corrupt_files: None,
+      friendly_missing_keys: None,
+      fit_text: None,
+      abbreviations_file_path: None,
+      locale: None,
+      board_cols: None,
+      board_rows: None,
+      idle_widget: None,
+      idle_input: None,
+      splash_widget: None,
+      splash_input: None,
+      ics_all_day_time: None,
+      night_mode: None,
+      quiet_start: None,
+      quiet_end: None,
+      webhook_url: None,
+      on_success: None,
+      on_failure: None,
+      pause_indicator: None,
+      error_display_seconds: None,
+      interval_jitter_seconds: Some(0),
+      maintenance_file_path: None,
+      maintenance_message: None,
+      blank_placeholder: None,
+      skip_blank: None,
+      error_accent_color: None,
+      theme_header: None,
+      theme_alignment: None,
+      weather_location: None,
+      weather_cache_seconds: None,
+      weather_cache_path: None,
+      weather_stale_minutes: None,
+      weather_lines: None,
+      on_this_day_file_path: None,
+      forex_api_url: None,
+      units: None,
       transport: None,
     };
     assert_eq!(config.get_transport(), TransportType::Local);
   }
 
+  #[test]
+  fn test_board_size_defaults_to_standard() {
+    assert_eq!(Config::default().get_board_size(), STANDARD_BOARD);
+  }
+
+  #[test]
+  fn test_board_size_toml_parsing() {
+    let toml_str = r#"
+      log_level = "info"
+      log_file_path = "data/vestaboard.log"
+      board_cols = 15
+      board_rows = 3
+    "#;
+    let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+    assert_eq!(config.get_board_size(), BoardSize { cols: 15, rows: 3 });
+  }
+
+  #[test]
+  fn test_display_queue_lock_sits_next_to_queue() {
+    let config = Config {
+      display_queue_path: Some("queue/shows.json".to_string()),
+      ..Config::default()
+    };
+    assert_eq!(config.get_display_queue_path(), std::path::PathBuf::from("queue/shows.json"));
+    assert_eq!(config.get_display_queue_lock_path(), std::path::PathBuf::from("queue/shows.lock"));
+  }
+
+  #[test]
+  fn test_weather_cache_path_toml_parsing() {
+    let toml_str = r#"
+      log_level = "info"
+      log_file_path = "data/vestaboard.log"
+      weather_cache_seconds = 600
+      weather_cache_path = "cache/weather.json"
+    "#;
+    let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+    let cache = config.get_weather_cache().expect("caching should be on");
+    assert_eq!(cache.path, std::path::PathBuf::from("cache/weather.json"));
+  }
+
+  #[test]
+  fn test_weather_lines_toml_parsing() {
+    let toml_str = r#"
+      log_level = "info"
+      log_file_path = "data/vestaboard.log"
+      weather_lines = ["time", "temps", "wind"]
+    "#;
+    let config: Config = toml::from_str(toml_str).expect("Failed to parse TOML");
+    assert_eq!(config.get_weather_lines(), Some(vec![WeatherLine::Time, WeatherLine::Temps, WeatherLine::Wind]));
+
+    let unknown = Config {
+      weather_lines: Some(vec!["time".to_string(), "tides".to_string()]),
+      ..Config::default()
+    };
+    assert_eq!(unknown.get_weather_lines(), None);
+    assert_eq!(Config::default().get_weather_lines(), None);
+  }
+
+  #[test]
+  fn test_invalid_board_size_falls_back_to_standard() {
+    let too_wide = Config {
+      board_cols: Some(30),
+      ..Config::default()
+    };
+    assert_eq!(too_wide.get_board_size(), STANDARD_BOARD);
+
+    let no_rows = Config {
+      board_rows: Some(0),
+      ..Config::default()
+    };
+    assert_eq!(no_rows.get_board_size(), STANDARD_BOARD);
+  }
+
   #[test]
   fn test_transport_toml_parsing_local() {
     let toml_str = r#"
@@ -188,4 +399,62 @@ mod tests {
     let toml_str = toml::to_string(&config).expect("Failed to serialize config");
     assert!(toml_str.contains("transport = \"internet\""), "Serialized config should contain transport");
   }
+
+  #[test]
+  fn test_data_dirs_default_to_data() {
+    assert_eq!(Config::default().get_data_dirs(), vec![PathBuf::from("data")]);
+  }
+
+  #[test]
+  fn test_data_dirs_list_each_directory_once() {
+    let config = Config {
+      playlist_file_path: Some("/srv/vbl/playlist.json".to_string()),
+      history_file_path: Some("history.json".to_string()),
+      ..Default::default()
+    };
+
+    assert_eq!(config.get_data_dirs(), vec![PathBuf::from("data"), PathBuf::from("/srv/vbl"), PathBuf::from(".")]);
+  }
+
+  #[test]
+  fn test_data_dirs_include_log_and_display_queue() {
+    let config = Config {
+      log_file_path: "/var/log/vbl/vestaboard.log".to_string(),
+      display_queue_path: Some("/run/vbl/queue.json".to_string()),
+      ..Default::default()
+    };
+
+    let dirs = config.get_data_dirs();
+    assert!(dirs.contains(&PathBuf::from("/var/log/vbl")), "{:?}", dirs);
+    assert!(dirs.contains(&PathBuf::from("/run/vbl")), "{:?}", dirs);
+  }
+
+  #[test]
+  fn test_unwritable_data_dir_gives_one_clear_error() {
+    let dir = tempfile::tempdir().unwrap();
+    // A regular file where the data directory should be can't hold files, even for root
+    let blocked = dir.path().join("data");
+    std::fs::write(&blocked, "").unwrap();
+
+    let error = ensure_writable(&blocked).unwrap_err();
+    assert!(matches!(error, VestaboardError::IOError { .. }));
+    let message = error.to_user_message();
+    assert!(message.contains(&blocked.display().to_string()), "{}", message);
+    assert!(message.contains("is not writable"), "{}", message);
+
+    let config = Config {
+      schedule_file_path: Some(blocked.join("schedule.json").to_string_lossy().to_string()),
+      ..Default::default()
+    };
+    assert_eq!(config.check_data_dirs_writable().unwrap_err(), error);
+  }
+
+  #[test]
+  fn test_writable_data_dir_is_created() {
+    let dir = tempfile::tempdir().unwrap();
+    let fresh = dir.path().join("fresh");
+    assert!(ensure_writable(&fresh).is_ok());
+    assert!(fresh.is_dir());
+    assert_eq!(std::fs::read_dir(&fresh).unwrap().count(), 0, "the probe file is removed");
+  }
 }
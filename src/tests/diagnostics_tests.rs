@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::fs;
+
+use tempfile::tempdir;
+
+use crate::api::{MockTransport, Transport, TransportType};
+use crate::diagnostics::{
+  check_config, check_data_dir, check_lock_file, check_transport_env, check_weather_key, diagnose, doctor_exit_code,
+  send_test_pattern, test_pattern, CheckStatus,
+};
+use crate::errors::VestaboardError;
+
+#[test]
+fn test_pattern_fills_the_board() {
+  let pattern = test_pattern();
+  assert_eq!(pattern.len(), 6);
+  assert!(pattern.iter().all(|line| line.chars().count() <= 22));
+  assert!(crate::api_broker::validate_message_content(&pattern).is_ok());
+}
+
+#[tokio::test]
+async fn test_send_test_pattern_via_mock() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  send_test_pattern(&transport).await.unwrap();
+
+  let sent = mock.sent_codes();
+  assert_eq!(sent.len(), 1);
+  assert_eq!(sent[0], crate::api_broker::message_to_codes(test_pattern()));
+}
+
+#[tokio::test]
+async fn test_failing_transport_reports_rejected_key() {
+  let transport = Transport::Mock(MockTransport::failing(401));
+
+  let error = send_test_pattern(&transport).await.unwrap_err();
+
+  assert_eq!(
+    diagnose(&error, "local"),
+    "The Vestaboard rejected the API key. Check that LOCAL_API_KEY is correct."
+  );
+  assert!(diagnose(&error, "internet").contains("INTERNET_API_KEY"));
+}
+
+#[tokio::test]
+async fn test_failing_transport_reports_rate_limit() {
+  let transport = Transport::Mock(MockTransport::failing(429));
+
+  let error = send_test_pattern(&transport).await.unwrap_err();
+
+  assert!(diagnose(&error, "local").starts_with("Rate limited"));
+}
+
+#[test]
+fn test_diagnose_missing_env_var() {
+  let error = VestaboardError::config_error("IP_ADDRESS", "Environment variable not set.");
+  assert_eq!(diagnose(&error, "local"), "IP_ADDRESS is not set. Export it or add it to your .env file.");
+}
+
+#[tokio::test]
+async fn test_diagnose_connection_refused() {
+  // Nothing listens on port 1, so the request fails to connect
+  let source = reqwest::Client::new()
+    .get("http://127.0.0.1:1/")
+    .send()
+    .await
+    .unwrap_err();
+  let error = VestaboardError::reqwest_error(source, "Vestaboard");
+
+  assert!(diagnose(&error, "local").contains("Check IP_ADDRESS"));
+  assert!(diagnose(&error, "internet").contains("internet connection"));
+}
+
+#[test]
+fn test_diagnose_falls_back_to_user_message() {
+  let error = VestaboardError::api_error(Some(500), "Internal error");
+  assert_eq!(diagnose(&error, "local"), error.to_user_message());
+}
+
+/// An environment lookup backed by a fixed set of variables
+fn env_with(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+  let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+  move |name: &str| vars.get(name).cloned()
+}
+
+#[test]
+fn test_doctor_transport_env_missing_vars_fail() {
+  let env = env_with(&[("LOCAL_API_KEY", "key")]);
+  let result = check_transport_env(TransportType::Local, &env);
+  assert_eq!(result.status, CheckStatus::Fail);
+  assert!(result.detail.contains("IP_ADDRESS"));
+  assert!(!result.detail.contains("LOCAL_API_KEY"));
+
+  let result = check_transport_env(TransportType::Internet, &env);
+  assert_eq!(result.status, CheckStatus::Fail);
+  assert!(result.detail.contains("INTERNET_API_KEY"));
+}
+
+#[test]
+fn test_doctor_transport_env_set_passes() {
+  let env = env_with(&[("LOCAL_API_KEY", "key"), ("IP_ADDRESS", "192.168.1.2")]);
+  assert_eq!(check_transport_env(TransportType::Local, &env).status, CheckStatus::Pass);
+}
+
+#[test]
+fn test_doctor_missing_weather_key_warns() {
+  let result = check_weather_key(&env_with(&[]));
+  assert_eq!(result.status, CheckStatus::Warn);
+  assert!(result.hint.is_some());
+  assert_eq!(check_weather_key(&env_with(&[("WEATHER_API_KEY", "key")])).status, CheckStatus::Pass);
+}
+
+#[test]
+fn test_doctor_invalid_config_fails() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("vblconfig.toml");
+  fs::write(&path, "log_level = [not toml").unwrap();
+
+  let result = check_config(&path);
+  assert_eq!(result.status, CheckStatus::Fail);
+  assert!(result.detail.starts_with("Invalid config format"));
+}
+
+#[test]
+fn test_doctor_config_with_bad_values_warns() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("vblconfig.toml");
+  fs::write(
+    &path,
+    "log_level = \"info\"\nlog_file_path = \"data/vestaboard.log\"\nnight_mode = \"late\"\nidle_widget = \"nope\"\n",
+  )
+  .unwrap();
+
+  let result = check_config(&path);
+  assert_eq!(result.status, CheckStatus::Warn);
+  assert!(result.detail.contains("night_mode"));
+  assert!(result.detail.contains("idle_widget"));
+}
+
+#[test]
+fn test_doctor_valid_and_missing_config() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("vblconfig.toml");
+  assert_eq!(check_config(&path).status, CheckStatus::Warn);
+
+  fs::write(&path, "log_level = \"info\"\nlog_file_path = \"data/vestaboard.log\"\n").unwrap();
+  assert_eq!(check_config(&path).status, CheckStatus::Pass);
+}
+
+#[test]
+fn test_doctor_unwritable_data_dir_fails() {
+  let dir = tempdir().unwrap();
+  // A regular file where the data directory should be can't hold files
+  let blocked = dir.path().join("data");
+  fs::write(&blocked, "").unwrap();
+
+  assert_eq!(check_data_dir(&blocked).status, CheckStatus::Fail);
+  assert_eq!(check_data_dir(&dir.path().join("fresh")).status, CheckStatus::Pass);
+}
+
+#[test]
+fn test_doctor_lock_file_states() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("vestaboard.lock");
+  assert_eq!(check_lock_file(&path).status, CheckStatus::Pass);
+
+  fs::write(&path, "not json").unwrap();
+  let result = check_lock_file(&path);
+  assert_eq!(result.status, CheckStatus::Warn);
+  assert!(result.detail.contains("no longer running"));
+}
+
+#[test]
+fn test_doctor_exit_code_only_fails_on_critical() {
+  let dir = tempdir().unwrap();
+  let warn_only = vec![
+    check_weather_key(&env_with(&[])),
+    check_lock_file(&dir.path().join("none.lock")),
+  ];
+  assert_eq!(doctor_exit_code(&warn_only), 0);
+
+  let with_failure = vec![
+    check_weather_key(&env_with(&[])),
+    check_transport_env(TransportType::Internet, &env_with(&[])),
+  ];
+  assert_eq!(doctor_exit_code(&with_failure), 1);
+}
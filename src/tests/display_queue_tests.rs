@@ -0,0 +1,159 @@
+//! Tests for the display queue used by `vbl show`.
+
+use serial_test::serial;
+use std::cell::RefCell;
+use std::time::Duration;
+use tempfile::tempdir;
+
+use crate::api::{MockTransport, Transport};
+use crate::api_broker::message_to_codes;
+use crate::config::Config;
+use crate::display_queue::{drain_queue, enqueue, show_queued_at, take_outcome, QUEUE_SEND_GAP};
+use crate::errors::VestaboardError;
+use crate::runner::lock::InstanceLock;
+use crate::runtime_state::DisplayQueue;
+
+fn lines(text: &str) -> Vec<String> {
+  vec![text.to_string()]
+}
+
+fn is_queued(queue: &DisplayQueue, id: &str) -> bool {
+  queue.entries.iter().any(|entry| entry.id == id)
+}
+
+#[tokio::test]
+async fn test_enqueue_appends_in_order() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("queue.json");
+
+  let first = enqueue(&path, "text", lines("first")).await;
+  let second = enqueue(&path, "text", lines("second")).await;
+
+  let mut queue = DisplayQueue::load(&path);
+  assert!(is_queued(&queue, &first) && is_queued(&queue, &second));
+  assert_eq!(queue.pop_front().unwrap().message, lines("first"));
+  assert_eq!(queue.pop_front().unwrap().message, lines("second"));
+  assert_eq!(queue.pop_front(), None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_enqueues_keep_every_message() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("queue.json");
+
+  let workers: Vec<_> = (0..2)
+    .map(|worker| {
+      let path = path.clone();
+      tokio::spawn(async move {
+        let mut ids = Vec::new();
+        for n in 0..25 {
+          ids.push(enqueue(&path, "text", lines(&format!("{} {}", worker, n))).await);
+        }
+        ids
+      })
+    })
+    .collect();
+  let mut ids = Vec::new();
+  for worker in workers {
+    ids.extend(worker.await.unwrap());
+  }
+
+  let queue = DisplayQueue::load(&path);
+  assert_eq!(queue.entries.len(), 50);
+  assert!(ids.iter().all(|id| is_queued(&queue, id)));
+  assert!(!path.with_extension("json.lock").exists(), "the file lock is released");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_drain_sends_quick_enqueues_in_order_with_spacing() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("queue.json");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let first = enqueue(&path, "text", lines("first")).await;
+  let second = enqueue(&path, "text", lines("second")).await;
+
+  let sleeps = RefCell::new(Vec::new());
+  let results = drain_queue(&path, &transport, &Config::default(), QUEUE_SEND_GAP, |delay| {
+    sleeps.borrow_mut().push(delay);
+    async {}
+  })
+  .await;
+
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(lines("first")), message_to_codes(lines("second"))]);
+  // Spaced apart once, between the two sends
+  assert_eq!(sleeps.into_inner(), vec![QUEUE_SEND_GAP]);
+  let ids: Vec<_> = results.iter().map(|(id, _)| id.clone()).collect();
+  assert_eq!(ids, vec![first, second]);
+  assert!(results.iter().all(|(_, result)| result.is_ok()));
+  assert!(DisplayQueue::load(&path).entries.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_drain_keeps_going_after_a_failed_send() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("queue.json");
+  let transport = Transport::Mock(MockTransport::failing(503));
+  enqueue(&path, "text", lines("first")).await;
+  enqueue(&path, "weather", lines("second")).await;
+
+  let results = drain_queue(&path, &transport, &Config::default(), Duration::ZERO, |_| async {}).await;
+
+  assert_eq!(results.len(), 2);
+  assert!(results.iter().all(|(_, result)| result.is_err()));
+  assert!(DisplayQueue::load(&path).entries.is_empty());
+}
+
+#[tokio::test]
+async fn test_drain_of_empty_queue_sends_nothing() {
+  let dir = tempdir().unwrap();
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  let results =
+    drain_queue(&dir.path().join("queue.json"), &transport, &Config::default(), QUEUE_SEND_GAP, |_| async {}).await;
+
+  assert!(results.is_empty());
+  assert!(mock.sent_codes().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_drain_records_outcomes_for_waiting_shows() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("queue.json");
+  let transport = Transport::Mock(MockTransport::failing(503));
+  let id = enqueue(&path, "text", lines("first")).await;
+
+  drain_queue(&path, &transport, &Config::default(), Duration::ZERO, |_| async {}).await;
+
+  assert!(matches!(take_outcome(&path, &id).await, Some(Err(VestaboardError::Other { .. }))));
+  // Read once, then removed
+  assert!(take_outcome(&path, &id).await.is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_waiting_show_reports_the_failed_send_of_another_drainer() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("queue.json");
+  let lock_path = dir.path().join("queue.lock");
+  let transport = Transport::Mock(MockTransport::failing(503));
+  // Another show is sending the queue
+  let lock = InstanceLock::acquire_at("display queue", &lock_path).unwrap();
+
+  let config = Config::default();
+  let waiter = show_queued_at(&path, &lock_path, "text", lines("waiting"), &transport, &config);
+  let drainer = async {
+    while DisplayQueue::load(&path).entries.is_empty() {
+      tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    drain_queue(&path, &transport, &config, Duration::ZERO, |_| async {}).await;
+    drop(lock);
+  };
+  let (result, _) = tokio::join!(waiter, drainer);
+
+  assert!(result.is_err(), "the failed send is reported, not treated as sent");
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-  use crate::errors::VestaboardError;
+  use crate::errors::{exit_code_for, VestaboardError, EXIT_CONFIG, EXIT_GENERAL, EXIT_IO, EXIT_LOCK, EXIT_NETWORK};
   use std::error::Error;
   use std::io::{Error as IoError, ErrorKind};
 
@@ -317,6 +317,31 @@ mod tests {
     assert_eq!(user_msg, "Unexpected error occurred");
   }
 
+  #[test]
+  fn test_exit_code_for_each_variant() {
+    let json_err = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+    // An invalid URL fails while building the request, without any network access
+    let reqwest_err = reqwest::Client::new().get("not a url").build().unwrap_err();
+
+    let cases = [
+      (VestaboardError::config_error("LOCAL_API_KEY", "not set"), EXIT_CONFIG),
+      (VestaboardError::api_error(Some(500), "server error"), EXIT_NETWORK),
+      (VestaboardError::reqwest_error(reqwest_err, "sending message"), EXIT_NETWORK),
+      (VestaboardError::io_error(IoError::new(ErrorKind::NotFound, "missing"), "reading file"), EXIT_IO),
+      (VestaboardError::json_error(json_err, "parsing schedule"), EXIT_IO),
+      (VestaboardError::lock_error("already running"), EXIT_LOCK),
+      (VestaboardError::widget_error("weather", "failed"), EXIT_GENERAL),
+      (VestaboardError::schedule_error("add", "failed"), EXIT_GENERAL),
+      (VestaboardError::input_error("bad input"), EXIT_GENERAL),
+      (VestaboardError::validation_error("invalid"), EXIT_GENERAL),
+      (VestaboardError::other("unexpected"), EXIT_GENERAL),
+    ];
+
+    for (error, expected) in cases {
+      assert_eq!(exit_code_for(&error), expected, "wrong exit code for {:?}", error);
+    }
+  }
+
   // Note: Reqwest error testing is better done in integration tests
   // since creating reqwest::Error instances in unit tests is difficult
   // without making actual network requests
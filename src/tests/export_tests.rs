@@ -0,0 +1,270 @@
+//! Tests for exporting the schedule and playlist as commands.
+
+use crate::cli_setup::{Cli, Command, PlaylistArgs, ScheduleArgs};
+use crate::datetime::datetime_to_utc;
+use crate::export::{args_to_input, input_to_args, playlist_commands, schedule_commands, shell_quote};
+use crate::playlist::{OnError, Playlist, PlaylistItem};
+use crate::recurrence::Recurrence;
+use crate::scheduler::{Schedule, ScheduledTask};
+use chrono::{TimeZone, Utc};
+use clap::Parser;
+use serde_json::json;
+
+/// Split a line the way a POSIX shell would for the quoting `shell_quote` produces
+fn split_shell_words(line: &str) -> Vec<String> {
+  let mut words = Vec::new();
+  let mut current = String::new();
+  let mut in_word = false;
+  let mut in_quotes = false;
+  let mut chars = line.chars();
+  while let Some(c) = chars.next() {
+    match c {
+      '\'' => {
+        in_quotes = !in_quotes;
+        in_word = true;
+      },
+      '\\' if !in_quotes => {
+        current.extend(chars.next());
+        in_word = true;
+      },
+      ' ' if !in_quotes => {
+        if in_word {
+          words.push(std::mem::take(&mut current));
+          in_word = false;
+        }
+      },
+      _ => {
+        current.push(c);
+        in_word = true;
+      },
+    }
+  }
+  if in_word {
+    words.push(current);
+  }
+  words
+}
+
+/// Lines that run a command, skipping the shebang and comments
+fn command_lines(lines: &[String]) -> Vec<&String> {
+  lines.iter().filter(|line| !line.starts_with('#')).collect()
+}
+
+#[test]
+fn test_shell_quote() {
+  assert_eq!(shell_quote("weather"), "weather");
+  assert_eq!(shell_quote("hello world"), "'hello world'");
+  assert_eq!(shell_quote("don't panic"), "'don'\\''t panic'");
+  assert_eq!(shell_quote("$HOME"), "'$HOME'");
+  assert_eq!(shell_quote(""), "''");
+  assert_eq!(split_shell_words(&shell_quote("don't $panic")), vec!["don't $panic"]);
+}
+
+#[test]
+fn test_input_to_args_reverses_args_to_input() {
+  let cases = [
+    ("weather", json!(null)),
+    ("text", json!("hello world")),
+    ("file", json!("messages/hi.txt")),
+    ("forex", json!({"from": "usd", "to": "eur"})),
+    ("random", json!({"choices": ["weather", "jokes"]})),
+  ];
+  for (widget, input) in cases {
+    let args = input_to_args(widget, &input).expect("addable input");
+    assert_eq!(args_to_input(widget, &args), Some(input));
+  }
+}
+
+#[test]
+fn test_input_to_args_rejects_json_only_inputs() {
+  assert_eq!(input_to_args("weather", &json!({"mode": "feels"})), None);
+  assert_eq!(input_to_args("kv", &json!([["temp", "72"]])), None);
+  assert_eq!(input_to_args("text", &json!({"message": "hi"})), None);
+  assert_eq!(input_to_args("forex", &json!({"from": "usd", "to": "eur", "amount": 2})), None);
+}
+
+#[test]
+fn test_schedule_commands_reconstruct_schedule() {
+  let schedule = Schedule {
+    tasks: vec![
+      ScheduledTask {
+        id: "a1b2".to_string(),
+        time: Utc.with_ymd_and_hms(2030, 5, 1, 15, 30, 0).unwrap(),
+        widget: "text".to_string(),
+        input: json!("don't panic! $5 & 'quotes'"),
+        recurrence: None,
+        uid: None,
+      },
+      ScheduledTask {
+        id: "c3d4".to_string(),
+        time: Utc.with_ymd_and_hms(2030, 5, 2, 12, 0, 0).unwrap(),
+        widget: "weather".to_string(),
+        input: json!(null),
+        recurrence: Some(Recurrence::parse("weekdays").unwrap()),
+        uid: None,
+      },
+      ScheduledTask {
+        id: "e5f6".to_string(),
+        time: Utc.with_ymd_and_hms(2030, 5, 3, 9, 0, 0).unwrap(),
+        widget: "forex".to_string(),
+        input: json!({"from": "usd", "to": "eur"}),
+        recurrence: Some(Recurrence::parse("mon,fri").unwrap()),
+        uid: None,
+      },
+      ScheduledTask {
+        id: "g7h8".to_string(),
+        time: Utc.with_ymd_and_hms(2030, 5, 4, 9, 0, 0).unwrap(),
+        widget: "text".to_string(),
+        input: json!("-5 degrees"),
+        recurrence: None,
+        uid: None,
+      },
+    ],
+  };
+
+  let lines = schedule_commands(&schedule);
+  let commands = command_lines(&lines);
+  assert_eq!(commands.len(), schedule.tasks.len());
+
+  for (line, task) in commands.into_iter().zip(&schedule.tasks) {
+    let cli = Cli::parse_from(split_shell_words(line));
+    match cli.command {
+      Command::Schedule {
+        action:
+          ScheduleArgs::Add {
+            time,
+            widget,
+            input,
+            input_file,
+            repeat,
+            show_now,
+          },
+      } => {
+        assert!(!show_now);
+        assert_eq!(input_file, None);
+        assert_eq!(datetime_to_utc(&time).unwrap(), task.time);
+        assert_eq!(widget, task.widget);
+        assert_eq!(args_to_input(&widget, &input), Some(task.input.clone()));
+        assert_eq!(repeat.map(|spec| Recurrence::parse(&spec).unwrap()), task.recurrence);
+      },
+      _ => panic!("Expected schedule add from: {}", line),
+    }
+  }
+}
+
+#[test]
+fn test_schedule_commands_keep_unsupported_tasks_as_comments() {
+  let schedule = Schedule {
+    tasks: vec![ScheduledTask {
+      id: "kv01".to_string(),
+      time: Utc.with_ymd_and_hms(2030, 5, 1, 15, 30, 0).unwrap(),
+      widget: "kv".to_string(),
+      input: json!([["temp", "72"]]),
+      recurrence: None,
+      uid: None,
+    }],
+  };
+
+  let lines = schedule_commands(&schedule);
+
+  assert!(command_lines(&lines).is_empty());
+  assert!(lines.iter().any(|line| line.starts_with("# skipped kv01: kv")));
+}
+
+#[test]
+fn test_playlist_commands_reconstruct_playlist() {
+  let playlist = Playlist {
+    interval_seconds: 120,
+    items: vec![
+      PlaylistItem {
+        id: "p001".to_string(),
+        widget: "weather".to_string(),
+        input: json!(null),
+        on_error: OnError::Show,
+        pinned_first: false,
+        tags: Vec::new(),
+      },
+      PlaylistItem {
+        id: "p002".to_string(),
+        widget: "text".to_string(),
+        input: json!("hello \"world\""),
+        on_error: OnError::Show,
+        pinned_first: false,
+        tags: Vec::new(),
+      },
+      PlaylistItem {
+        id: "p003".to_string(),
+        widget: "random".to_string(),
+        input: json!({"choices": ["sat-word", "jokes"]}),
+        on_error: OnError::Show,
+        pinned_first: false,
+        tags: Vec::new(),
+      },
+    ],
+  };
+
+  let lines = playlist_commands(&playlist);
+  let commands = command_lines(&lines);
+
+  let mut rebuilt = Playlist::default();
+  for line in commands {
+    match Cli::parse_from(split_shell_words(line)).command {
+      Command::Playlist {
+        action: PlaylistArgs::Interval { seconds },
+      } => rebuilt.interval_seconds = seconds.expect("interval is set"),
+      Command::Playlist {
+        action: PlaylistArgs::Add { widget, input, at, .. },
+      } => {
+        assert_eq!(at, None);
+        rebuilt.add_widget(&widget, args_to_input(&widget, &input).expect("valid input"));
+      },
+      _ => panic!("Unexpected command: {}", line),
+    }
+  }
+
+  assert_eq!(rebuilt.interval_seconds, playlist.interval_seconds);
+  let summary = |p: &Playlist| {
+    p.items
+      .iter()
+      .map(|i| (i.widget.clone(), i.input.clone()))
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(summary(&rebuilt), summary(&playlist));
+}
+
+#[test]
+fn test_playlist_commands_note_settings_add_cannot_set() {
+  let playlist = Playlist {
+    interval_seconds: 300,
+    items: vec![
+      PlaylistItem {
+        id: "p001".to_string(),
+        widget: "weather".to_string(),
+        input: json!(null),
+        on_error: OnError::Skip,
+        pinned_first: true,
+        tags: vec!["morning".to_string()],
+      },
+      PlaylistItem {
+        id: "p002".to_string(),
+        widget: "jokes".to_string(),
+        input: json!(null),
+        on_error: OnError::Show,
+        pinned_first: false,
+        tags: Vec::new(),
+      },
+    ],
+  };
+
+  let lines = playlist_commands(&playlist);
+  assert_eq!(command_lines(&lines).len(), 3, "the interval and both items are still added");
+  let weather = lines
+    .iter()
+    .position(|line| line == "vbl playlist add weather")
+    .unwrap();
+  assert_eq!(
+    lines[weather + 1],
+    r#"# p001 also set "on_error": "skip", "pinned_first": true, "tags": ["morning"], which playlist add can't; edit the playlist file"#
+  );
+  assert!(!lines.iter().any(|line| line.starts_with("# p002")));
+}
@@ -0,0 +1,284 @@
+use chrono::{TimeZone, Utc};
+use serial_test::serial;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+use crate::api::{MockTransport, Transport};
+use crate::api_broker::{handle_message, message_to_codes, MessageDestination};
+use crate::config::Config;
+use crate::history::{
+  format_history_line, latest_entry, record_quiet_hours_clear, record_to, repeat_from, revert_after,
+};
+use crate::runner::common::PAUSED_INDICATOR_SOURCE;
+use crate::runtime_state::{HistoryEntry, MessageHistory};
+
+fn entry(widget: &str, line: &str) -> HistoryEntry {
+  HistoryEntry {
+    time: Utc.with_ymd_and_hms(2025, 5, 1, 12, 0, 0).unwrap(),
+    widget: widget.to_string(),
+    message: vec![String::new(), line.to_string()],
+  }
+}
+
+#[test]
+fn test_record_appends_entry() {
+  let temp_dir = tempdir().unwrap();
+  let path = temp_dir.path().join("history.json");
+
+  record_to(&path, "text", &["hello".to_string()], 10);
+  record_to(&path, "weather", &["sunny".to_string()], 10);
+
+  let history = MessageHistory::load(&path);
+  assert_eq!(history.entries.len(), 2);
+  assert_eq!(history.entries[1].widget, "weather");
+  assert_eq!(history.entries[1].message, vec!["sunny"]);
+}
+
+#[test]
+fn test_history_is_capped() {
+  let mut history = MessageHistory::default();
+  for i in 0..5 {
+    history.push(entry("text", &i.to_string()), 3);
+  }
+
+  let lines: Vec<&str> = history.entries.iter().map(|e| e.message[1].as_str()).collect();
+  assert_eq!(lines, vec!["2", "3", "4"]);
+}
+
+#[test]
+fn test_recent_counts_back_from_latest() {
+  let mut history = MessageHistory::default();
+  history.push(entry("text", "first"), 10);
+  history.push(entry("text", "second"), 10);
+
+  assert_eq!(history.recent(1).unwrap().message[1], "second");
+  assert_eq!(history.recent(2).unwrap().message[1], "first");
+  assert!(history.recent(0).is_none());
+  assert!(history.recent(3).is_none());
+}
+
+#[test]
+fn test_format_history_line_uses_first_non_blank_line() {
+  let line = format_history_line(1, &entry("weather", "  sunny  "));
+  assert!(line.starts_with("  1  "));
+  assert!(line.contains("weather"));
+  assert!(line.ends_with("sunny"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_handle_message_records_board_sends_only() {
+  let mock = MockTransport::new();
+  let path = mock.history_path();
+  let transport = Transport::Mock(mock);
+
+  handle_message(vec!["preview".to_string()], "text", MessageDestination::Console, &transport, &Config::default())
+    .await
+    .unwrap();
+  assert!(MessageHistory::load(&path).entries.is_empty());
+
+  handle_message(vec!["sent".to_string()], "text", MessageDestination::Vestaboard, &transport, &Config::default())
+    .await
+    .unwrap();
+  let history = MessageHistory::load(&path);
+  assert_eq!(history.entries.len(), 1);
+  assert_eq!(history.recent(1).unwrap().message, vec!["sent"]);
+  assert_eq!(history.recent(1).unwrap().widget, "text");
+}
+
+#[tokio::test]
+async fn test_repeat_resends_last_grid() {
+  let temp_dir = tempdir().unwrap();
+  let path = temp_dir.path().join("history.json");
+  let first = vec![String::new(), "first".to_string()];
+  let last = vec!["RRR".to_string(), "last".to_string()];
+  record_to(&path, "text", &first, 10);
+  record_to(&path, "weather", &last, 10);
+
+  let mock = MockTransport::new();
+  let widget = repeat_from(&path, &Transport::Mock(mock.clone())).await.unwrap();
+
+  assert_eq!(widget, "weather");
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(last)]);
+}
+
+#[tokio::test]
+async fn test_repeat_errors_on_empty_history() {
+  let temp_dir = tempdir().unwrap();
+  let mock = MockTransport::new();
+
+  let result = repeat_from(&temp_dir.path().join("history.json"), &Transport::Mock(mock.clone())).await;
+
+  assert!(result.unwrap_err().to_string().contains("nothing to repeat"));
+  assert!(mock.sent_codes().is_empty());
+}
+
+#[test]
+fn test_latest_entry_is_most_recent() {
+  let temp_dir = tempdir().unwrap();
+  let path = temp_dir.path().join("history.json");
+  assert!(latest_entry(&path).is_none());
+
+  record_to(&path, "text", &["first".to_string()], 10);
+  record_to(&path, "weather", &["sunny".to_string()], 10);
+
+  assert_eq!(latest_entry(&path).unwrap().widget, "weather");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_revert_after_restores_previous_message_after_delay() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let previous = entry("weather", "sunny");
+  let started = Instant::now();
+
+  revert_after(Duration::from_millis(150), Some(previous.clone()), &transport, &Config::default(), || false)
+    .await
+    .unwrap();
+
+  assert!(started.elapsed() >= Duration::from_millis(150));
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(previous.message)]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_revert_after_rejects_a_delay_past_the_clock_range() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  assert!(revert_after(Duration::MAX, None, &transport, &Config::default(), || false)
+    .await
+    .is_err());
+  assert!(mock.sent_codes().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_revert_after_clears_without_previous_message() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  revert_after(Duration::from_millis(10), None, &transport, &Config::default(), || false)
+    .await
+    .unwrap();
+
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(vec![String::new()])]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_revert_after_reverts_immediately_when_cancelled() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let started = Instant::now();
+
+  revert_after(Duration::from_secs(60), Some(entry("text", "hello")), &transport, &Config::default(), || true)
+    .await
+    .unwrap();
+
+  assert!(started.elapsed() < Duration::from_secs(5));
+  assert_eq!(mock.sent_codes().len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_handle_message_keeps_paused_indicator_out_of_history() {
+  let mock = MockTransport::new();
+  let path = mock.history_path();
+  let transport = Transport::Mock(mock.clone());
+
+  handle_message(
+    vec!["paused".to_string()],
+    PAUSED_INDICATOR_SOURCE,
+    MessageDestination::Vestaboard,
+    &transport,
+    &Config::default(),
+  )
+  .await
+  .unwrap();
+
+  assert_eq!(mock.sent_codes().len(), 1);
+  assert!(MessageHistory::load(&path).entries.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_handle_message_records_the_undimmed_message_at_night() {
+  let mock = MockTransport::new();
+  let path = mock.history_path();
+  let transport = Transport::Mock(mock.clone());
+  let now = chrono::Local::now().time();
+  let window = format!(
+    "{}-{}",
+    (now - chrono::Duration::hours(1)).format("%H:%M"),
+    (now + chrono::Duration::hours(1)).format("%H:%M")
+  );
+  let config = Config {
+    night_mode: Some(window),
+    ..Config::default()
+  };
+  let message = vec!["WWWW".to_string()];
+
+  handle_message(message.clone(), "text", MessageDestination::Vestaboard, &transport, &config)
+    .await
+    .unwrap();
+
+  assert_ne!(mock.sent_codes(), vec![message_to_codes(message.clone())], "the board gets the dimmed grid");
+  assert_eq!(MessageHistory::load(&path).recent(1).unwrap().message, message);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_quiet_hours_clear_stays_out_of_history() {
+  let mock = MockTransport::new();
+  let path = mock.history_path();
+  let transport = Transport::Mock(mock.clone());
+  record_to(&path, "weather", &["sunny".to_string()], 10);
+  let now = chrono::Local::now().time();
+  let config = Config {
+    quiet_start: Some((now - chrono::Duration::hours(1)).format("%H:%M").to_string()),
+    quiet_end: Some((now + chrono::Duration::hours(1)).format("%H:%M").to_string()),
+    ..Config::default()
+  };
+
+  for line in ["first", "second"] {
+    handle_message(vec![line.to_string()], "text", MessageDestination::Vestaboard, &transport, &config)
+      .await
+      .unwrap();
+  }
+
+  // One clear, then the second message is dropped; repeat still finds the weather
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(vec![String::new()])]);
+  let history = MessageHistory::load(&path);
+  assert_eq!(history.entries.len(), 1);
+  assert_eq!(history.recent(1).unwrap().widget, "weather");
+  assert!(history.quiet_hours_cleared.is_some());
+
+  // The next recorded message means the board is no longer blank for quiet hours
+  record_to(&path, "text", &["morning".to_string()], 10);
+  assert!(MessageHistory::load(&path).quiet_hours_cleared.is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_revert_after_respects_quiet_hours() {
+  let mock = MockTransport::new();
+  let path = mock.history_path();
+  let transport = Transport::Mock(mock.clone());
+  record_to(&path, "weather", &["sunny".to_string()], 10);
+  record_quiet_hours_clear(&path);
+  let now = chrono::Local::now().time();
+  let config = Config {
+    quiet_start: Some((now - chrono::Duration::hours(1)).format("%H:%M").to_string()),
+    quiet_end: Some((now + chrono::Duration::hours(1)).format("%H:%M").to_string()),
+    ..Config::default()
+  };
+
+  revert_after(Duration::from_millis(10), Some(entry("weather", "sunny")), &transport, &config, || false)
+    .await
+    .unwrap();
+
+  // The board was already blanked for quiet hours, so the revert doesn't light it up
+  assert!(mock.sent_codes().is_empty());
+}
@@ -0,0 +1,130 @@
+//! Tests for the on_success and on_failure hooks.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use serial_test::serial;
+use tempfile::tempdir;
+
+use crate::api::{MockTransport, Transport};
+use crate::api_broker::{handle_message, MessageDestination};
+use crate::config::Config;
+use crate::hooks::{run_hook, spawn_hook, HookEvent};
+
+/// Write a hook script that appends its arguments as a line to `record`
+fn recording_stub(dir: &Path, record: &Path) -> PathBuf {
+  let script = dir.join("hook.sh");
+  fs::write(&script, format!("#!/bin/sh\necho \"$@\" >> {}\n", record.display())).unwrap();
+  fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+  script
+}
+
+#[tokio::test]
+async fn test_hook_receives_widget_and_success() {
+  let dir = tempdir().unwrap();
+  let record = dir.path().join("calls.txt");
+  let script = recording_stub(dir.path(), &record);
+
+  let status = spawn_hook(script.to_str().unwrap(), "weather", HookEvent::Success)
+    .unwrap()
+    .wait()
+    .await
+    .unwrap();
+
+  assert!(status.success());
+  assert_eq!(fs::read_to_string(&record).unwrap(), "weather success\n");
+}
+
+#[tokio::test]
+async fn test_hook_receives_configured_args_then_failure() {
+  let dir = tempdir().unwrap();
+  let record = dir.path().join("calls.txt");
+  let script = recording_stub(dir.path(), &record);
+  let command = format!("{} --board kitchen", script.display());
+
+  spawn_hook(&command, "text", HookEvent::Failure)
+    .unwrap()
+    .wait()
+    .await
+    .unwrap();
+
+  assert_eq!(fs::read_to_string(&record).unwrap(), "--board kitchen text failure\n");
+}
+
+#[tokio::test]
+async fn test_hook_that_cannot_start_is_an_error() {
+  assert!(spawn_hook("/nonexistent/vbl-hook", "weather", HookEvent::Success).is_err());
+  assert!(spawn_hook("   ", "weather", HookEvent::Success).is_err());
+}
+
+#[tokio::test]
+async fn test_run_hook_uses_the_command_for_the_event() {
+  let dir = tempdir().unwrap();
+  let record = dir.path().join("calls.txt");
+  let script = recording_stub(dir.path(), &record);
+  let config = Config {
+    on_failure: Some(script.display().to_string()),
+    on_success: Some("/nonexistent/vbl-hook".to_string()),
+    ..Config::default()
+  };
+
+  // A success hook that can't start is only logged
+  run_hook(&config, "weather", HookEvent::Success);
+  run_hook(&config, "weather", HookEvent::Failure);
+
+  for _ in 0..50 {
+    if fs::read_to_string(&record).is_ok_and(|calls| calls.ends_with('\n')) {
+      break;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+  }
+  assert_eq!(fs::read_to_string(&record).unwrap(), "weather failure\n");
+}
+
+#[test]
+fn test_blank_hook_commands_are_ignored() {
+  let config = Config {
+    on_success: Some("  ".to_string()),
+    ..Config::default()
+  };
+  assert_eq!(config.get_on_success(), None);
+  assert_eq!(config.get_on_failure(), None);
+}
+
+/// Wait for `count` hook calls to be recorded, then return the record
+async fn wait_for_calls(record: &Path, count: usize) -> String {
+  for _ in 0..50 {
+    if fs::read_to_string(record).is_ok_and(|calls| calls.lines().count() >= count) {
+      break;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+  }
+  fs::read_to_string(record).unwrap_or_default()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_handle_message_runs_success_and_failure_hooks() {
+  let dir = tempdir().unwrap();
+  let record = dir.path().join("calls.txt");
+  let script = recording_stub(dir.path(), &record).display().to_string();
+  let config = Config {
+    on_success: Some(format!("{} sent", script)),
+    on_failure: Some(format!("{} failed", script)),
+    ..Config::default()
+  };
+  let message = vec!["hello".to_string()];
+
+  let transport = Transport::Mock(MockTransport::new());
+  handle_message(message.clone(), "text", MessageDestination::Vestaboard, &transport, &config)
+    .await
+    .unwrap();
+  assert_eq!(wait_for_calls(&record, 1).await, "sent text success\n");
+
+  let transport = Transport::Mock(MockTransport::failing(503));
+  assert!(handle_message(message, "weather", MessageDestination::Vestaboard, &transport, &config)
+    .await
+    .is_err());
+  assert_eq!(wait_for_calls(&record, 2).await, "sent text success\nfailed weather failure\n");
+}
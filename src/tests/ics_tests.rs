@@ -0,0 +1,156 @@
+//! Tests for reading calendar events from iCalendar files.
+
+use chrono::{Local, NaiveTime, TimeZone, Utc};
+use tempfile::tempdir;
+
+use crate::ics::{events_to_tasks, parse_ics, IcsEvent};
+use crate::scheduler::{import_ics_to, load_schedule_silent};
+
+fn eight_am() -> NaiveTime {
+  NaiveTime::from_hms_opt(8, 0, 0).unwrap()
+}
+
+const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example//Calendar//EN\r
+BEGIN:VEVENT\r
+UID:1@example.com\r
+DTSTART:20250505T143000Z\r
+DTEND:20250505T153000Z\r
+SUMMARY:Team Standup\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+SUMMARY:Reminder\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:2@example.com\r
+DTSTART;VALUE=DATE:20250506\r
+SUMMARY:Mom's birthday\\, call her\r
+X-UNKNOWN;FOO=bar:whatever\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+#[test]
+fn test_parse_ics_reads_summary_and_start() {
+  let events = parse_ics(CALENDAR, eight_am()).unwrap();
+
+  assert_eq!(
+    events,
+    vec![
+      IcsEvent {
+        uid: Some("1@example.com".to_string()),
+        summary: "Team Standup".to_string(),
+        start: Utc.with_ymd_and_hms(2025, 5, 5, 14, 30, 0).unwrap(),
+      },
+      IcsEvent {
+        uid: Some("2@example.com".to_string()),
+        summary: "Mom's birthday, call her".to_string(),
+        start: Local.with_ymd_and_hms(2025, 5, 6, 8, 0, 0).unwrap().with_timezone(&Utc),
+      },
+    ]
+  );
+}
+
+#[test]
+fn test_parse_ics_local_and_folded_lines() {
+  let content = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART;TZID=America/Chicago:20250507T090000\nSUMMARY:Dentist appoint\n ment\nEND:VEVENT\nEND:VCALENDAR\n";
+  let events = parse_ics(content, eight_am()).unwrap();
+
+  assert_eq!(events.len(), 1);
+  assert_eq!(events[0].summary, "Dentist appointment");
+  assert_eq!(events[0].start, Local.with_ymd_and_hms(2025, 5, 7, 9, 0, 0).unwrap().with_timezone(&Utc));
+}
+
+#[test]
+fn test_parse_ics_explicit_date_time_start() {
+  let content = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nDTSTART;TZID=America/Chicago;VALUE=DATE-TIME:20261017T090000\nSUMMARY:Farmers market\nEND:VEVENT\nEND:VCALENDAR\n";
+  let events = parse_ics(content, eight_am()).unwrap();
+
+  assert_eq!(events.len(), 1);
+  assert_eq!(
+    events[0].start,
+    Local
+      .with_ymd_and_hms(2026, 10, 17, 9, 0, 0)
+      .unwrap()
+      .with_timezone(&Utc)
+  );
+}
+
+#[test]
+fn test_parse_ics_skips_incomplete_events() {
+  let content = "BEGIN:VCALENDAR\nBEGIN:VEVENT\nSUMMARY:No start\nEND:VEVENT\nBEGIN:VEVENT\nDTSTART:garbage\nSUMMARY:Bad start\nEND:VEVENT\nEND:VCALENDAR\n";
+  assert!(parse_ics(content, eight_am()).unwrap().is_empty());
+}
+
+#[test]
+fn test_parse_ics_rejects_non_calendar() {
+  assert!(parse_ics("hello world", eight_am()).is_err());
+}
+
+#[test]
+fn test_events_to_tasks_creates_text_tasks() {
+  let events = parse_ics(CALENDAR, eight_am()).unwrap();
+  let tasks = events_to_tasks(&events);
+
+  assert_eq!(tasks.len(), 2);
+  assert_eq!(tasks[0].widget, "text");
+  assert_eq!(tasks[0].input, serde_json::json!("team standup"));
+  assert_eq!(tasks[0].time, events[0].start);
+  assert_eq!(tasks[1].input, serde_json::json!("mom's birthday, call her"));
+  assert!(tasks.iter().all(|task| task.recurrence.is_none()));
+  assert_eq!(tasks[0].uid.as_deref(), Some("1@example.com"));
+}
+
+const UPCOMING: &str = "BEGIN:VCALENDAR
+BEGIN:VEVENT
+UID:standup@example.com
+DTSTART:20990505T143000Z
+SUMMARY:Team Standup
+END:VEVENT
+BEGIN:VEVENT
+DTSTART:20990506T143000Z
+SUMMARY:Lunch
+END:VEVENT
+BEGIN:VEVENT
+UID:party@example.com
+DTSTART:20990507T143000Z
+SUMMARY:Party 🎉
+END:VEVENT
+END:VCALENDAR
+";
+
+#[test]
+fn test_import_ics_skips_unshowable_summaries() {
+  let dir = tempdir().unwrap();
+  let calendar = dir.path().join("calendar.ics");
+  let schedule_path = dir.path().join("schedule.json");
+  std::fs::write(&calendar, UPCOMING).unwrap();
+
+  assert_eq!(import_ics_to(&schedule_path, &calendar, eight_am()).unwrap(), 2);
+
+  let schedule = load_schedule_silent(&schedule_path).unwrap();
+  let inputs: Vec<_> = schedule.tasks.iter().map(|task| task.input.clone()).collect();
+  assert_eq!(inputs, vec![serde_json::json!("team standup"), serde_json::json!("lunch")]);
+}
+
+#[test]
+fn test_import_ics_twice_adds_nothing_new() {
+  let dir = tempdir().unwrap();
+  let calendar = dir.path().join("calendar.ics");
+  let schedule_path = dir.path().join("schedule.json");
+  std::fs::write(&calendar, UPCOMING).unwrap();
+  import_ics_to(&schedule_path, &calendar, eight_am()).unwrap();
+
+  assert_eq!(import_ics_to(&schedule_path, &calendar, eight_am()).unwrap(), 0);
+  assert_eq!(load_schedule_silent(&schedule_path).unwrap().tasks.len(), 2);
+
+  // A renamed event keeps its UID, so it still isn't added again
+  std::fs::write(&calendar, UPCOMING.replace("Team Standup", "Daily Standup")).unwrap();
+  assert_eq!(import_ics_to(&schedule_path, &calendar, eight_am()).unwrap(), 0);
+
+  // The same UID at a new time is a new occurrence
+  std::fs::write(&calendar, UPCOMING.replace("20990505", "20990512")).unwrap();
+  assert_eq!(import_ics_to(&schedule_path, &calendar, eight_am()).unwrap(), 1);
+}
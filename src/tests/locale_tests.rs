@@ -0,0 +1,69 @@
+//! Tests for localized day and month names.
+
+use crate::locale::{Locale, LOCALE_NAMES};
+use chrono::{Local, TimeZone, Weekday};
+
+#[test]
+fn test_parse_locale_codes() {
+  assert_eq!(Locale::parse("es"), Some(Locale::Spanish));
+  assert_eq!(Locale::parse("es-MX"), Some(Locale::Spanish));
+  assert_eq!(Locale::parse("FR_fr"), Some(Locale::French));
+  assert_eq!(Locale::parse(" de "), Some(Locale::German));
+  assert_eq!(Locale::parse("xx"), None);
+  for code in LOCALE_NAMES {
+    assert!(Locale::parse(code).is_some(), "{} should parse", code);
+  }
+}
+
+#[test]
+fn test_unknown_locale_falls_back_to_english() {
+  assert_eq!(Locale::parse_or_english("klingon"), Locale::English);
+  assert_eq!(Locale::parse_or_english(""), Locale::English);
+  assert_eq!(Locale::parse_or_english("pt-BR"), Locale::Portuguese);
+}
+
+#[test]
+fn test_localized_names_for_a_date() {
+  // Wednesday, 3 December 2025
+  let date = Local.with_ymd_and_hms(2025, 12, 3, 9, 0, 0).unwrap();
+  let cases = [
+    (Locale::English, "wednesday", "dec 3"),
+    (Locale::Spanish, "miercoles", "3 dic"),
+    (Locale::French, "mercredi", "3 dec"),
+    (Locale::German, "mittwoch", "3 dez"),
+    (Locale::Portuguese, "quarta", "3 dez"),
+  ];
+  for (locale, weekday, formatted) in cases {
+    assert_eq!(locale.weekday_name(Weekday::Wed), weekday);
+    assert_eq!(locale.format_date(&date), formatted);
+  }
+}
+
+#[test]
+fn test_localized_names_use_only_board_letters() {
+  let locales = [
+    Locale::English,
+    Locale::Spanish,
+    Locale::French,
+    Locale::German,
+    Locale::Portuguese,
+  ];
+  let weekdays = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+  ];
+  for locale in locales {
+    let names = weekdays
+      .iter()
+      .map(|&day| locale.weekday_name(day))
+      .chain((1..=12).map(|month| locale.month_abbrev(month)));
+    for name in names {
+      assert!(name.chars().all(|c| c.is_ascii_lowercase()), "{:?}: {}", locale, name);
+    }
+  }
+}
@@ -1,6 +1,7 @@
 //! Tests for the runner/lock module.
 
-use crate::runner::lock::InstanceLock;
+use crate::config::Config;
+use crate::runner::lock::{unlock, InstanceLock, UnlockOutcome};
 use std::fs;
 use tempfile::tempdir;
 
@@ -14,6 +15,20 @@ fn test_lock_acquires_when_no_existing_lock() {
   assert!(lock_path.exists());
 }
 
+#[test]
+fn test_lock_acquires_at_configured_path() {
+  let temp_dir = tempdir().unwrap();
+  let lock_path = temp_dir.path().join("custom.lock");
+  let config = Config {
+    lock_file_path: Some(lock_path.to_string_lossy().to_string()),
+    ..Config::default()
+  };
+
+  let lock = InstanceLock::acquire("daemon", &config).unwrap();
+  assert_eq!(lock.path(), &lock_path);
+  assert!(lock_path.exists());
+}
+
 #[test]
 fn test_lock_file_contains_correct_data() {
   let temp_dir = tempdir().unwrap();
@@ -105,3 +120,44 @@ fn test_lock_creates_parent_directory() {
   assert!(lock.is_ok());
   assert!(nested_path.exists());
 }
+
+#[test]
+fn test_unlock_removes_stale_lock() {
+  let temp_dir = tempdir().unwrap();
+  let lock_path = temp_dir.path().join("test.lock");
+  let fake_lock = r#"{"mode":"playlist","pid":999999999,"started_at":"2025-01-01T00:00:00Z"}"#;
+  fs::write(&lock_path, fake_lock).unwrap();
+
+  assert_eq!(unlock(&lock_path, false).unwrap(), UnlockOutcome::RemovedStale);
+  assert!(!lock_path.exists());
+}
+
+#[test]
+fn test_unlock_refuses_live_lock_without_force() {
+  let temp_dir = tempdir().unwrap();
+  let lock_path = temp_dir.path().join("test.lock");
+  let lock = InstanceLock::acquire_at("daemon", &lock_path).unwrap();
+
+  let err = unlock(&lock_path, false).unwrap_err();
+  assert!(err.to_string().contains("still running"));
+  assert!(lock_path.exists());
+
+  assert_eq!(
+    unlock(&lock_path, true).unwrap(),
+    UnlockOutcome::RemovedLive {
+      mode: "daemon".to_string(),
+      pid: std::process::id(),
+    }
+  );
+  assert!(!lock_path.exists());
+  drop(lock);
+}
+
+#[test]
+fn test_unlock_without_lock_file() {
+  let temp_dir = tempdir().unwrap();
+  let lock_path = temp_dir.path().join("test.lock");
+
+  assert_eq!(unlock(&lock_path, false).unwrap(), UnlockOutcome::NoLock);
+  assert_eq!(unlock(&lock_path, true).unwrap(), UnlockOutcome::NoLock);
+}
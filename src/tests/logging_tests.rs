@@ -68,12 +68,57 @@ console_log_level = "info""#,
       log_level: "debug".to_string(),
       log_file_path: "custom/path/log.txt".to_string(),
       console_log_level: Some("warn".to_string()),
+      log_max_bytes: None,
+      log_max_files: None,
+      log_compress: None,
       schedule_file_path: Some("custom/schedule.json".to_string()),
       schedule_backup_path: Some("custom/backup.json".to_string()),
       check_interval_seconds: Some(5),
+      catch_up_minutes: None,
       playlist_file_path: None,
       runtime_state_path: None,
+      schedule_state_path: None,
       lock_file_path: None,
+      history_file_path: None,
+      history_size: None,
+      display_queue_path: None,
+      skip_duplicate_renders: None,
+      recover_corrupt_files: None,
+      friendly_missing_keys: None,
+      fit_text: None,
+      abbreviations_file_path: None,
+      locale: None,
+      board_cols: None,
+      board_rows: None,
+      idle_widget: None,
+      idle_input: None,
+      splash_widget: None,
+      splash_input: None,
+      ics_all_day_time: None,
+      night_mode: None,
+      quiet_start: None,
+      quiet_end: None,
+      webhook_url: None,
+      on_success: None,
+      on_failure: None,
+      pause_indicator: None,
+      error_display_seconds: None,
+      interval_jitter_seconds: Some(0),
+      maintenance_file_path: None,
+      maintenance_message: None,
+      blank_placeholder: None,
+      skip_blank: None,
+      error_accent_color: None,
+      theme_header: None,
+      theme_alignment: None,
+      weather_location: None,
+      weather_cache_seconds: None,
+      weather_cache_path: None,
+      weather_stale_minutes: None,
+      weather_lines: None,
+      on_this_day_file_path: None,
+      forex_api_url: None,
+      units: None,
       transport: None,
     };
 
@@ -151,4 +196,115 @@ console_log_level = "info""#,
       formatted
     );
   }
+
+  #[test]
+  fn test_rotate_logs_shifts_and_deletes_oldest() {
+    use crate::logging::{rotate_logs, rotated_log_path};
+
+    let temp_dir = tempdir().unwrap();
+    let log_path = temp_dir.path().join("vestaboard.log");
+    for n in 1..=3 {
+      fs::write(rotated_log_path(&log_path, n, false), format!("rotation {}", n)).unwrap();
+    }
+    fs::write(&log_path, "current").unwrap();
+
+    rotate_logs(&log_path, 3, false).unwrap();
+
+    assert!(!log_path.exists());
+    assert_eq!(fs::read_to_string(rotated_log_path(&log_path, 1, false)).unwrap(), "current");
+    assert_eq!(fs::read_to_string(rotated_log_path(&log_path, 2, false)).unwrap(), "rotation 1");
+    assert_eq!(fs::read_to_string(rotated_log_path(&log_path, 3, false)).unwrap(), "rotation 2");
+    // "rotation 3" was the oldest and is gone
+    assert!(!rotated_log_path(&log_path, 4, false).exists());
+  }
+
+  #[test]
+  fn test_rotate_if_needed_respects_size() {
+    use crate::logging::{rotate_if_needed, rotated_log_path};
+
+    let temp_dir = tempdir().unwrap();
+    let log_path = temp_dir.path().join("vestaboard.log");
+    fs::write(&log_path, "0123456789").unwrap();
+
+    assert!(!rotate_if_needed(&log_path, 100, 2, false).unwrap());
+    assert!(log_path.exists());
+
+    assert!(rotate_if_needed(&log_path, 10, 2, false).unwrap());
+    assert!(!log_path.exists());
+    assert!(rotated_log_path(&log_path, 1, false).exists());
+  }
+
+  #[test]
+  fn test_rotating_log_file_rotates_while_writing() {
+    use crate::logging::{rotated_log_path, RotatingLogFile};
+    use std::io::Write;
+
+    let temp_dir = tempdir().unwrap();
+    let log_path = temp_dir.path().join("vbl.log");
+    let mut log = RotatingLogFile::open(&log_path, 10, 2, false).unwrap();
+
+    log.write_all(b"first line\n").unwrap();
+    assert!(!rotated_log_path(&log_path, 1, false).exists());
+
+    // The file is past max_bytes, so the next write starts a new one
+    log.write_all(b"second\n").unwrap();
+    log.flush().unwrap();
+    assert_eq!(fs::read_to_string(rotated_log_path(&log_path, 1, false)).unwrap(), "first line\n");
+    assert_eq!(fs::read_to_string(&log_path).unwrap(), "second\n");
+  }
+
+  #[test]
+  fn test_rotation_with_compression_doesnt_reenter_the_logger() {
+    use crate::logging::{file_logger, rotated_log_path, RotatingLogFile};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    let temp_dir = tempdir().unwrap();
+    let log_path = temp_dir.path().join("vbl.log");
+    let log_file = RotatingLogFile::open(&log_path, 10, 2, true).unwrap();
+
+    // The only test that installs the global logger, so a log call from inside the writer
+    // would come back to this one
+    let logger = file_logger(log_file, log::LevelFilter::Warn).build();
+    log::set_boxed_logger(Box::new(logger)).expect("no other test sets the logger");
+    log::set_max_level(log::LevelFilter::Warn);
+
+    // Rotating on the second record must not deadlock on the logger's own pipe
+    let (done, finished) = mpsc::channel();
+    thread::spawn(move || {
+      log::warn!("first record, long enough to rotate");
+      log::warn!("second record");
+      done.send(()).unwrap();
+    });
+    finished
+      .recv_timeout(Duration::from_secs(5))
+      .expect("logging hung while rotating");
+
+    assert!(rotated_log_path(&log_path, 1, cfg!(feature = "log-compression")).exists());
+    assert!(log_path.exists());
+  }
+
+  #[cfg(feature = "log-compression")]
+  #[test]
+  fn test_rotate_logs_compresses_when_enabled() {
+    use crate::logging::{rotate_logs, rotated_log_path};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let temp_dir = tempdir().unwrap();
+    let log_path = temp_dir.path().join("vestaboard.log");
+    fs::write(&log_path, "compress me").unwrap();
+
+    rotate_logs(&log_path, 2, true).unwrap();
+
+    let gz_path = rotated_log_path(&log_path, 1, true);
+    assert!(gz_path.exists());
+    assert!(!rotated_log_path(&log_path, 1, false).exists());
+    let mut contents = String::new();
+    GzDecoder::new(fs::File::open(gz_path).unwrap())
+      .read_to_string(&mut contents)
+      .unwrap();
+    assert_eq!(contents, "compress me");
+  }
 }
@@ -4,15 +4,31 @@ mod api_tests;
 mod cli_display_tests;
 mod cli_output_integration_tests;
 mod cli_setup_tests;
+mod combined_runner_tests;
 mod config_tests;
+mod diagnostics_tests;
+mod display_queue_tests;
 mod error_tests;
+mod export_tests;
+mod history_tests;
+mod hooks_tests;
+mod ics_tests;
 mod keyboard_tests;
+mod locale_tests;
 mod lock_tests;
 mod logging_tests;
+mod night_mode_tests;
 mod playlist_runner_tests;
 mod playlist_tests;
 mod process_control_tests;
+mod quiet_hours_tests;
+mod recovery_tests;
+mod recurrence_tests;
+mod retry_tests;
 mod runner_tests;
 mod runtime_state_tests;
 mod schedule_runner_tests;
 mod scheduler_tests;
+mod secrets_tests;
+mod timer_tests;
+mod tui_tests;
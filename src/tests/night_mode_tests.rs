@@ -0,0 +1,70 @@
+//! Tests for night mode dimming and its time window.
+
+use chrono::NaiveTime;
+
+use crate::datetime::TimeWindow;
+use crate::night_mode::{apply_night_mode, dim_message};
+
+fn at(hour: u32, minute: u32) -> NaiveTime {
+  NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn lines(rows: &[&str]) -> Vec<String> {
+  rows.iter().map(|row| row.to_string()).collect()
+}
+
+#[test]
+fn test_time_window_parse() {
+  let window = TimeWindow::parse("22:00-07:30").unwrap();
+  assert_eq!(window.start, at(22, 0));
+  assert_eq!(window.end, at(7, 30));
+  assert_eq!(TimeWindow::parse(" 9:00 - 17:00 ").unwrap().start, at(9, 0));
+}
+
+#[test]
+fn test_time_window_parse_rejects_invalid() {
+  assert!(TimeWindow::parse("22:00").is_err());
+  assert!(TimeWindow::parse("25:00-07:00").is_err());
+  assert!(TimeWindow::parse("night").is_err());
+}
+
+#[test]
+fn test_time_window_contains_same_day() {
+  let window = TimeWindow::parse("09:00-17:00").unwrap();
+  assert!(window.contains(at(9, 0)));
+  assert!(window.contains(at(12, 0)));
+  assert!(!window.contains(at(17, 0)));
+  assert!(!window.contains(at(8, 59)));
+}
+
+#[test]
+fn test_time_window_contains_wraps_midnight() {
+  let window = TimeWindow::parse("22:00-07:00").unwrap();
+  assert!(window.contains(at(22, 0)));
+  assert!(window.contains(at(23, 59)));
+  assert!(window.contains(at(3, 0)));
+  assert!(!window.contains(at(7, 0)));
+  assert!(!window.contains(at(12, 0)));
+}
+
+#[test]
+fn test_dim_message_leaves_text_alone() {
+  let message = lines(&["good night", "", "72D and clear"]);
+  assert_eq!(dim_message(&message), message);
+}
+
+#[test]
+fn test_dim_message_quiets_and_thins_color_tiles() {
+  let message = lines(&["WWWW", "YYYY", "RG"]);
+  assert_eq!(dim_message(&message), lines(&["B B ", " O O", "R "]));
+}
+
+#[test]
+fn test_apply_night_mode_is_gated_by_window() {
+  let window = TimeWindow::parse("22:00-07:00").unwrap();
+  let message = lines(&["WWWW"]);
+
+  assert_eq!(apply_night_mode(message.clone(), Some(&window), at(23, 0)), lines(&["B B "]));
+  assert_eq!(apply_night_mode(message.clone(), Some(&window), at(12, 0)), message);
+  assert_eq!(apply_night_mode(message.clone(), None, at(23, 0)), message);
+}
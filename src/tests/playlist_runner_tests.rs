@@ -1,15 +1,23 @@
 //! Tests for the PlaylistRunner.
 
 use crossterm::event::KeyCode;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde_json::json;
-use std::time::Instant;
+use serial_test::serial;
+use std::time::{Duration, Instant};
 use tempfile::tempdir;
 
-use crate::api::{Transport, TransportType};
-use crate::playlist::{Playlist, PlaylistItem};
+use crate::api::{MockTransport, Transport, TransportType};
+use crate::api_broker::message_to_codes;
+use crate::config::Config;
+use crate::playlist::{OnError, Playlist, PlaylistItem};
+use crate::runner::common::paused_frame;
 use crate::runner::playlist_runner::PlaylistRunner;
 use crate::runner::{ControlFlow, Runner};
 use crate::runtime_state::{PlaylistState, RuntimeState};
+use crate::widgets::resolver::execute_widget;
+use crate::widgets::widget_utils::error_to_display_message;
 
 /// Create a test transport. Sets env vars if needed.
 fn create_test_transport() -> Transport {
@@ -30,16 +38,25 @@ fn create_test_playlist() -> Playlist {
     id: "a".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   playlist.add_item(PlaylistItem {
     id: "b".to_string(),
     widget: "text".to_string(),
     input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   playlist.add_item(PlaylistItem {
     id: "c".to_string(),
     widget: "sat-word".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   playlist
 }
@@ -183,6 +200,28 @@ fn test_playlist_runner_once_mode_sets_complete() {
   assert!(runner.is_complete());
 }
 
+#[test]
+fn test_playlist_runner_reverse_skips_backward() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = create_test_transport();
+  let mut runner =
+    PlaylistRunner::new(create_test_playlist(), state_path, 2, true, true, &transport).with_reverse(true);
+
+  runner.start();
+  runner.skip_to_next(); // 2 -> 1
+  assert_eq!(runner.current_index(), 1);
+  assert!(!runner.is_complete());
+
+  runner.skip_to_next(); // 1 -> 0
+  assert_eq!(runner.current_index(), 0);
+  assert!(!runner.is_complete());
+
+  runner.skip_to_next(); // 0 -> 2 (wrap), cycle complete
+  assert_eq!(runner.current_index(), 2);
+  assert!(runner.is_complete());
+}
+
 #[test]
 fn test_playlist_runner_p_key_pauses() {
   let temp_dir = tempdir().unwrap();
@@ -299,6 +338,47 @@ fn test_playlist_runner_restores_state() {
   assert_eq!(runner.current_index(), 2);
 }
 
+#[test]
+fn test_playlist_runner_restore_starts_at_pinned_item() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+
+  let state = RuntimeState {
+    playlist_index: 2,
+    ..Default::default()
+  };
+  state.save(&state_path);
+
+  let mut playlist = create_test_playlist();
+  playlist.items[1].pinned_first = true;
+  let transport = create_test_transport();
+  let mut runner = PlaylistRunner::restore_from_state(playlist, state_path, false, true, &transport);
+  assert_eq!(runner.current_index(), 1);
+
+  // After the pinned item, the playlist continues in order
+  runner.start();
+  runner.skip_to_next();
+  assert_eq!(runner.current_index(), 2);
+}
+
+#[test]
+fn test_playlist_runner_restore_without_pinned_item_uses_saved_index() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+
+  let state = RuntimeState {
+    playlist_index: 2,
+    ..Default::default()
+  };
+  state.save(&state_path);
+
+  let playlist = create_test_playlist();
+  assert!(playlist.items.iter().all(|item| !item.pinned_first));
+  let transport = create_test_transport();
+  let runner = PlaylistRunner::restore_from_state(playlist, state_path, false, true, &transport);
+  assert_eq!(runner.current_index(), 2);
+}
+
 #[test]
 fn test_playlist_runner_help_text() {
   let temp_dir = tempdir().unwrap();
@@ -393,3 +473,475 @@ fn test_pressing_n_while_paused_at_startup_advances() {
   runner.handle_key(KeyCode::Char('n'));
   assert_eq!(runner.current_index(), 1, "'n' at startup should advance since timer is None");
 }
+
+fn create_offline_playlist() -> Playlist {
+  let mut playlist = Playlist::default();
+  playlist.add_item(PlaylistItem {
+    id: "a".to_string(),
+    widget: "text".to_string(),
+    input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
+  });
+  playlist.add_item(PlaylistItem {
+    id: "b".to_string(),
+    widget: "clear".to_string(),
+    input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
+  });
+  playlist
+}
+
+/// A playlist whose first item always fails (the file doesn't exist), followed by a text item
+fn create_failing_playlist(on_error: OnError) -> Playlist {
+  let mut playlist = Playlist::default();
+  playlist.add_item(PlaylistItem {
+    id: "bad".to_string(),
+    widget: "file".to_string(),
+    input: json!("/nonexistent/vbl-playlist-test.txt"),
+    on_error,
+    pinned_first: false,
+    tags: Vec::new(),
+  });
+  playlist.add_item(PlaylistItem {
+    id: "ok".to_string(),
+    widget: "text".to_string(),
+    input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
+  });
+  playlist
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_skip_on_error_advances_without_sending() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = PlaylistRunner::new(create_failing_playlist(OnError::Skip), state_path, 0, false, false, &transport);
+
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  assert!(mock.sent_codes().is_empty());
+  assert_eq!(runner.current_index(), 1);
+  assert!(runner.stats().last_error.is_some());
+  assert!(runner.should_display_next(), "the next item should show without waiting");
+
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 1);
+  assert_eq!(runner.current_index(), 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_show_on_error_sends_error_frame() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let playlist = create_failing_playlist(OnError::Show);
+  let error = execute_widget(&playlist.items[0].widget, &playlist.items[0].input, &Config::default())
+    .await
+    .unwrap_err();
+  let mut runner = PlaylistRunner::new(playlist, state_path, 0, false, false, &transport);
+
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(error_to_display_message(&error))]);
+  assert_eq!(runner.current_index(), 1);
+  assert!(!runner.should_display_next(), "the error should stay up for the interval");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_retries_failed_item_after_error_display() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  // The file widget fails until the file exists
+  let message_path = temp_dir.path().join("message.txt");
+  let mut playlist = create_failing_playlist(OnError::Show);
+  playlist.items[0].input = json!(message_path.to_str().unwrap());
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = PlaylistRunner::new(playlist, state_path, 0, false, false, &transport)
+    .with_error_display(Some(Duration::from_secs(5)));
+
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 1, "the error frame is sent");
+  assert_eq!(runner.current_index(), 0, "the failed item stays current");
+  assert!(!runner.should_display_next(), "the error stays up for the error display time");
+
+  std::fs::write(&message_path, "back online").unwrap();
+  let expected = execute_widget("file", &json!(message_path.to_str().unwrap()), &Config::default())
+    .await
+    .unwrap();
+  runner.last_display_time = Instant::now().checked_sub(Duration::from_secs(6));
+  assert!(runner.should_display_next(), "the item is retried well before the interval");
+  runner.run_iteration().await.unwrap();
+
+  let sent = mock.sent_codes();
+  assert_eq!(sent.len(), 2);
+  assert_eq!(sent[1], message_to_codes(expected));
+  assert_eq!(runner.current_index(), 1);
+  assert!(!runner.should_display_next(), "the recovered item stays up for the interval");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_retries_failed_item_only_once() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = PlaylistRunner::new(create_failing_playlist(OnError::Show), state_path, 0, false, false, &transport)
+    .with_error_display(Some(Duration::from_secs(5)));
+
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  runner.last_display_time = Instant::now().checked_sub(Duration::from_secs(6));
+  runner.run_iteration().await.unwrap();
+
+  assert_eq!(mock.sent_codes().len(), 2);
+  assert_eq!(runner.current_index(), 1, "a second failure moves on");
+  runner.last_display_time = Instant::now().checked_sub(Duration::from_secs(6));
+  assert!(!runner.should_display_next(), "the second error stays up for the interval");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_waits_when_every_item_is_skipped() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut playlist = create_failing_playlist(OnError::Skip);
+  playlist.items.truncate(1);
+  let mut runner = PlaylistRunner::new(playlist, state_path, 0, false, false, &transport);
+
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  assert!(mock.sent_codes().is_empty());
+  assert!(!runner.should_display_next());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_display_increments_stats() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = Transport::Mock(MockTransport::new());
+  let mut runner = PlaylistRunner::new(create_offline_playlist(), state_path, 0, false, false, &transport);
+
+  runner.start();
+  for _ in 0..3 {
+    runner.last_display_time = None;
+    runner.run_iteration().await.unwrap();
+  }
+
+  let stats = runner.stats();
+  assert_eq!(stats.total_displayed, 3);
+  assert_eq!(stats.widget_counts.get("text"), Some(&2));
+  assert_eq!(stats.widget_counts.get("clear"), Some(&1));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_stats_persist_across_runs() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = Transport::Mock(MockTransport::new());
+
+  let mut runner = PlaylistRunner::new(create_offline_playlist(), state_path.clone(), 0, false, false, &transport);
+  runner.start();
+  runner.run_iteration().await.unwrap();
+
+  let state = RuntimeState::load(&state_path);
+  assert_eq!(state.stats.total_displayed, 1);
+  assert_eq!(state.stats.widget_counts.get("text"), Some(&1));
+
+  // A new runner picks up the saved counters and keeps adding to them
+  let mut runner = PlaylistRunner::restore_from_state(create_offline_playlist(), state_path, false, false, &transport);
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(runner.stats().total_displayed, 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_counts_only_successful_live_sends() {
+  let temp_dir = tempdir().unwrap();
+  let transport = Transport::Mock(MockTransport::failing(503));
+  let mut runner =
+    PlaylistRunner::new(create_offline_playlist(), temp_dir.path().join("failing.json"), 0, false, false, &transport);
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(runner.stats().total_displayed, 0);
+  assert!(runner.stats().last_error.is_some());
+
+  let transport = create_test_transport();
+  let mut runner =
+    PlaylistRunner::new(create_offline_playlist(), temp_dir.path().join("dry_run.json"), 0, false, true, &transport);
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(runner.stats().total_displayed, 0, "dry runs aren't displays");
+}
+
+#[tokio::test]
+async fn test_playlist_runner_dashboard_view_tracks_current_and_next() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = create_test_transport();
+  let mut runner = PlaylistRunner::new(create_offline_playlist(), state_path, 0, false, true, &transport);
+
+  runner.start();
+  let view = runner.dashboard_view();
+  assert!(view.current.is_none());
+  assert_eq!(view.next.as_deref(), Some("text [a]"));
+  assert!(view.remaining.is_none());
+
+  runner.run_iteration().await.unwrap();
+  let view = runner.dashboard_view();
+  assert_eq!(view.current.as_deref(), Some("text [a]"));
+  assert_eq!(view.next.as_deref(), Some("clear [b]"));
+  assert_eq!(view.next_index, 1);
+  assert_eq!(view.total, 2);
+  assert!(view.remaining.is_some());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_reverse_once_visits_items_in_descending_order() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = Transport::Mock(MockTransport::new());
+  let mut playlist = create_offline_playlist();
+  playlist.add_item(PlaylistItem {
+    id: "c".to_string(),
+    widget: "text".to_string(),
+    input: json!("bye"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
+  });
+  let mut runner = PlaylistRunner::new(playlist, state_path, 2, true, false, &transport).with_reverse(true);
+
+  runner.start();
+  let mut shown = Vec::new();
+  loop {
+    runner.last_display_time = None;
+    let next = runner.dashboard_view().next;
+    if runner.run_iteration().await.unwrap() == ControlFlow::Exit {
+      break;
+    }
+    shown.push(next.unwrap());
+  }
+
+  assert_eq!(shown, vec!["text [c]", "clear [b]", "text [a]"]);
+  assert_eq!(runner.stats().total_displayed, 3);
+}
+
+#[tokio::test]
+async fn test_playlist_runner_shuffle_once_shows_each_item_once() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = create_test_transport();
+  let mut playlist = create_offline_playlist();
+  for id in ["c", "d", "e"] {
+    playlist.add_item(PlaylistItem {
+      id: id.to_string(),
+      widget: "text".to_string(),
+      input: json!(id),
+      on_error: OnError::Show,
+      pinned_first: false,
+      tags: Vec::new(),
+    });
+  }
+  let mut runner = PlaylistRunner::new(playlist, state_path, 3, true, true, &transport)
+    .with_shuffle(true, &mut StdRng::seed_from_u64(7));
+
+  runner.start();
+  let mut shown = Vec::new();
+  for _ in 0..10 {
+    runner.last_display_time = None;
+    let next = runner.dashboard_view().next;
+    if runner.run_iteration().await.unwrap() == ControlFlow::Exit {
+      break;
+    }
+    shown.push(next.unwrap());
+  }
+
+  // Every item exactly once, then the runner stops instead of looping
+  assert_eq!(shown.len(), 5);
+  assert!(runner.is_complete());
+  assert_eq!(runner.state(), PlaylistState::Stopped);
+  let mut sorted = shown.clone();
+  sorted.sort();
+  assert_eq!(sorted, vec!["clear [b]", "text [a]", "text [c]", "text [d]", "text [e]"]);
+}
+
+#[test]
+fn test_playlist_runner_shuffle_keeps_pinned_item_first() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = create_test_transport();
+  let mut playlist = create_test_playlist();
+  playlist.items[2].pinned_first = true;
+
+  for seed in 0..5 {
+    let runner = PlaylistRunner::new(playlist.clone(), state_path.clone(), 0, true, true, &transport)
+      .with_shuffle(true, &mut StdRng::seed_from_u64(seed));
+    assert_eq!(runner.current_index(), 0);
+    assert_eq!(runner.dashboard_view().next.as_deref(), Some("sat-word [c]"));
+  }
+}
+
+#[test]
+fn test_paused_frame_replaces_bottom_row() {
+  let message: Vec<String> = (1..=6).map(|n| format!("line {}", n)).collect();
+  let frame = paused_frame(&message);
+  assert_eq!(frame.len(), 6);
+  assert_eq!(frame[..5], message[..5]);
+  assert_eq!(frame[5], "        paused        ");
+
+  // Short messages are padded so the indicator stays on the bottom row
+  let frame = paused_frame(&["hello".to_string()]);
+  assert_eq!(frame.len(), 6);
+  assert_eq!(frame[5], "        paused        ");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_pause_sends_indicator_and_resume_restores() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner =
+    PlaylistRunner::new(create_offline_playlist(), state_path, 0, false, false, &transport).with_pause_indicator(true);
+
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 1);
+  let item_codes = mock.sent_codes()[0];
+
+  runner.handle_key(KeyCode::Char('p'));
+  runner.run_iteration().await.unwrap();
+  let sent = mock.sent_codes();
+  assert_eq!(sent.len(), 2);
+  let paused_row = message_to_codes(vec!["        paused        ".to_string()])[0];
+  assert_eq!(sent[1][5], paused_row);
+  assert_eq!(sent[1][..5], item_codes[..5]);
+
+  runner.handle_key(KeyCode::Char('r'));
+  runner.run_iteration().await.unwrap();
+  let sent = mock.sent_codes();
+  assert_eq!(sent.len(), 3);
+  assert_eq!(sent[2], item_codes);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_playlist_runner_pause_without_indicator_sends_nothing() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = PlaylistRunner::new(create_offline_playlist(), state_path, 0, false, false, &transport);
+
+  runner.start();
+  runner.run_iteration().await.unwrap();
+  runner.handle_key(KeyCode::Char('p'));
+  runner.run_iteration().await.unwrap();
+  runner.handle_key(KeyCode::Char('r'));
+  runner.run_iteration().await.unwrap();
+
+  assert_eq!(mock.sent_codes().len(), 1);
+}
+
+#[test]
+fn test_interval_override_is_used_for_timing() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let mut playlist = create_test_playlist();
+  playlist.interval_seconds = 300;
+  let transport = create_test_transport();
+  let mut runner = PlaylistRunner::new(playlist, state_path, 0, false, true, &transport)
+    .with_interval(Some(60))
+    .unwrap();
+  runner.start();
+
+  // 90 seconds is past the 60 second override but short of the saved 300
+  runner.last_display_time = Instant::now().checked_sub(std::time::Duration::from_secs(90));
+  assert!(runner.should_display_next());
+
+  runner.last_display_time = Instant::now().checked_sub(std::time::Duration::from_secs(30));
+  assert!(!runner.should_display_next());
+}
+
+#[test]
+fn test_interval_override_below_minimum_is_rejected() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = create_test_transport();
+  let result =
+    PlaylistRunner::new(create_test_playlist(), state_path, 0, false, true, &transport).with_interval(Some(30));
+
+  assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_interval_override_leaves_playlist_file_unchanged() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let playlist_path = temp_dir.path().join("playlist.json");
+  let mut playlist = create_test_playlist();
+  playlist.interval_seconds = 300;
+  playlist.save_silent(&playlist_path).unwrap();
+
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock);
+  let loaded = Playlist::load_silent(&playlist_path).unwrap();
+  let mut runner = PlaylistRunner::new(loaded, state_path, 0, false, true, &transport)
+    .with_interval(Some(60))
+    .unwrap();
+  runner.start();
+  runner.run_iteration().await.unwrap();
+
+  assert_eq!(Playlist::load_silent(&playlist_path).unwrap().interval_seconds, 300);
+}
+
+#[test]
+fn test_playlist_runner_interval_jitter_varies_next_display() {
+  let temp_dir = tempdir().unwrap();
+  let state_path = temp_dir.path().join("state.json");
+  let transport = create_test_transport();
+  let mut runner = PlaylistRunner::new(create_test_playlist(), state_path, 0, false, true, &transport)
+    .with_interval_jitter(30, StdRng::seed_from_u64(3))
+    .with_interval(Some(120))
+    .unwrap();
+  runner.start();
+
+  let mut intervals = Vec::new();
+  for _ in 0..50 {
+    runner.restart_interval();
+    let seconds = runner.current_interval_seconds;
+    assert!((90..=150).contains(&seconds), "interval {} is outside 120 +/- 30", seconds);
+
+    // The next item waits for this interval's jittered length
+    runner.last_display_time = Instant::now().checked_sub(Duration::from_secs(seconds - 1));
+    assert!(!runner.should_display_next());
+    runner.last_display_time = Instant::now().checked_sub(Duration::from_secs(seconds));
+    assert!(runner.should_display_next());
+    intervals.push(seconds);
+  }
+  intervals.sort();
+  intervals.dedup();
+  assert!(intervals.len() > 1, "jitter should vary the interval");
+}
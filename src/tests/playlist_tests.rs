@@ -1,6 +1,15 @@
 //! Tests for the playlist module.
 
-use crate::playlist::{Playlist, PlaylistItem};
+use crate::api::{MockTransport, Transport};
+use crate::config::Config;
+use crate::errors::VestaboardError;
+use crate::playlist::{
+  jittered_interval, preview_item, MergeCounts, OnError, Playlist, PlaylistItem, MIN_INTERVAL_SECONDS,
+};
+use crate::widgets::text::get_text;
+use crate::widgets::widget_utils::STANDARD_BOARD;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde_json::json;
 use std::io::Write;
 use tempfile::NamedTempFile;
@@ -13,6 +22,9 @@ fn test_playlist_item_creation() {
     id: "abc1".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   };
   assert_eq!(item.id, "abc1");
   assert_eq!(item.widget, "weather");
@@ -31,6 +43,9 @@ fn test_playlist_item_serializes_to_json() {
     id: "abc1".to_string(),
     widget: "text".to_string(),
     input: json!("hello world"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   };
   let serialized = serde_json::to_string(&item).unwrap();
   assert!(serialized.contains("\"widget\":\"text\""));
@@ -43,6 +58,31 @@ fn test_playlist_item_deserializes_from_json() {
   let item: PlaylistItem = serde_json::from_str(json_str).unwrap();
   assert_eq!(item.id, "xyz9");
   assert_eq!(item.widget, "weather");
+  assert_eq!(item.on_error, OnError::Show);
+}
+
+#[test]
+fn test_playlist_item_on_error_round_trips() {
+  let json_str = r#"{"id":"xyz9","widget":"weather","input":null,"on_error":"skip"}"#;
+  let item: PlaylistItem = serde_json::from_str(json_str).unwrap();
+  assert_eq!(item.on_error, OnError::Skip);
+  assert!(serde_json::to_string(&item).unwrap().contains("\"on_error\":\"skip\""));
+
+  // The default is left out so existing playlist files don't change
+  let shown = PlaylistItem::new("weather".to_string(), json!(null));
+  assert!(!serde_json::to_string(&shown).unwrap().contains("on_error"));
+}
+
+#[test]
+fn test_playlist_item_pinned_first_round_trips() {
+  let json_str = r#"{"id":"gm01","widget":"text","input":"good morning","pinned_first":true}"#;
+  let item: PlaylistItem = serde_json::from_str(json_str).unwrap();
+  assert!(item.pinned_first);
+  assert!(serde_json::to_string(&item).unwrap().contains("\"pinned_first\":true"));
+
+  let unpinned = PlaylistItem::new("weather".to_string(), json!(null));
+  assert!(!unpinned.pinned_first);
+  assert!(!serde_json::to_string(&unpinned).unwrap().contains("pinned_first"));
 }
 
 #[test]
@@ -77,6 +117,9 @@ fn test_playlist_add_item() {
     id: "abc1".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   };
   playlist.add_item(item);
   assert_eq!(playlist.items.len(), 1);
@@ -90,16 +133,25 @@ fn test_playlist_add_multiple_items_preserves_order() {
     id: "a".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   playlist.add_item(PlaylistItem {
     id: "b".to_string(),
     widget: "text".to_string(),
     input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   playlist.add_item(PlaylistItem {
     id: "c".to_string(),
     widget: "sat-word".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   assert_eq!(playlist.items.len(), 3);
   assert_eq!(playlist.items[0].id, "a");
@@ -124,11 +176,17 @@ fn test_playlist_remove_item_by_id() {
     id: "abc1".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   playlist.add_item(PlaylistItem {
     id: "def2".to_string(),
     widget: "text".to_string(),
     input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
 
   let removed = playlist.remove_item("abc1");
@@ -144,6 +202,9 @@ fn test_playlist_remove_nonexistent_returns_false() {
     id: "abc1".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
 
   let removed = playlist.remove_item("nonexistent");
@@ -161,6 +222,99 @@ fn test_playlist_remove_from_empty_returns_false() {
   assert!(playlist.is_empty());
 }
 
+fn playlist_with_ids(ids: &[&str]) -> Playlist {
+  let mut playlist = Playlist::default();
+  for id in ids {
+    playlist.add_item(PlaylistItem {
+      id: id.to_string(),
+      widget: "weather".to_string(),
+      input: json!(null),
+      on_error: OnError::Show,
+      pinned_first: false,
+      tags: Vec::new(),
+    });
+  }
+  playlist
+}
+
+fn item_ids(playlist: &Playlist) -> Vec<&str> {
+  playlist.items.iter().map(|item| item.id.as_str()).collect()
+}
+
+#[test]
+fn test_playlist_insert_item_at_start() {
+  let mut playlist = playlist_with_ids(&["a", "b", "c"]);
+  let id = playlist.insert_item(0, PlaylistItem::new("text".to_string(), json!("new")));
+  assert_eq!(playlist.items[0].id, id);
+  assert_eq!(&item_ids(&playlist)[1..], &["a", "b", "c"]);
+}
+
+#[test]
+fn test_playlist_insert_item_in_middle() {
+  let mut playlist = playlist_with_ids(&["a", "b", "c"]);
+  let id = playlist.insert_item(1, PlaylistItem::new("text".to_string(), json!("new")));
+  assert_eq!(item_ids(&playlist), vec!["a", id.as_str(), "b", "c"]);
+}
+
+#[test]
+fn test_playlist_insert_item_at_end() {
+  let mut playlist = playlist_with_ids(&["a", "b", "c"]);
+  let id = playlist.insert_item(3, PlaylistItem::new("text".to_string(), json!("new")));
+  assert_eq!(item_ids(&playlist), vec!["a", "b", "c", id.as_str()]);
+}
+
+#[test]
+fn test_playlist_insert_item_beyond_end_appends() {
+  let mut playlist = playlist_with_ids(&["a", "b"]);
+  let id = playlist.insert_item(99, PlaylistItem::new("text".to_string(), json!("new")));
+  assert_eq!(item_ids(&playlist), vec!["a", "b", id.as_str()]);
+}
+
+#[test]
+fn test_playlist_update_item_input() {
+  let mut playlist = Playlist::default();
+  playlist.add_item(PlaylistItem {
+    id: "abc1".to_string(),
+    widget: "text".to_string(),
+    input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
+  });
+  playlist.add_item(PlaylistItem {
+    id: "def2".to_string(),
+    widget: "weather".to_string(),
+    input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
+  });
+
+  let updated = playlist.update_item_input("abc1", json!("goodbye"));
+  assert!(updated);
+  assert_eq!(playlist.items.len(), 2);
+  assert_eq!(playlist.items[0].id, "abc1");
+  assert_eq!(playlist.items[0].widget, "text");
+  assert_eq!(playlist.items[0].input, json!("goodbye"));
+}
+
+#[test]
+fn test_playlist_update_nonexistent_returns_false() {
+  let mut playlist = Playlist::default();
+  playlist.add_item(PlaylistItem {
+    id: "abc1".to_string(),
+    widget: "text".to_string(),
+    input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
+  });
+
+  let updated = playlist.update_item_input("nonexistent", json!("goodbye"));
+  assert!(!updated);
+  assert_eq!(playlist.items[0].input, json!("hello"));
+}
+
 #[test]
 fn test_playlist_is_empty() {
   let playlist = Playlist::default();
@@ -171,6 +325,9 @@ fn test_playlist_is_empty() {
     id: "abc1".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   assert!(!playlist_with_items.is_empty());
 }
@@ -205,6 +362,9 @@ fn test_playlist_get_item() {
     id: "abc1".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
 
   let item = playlist.get_item("abc1");
@@ -222,11 +382,17 @@ fn test_playlist_get_item_by_index() {
     id: "a".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   playlist.add_item(PlaylistItem {
     id: "b".to_string(),
     widget: "text".to_string(),
     input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
 
   let item = playlist.get_item_by_index(0);
@@ -248,11 +414,17 @@ fn test_playlist_find_index_by_id() {
     id: "a".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
   playlist.add_item(PlaylistItem {
     id: "b".to_string(),
     widget: "text".to_string(),
     input: json!("hello"),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
 
   assert_eq!(playlist.find_index_by_id("a"), Some(0));
@@ -260,6 +432,59 @@ fn test_playlist_find_index_by_id() {
   assert_eq!(playlist.find_index_by_id("c"), None);
 }
 
+// --- Tag filter tests ---
+
+/// Items "a" (morning), "b" (morning, news), "c" (untagged), "d" (News)
+fn create_tagged_playlist() -> Playlist {
+  let mut playlist = Playlist::default();
+  for (id, tags) in [
+    ("a", vec!["morning"]),
+    ("b", vec!["morning", "news"]),
+    ("c", vec![]),
+    ("d", vec!["News"]),
+  ] {
+    let mut item = PlaylistItem::new("text".to_string(), json!(id));
+    item.id = id.to_string();
+    item.tags = tags.into_iter().map(String::from).collect();
+    playlist.add_item(item);
+  }
+  playlist.interval_seconds = 120;
+  playlist
+}
+
+fn ids(playlist: &Playlist) -> Vec<&str> {
+  playlist.items.iter().map(|item| item.id.as_str()).collect()
+}
+
+#[test]
+fn test_playlist_with_tag_selects_tagged_items_in_order() {
+  let playlist = create_tagged_playlist();
+
+  let morning = playlist.with_tag("morning");
+  assert_eq!(ids(&morning), vec!["a", "b"]);
+  assert_eq!(morning.interval_seconds, 120);
+  assert_eq!(ids(&playlist.with_tag("news")), vec!["b", "d"], "tags match case-insensitively");
+  assert_eq!(playlist.len(), 4, "the original playlist is unchanged");
+}
+
+#[test]
+fn test_playlist_with_unknown_tag_is_empty() {
+  let playlist = create_tagged_playlist();
+  assert!(playlist.with_tag("evening").is_empty());
+  assert!(Playlist::default().with_tag("morning").is_empty());
+}
+
+#[test]
+fn test_playlist_item_tags_round_trip() {
+  let json_str = r#"{"id":"gm01","widget":"text","input":"good morning","tags":["morning"]}"#;
+  let item: PlaylistItem = serde_json::from_str(json_str).unwrap();
+  assert_eq!(item.tags, vec!["morning"]);
+  assert!(serde_json::to_string(&item).unwrap().contains("\"tags\":[\"morning\"]"));
+
+  let untagged = PlaylistItem::new("weather".to_string(), json!(null));
+  assert!(!serde_json::to_string(&untagged).unwrap().contains("tags"));
+}
+
 // --- Interval validation tests ---
 
 #[test]
@@ -295,6 +520,9 @@ fn test_playlist_save_and_load() {
     id: "abc1".to_string(),
     widget: "weather".to_string(),
     input: json!(null),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
   });
 
   let temp_file = NamedTempFile::new().unwrap();
@@ -347,3 +575,163 @@ fn test_playlist_save_silent_and_load_silent() {
   assert_eq!(loaded.interval_seconds, 180);
   assert_eq!(loaded.items.len(), 1);
 }
+
+#[tokio::test]
+async fn test_preview_item_renders_known_item() {
+  let mut playlist = Playlist::default();
+  playlist.add_widget("text", json!("first"));
+  let id = playlist.add_widget("text", json!("second"));
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  let rendered = preview_item(&playlist, &id, &transport, &Config::default())
+    .await
+    .unwrap();
+
+  assert_eq!(rendered, get_text("second", STANDARD_BOARD).unwrap());
+  assert!(mock.sent_codes().is_empty());
+}
+
+#[tokio::test]
+async fn test_preview_item_errors_on_unknown_id() {
+  let playlist = Playlist::default();
+  let transport = Transport::Mock(MockTransport::new());
+
+  let err = preview_item(&playlist, "nope", &transport, &Config::default())
+    .await
+    .unwrap_err();
+
+  assert!(matches!(err, VestaboardError::ValidationError { .. }));
+  assert!(err.to_string().contains("Item 'nope' not found"));
+}
+
+fn text_item(id: &str, message: &str) -> PlaylistItem {
+  PlaylistItem {
+    id: id.to_string(),
+    widget: "text".to_string(),
+    input: json!(message),
+    on_error: OnError::Show,
+    pinned_first: false,
+    tags: Vec::new(),
+  }
+}
+
+fn messages(playlist: &Playlist) -> Vec<(String, String)> {
+  playlist
+    .items
+    .iter()
+    .map(|item| (item.id.clone(), item.input.as_str().unwrap_or_default().to_string()))
+    .collect()
+}
+
+fn existing_playlist() -> Playlist {
+  Playlist {
+    interval_seconds: 120,
+    items: vec![text_item("aaaa", "old a"), text_item("bbbb", "old b")],
+  }
+}
+
+fn overlapping_import() -> Playlist {
+  Playlist {
+    interval_seconds: 600,
+    items: vec![text_item("bbbb", "new b"), text_item("cccc", "new c")],
+  }
+}
+
+#[test]
+fn test_merge_replaces_matching_ids_in_place() {
+  let mut playlist = existing_playlist();
+
+  let counts = playlist.merge(overlapping_import(), false);
+
+  assert_eq!(
+    counts,
+    MergeCounts {
+      replaced: 1,
+      added: 1,
+      skipped: 0
+    }
+  );
+  assert_eq!(
+    messages(&playlist),
+    vec![
+      ("aaaa".to_string(), "old a".to_string()),
+      ("bbbb".to_string(), "new b".to_string()),
+      ("cccc".to_string(), "new c".to_string()),
+    ]
+  );
+  assert_eq!(playlist.interval_seconds, 120);
+}
+
+#[test]
+fn test_merge_keep_existing_skips_matching_ids() {
+  let mut playlist = existing_playlist();
+
+  let counts = playlist.merge(overlapping_import(), true);
+
+  assert_eq!(
+    counts,
+    MergeCounts {
+      replaced: 0,
+      added: 1,
+      skipped: 1
+    }
+  );
+  assert_eq!(
+    messages(&playlist),
+    vec![
+      ("aaaa".to_string(), "old a".to_string()),
+      ("bbbb".to_string(), "old b".to_string()),
+      ("cccc".to_string(), "new c".to_string()),
+    ]
+  );
+}
+
+#[test]
+fn test_merge_disjoint_ids_appends_in_both_modes() {
+  let disjoint = Playlist {
+    interval_seconds: 300,
+    items: vec![text_item("dddd", "d"), text_item("eeee", "e")],
+  };
+  for keep_existing in [false, true] {
+    let mut playlist = existing_playlist();
+
+    let counts = playlist.merge(disjoint.clone(), keep_existing);
+
+    assert_eq!(
+      counts,
+      MergeCounts {
+        replaced: 0,
+        added: 2,
+        skipped: 0
+      }
+    );
+    let ids: Vec<_> = playlist.items.iter().map(|item| item.id.as_str()).collect();
+    assert_eq!(ids, vec!["aaaa", "bbbb", "dddd", "eeee"]);
+  }
+}
+
+#[test]
+fn test_jittered_interval_stays_within_bounds() {
+  let mut rng = StdRng::seed_from_u64(42);
+  let intervals: Vec<u64> = (0..200).map(|_| jittered_interval(300, 30, &mut rng)).collect();
+
+  assert!(intervals.iter().all(|seconds| (270..=330).contains(seconds)));
+  assert!(intervals.iter().any(|seconds| *seconds < 300));
+  assert!(intervals.iter().any(|seconds| *seconds > 300));
+}
+
+#[test]
+fn test_jittered_interval_never_below_minimum() {
+  let mut rng = StdRng::seed_from_u64(42);
+  for _ in 0..200 {
+    let seconds = jittered_interval(MIN_INTERVAL_SECONDS, 45, &mut rng);
+    assert!((MIN_INTERVAL_SECONDS..=MIN_INTERVAL_SECONDS + 45).contains(&seconds));
+  }
+}
+
+#[test]
+fn test_jittered_interval_zero_jitter_keeps_interval() {
+  let mut rng = StdRng::seed_from_u64(42);
+  assert_eq!(jittered_interval(300, 0, &mut rng), 300);
+}
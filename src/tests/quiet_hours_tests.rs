@@ -0,0 +1,62 @@
+//! Tests for quiet hours suppression and its config.
+
+use chrono::NaiveTime;
+
+use crate::config::Config;
+use crate::datetime::TimeWindow;
+use crate::quiet_hours::{quiet_hours_action, QuietHoursAction};
+
+fn at(hour: u32, minute: u32) -> NaiveTime {
+  NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+#[test]
+fn test_quiet_hours_clears_once_then_suppresses() {
+  let window = TimeWindow::parse("23:00-06:30").unwrap();
+
+  // Entering the window: the board hasn't been cleared yet
+  assert_eq!(quiet_hours_action(Some(&window), at(23, 0), false), QuietHoursAction::Clear);
+
+  // Once cleared, later messages in the window are dropped, past midnight too
+  assert_eq!(quiet_hours_action(Some(&window), at(23, 30), true), QuietHoursAction::Suppress);
+  assert_eq!(quiet_hours_action(Some(&window), at(3, 0), true), QuietHoursAction::Suppress);
+}
+
+#[test]
+fn test_quiet_hours_resume_after_window() {
+  let window = TimeWindow::parse("23:00-06:30").unwrap();
+
+  assert_eq!(quiet_hours_action(Some(&window), at(6, 30), true), QuietHoursAction::Send);
+  assert_eq!(quiet_hours_action(Some(&window), at(12, 0), false), QuietHoursAction::Send);
+}
+
+#[test]
+fn test_quiet_hours_disabled_without_window() {
+  assert_eq!(quiet_hours_action(None, at(3, 0), false), QuietHoursAction::Send);
+}
+
+#[test]
+fn test_config_quiet_hours() {
+  let config = Config {
+    quiet_start: Some("22:00".to_string()),
+    quiet_end: Some("07:00".to_string()),
+    ..Config::default()
+  };
+  assert_eq!(config.get_quiet_hours(), Some(TimeWindow::parse("22:00-07:00").unwrap()));
+
+  assert_eq!(Config::default().parse_quiet_hours(), Ok(None));
+
+  let only_start = Config {
+    quiet_start: Some("22:00".to_string()),
+    ..Config::default()
+  };
+  assert!(only_start.parse_quiet_hours().is_err());
+  assert_eq!(only_start.get_quiet_hours(), None);
+
+  let invalid = Config {
+    quiet_start: Some("late".to_string()),
+    quiet_end: Some("07:00".to_string()),
+    ..Config::default()
+  };
+  assert!(invalid.parse_quiet_hours().is_err());
+}
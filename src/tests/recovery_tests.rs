@@ -0,0 +1,155 @@
+//! Tests for recovering from corrupt schedule and playlist files.
+
+use std::fs;
+
+use chrono::{Local, TimeZone};
+use tempfile::tempdir;
+
+use crate::errors::VestaboardError;
+use crate::playlist::{wait_for_items, Playlist};
+use crate::process_control::ProcessController;
+use crate::recovery::{corrupt_file_path, recover_corrupt};
+use crate::scheduler::{load_schedule_silent, Schedule, ScheduleMonitor};
+
+/// Files in `dir` whose name starts with `prefix`
+fn files_starting_with(dir: &std::path::Path, prefix: &str) -> Vec<String> {
+  fs::read_dir(dir)
+    .unwrap()
+    .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+    .filter(|name| name.starts_with(prefix))
+    .collect()
+}
+
+#[test]
+fn test_corrupt_file_path_appends_timestamp() {
+  let now = Local.with_ymd_and_hms(2025, 5, 6, 7, 8, 9).unwrap();
+  let moved = corrupt_file_path(std::path::Path::new("data/schedule.json"), now);
+  assert_eq!(moved, std::path::PathBuf::from("data/schedule.json.corrupt-20250506-070809"));
+}
+
+#[test]
+fn test_corrupt_schedule_is_moved_aside_and_reset() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("schedule.json");
+  fs::write(&path, "{ not json").unwrap();
+
+  let schedule = recover_corrupt(&path, "schedule", load_schedule_silent(&path), true).unwrap();
+
+  assert!(schedule.tasks.is_empty());
+  assert!(!path.exists());
+  let moved = files_starting_with(dir.path(), "schedule.json.corrupt-");
+  assert_eq!(moved.len(), 1);
+  assert_eq!(fs::read_to_string(dir.path().join(&moved[0])).unwrap(), "{ not json");
+}
+
+#[test]
+fn test_corrupt_playlist_is_moved_aside_and_reset() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("playlist.json");
+  fs::write(&path, r#"{"interval_seconds": "soon"}"#).unwrap();
+
+  let playlist = recover_corrupt(&path, "playlist", Playlist::load_silent(&path), true).unwrap();
+
+  assert!(playlist.is_empty());
+  assert!(!path.exists());
+  assert_eq!(files_starting_with(dir.path(), "playlist.json.corrupt-").len(), 1);
+}
+
+#[test]
+fn test_valid_file_is_untouched() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("schedule.json");
+  let content = r#"{"tasks": [{"id": "ab12", "time": "2030-01-01T09:00:00Z", "widget": "text", "input": "hi"}]}"#;
+  fs::write(&path, content).unwrap();
+
+  let schedule = recover_corrupt(&path, "schedule", load_schedule_silent(&path), true).unwrap();
+
+  assert_eq!(schedule.tasks.len(), 1);
+  assert_eq!(fs::read_to_string(&path).unwrap(), content);
+  assert!(files_starting_with(dir.path(), "schedule.json.corrupt-").is_empty());
+}
+
+#[test]
+fn test_corrupt_file_is_an_error_without_recovery() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("schedule.json");
+  fs::write(&path, "{ not json").unwrap();
+
+  let result = recover_corrupt(&path, "schedule", load_schedule_silent(&path), false);
+
+  assert!(matches!(result, Err(VestaboardError::JsonError { .. })));
+  assert!(path.exists());
+}
+
+#[test]
+fn test_other_errors_are_not_recovered() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("schedule.json");
+  let loaded: Result<Schedule, _> = Err(VestaboardError::input_error("bad input"));
+
+  assert!(recover_corrupt(&path, "schedule", loaded, true).is_err());
+}
+
+#[test]
+fn test_schedule_monitor_recovers_a_file_corrupted_while_running() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("schedule.json");
+  fs::write(
+    &path,
+    r#"{"tasks": [{"id": "ab12", "time": "2030-01-01T09:00:00Z", "widget": "jokes", "input": null}]}"#,
+  )
+  .unwrap();
+  let mut monitor = ScheduleMonitor::new(&path).with_recover_corrupt(true);
+  monitor.initialize().unwrap();
+  assert_eq!(monitor.get_current_schedule().tasks.len(), 1);
+
+  fs::write(&path, "{ not json").unwrap();
+  monitor.reload_schedule().unwrap();
+
+  assert!(monitor.get_current_schedule().tasks.is_empty());
+  assert!(!path.exists());
+  assert_eq!(files_starting_with(dir.path(), "schedule.json.corrupt-").len(), 1);
+}
+
+#[test]
+fn test_schedule_monitor_keeps_the_old_schedule_without_recovery() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("schedule.json");
+  fs::write(
+    &path,
+    r#"{"tasks": [{"id": "ab12", "time": "2030-01-01T09:00:00Z", "widget": "jokes", "input": null}]}"#,
+  )
+  .unwrap();
+  let mut monitor = ScheduleMonitor::new(&path);
+  monitor.initialize().unwrap();
+
+  fs::write(&path, "{ not json").unwrap();
+  assert!(monitor.reload_schedule().is_err());
+
+  assert_eq!(monitor.get_current_schedule().tasks.len(), 1);
+  assert!(path.exists());
+}
+
+#[tokio::test]
+async fn test_wait_for_items_returns_once_the_playlist_has_items() {
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("playlist.json");
+  let mut playlist = Playlist::default();
+  playlist.add_widget("jokes", serde_json::Value::Null);
+  playlist.save_silent(&path).unwrap();
+
+  let waited = wait_for_items(&path, &ProcessController::new()).await;
+
+  assert_eq!(waited.map(|p| p.len()), Some(1));
+}
+
+#[tokio::test]
+async fn test_wait_for_items_stops_on_shutdown() {
+  let dir = tempdir().unwrap();
+  let controller = ProcessController::new();
+  controller.request_shutdown();
+
+  assert!(wait_for_items(&dir.path().join("playlist.json"), &controller)
+    .await
+    .is_none());
+}
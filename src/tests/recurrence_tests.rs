@@ -0,0 +1,86 @@
+//! Tests for schedule recurrence specs and next-occurrence calculation.
+
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Utc, Weekday};
+
+use crate::recurrence::Recurrence;
+
+/// A local time on a known Monday (2025-05-05), as UTC
+fn monday_at(hour: u32) -> DateTime<Utc> {
+  Local
+    .with_ymd_and_hms(2025, 5, 5, hour, 0, 0)
+    .unwrap()
+    .with_timezone(&Utc)
+}
+
+fn local_weekday(time: DateTime<Utc>) -> Weekday {
+  time.with_timezone(&Local).weekday()
+}
+
+#[test]
+fn test_parse_named_specs() {
+  assert_eq!(Recurrence::parse("daily").unwrap().to_string(), "daily");
+  assert_eq!(Recurrence::parse("Weekdays").unwrap().to_string(), "weekdays");
+  assert_eq!(Recurrence::parse("weekends").unwrap().to_string(), "weekends");
+}
+
+#[test]
+fn test_parse_day_list_normalizes_order() {
+  let recurrence = Recurrence::parse("fri, mon,wed,mon").unwrap();
+  assert_eq!(recurrence.to_string(), "mon,wed,fri");
+  assert!(recurrence.matches(Weekday::Wed));
+  assert!(!recurrence.matches(Weekday::Tue));
+
+  // A list covering every weekday is shown by name
+  assert_eq!(Recurrence::parse("mon,tue,wed,thu,fri").unwrap().to_string(), "weekdays");
+}
+
+#[test]
+fn test_parse_rejects_unknown_tokens() {
+  for spec in ["", "hourly", "mon,funday", "mon,,fri", "every day"] {
+    assert!(Recurrence::parse(spec).is_err(), "expected '{}' to be rejected", spec);
+  }
+}
+
+#[test]
+fn test_serializes_as_spec_string() {
+  let recurrence = Recurrence::parse("mon,wed,fri").unwrap();
+  let json = serde_json::to_string(&recurrence).unwrap();
+  assert_eq!(json, "\"mon,wed,fri\"");
+  assert_eq!(serde_json::from_str::<Recurrence>(&json).unwrap(), recurrence);
+  assert!(serde_json::from_str::<Recurrence>("\"someday\"").is_err());
+}
+
+#[test]
+fn test_first_at_or_after_rolls_to_matching_day() {
+  let weekends = Recurrence::parse("weekends").unwrap();
+  let first = weekends.first_at_or_after(monday_at(8));
+  assert_eq!(local_weekday(first), Weekday::Sat);
+  assert_eq!(first.with_timezone(&Local).time(), monday_at(8).with_timezone(&Local).time());
+
+  // A time already on a matching day is kept
+  let daily = Recurrence::parse("daily").unwrap();
+  assert_eq!(daily.first_at_or_after(monday_at(8)), monday_at(8));
+}
+
+#[test]
+fn test_next_after_moves_past_now() {
+  let daily = Recurrence::parse("daily").unwrap();
+  let next = daily.next_after(monday_at(8), monday_at(8));
+  assert_eq!(local_weekday(next), Weekday::Tue);
+  assert_eq!(next.with_timezone(&Local).time(), monday_at(8).with_timezone(&Local).time());
+
+  // Occurrences missed while nothing was running are skipped
+  let next = daily.next_after(monday_at(8), monday_at(8) + Duration::days(3) + Duration::hours(1));
+  assert_eq!(local_weekday(next), Weekday::Fri);
+}
+
+#[test]
+fn test_next_after_skips_to_listed_days() {
+  let recurrence = Recurrence::parse("mon,wed,fri").unwrap();
+  let wednesday = recurrence.next_after(monday_at(8), monday_at(9));
+  assert_eq!(local_weekday(wednesday), Weekday::Wed);
+  let friday = recurrence.next_after(wednesday, wednesday);
+  assert_eq!(local_weekday(friday), Weekday::Fri);
+  let monday = recurrence.next_after(friday, friday);
+  assert_eq!(local_weekday(monday), Weekday::Mon);
+}
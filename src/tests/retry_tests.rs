@@ -0,0 +1,110 @@
+//! Tests for the shared retry policy.
+
+use crate::api::retry::{is_retryable, RetryPolicy};
+use crate::errors::VestaboardError;
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+const POLICY: RetryPolicy = RetryPolicy {
+  attempts: 3,
+  base_delay: Duration::from_millis(100),
+  max_delay: Duration::from_millis(150),
+  jitter: false,
+};
+
+fn unavailable() -> VestaboardError {
+  VestaboardError::api_error(Some(503), "temporarily unavailable")
+}
+
+/// Runs `POLICY` with an operation that returns `results` in order, recording the
+/// requested sleeps instead of waiting. Returns the result, the call count, and the sleeps.
+async fn run_with_results(
+  policy: RetryPolicy,
+  results: Vec<Result<u32, VestaboardError>>,
+) -> (Result<u32, VestaboardError>, u32, Vec<Duration>) {
+  let results = RefCell::new(results.into_iter());
+  let calls = Cell::new(0);
+  let sleeps = RefCell::new(Vec::new());
+
+  let result = policy
+    .run_with_sleep(
+      || {
+        calls.set(calls.get() + 1);
+        let next = results.borrow_mut().next().expect("operation called too many times");
+        async move { next }
+      },
+      |delay| {
+        sleeps.borrow_mut().push(delay);
+        async {}
+      },
+    )
+    .await;
+
+  (result, calls.get(), sleeps.into_inner())
+}
+
+#[test]
+fn test_backoff_doubles_up_to_max_delay() {
+  assert_eq!(POLICY.backoff(1), Duration::from_millis(100));
+  assert_eq!(POLICY.backoff(2), Duration::from_millis(150));
+  assert_eq!(POLICY.backoff(40), Duration::from_millis(150));
+
+  let uncapped = RetryPolicy {
+    max_delay: Duration::from_secs(60),
+    ..POLICY
+  };
+  assert_eq!(uncapped.backoff(3), Duration::from_millis(400));
+}
+
+#[tokio::test]
+async fn test_succeeds_after_retries() {
+  let (result, calls, sleeps) = run_with_results(POLICY, vec![Err(unavailable()), Err(unavailable()), Ok(7)]).await;
+
+  assert_eq!(result.unwrap(), 7);
+  assert_eq!(calls, 3);
+  assert_eq!(sleeps, vec![Duration::from_millis(100), Duration::from_millis(150)]);
+}
+
+#[tokio::test]
+async fn test_gives_up_after_attempts() {
+  let results = vec![Err(unavailable()), Err(unavailable()), Err(unavailable())];
+  let (result, calls, sleeps) = run_with_results(POLICY, results).await;
+
+  assert_eq!(result.unwrap_err(), unavailable());
+  assert_eq!(calls, 3);
+  assert_eq!(sleeps.len(), 2);
+}
+
+#[tokio::test]
+async fn test_non_retryable_error_short_circuits() {
+  let not_found = VestaboardError::api_error(Some(404), "not found");
+  let (result, calls, sleeps) = run_with_results(POLICY, vec![Err(not_found)]).await;
+
+  assert_eq!(result.unwrap_err(), VestaboardError::api_error(Some(404), "not found"));
+  assert_eq!(calls, 1);
+  assert!(sleeps.is_empty());
+}
+
+#[tokio::test]
+async fn test_jitter_keeps_delay_within_half_and_full_backoff() {
+  let policy = RetryPolicy { jitter: true, ..POLICY };
+  let (result, _, sleeps) = run_with_results(policy, vec![Err(unavailable()), Ok(1)]).await;
+
+  assert!(result.is_ok());
+  assert!(sleeps[0] >= Duration::from_millis(50) && sleeps[0] <= Duration::from_millis(100));
+}
+
+#[test]
+fn test_is_retryable_classification() {
+  assert!(is_retryable(&unavailable()));
+  assert!(is_retryable(&VestaboardError::api_error(Some(500), "server error")));
+  assert!(!is_retryable(&VestaboardError::api_error(Some(429), "rate limited")));
+  assert!(!is_retryable(&VestaboardError::api_error(Some(400), "bad request")));
+  assert!(!is_retryable(&VestaboardError::api_error(None, "unknown")));
+  assert!(!is_retryable(&VestaboardError::config_error("WEATHER_API_KEY", "not set")));
+  assert!(!is_retryable(&VestaboardError::input_error("bad input")));
+
+  // A malformed URL fails before any connection is made, so retrying won't help
+  let builder_error = reqwest::Client::new().get("not a url").build().unwrap_err();
+  assert!(!is_retryable(&VestaboardError::reqwest_error(builder_error, "test")));
+}
@@ -1,5 +1,8 @@
 //! Tests for the runner module.
 
+use crate::api::{MockTransport, Transport};
+use crate::config::Config;
+use crate::runner::common::send_unless_blank;
 use crate::runner::{ControlFlow, PLAYLIST_HELP, SCHEDULE_HELP};
 
 #[test]
@@ -23,3 +26,43 @@ fn test_schedule_help_contains_expected_keys() {
   assert!(SCHEDULE_HELP.contains("q"));
   assert!(SCHEDULE_HELP.contains("?"));
 }
+
+#[tokio::test]
+async fn test_skip_blank_drops_blank_live_sends() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  let blank = vec![String::new(), "   ".to_string()];
+  send_unless_blank(blank, "text", false, "Item 1", &transport, &Config::default(), true)
+    .await
+    .unwrap();
+
+  assert!(mock.sent_codes().is_empty());
+}
+
+#[tokio::test]
+async fn test_skip_blank_still_sends_content_and_clear() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  send_unless_blank(vec!["hello".to_string()], "text", false, "Item 1", &transport, &Config::default(), true)
+    .await
+    .unwrap();
+  send_unless_blank(vec![String::new()], "clear", false, "Item 2", &transport, &Config::default(), true)
+    .await
+    .unwrap();
+
+  assert_eq!(mock.sent_codes().len(), 2);
+}
+
+#[tokio::test]
+async fn test_blank_is_sent_when_skip_blank_is_off() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  send_unless_blank(vec![String::new()], "text", false, "Item 1", &transport, &Config::default(), false)
+    .await
+    .unwrap();
+
+  assert_eq!(mock.sent_codes().len(), 1);
+}
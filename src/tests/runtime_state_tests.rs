@@ -1,6 +1,6 @@
 //! Tests for the runtime_state module.
 
-use crate::runtime_state::{PlaylistState, RuntimeState};
+use crate::runtime_state::{PlaylistState, PlaylistStats, RuntimeState, ScheduleState};
 use chrono::Utc;
 use std::io::Write;
 use tempfile::NamedTempFile;
@@ -118,3 +118,68 @@ fn test_runtime_state_load_partial_json() {
   assert_eq!(state.playlist_index, 5);
   assert_eq!(state.playlist_state, PlaylistState::Stopped); // default
 }
+
+#[test]
+fn test_playlist_stats_record_display_and_error() {
+  let mut stats = PlaylistStats::default();
+  stats.record_display("weather");
+  stats.record_display("weather");
+  stats.record_display("text");
+  stats.record_error("Network error");
+
+  assert_eq!(stats.total_displayed, 3);
+  assert_eq!(stats.widget_counts.get("weather"), Some(&2));
+  assert_eq!(stats.widget_counts.get("text"), Some(&1));
+  assert_eq!(stats.last_error.as_deref(), Some("Network error"));
+}
+
+#[test]
+fn test_runtime_state_stats_survive_save_and_load() {
+  let mut state = RuntimeState::default();
+  state.stats.record_display("jokes");
+  state.stats.record_error("Widget jokes failed");
+
+  let temp_file = NamedTempFile::new().unwrap();
+  state.save(temp_file.path());
+
+  let loaded = RuntimeState::load(temp_file.path());
+  assert_eq!(loaded.stats, state.stats);
+}
+
+#[test]
+fn test_runtime_state_load_without_stats_uses_defaults() {
+  let mut temp_file = NamedTempFile::new().unwrap();
+  writeln!(temp_file, r#"{{"playlist_state": "Paused", "playlist_index": 2, "last_shown_time": null}}"#).unwrap();
+
+  let state = RuntimeState::load(temp_file.path());
+  assert_eq!(state.playlist_state, PlaylistState::Paused);
+  assert_eq!(state.playlist_index, 2);
+  assert_eq!(state.stats, PlaylistStats::default());
+}
+
+#[test]
+fn test_schedule_state_save_and_load() {
+  let today = Utc::now().date_naive();
+  let mut state = ScheduleState::default();
+  state.mark_executed("abc123", today);
+
+  let temp_file = NamedTempFile::new().unwrap();
+  state.save(temp_file.path());
+
+  let loaded = ScheduleState::load(temp_file.path());
+  assert_eq!(loaded, state);
+  assert_eq!(loaded.executed_on(today).collect::<Vec<_>>(), vec!["abc123"]);
+}
+
+#[test]
+fn test_schedule_state_prune_drops_previous_days() {
+  let today = Utc::now().date_naive();
+  let yesterday = today - chrono::Duration::days(1);
+  let mut state = ScheduleState::default();
+  state.mark_executed("old", yesterday);
+  state.mark_executed("new", today);
+
+  state.prune(today);
+  assert!(!state.executed.contains_key("old"));
+  assert!(state.executed.contains_key("new"));
+}
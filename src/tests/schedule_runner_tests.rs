@@ -1,13 +1,21 @@
 //! Tests for the ScheduleRunner.
 
-use chrono::{Duration, Utc};
+use chrono::{Duration, Local, Utc};
 use crossterm::event::KeyCode;
 use serde_json::json;
+use serial_test::serial;
 
-use crate::api::{Transport, TransportType};
+use crate::api::{MockTransport, Transport, TransportType};
+use crate::api_broker::message_to_codes;
+use crate::config::Config;
+use crate::recurrence::Recurrence;
 use crate::runner::schedule_runner::ScheduleRunner;
 use crate::runner::{ControlFlow, Runner};
-use crate::scheduler::{Schedule, ScheduledTask};
+use crate::runtime_state::ScheduleState;
+use crate::scheduler::{load_schedule_silent, save_schedule_silent, Schedule, ScheduledTask};
+use crate::widgets::resolver::execute_widget;
+use crate::widgets::text::get_text;
+use crate::widgets::widget_utils::STANDARD_BOARD;
 
 /// Create a test transport. Sets env vars if needed.
 fn create_test_transport() -> Transport {
@@ -33,6 +41,8 @@ fn create_task(id: &str, hours_offset: i64, widget: &str) -> ScheduledTask {
     time,
     widget: widget.to_string(),
     input: json!(null),
+    recurrence: None,
+    uid: None,
   }
 }
 
@@ -63,12 +73,16 @@ fn test_schedule_runner_skips_past_tasks() {
         time: now - Duration::hours(2),
         widget: "weather".to_string(),
         input: json!(null),
+        recurrence: None,
+        uid: None,
       },
       ScheduledTask {
         id: "past2".to_string(),
         time: now - Duration::hours(1),
         widget: "text".to_string(),
         input: json!("hello"),
+        recurrence: None,
+        uid: None,
       },
     ],
   };
@@ -141,12 +155,16 @@ fn test_schedule_runner_returns_tasks_in_chronological_order() {
         time: now + Duration::hours(2),
         widget: "text".to_string(),
         input: json!("later"),
+        recurrence: None,
+        uid: None,
       },
       ScheduledTask {
         id: "sooner".to_string(),
         time: now + Duration::hours(1),
         widget: "weather".to_string(),
         input: json!(null),
+        recurrence: None,
+        uid: None,
       },
     ],
   };
@@ -221,6 +239,8 @@ fn test_schedule_runner_time_until_next_all_past() {
       time: now - Duration::hours(2),
       widget: "weather".to_string(),
       input: json!(null),
+      recurrence: None,
+      uid: None,
     }],
   };
 
@@ -275,3 +295,424 @@ fn test_schedule_runner_reload_clears_executed_set() {
   assert!(next.is_some());
   assert_eq!(next.unwrap().id, "future");
 }
+
+/// A schedule with one text task that came due a minute ago
+fn create_due_text_schedule(id: &str) -> Schedule {
+  Schedule {
+    tasks: vec![ScheduledTask {
+      id: id.to_string(),
+      time: Utc::now() - Duration::minutes(1),
+      widget: "text".to_string(),
+      input: json!("hello"),
+      recurrence: None,
+      uid: None,
+    }],
+  }
+}
+
+#[tokio::test]
+async fn test_schedule_runner_restart_does_not_refire_executed_task() {
+  let temp_dir = tempfile::tempdir().unwrap();
+  let state_path = temp_dir.path().join("schedule_state.json");
+  let transport = create_test_transport();
+
+  let mut runner =
+    ScheduleRunner::new(create_due_text_schedule("once"), true, &transport).with_state_path(state_path.clone());
+  runner.run_iteration().await.unwrap();
+  assert!(runner.has_executed("once"));
+
+  // Simulate a restart with the same schedule
+  let restarted = ScheduleRunner::new(create_due_text_schedule("once"), true, &transport).with_state_path(state_path);
+  assert!(restarted.has_executed("once"));
+}
+
+#[tokio::test]
+async fn test_schedule_runner_restart_fires_new_task() {
+  let temp_dir = tempfile::tempdir().unwrap();
+  let state_path = temp_dir.path().join("schedule_state.json");
+  let transport = create_test_transport();
+
+  let mut runner =
+    ScheduleRunner::new(create_due_text_schedule("old"), true, &transport).with_state_path(state_path.clone());
+  runner.run_iteration().await.unwrap();
+
+  let mut restarted =
+    ScheduleRunner::new(create_due_text_schedule("new"), true, &transport).with_state_path(state_path);
+  assert!(!restarted.has_executed("new"));
+  restarted.run_iteration().await.unwrap();
+  assert!(restarted.has_executed("new"));
+}
+
+#[test]
+fn test_schedule_runner_ignores_executions_from_previous_days() {
+  let temp_dir = tempfile::tempdir().unwrap();
+  let state_path = temp_dir.path().join("schedule_state.json");
+  let yesterday = chrono::Local::now().date_naive() - Duration::days(1);
+
+  let mut state = ScheduleState::default();
+  state.mark_executed("future", yesterday);
+  state.save(&state_path);
+
+  let transport = create_test_transport();
+  let runner = ScheduleRunner::new(create_test_schedule(), false, &transport).with_state_path(state_path);
+  assert!(!runner.has_executed("future"));
+}
+
+/// Two back-to-back due tasks that render the same grid
+fn create_duplicate_clear_schedule() -> Schedule {
+  let now = Utc::now();
+  Schedule {
+    tasks: vec![
+      ScheduledTask {
+        id: "clear1".to_string(),
+        time: now - Duration::minutes(2),
+        widget: "clear".to_string(),
+        input: json!(null),
+        recurrence: None,
+        uid: None,
+      },
+      ScheduledTask {
+        id: "clear2".to_string(),
+        time: now - Duration::minutes(1),
+        widget: "clear".to_string(),
+        input: json!(null),
+        recurrence: None,
+        uid: None,
+      },
+    ],
+  }
+}
+
+#[tokio::test]
+async fn test_schedule_runner_skips_duplicate_render() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = ScheduleRunner::new(create_duplicate_clear_schedule(), false, &transport).with_skip_duplicates(true);
+
+  runner.run_iteration().await.unwrap();
+  runner.run_iteration().await.unwrap();
+
+  assert!(runner.has_executed("clear1"));
+  assert!(runner.has_executed("clear2"));
+  assert_eq!(mock.sent_codes().len(), 1);
+}
+
+#[tokio::test]
+async fn test_schedule_runner_sends_duplicates_when_option_off() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = ScheduleRunner::new(create_duplicate_clear_schedule(), false, &transport);
+
+  runner.run_iteration().await.unwrap();
+  runner.run_iteration().await.unwrap();
+
+  assert_eq!(mock.sent_codes().len(), 2);
+}
+
+#[tokio::test]
+async fn test_schedule_runner_sends_different_renders() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut schedule = create_duplicate_clear_schedule();
+  schedule.tasks[1].widget = "text".to_string();
+  schedule.tasks[1].input = json!("hello");
+  let mut runner = ScheduleRunner::new(schedule, false, &transport).with_skip_duplicates(true);
+
+  runner.run_iteration().await.unwrap();
+  runner.run_iteration().await.unwrap();
+
+  assert_eq!(mock.sent_codes().len(), 2);
+}
+
+#[tokio::test]
+async fn test_schedule_runner_moves_recurring_task_forward() {
+  let transport = create_test_transport();
+  let mut schedule = create_due_text_schedule("daily");
+  let due_time = schedule.tasks[0].time;
+  schedule.tasks[0].recurrence = Some(Recurrence::parse("daily").unwrap());
+
+  let mut runner = ScheduleRunner::new(schedule, true, &transport);
+  runner.run_iteration().await.unwrap();
+
+  // Not marked executed: it stays pending at the same time tomorrow
+  assert!(!runner.has_executed("daily"));
+  let next = runner
+    .next_pending_task()
+    .expect("recurring task should still be pending");
+  assert_eq!(next.id, "daily");
+  assert!(next.time > Utc::now());
+  assert_eq!(next.time.with_timezone(&Local).time(), due_time.with_timezone(&Local).time());
+}
+
+#[tokio::test]
+async fn test_schedule_runner_saves_recurring_task_next_time() {
+  let temp_dir = tempfile::tempdir().unwrap();
+  let schedule_path = temp_dir.path().join("schedule.json");
+  let transport = create_test_transport();
+  let mut schedule = create_due_text_schedule("daily");
+  schedule.tasks[0].recurrence = Some(Recurrence::parse("daily").unwrap());
+  save_schedule_silent(&schedule, &schedule_path).unwrap();
+
+  let mut runner = ScheduleRunner::new(schedule, true, &transport).with_schedule_path(schedule_path.clone());
+  runner.run_iteration().await.unwrap();
+
+  let saved = load_schedule_silent(&schedule_path).unwrap();
+  assert!(saved.tasks[0].time > Utc::now());
+  assert_eq!(saved.tasks[0].recurrence, Some(Recurrence::parse("daily").unwrap()));
+}
+
+/// Idle widget used by the idle tests
+fn idle_text() -> Option<(String, serde_json::Value)> {
+  Some(("text".to_string(), json!("be right back")))
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schedule_runner_shows_idle_when_no_task_is_near() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let schedule = Schedule {
+    tasks: vec![create_task("later", 3, "text")],
+  };
+  let mut runner = ScheduleRunner::new(schedule, false, &transport).with_idle(idle_text());
+
+  assert!(runner.should_show_idle());
+  runner.run_iteration().await.unwrap();
+  let sent = mock.sent_codes();
+  assert_eq!(sent.len(), 1);
+  assert_eq!(sent[0], message_to_codes(get_text("be right back", STANDARD_BOARD).unwrap()));
+
+  // Shown once per gap, not on every iteration
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schedule_runner_idle_waits_for_near_task() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut task = create_task("soon", 0, "text");
+  task.time = Utc::now() + Duration::minutes(10);
+  let mut runner = ScheduleRunner::new(Schedule { tasks: vec![task] }, false, &transport).with_idle(idle_text());
+
+  assert!(!runner.should_show_idle());
+  runner.run_iteration().await.unwrap();
+  assert!(mock.sent_codes().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schedule_runner_due_task_preempts_idle() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = ScheduleRunner::new(create_due_text_schedule("due"), false, &transport).with_idle(idle_text());
+
+  runner.run_iteration().await.unwrap();
+  let sent = mock.sent_codes();
+  assert_eq!(sent.len(), 1);
+  assert_eq!(sent[0], message_to_codes(get_text("hello", STANDARD_BOARD).unwrap()));
+
+  // The task's content stays up instead of being replaced by idle right away
+  assert!(!runner.should_show_idle());
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 1);
+}
+
+#[test]
+fn test_schedule_runner_without_idle_never_idles() {
+  let transport = create_test_transport();
+  let runner = ScheduleRunner::new(Schedule { tasks: vec![] }, false, &transport);
+  assert!(!runner.should_show_idle());
+}
+
+/// A schedule with one text task that came due `minutes` ago
+fn create_missed_text_schedule(minutes: i64) -> Schedule {
+  Schedule {
+    tasks: vec![ScheduledTask {
+      id: "missed".to_string(),
+      time: Utc::now() - Duration::minutes(minutes),
+      widget: "text".to_string(),
+      input: json!("missed"),
+      recurrence: None,
+      uid: None,
+    }],
+  }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_catch_up_fires_task_missed_within_window() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner =
+    ScheduleRunner::new(create_missed_text_schedule(5), false, &transport).with_catch_up(Some(Duration::minutes(10)));
+  runner.start();
+  runner.run_iteration().await.unwrap();
+
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(get_text("missed", STANDARD_BOARD).unwrap())]);
+  assert!(runner.has_executed("missed"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_catch_up_skips_task_missed_outside_window() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner =
+    ScheduleRunner::new(create_missed_text_schedule(60), false, &transport).with_catch_up(Some(Duration::minutes(10)));
+  runner.start();
+  runner.run_iteration().await.unwrap();
+
+  assert!(mock.sent_codes().is_empty());
+  assert!(!runner.has_due_task());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_missed_task_skipped_without_catch_up() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = ScheduleRunner::new(create_missed_text_schedule(5), false, &transport);
+  runner.start();
+  runner.run_iteration().await.unwrap();
+
+  assert!(mock.sent_codes().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_run_once_fires_due_task_then_exits() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut schedule = create_missed_text_schedule(5);
+  schedule.tasks.push(create_task("later", 3, "text"));
+  let mut runner = ScheduleRunner::new(schedule, false, &transport)
+    .with_catch_up(Some(Duration::minutes(10)))
+    .with_idle(idle_text())
+    .with_run_once(true);
+  runner.start();
+
+  let flow = runner.run_iteration().await.unwrap();
+
+  assert_eq!(flow, ControlFlow::Exit);
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(get_text("missed", STANDARD_BOARD).unwrap())]);
+  assert!(runner.has_executed("missed"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_run_once_waits_for_task_due_within_window() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut task = create_task("soon", 0, "text");
+  task.time = Utc::now() + Duration::seconds(30);
+  let mut runner = ScheduleRunner::new(Schedule { tasks: vec![task] }, false, &transport).with_run_once(true);
+
+  assert_eq!(runner.run_iteration().await.unwrap(), ControlFlow::Continue);
+  assert!(mock.sent_codes().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_run_once_exits_when_nothing_is_near() {
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let schedule = Schedule {
+    tasks: vec![create_task("later", 3, "text")],
+  };
+  let mut runner = ScheduleRunner::new(schedule, false, &transport)
+    .with_idle(idle_text())
+    .with_run_once(true);
+
+  assert_eq!(runner.run_iteration().await.unwrap(), ControlFlow::Exit);
+  // A one-shot run doesn't put idle content up
+  assert!(mock.sent_codes().is_empty());
+
+  let mut long_running = ScheduleRunner::new(Schedule { tasks: vec![] }, false, &transport);
+  assert_eq!(long_running.run_iteration().await.unwrap(), ControlFlow::Continue);
+}
+
+/// A schedule with one file task, due a minute ago, reading `path`
+fn create_due_file_schedule(path: &std::path::Path) -> Schedule {
+  let mut schedule = create_due_text_schedule("file");
+  schedule.tasks[0].widget = "file".to_string();
+  schedule.tasks[0].input = json!(path.to_str().unwrap());
+  schedule
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schedule_runner_retries_failed_task_after_error_display() {
+  let temp_dir = tempfile::tempdir().unwrap();
+  // The file widget fails until the file exists
+  let message_path = temp_dir.path().join("message.txt");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = ScheduleRunner::new(create_due_file_schedule(&message_path), false, &transport)
+    .with_error_display(Some(std::time::Duration::ZERO));
+
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 1, "the error frame is sent");
+  assert!(runner.has_due_task(), "the retry is due once the error display time is up");
+
+  std::fs::write(&message_path, "back online").unwrap();
+  let expected = execute_widget("file", &json!(message_path.to_str().unwrap()), &Config::default())
+    .await
+    .unwrap();
+  runner.run_iteration().await.unwrap();
+
+  let sent = mock.sent_codes();
+  assert_eq!(sent.len(), 2);
+  assert_eq!(sent[1], message_to_codes(expected));
+  assert!(!runner.has_due_task());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schedule_runner_retries_failed_task_only_once() {
+  let temp_dir = tempfile::tempdir().unwrap();
+  let message_path = temp_dir.path().join("missing.txt");
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner = ScheduleRunner::new(create_due_file_schedule(&message_path), false, &transport)
+    .with_error_display(Some(std::time::Duration::ZERO));
+
+  runner.run_iteration().await.unwrap();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 2, "the error and the failed retry are sent");
+  assert!(!runner.has_due_task(), "a second failure leaves its error up");
+
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schedule_runner_without_error_display_does_not_retry() {
+  let temp_dir = tempfile::tempdir().unwrap();
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let mut runner =
+    ScheduleRunner::new(create_due_file_schedule(&temp_dir.path().join("missing.txt")), false, &transport);
+
+  runner.run_iteration().await.unwrap();
+  runner.run_iteration().await.unwrap();
+  assert_eq!(mock.sent_codes().len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schedule_runner_skip_blank_leaves_board_alone() {
+  let temp_dir = tempfile::tempdir().unwrap();
+  let message_path = temp_dir.path().join("blank.txt");
+  std::fs::write(&message_path, "   ").unwrap();
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+
+  let mut runner =
+    ScheduleRunner::new(create_due_file_schedule(&message_path), false, &transport).with_skip_blank(true);
+  runner.run_iteration().await.unwrap();
+  assert!(mock.sent_codes().is_empty());
+  assert!(runner.has_executed("file"));
+}
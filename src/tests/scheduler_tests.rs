@@ -1,11 +1,14 @@
-use crate::config::DEFAULT_SCHEDULE_FILE_PATH;
+use crate::config::{Config, DEFAULT_SCHEDULE_FILE_PATH};
 use crate::errors::VestaboardError;
+use crate::recurrence::Recurrence;
 use crate::scheduler::{
-  add_task_to_schedule, clear_schedule, list_schedule, load_schedule, remove_task_from_schedule, save_schedule,
-  Schedule, ScheduleMonitor, ScheduledTask, CUSTOM_ALPHABET, ID_LENGTH,
+  add_task_to_schedule, clear_schedule, filter_tasks_by_range, format_next_task, format_schedule_table, list_schedule,
+  load_schedule, load_schedule_readonly, preview_next_tasks, remove_task_from_schedule, save_schedule,
+  simulate_schedule, Schedule, ScheduleMonitor, ScheduledTask, CUSTOM_ALPHABET, ID_LENGTH,
 };
 use crate::widgets::text::get_text;
-use chrono::{DateTime, TimeZone, Utc};
+use crate::widgets::widget_utils::STANDARD_BOARD;
+use chrono::{DateTime, Datelike, Local, TimeZone, Utc};
 use serde_json::json;
 use serial_test::serial;
 use std::io::{Seek, Write};
@@ -21,6 +24,8 @@ fn create_valid_json_content() -> (String, DateTime<Utc>, String) {
       time: task_time,
       widget: "test_widget".to_string(),
       input: json!({"value": "test_input"}),
+      recurrence: None,
+      uid: None,
     }],
   };
   let json_string = serde_json::to_string_pretty(&schedule).unwrap();
@@ -107,6 +112,27 @@ fn test_load_schedule_file_not_found() {
   assert!(schedule.tasks.is_empty());
 }
 
+#[test]
+fn test_load_schedule_creates_missing_file() {
+  let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+  let path = temp_dir.path().join("schedule.json");
+
+  load_schedule(&path).unwrap();
+
+  assert!(path.exists());
+}
+
+#[test]
+fn test_load_schedule_readonly_does_not_create_file() {
+  let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+  let path = temp_dir.path().join("schedule.json");
+
+  let schedule = load_schedule_readonly(&path).unwrap();
+
+  assert!(schedule.tasks.is_empty());
+  assert!(!path.exists());
+}
+
 #[test]
 fn test_load_schedule_empty_file() {
   let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
@@ -227,6 +253,57 @@ fn test_schedule_add_task() {
   assert_eq!(schedule.tasks[3].id, task2.id);
 }
 
+/// Tasks "c1", "a1", "b1" at the same time, plus an earlier "z9"
+fn same_time_tasks() -> Vec<ScheduledTask> {
+  let time = Utc.with_ymd_and_hms(2025, 5, 1, 9, 0, 0).unwrap();
+  let earlier = Utc.with_ymd_and_hms(2025, 5, 1, 8, 0, 0).unwrap();
+  [("c1", time), ("a1", time), ("z9", earlier), ("b1", time)]
+    .into_iter()
+    .map(|(id, time)| {
+      let mut task = ScheduledTask::new(time, "text".to_string(), json!(id));
+      task.id = id.to_string();
+      task
+    })
+    .collect()
+}
+
+fn task_ids(schedule: &Schedule) -> Vec<&str> {
+  schedule.tasks.iter().map(|task| task.id.as_str()).collect()
+}
+
+#[test]
+fn test_schedule_add_task_orders_same_time_tasks_by_id() {
+  let mut schedule = Schedule::default();
+  for task in same_time_tasks() {
+    schedule.add_task(task);
+  }
+  assert_eq!(task_ids(&schedule), vec!["z9", "a1", "b1", "c1"]);
+}
+
+#[test]
+fn test_load_schedule_orders_same_time_tasks_by_id() {
+  let file_order = Schedule {
+    tasks: same_time_tasks(),
+  };
+  let mut temp_file = NamedTempFile::new().unwrap();
+  write!(temp_file, "{}", serde_json::to_string(&file_order).unwrap()).unwrap();
+  temp_file.as_file_mut().flush().unwrap();
+
+  let loaded = load_schedule(&temp_file.path().to_path_buf()).unwrap();
+  assert_eq!(task_ids(&loaded), vec!["z9", "a1", "b1", "c1"]);
+
+  // Saving and loading again keeps the same order
+  save_schedule(&loaded, &temp_file.path().to_path_buf()).unwrap();
+  let reloaded = load_schedule(&temp_file.path().to_path_buf()).unwrap();
+  assert_eq!(task_ids(&reloaded), task_ids(&loaded));
+
+  let mut added = Schedule::default();
+  for task in same_time_tasks().into_iter().rev() {
+    added.add_task(task);
+  }
+  assert_eq!(task_ids(&added), task_ids(&loaded), "add_task and load agree");
+}
+
 #[test]
 fn test_schedule_get_tasks() {
   let mut schedule = Schedule::default();
@@ -263,6 +340,55 @@ fn test_schedule_get_task_mut() {
   assert_eq!(task.widget, "text");
 }
 
+#[test]
+fn test_schedule_reschedule_moves_and_resorts() {
+  let mut schedule = Schedule::default();
+  let task1 =
+    ScheduledTask::new(Utc.with_ymd_and_hms(2025, 5, 1, 9, 0, 0).unwrap(), "weather".to_string(), json!(null));
+  let task2 = ScheduledTask::new(Utc.with_ymd_and_hms(2025, 5, 1, 17, 30, 0).unwrap(), "text".to_string(), json!("hi"));
+  schedule.add_task(task1.clone());
+  schedule.add_task(task2.clone());
+
+  let new_time = Utc.with_ymd_and_hms(2025, 5, 2, 8, 0, 0).unwrap();
+  assert_eq!(schedule.reschedule(&task1.id, new_time).unwrap(), new_time);
+
+  let ids: Vec<&str> = schedule.tasks.iter().map(|t| t.id.as_str()).collect();
+  assert_eq!(ids, vec![task2.id.as_str(), task1.id.as_str()]);
+  let moved = &schedule.tasks[1];
+  assert_eq!(moved.time, new_time);
+  assert_eq!(moved.widget, "weather");
+  assert_eq!(moved.input, json!(null));
+}
+
+#[test]
+fn test_schedule_reschedule_snaps_repeating_task() {
+  let mut schedule = Schedule::default();
+  let mut task =
+    ScheduledTask::new(Utc.with_ymd_and_hms(2025, 5, 5, 9, 0, 0).unwrap(), "weather".to_string(), json!(null));
+  task.recurrence = Some(Recurrence::parse("weekdays").unwrap());
+  schedule.add_task(task.clone());
+
+  // Saturday moves to the following Monday
+  let saturday = chrono::Local
+    .with_ymd_and_hms(2025, 5, 10, 9, 0, 0)
+    .unwrap()
+    .with_timezone(&Utc);
+  let new_time = schedule.reschedule(&task.id, saturday).unwrap();
+  assert_eq!(new_time.with_timezone(&chrono::Local).weekday(), chrono::Weekday::Mon);
+  assert_eq!(schedule.tasks[0].time, new_time);
+}
+
+#[test]
+fn test_schedule_reschedule_unknown_id_errors() {
+  let mut schedule = Schedule::default();
+  let time = Utc.with_ymd_and_hms(2025, 5, 1, 9, 0, 0).unwrap();
+  schedule.add_task(ScheduledTask::new(time, "weather".to_string(), json!(null)));
+
+  let result = schedule.reschedule("nope", Utc.with_ymd_and_hms(2025, 6, 1, 9, 0, 0).unwrap());
+  assert!(matches!(result, Err(VestaboardError::ScheduleError { .. })));
+  assert_eq!(schedule.tasks[0].time, time);
+}
+
 #[test]
 fn test_schedule_is_empty() {
   let schedule = Schedule::default();
@@ -390,7 +516,7 @@ fn test_add_task_to_schedule() {
   let widget = "text".to_string();
   let input = json!({"message": "test message"});
 
-  let result = add_task_to_schedule(time, widget.clone(), input.clone());
+  let result = add_task_to_schedule(time, widget.clone(), input.clone(), None);
   assert!(result.is_ok(), "add_task_to_schedule should succeed");
 
   // Verify task was added by loading the schedule
@@ -409,6 +535,100 @@ fn test_add_task_to_schedule() {
   }
 }
 
+#[tokio::test]
+#[serial]
+async fn test_schedule_and_show_displays_and_persists_task() {
+  use crate::api::{MockTransport, Transport};
+  use crate::api_broker::{message_to_codes, MessageDestination};
+
+  let dir = tempfile::tempdir().unwrap();
+  let schedule_path = dir.path().join("schedule.json");
+  let config = Config {
+    schedule_file_path: Some(schedule_path.to_string_lossy().to_string()),
+    ..Config::default()
+  };
+
+  let transport = Transport::Mock(MockTransport::new());
+  let time = Utc.with_ymd_and_hms(2030, 5, 4, 18, 30, 0).unwrap();
+  let result = crate::schedule_and_show(
+    time,
+    "text",
+    json!("dinner at six"),
+    None,
+    MessageDestination::Vestaboard,
+    &transport,
+    &config,
+  )
+  .await;
+
+  let task_id = result.expect("schedule_and_show should succeed");
+  let Transport::Mock(mock) = &transport else {
+    unreachable!()
+  };
+  let expected = get_text("dinner at six", STANDARD_BOARD).unwrap();
+  assert_eq!(mock.sent_codes(), vec![message_to_codes(expected)]);
+
+  let loaded = load_schedule(&schedule_path).expect("Failed to load schedule");
+  assert_eq!(loaded.tasks.len(), 1);
+  assert_eq!(loaded.tasks[0].id, task_id);
+  assert_eq!(loaded.tasks[0].time, time);
+  assert_eq!(loaded.tasks[0].input, json!("dinner at six"));
+}
+
+#[test]
+#[serial]
+fn test_add_task_to_schedule_with_repeat() {
+  use std::fs;
+  use std::path::Path;
+
+  let schedule_path = Path::new(DEFAULT_SCHEDULE_FILE_PATH);
+  let backup_path = Path::new("./data/schedule_backup_test.json");
+  let had_existing_file = schedule_path.exists();
+  if had_existing_file {
+    fs::copy(schedule_path, backup_path).expect("Failed to backup existing schedule");
+  }
+  if let Some(parent) = schedule_path.parent() {
+    fs::create_dir_all(parent).expect("Failed to create data directory");
+  }
+  save_schedule(&Schedule::default(), &schedule_path.to_path_buf()).expect("Failed to save initial schedule");
+
+  let time = Utc.with_ymd_and_hms(2025, 5, 4, 18, 30, 0).unwrap();
+  for (spec, expected) in [
+    ("daily", "daily"),
+    ("weekdays", "weekdays"),
+    ("mon,wed,fri", "mon,wed,fri"),
+  ] {
+    let id = add_task_to_schedule(time, "text".to_string(), json!("hello"), Some(spec)).expect("Failed to add task");
+    let loaded = load_schedule(&schedule_path.to_path_buf()).unwrap();
+    let task = loaded.get_task(&id).unwrap();
+    assert_eq!(task.recurrence.as_ref().map(|r| r.to_string()).as_deref(), Some(expected));
+    assert!(task
+      .recurrence
+      .as_ref()
+      .unwrap()
+      .matches(task.time.with_timezone(&chrono::Local).weekday()));
+  }
+
+  // An invalid spec is rejected and nothing is saved
+  let result = add_task_to_schedule(time, "text".to_string(), json!("hello"), Some("mon,someday"));
+  assert!(matches!(result, Err(VestaboardError::ValidationError { .. })));
+  assert_eq!(load_schedule(&schedule_path.to_path_buf()).unwrap().tasks.len(), 3);
+
+  if had_existing_file {
+    fs::copy(backup_path, schedule_path).expect("Failed to restore original schedule");
+    fs::remove_file(backup_path).expect("Failed to remove backup file");
+  } else {
+    fs::remove_file(schedule_path).ok();
+  }
+}
+
+#[test]
+fn test_recurrence_omitted_from_json_when_unset() {
+  let task = ScheduledTask::new(Utc::now(), "text".to_string(), json!("hi"));
+  let json = serde_json::to_string(&task).unwrap();
+  assert!(!json.contains("recurrence"));
+}
+
 #[test]
 #[serial]
 fn test_remove_task_from_schedule() {
@@ -439,7 +659,7 @@ fn test_remove_task_from_schedule() {
   save_schedule(&empty_schedule, &schedule_path.to_path_buf()).expect("Failed to save initial schedule");
 
   // Add a task using the global function
-  add_task_to_schedule(time, widget, input).expect("Failed to add task");
+  add_task_to_schedule(time, widget, input, None).expect("Failed to add task");
 
   // Get the task ID
   let loaded_schedule = load_schedule(&schedule_path.to_path_buf()).expect("Failed to load schedule");
@@ -499,9 +719,9 @@ fn test_clear_schedule() {
   save_schedule(&empty_schedule, &schedule_path.to_path_buf()).expect("Failed to save initial schedule");
 
   // Add multiple tasks
-  add_task_to_schedule(time, "text".to_string(), json!({"message": "test1"})).expect("Failed to add task 1");
-  add_task_to_schedule(time, "weather".to_string(), json!({})).expect("Failed to add task 2");
-  add_task_to_schedule(time, "sat-word".to_string(), json!({})).expect("Failed to add task 3");
+  add_task_to_schedule(time, "text".to_string(), json!({"message": "test1"}), None).expect("Failed to add task 1");
+  add_task_to_schedule(time, "weather".to_string(), json!({}), None).expect("Failed to add task 2");
+  add_task_to_schedule(time, "sat-word".to_string(), json!({}), None).expect("Failed to add task 3");
 
   // Verify tasks were added
   let loaded_schedule = load_schedule(&schedule_path.to_path_buf()).expect("Failed to load schedule");
@@ -554,12 +774,12 @@ fn test_list_schedule() {
   save_schedule(&empty_schedule, &schedule_path.to_path_buf()).expect("Failed to save initial schedule");
 
   // Add tasks
-  add_task_to_schedule(time1, "text".to_string(), json!({"message": "hello"})).expect("Failed to add task 1");
-  add_task_to_schedule(time2, "weather".to_string(), json!({})).expect("Failed to add task 2");
+  add_task_to_schedule(time1, "text".to_string(), json!({"message": "hello"}), None).expect("Failed to add task 1");
+  add_task_to_schedule(time2, "weather".to_string(), json!({}), None).expect("Failed to add task 2");
 
   // Test that list_schedule can run without panicking
   // (We can't easily test the printed output, but we can test that it doesn't crash)
-  let result = list_schedule();
+  let result = list_schedule(None, None);
   assert!(result.is_ok(), "list_schedule should succeed");
 
   // Verify the underlying schedule is correct
@@ -611,7 +831,7 @@ async fn test_print_schedule() {
     match task.widget.as_str() {
       "text" => {
         let text_input = task.input.as_str().unwrap_or("default text");
-        let _result = get_text(text_input); // Should not panic
+        let _result = get_text(text_input, STANDARD_BOARD); // Should not panic
       },
       "weather" => {
         // Weather widget test would require network, so we'll skip actual execution
@@ -656,6 +876,8 @@ fn schedule_monitor_initialize_with_existing_file_test() {
       time: task_time,
       widget: "text".to_string(),
       input: json!("test message"),
+      recurrence: None,
+      uid: None,
     }],
   };
 
@@ -744,6 +966,8 @@ fn schedule_monitor_reload_if_modified_test() {
       time: task_time,
       widget: "text".to_string(),
       input: json!("new message"),
+      recurrence: None,
+      uid: None,
     }],
   };
 
@@ -777,3 +1001,362 @@ fn schedule_monitor_handles_file_not_found_test() {
   let result = monitor.check_for_updates();
   assert!(result.is_ok());
 }
+
+#[test]
+fn test_format_schedule_table_plain_output() {
+  let now = Utc::now();
+  let tasks = vec![
+    ScheduledTask {
+      id: "soon".to_string(),
+      time: now + chrono::Duration::minutes(10),
+      widget: "text".to_string(),
+      input: json!("short"),
+      recurrence: None,
+      uid: None,
+    },
+    ScheduledTask {
+      id: "later".to_string(),
+      time: now + chrono::Duration::days(2),
+      widget: "text".to_string(),
+      input: json!("this input is far too long to fit in the input column"),
+      recurrence: None,
+      uid: None,
+    },
+  ];
+
+  let lines = format_schedule_table(&tasks, now, false);
+  assert_eq!(lines.len(), 5); // header, separator, 2 rows, footer
+  assert!(lines.iter().all(|line| !line.contains('\x1b')), "Plain output should have no color codes");
+  assert!(lines[0].starts_with("ID "));
+  assert!(lines[2].ends_with("| \"short\""));
+  assert!(lines[3].ends_with("| \"this input is far too lo..."));
+}
+
+#[test]
+fn test_format_schedule_table_colors_header_and_imminent_tasks() {
+  let now = Utc::now();
+  let tasks = vec![
+    ScheduledTask {
+      id: "soon".to_string(),
+      time: now + chrono::Duration::minutes(10),
+      widget: "text".to_string(),
+      input: json!("hello"),
+      recurrence: None,
+      uid: None,
+    },
+    ScheduledTask {
+      id: "later".to_string(),
+      time: now + chrono::Duration::hours(3),
+      widget: "text".to_string(),
+      input: json!("hello"),
+      recurrence: None,
+      uid: None,
+    },
+  ];
+
+  let lines = format_schedule_table(&tasks, now, true);
+  assert!(lines[0].starts_with("\x1b[1;36m"));
+  assert!(lines[2].starts_with("\x1b[33msoon"));
+  assert!(lines[3].starts_with("later"));
+}
+
+fn create_task_at(id: &str, time: DateTime<Utc>) -> ScheduledTask {
+  ScheduledTask {
+    id: id.to_string(),
+    time,
+    widget: "weather".to_string(),
+    input: json!(null),
+    recurrence: None,
+    uid: None,
+  }
+}
+
+#[test]
+fn test_next_task_with_future_task() {
+  let now = Utc.with_ymd_and_hms(2025, 5, 4, 12, 0, 0).unwrap();
+  let schedule = Schedule {
+    tasks: vec![
+      create_task_at("past", now - chrono::Duration::hours(1)),
+      create_task_at("soon", now + chrono::Duration::seconds(3725)),
+      create_task_at("later", now + chrono::Duration::days(1)),
+    ],
+  };
+
+  let next = schedule.next_pending_task(now, &std::collections::HashSet::new());
+  assert_eq!(next.map(|t| t.id.as_str()), Some("soon"));
+
+  let message = format_next_task(&schedule, now);
+  assert!(message.starts_with("Next task: weather [soon] at "), "got: {}", message);
+  assert!(message.ends_with("(in 1:02:05)"), "got: {}", message);
+}
+
+#[test]
+fn test_next_task_all_past() {
+  let now = Utc.with_ymd_and_hms(2025, 5, 4, 12, 0, 0).unwrap();
+  let schedule = Schedule {
+    tasks: vec![
+      create_task_at("past1", now - chrono::Duration::hours(2)),
+      create_task_at("past2", now - chrono::Duration::minutes(1)),
+    ],
+  };
+
+  assert!(schedule
+    .next_pending_task(now, &std::collections::HashSet::new())
+    .is_none());
+  assert_eq!(format_next_task(&schedule, now), "No upcoming tasks in schedule.");
+}
+
+#[test]
+fn test_next_task_empty_schedule() {
+  let schedule = Schedule::default();
+  assert_eq!(format_next_task(&schedule, Utc::now()), "No upcoming tasks in schedule.");
+}
+
+fn create_range_tasks() -> Vec<ScheduledTask> {
+  vec![
+    create_task_at("may1", Utc.with_ymd_and_hms(2025, 5, 1, 9, 0, 0).unwrap()),
+    create_task_at("may3", Utc.with_ymd_and_hms(2025, 5, 3, 9, 0, 0).unwrap()),
+    create_task_at("may5", Utc.with_ymd_and_hms(2025, 5, 5, 9, 0, 0).unwrap()),
+    create_task_at("may7", Utc.with_ymd_and_hms(2025, 5, 7, 9, 0, 0).unwrap()),
+  ]
+}
+
+#[test]
+fn test_filter_tasks_by_inclusive_range() {
+  let tasks = create_range_tasks();
+  let from = Utc.with_ymd_and_hms(2025, 5, 3, 9, 0, 0).unwrap();
+  let to = Utc.with_ymd_and_hms(2025, 5, 5, 9, 0, 0).unwrap();
+
+  let filtered = filter_tasks_by_range(&tasks, Some(from), Some(to)).unwrap();
+  let ids: Vec<&str> = filtered.iter().map(|t| t.id.as_str()).collect();
+  assert_eq!(ids, vec!["may3", "may5"]);
+}
+
+#[test]
+fn test_filter_tasks_by_open_ended_range() {
+  let tasks = create_range_tasks();
+  let from = Utc.with_ymd_and_hms(2025, 5, 4, 0, 0, 0).unwrap();
+
+  let filtered = filter_tasks_by_range(&tasks, Some(from), None).unwrap();
+  assert_eq!(filtered.len(), 2);
+  assert_eq!(filter_tasks_by_range(&tasks, None, None).unwrap().len(), 4);
+}
+
+#[test]
+fn test_filter_tasks_by_range_with_no_matches() {
+  let tasks = create_range_tasks();
+  let from = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+  let to = Utc.with_ymd_and_hms(2025, 6, 30, 0, 0, 0).unwrap();
+
+  assert!(filter_tasks_by_range(&tasks, Some(from), Some(to)).unwrap().is_empty());
+}
+
+#[test]
+fn test_filter_tasks_by_range_rejects_from_after_to() {
+  let tasks = create_range_tasks();
+  let from = Utc.with_ymd_and_hms(2025, 5, 7, 0, 0, 0).unwrap();
+  let to = Utc.with_ymd_and_hms(2025, 5, 1, 0, 0, 0).unwrap();
+
+  let result = filter_tasks_by_range(&tasks, Some(from), Some(to));
+  assert!(matches!(result, Err(VestaboardError::ValidationError { .. })));
+}
+
+#[test]
+fn test_remove_before_drops_only_past_tasks() {
+  let cutoff = Utc.with_ymd_and_hms(2025, 5, 2, 12, 0, 0).unwrap();
+  let mut schedule = Schedule::default();
+  schedule.add_task(ScheduledTask::new(cutoff - chrono::Duration::days(1), "text".to_string(), json!("old")));
+  schedule.add_task(ScheduledTask::new(cutoff - chrono::Duration::minutes(1), "text".to_string(), json!("recent")));
+  schedule.add_task(ScheduledTask::new(cutoff, "text".to_string(), json!("at cutoff")));
+  schedule.add_task(ScheduledTask::new(cutoff + chrono::Duration::days(1), "text".to_string(), json!("future")));
+
+  assert_eq!(schedule.remove_before(cutoff), 2);
+
+  let remaining: Vec<&str> = schedule.tasks.iter().map(|t| t.input.as_str().unwrap()).collect();
+  assert_eq!(remaining, vec!["at cutoff", "future"]);
+}
+
+#[test]
+fn test_remove_before_keeps_recurring_tasks() {
+  let cutoff = Utc.with_ymd_and_hms(2025, 5, 2, 12, 0, 0).unwrap();
+  let mut schedule = Schedule::default();
+  let mut daily = ScheduledTask::new(cutoff - chrono::Duration::days(30), "weather".to_string(), json!(null));
+  daily.recurrence = Some(Recurrence::parse("daily").unwrap());
+  schedule.add_task(daily.clone());
+  schedule.add_task(ScheduledTask::new(cutoff - chrono::Duration::days(1), "text".to_string(), json!("old")));
+
+  assert_eq!(schedule.remove_before(cutoff), 1);
+  assert_eq!(schedule.tasks.len(), 1);
+  assert_eq!(schedule.tasks[0].id, daily.id);
+}
+
+#[test]
+fn test_remove_before_with_nothing_past() {
+  let cutoff = Utc.with_ymd_and_hms(2025, 5, 2, 12, 0, 0).unwrap();
+  let mut schedule = Schedule::default();
+  schedule.add_task(ScheduledTask::new(cutoff + chrono::Duration::hours(1), "clear".to_string(), json!(null)));
+
+  assert_eq!(schedule.remove_before(cutoff), 0);
+  assert_eq!(schedule.tasks.len(), 1);
+}
+
+#[test]
+fn test_dedupe_removes_exact_duplicates_keeping_first() {
+  let time = Utc.with_ymd_and_hms(2025, 5, 2, 12, 0, 0).unwrap();
+  let lunch = |id: &str| ScheduledTask {
+    id: id.to_string(),
+    ..ScheduledTask::new(time, "text".to_string(), json!("lunch"))
+  };
+  let mut schedule = Schedule::default();
+  // Same-time tasks are ordered by ID, so "b2" comes first whatever order they're added in
+  schedule.add_task(lunch("c3"));
+  schedule.add_task(lunch("b2"));
+  schedule.add_task(lunch("d4"));
+
+  assert_eq!(schedule.dedupe(chrono::Duration::zero()), 2);
+  assert_eq!(schedule.tasks.len(), 1);
+  assert_eq!(schedule.tasks[0].id, "b2");
+}
+
+#[test]
+fn test_dedupe_near_duplicates_inside_and_outside_window() {
+  let time = Utc.with_ymd_and_hms(2025, 5, 2, 12, 0, 0).unwrap();
+  let mut schedule = Schedule::default();
+  schedule.add_task(ScheduledTask::new(time, "weather".to_string(), json!(null)));
+  schedule.add_task(ScheduledTask::new(time + chrono::Duration::minutes(2), "weather".to_string(), json!(null)));
+  schedule.add_task(ScheduledTask::new(time + chrono::Duration::minutes(30), "weather".to_string(), json!(null)));
+
+  // Exact matching leaves near-duplicates alone
+  assert_eq!(schedule.clone().dedupe(chrono::Duration::zero()), 0);
+
+  assert_eq!(schedule.dedupe(chrono::Duration::minutes(5)), 1);
+  let times: Vec<_> = schedule.tasks.iter().map(|t| t.time).collect();
+  assert_eq!(times, vec![time, time + chrono::Duration::minutes(30)]);
+}
+
+#[test]
+fn test_dedupe_leaves_distinct_tasks_intact() {
+  let time = Utc.with_ymd_and_hms(2025, 5, 2, 12, 0, 0).unwrap();
+  let mut schedule = Schedule::default();
+  schedule.add_task(ScheduledTask::new(time, "text".to_string(), json!("lunch")));
+  schedule.add_task(ScheduledTask::new(time, "text".to_string(), json!("dinner")));
+  schedule.add_task(ScheduledTask::new(time, "weather".to_string(), json!(null)));
+  schedule.add_task(
+    ScheduledTask::new(time, "text".to_string(), json!("lunch")).with_recurrence(Recurrence::parse("daily").unwrap()),
+  );
+
+  assert_eq!(schedule.dedupe(chrono::Duration::hours(1)), 0);
+  assert_eq!(schedule.tasks.len(), 4);
+}
+
+/// A schedule around `now`: one task an hour ago and four upcoming, added out of order
+fn preview_schedule_around(now: DateTime<Utc>) -> Schedule {
+  let mut schedule = Schedule::default();
+  for (hours, text) in [(3, "third"), (-1, "past"), (1, "first"), (4, "fourth"), (2, "second")] {
+    schedule.add_task(ScheduledTask::new(now + chrono::Duration::hours(hours), "text".to_string(), json!(text)));
+  }
+  schedule
+}
+
+#[test]
+fn test_next_pending_tasks_are_upcoming_and_in_order() {
+  let now = Utc.with_ymd_and_hms(2030, 5, 1, 7, 0, 0).unwrap();
+  let schedule = preview_schedule_around(now);
+
+  let inputs = |count| {
+    schedule
+      .next_pending_tasks(now, &std::collections::HashSet::new(), count)
+      .iter()
+      .map(|task| task.input.clone())
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(inputs(3), vec![json!("first"), json!("second"), json!("third")]);
+  assert_eq!(inputs(10).len(), 4);
+}
+
+#[tokio::test]
+async fn test_preview_next_tasks_renders_at_most_count() {
+  use crate::api::{MockTransport, Transport};
+
+  let mock = MockTransport::new();
+  let transport = Transport::Mock(mock.clone());
+  let now = Utc.with_ymd_and_hms(2030, 5, 1, 7, 0, 0).unwrap();
+  let schedule = preview_schedule_around(now);
+
+  assert_eq!(preview_next_tasks(&schedule, 3, now, &transport, &Config::default()).await, 3);
+  // Only four tasks are upcoming; the one an hour ago is skipped
+  assert_eq!(preview_next_tasks(&schedule, 10, now, &transport, &Config::default()).await, 4);
+  assert_eq!(preview_next_tasks(&Schedule::default(), 3, now, &transport, &Config::default()).await, 0);
+  assert!(mock.sent_codes().is_empty());
+}
+
+fn local_time(day: u32, hour: u32) -> DateTime<Utc> {
+  Local
+    .with_ymd_and_hms(2030, 5, day, hour, 0, 0)
+    .unwrap()
+    .with_timezone(&Utc)
+}
+
+#[test]
+fn test_simulate_daily_task_fires_once_a_day() {
+  let schedule = Schedule {
+    tasks: vec![ScheduledTask {
+      id: "day1".to_string(),
+      time: local_time(1, 8),
+      widget: "weather".to_string(),
+      input: json!(null),
+      recurrence: Some(Recurrence::parse("daily").unwrap()),
+      uid: None,
+    }],
+  };
+
+  let fires = simulate_schedule(&schedule, local_time(1, 0), local_time(3, 23));
+
+  let times: Vec<_> = fires.iter().map(|(time, _)| *time).collect();
+  assert_eq!(times, vec![local_time(1, 8), local_time(2, 8), local_time(3, 8)]);
+  assert!(fires.iter().all(|(_, task)| task.id == "day1"));
+}
+
+#[test]
+fn test_simulate_starts_repeating_tasks_at_the_range() {
+  let schedule = Schedule {
+    tasks: vec![
+      ScheduledTask {
+        id: "rpt1".to_string(),
+        time: local_time(1, 8),
+        widget: "weather".to_string(),
+        input: json!(null),
+        recurrence: Some(Recurrence::parse("daily").unwrap()),
+        uid: None,
+      },
+      ScheduledTask {
+        id: "once".to_string(),
+        time: local_time(5, 8),
+        widget: "text".to_string(),
+        input: json!("hello"),
+        recurrence: None,
+        uid: None,
+      },
+      ScheduledTask {
+        id: "late".to_string(),
+        time: local_time(9, 8),
+        widget: "text".to_string(),
+        input: json!("later"),
+        recurrence: None,
+        uid: None,
+      },
+    ],
+  };
+
+  let fires = simulate_schedule(&schedule, local_time(5, 0), local_time(6, 12));
+
+  let summary: Vec<_> = fires.iter().map(|(time, task)| (*time, task.id.as_str())).collect();
+  assert_eq!(
+    summary,
+    vec![
+      (local_time(5, 8), "once"),
+      (local_time(5, 8), "rpt1"),
+      (local_time(6, 8), "rpt1"),
+    ]
+  );
+}
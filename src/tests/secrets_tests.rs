@@ -0,0 +1,70 @@
+//! Tests for reading secrets from environment variables and `_FILE` variants.
+
+use crate::secrets::secret;
+use serial_test::serial;
+use std::env;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+const NAME: &str = "VBL_TEST_SECRET";
+const FILE_NAME: &str = "VBL_TEST_SECRET_FILE";
+
+fn clear_vars() {
+  env::remove_var(NAME);
+  env::remove_var(FILE_NAME);
+}
+
+fn secret_file(contents: &str) -> NamedTempFile {
+  let mut file = NamedTempFile::new().unwrap();
+  file.write_all(contents.as_bytes()).unwrap();
+  file
+}
+
+#[test]
+#[serial]
+fn test_secret_reads_env_var() {
+  clear_vars();
+  env::set_var(NAME, "from-env");
+  assert_eq!(secret(NAME).as_deref(), Some("from-env"));
+  clear_vars();
+}
+
+#[test]
+#[serial]
+fn test_secret_reads_trimmed_file_when_var_missing() {
+  clear_vars();
+  let file = secret_file("  from-file\n");
+  env::set_var(FILE_NAME, file.path());
+  assert_eq!(secret(NAME).as_deref(), Some("from-file"));
+
+  // An empty direct variable counts as missing too
+  env::set_var(NAME, "");
+  assert_eq!(secret(NAME).as_deref(), Some("from-file"));
+  clear_vars();
+}
+
+#[test]
+#[serial]
+fn test_secret_prefers_env_var_over_file() {
+  clear_vars();
+  let file = secret_file("from-file");
+  env::set_var(NAME, "from-env");
+  env::set_var(FILE_NAME, file.path());
+  assert_eq!(secret(NAME).as_deref(), Some("from-env"));
+  clear_vars();
+}
+
+#[test]
+#[serial]
+fn test_secret_missing_or_unreadable_is_none() {
+  clear_vars();
+  assert_eq!(secret(NAME), None);
+
+  env::set_var(FILE_NAME, "/nonexistent/vbl-secret");
+  assert_eq!(secret(NAME), None);
+
+  let blank = secret_file(" \n");
+  env::set_var(FILE_NAME, blank.path());
+  assert_eq!(secret(NAME), None);
+  clear_vars();
+}
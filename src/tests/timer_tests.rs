@@ -0,0 +1,106 @@
+//! Tests for the countdown timer frames.
+
+use std::time::Duration;
+
+use crate::runner::timer::{
+  format_minutes_left, minutes_remaining, parse_timer_duration, timer_frame, validate_timer_label,
+};
+
+#[test]
+fn test_parse_timer_duration_units() {
+  assert_eq!(parse_timer_duration("25m").unwrap(), Duration::from_secs(25 * 60));
+  assert_eq!(parse_timer_duration("90s").unwrap(), Duration::from_secs(90));
+  assert_eq!(parse_timer_duration("1h").unwrap(), Duration::from_secs(3600));
+  assert_eq!(parse_timer_duration("1h30m").unwrap(), Duration::from_secs(5400));
+  assert_eq!(parse_timer_duration("10").unwrap(), Duration::from_secs(600));
+}
+
+#[test]
+fn test_parse_timer_duration_rejects_invalid() {
+  for text in ["", "0", "0m", "m", "25x", "25m5", "abc"] {
+    assert!(parse_timer_duration(text).is_err(), "expected '{}' to be rejected", text);
+  }
+}
+
+#[test]
+fn test_parse_timer_duration_rejects_lengths_that_overflow() {
+  for text in [
+    "18446744073709551615",
+    "9999999999999999h",
+    "999999999999999999m",
+    "18446744073709551615s1s",
+    "18446744073709551615s",
+    "9223372036854775808s",
+    "527041m",
+  ] {
+    let error = parse_timer_duration(text).unwrap_err();
+    assert!(error.to_string().contains("Invalid timer length"), "unexpected error for '{}': {}", text, error);
+  }
+}
+
+#[test]
+fn test_minutes_remaining_rounds_up() {
+  assert_eq!(minutes_remaining(Duration::ZERO), 0);
+  assert_eq!(minutes_remaining(Duration::from_secs(1)), 1);
+  assert_eq!(minutes_remaining(Duration::from_secs(60)), 1);
+  assert_eq!(minutes_remaining(Duration::from_secs(61)), 2);
+}
+
+#[test]
+fn test_format_minutes_left() {
+  assert_eq!(format_minutes_left(1), "1 min left");
+  assert_eq!(format_minutes_left(25), "25 min left");
+  assert_eq!(format_minutes_left(65), "1 hr 05 min left");
+}
+
+#[test]
+fn test_timer_frame_counting_down() {
+  let frame = timer_frame(Duration::from_secs(25 * 60), "Break Time");
+  assert_eq!(frame.len(), 6);
+  assert_eq!(frame[1], "      break time      ");
+  assert_eq!(frame[2], "");
+  assert_eq!(frame[3], "     25 min left      ");
+}
+
+#[test]
+fn test_timer_frame_last_minute() {
+  let frame = timer_frame(Duration::from_secs(30), "break time");
+  assert!(frame.iter().any(|line| line.trim() == "1 min left"));
+}
+
+#[test]
+fn test_timer_frame_over_an_hour() {
+  let frame = timer_frame(Duration::from_secs(90 * 60), "break time");
+  assert!(frame.iter().any(|line| line.trim() == "1 hr 30 min left"));
+}
+
+#[test]
+fn test_timer_frame_at_zero_shows_label() {
+  let frame = timer_frame(Duration::ZERO, "break time");
+  let text: Vec<&str> = frame
+    .iter()
+    .map(|line| line.trim())
+    .filter(|line| !line.is_empty())
+    .collect();
+  assert_eq!(text, vec!["break time"]);
+  assert!(crate::api_broker::validate_message_content(&frame).is_ok());
+}
+
+#[test]
+fn test_timer_frame_wraps_long_label() {
+  let frame = timer_frame(Duration::from_secs(25 * 60), "stand up and stretch your legs");
+  let text: Vec<&str> = frame
+    .iter()
+    .map(|line| line.trim())
+    .filter(|line| !line.is_empty())
+    .collect();
+  assert_eq!(text, vec!["stand up and stretch", "your legs", "25 min left"]);
+  assert!(crate::api_broker::validate_message_content(&frame).is_ok());
+}
+
+#[test]
+fn test_validate_timer_label_rejects_labels_that_do_not_fit() {
+  assert!(validate_timer_label("break time").is_ok());
+  assert!(validate_timer_label(&"word ".repeat(16)).is_ok());
+  assert!(validate_timer_label(&"word ".repeat(17)).is_err());
+}
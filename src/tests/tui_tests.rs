@@ -0,0 +1,63 @@
+//! Tests for the playlist dashboard formatting.
+
+use std::time::Duration;
+
+use crate::datetime::format_countdown;
+use crate::runner::tui::{render_dashboard, DashboardView};
+use crate::runtime_state::PlaylistState;
+
+fn create_view() -> DashboardView {
+  DashboardView {
+    state: PlaylistState::Running,
+    current: Some("weather [abc1]".to_string()),
+    next: Some("text [def2]".to_string()),
+    next_index: 1,
+    total: 3,
+    remaining: Some(Duration::from_secs(245)),
+    dry_run: false,
+  }
+}
+
+#[test]
+fn test_format_countdown_minutes_and_seconds() {
+  assert_eq!(format_countdown(Duration::from_secs(0)), "0:00");
+  assert_eq!(format_countdown(Duration::from_secs(9)), "0:09");
+  assert_eq!(format_countdown(Duration::from_secs(245)), "4:05");
+}
+
+#[test]
+fn test_format_countdown_hours() {
+  assert_eq!(format_countdown(Duration::from_secs(3600)), "1:00:00");
+  assert_eq!(format_countdown(Duration::from_secs(3725)), "1:02:05");
+}
+
+#[test]
+fn test_render_dashboard_running() {
+  let lines = render_dashboard(&create_view());
+  assert_eq!(lines[0], "Vestaboard Playlist (live mode)");
+  assert!(lines.contains(&"State:    Running".to_string()));
+  assert!(lines.contains(&"Showing:  weather [abc1]".to_string()));
+  assert!(lines.contains(&"Next:     text [def2] (2/3)".to_string()));
+  assert!(lines.contains(&"Next in:  4:05".to_string()));
+}
+
+#[test]
+fn test_render_dashboard_paused_hides_countdown() {
+  let mut view = create_view();
+  view.state = PlaylistState::Paused;
+  let lines = render_dashboard(&view);
+  assert!(lines.contains(&"State:    Paused".to_string()));
+  assert!(lines.contains(&"Next in:  paused".to_string()));
+}
+
+#[test]
+fn test_render_dashboard_before_first_display() {
+  let mut view = create_view();
+  view.current = None;
+  view.remaining = None;
+  view.dry_run = true;
+  let lines = render_dashboard(&view);
+  assert_eq!(lines[0], "Vestaboard Playlist (preview mode)");
+  assert!(lines.contains(&"Showing:  -".to_string()));
+  assert!(lines.contains(&"Next in:  now".to_string()));
+}
@@ -0,0 +1,135 @@
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::api::common::{read_checked, send_with_retry, MAX_RETRY_AFTER};
+use crate::api::retry::NETWORK_RETRY;
+use crate::api_broker::is_valid_character;
+use crate::cli_display::print_error;
+use crate::errors::VestaboardError;
+use crate::secrets::secret;
+use crate::widgets::widget_utils::{center_line, center_message, WidgetOutput, MAX_MESSAGE_HEIGHT, MAX_MESSAGE_LENGTH};
+
+// reference: https://www.weatherapi.com/docs/#apis-realtime (aqi=yes)
+
+#[derive(Deserialize, Debug)]
+struct AirQualityResponse {
+  location: Location,
+  current: Current,
+}
+
+#[derive(Deserialize, Debug)]
+struct Location {
+  name: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Current {
+  air_quality: AirQuality,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AirQuality {
+  #[allow(dead_code)]
+  pub co: f64,
+  #[allow(dead_code)]
+  pub no2: f64,
+  #[allow(dead_code)]
+  pub o3: f64,
+  #[allow(dead_code)]
+  pub so2: f64,
+  pub pm2_5: f64,
+  pub pm10: f64,
+  #[serde(rename = "us-epa-index")]
+  pub us_epa_index: u8,
+  #[allow(dead_code)]
+  #[serde(rename = "gb-defra-index")]
+  pub gb_defra_index: u8,
+}
+
+/// Map a US EPA index (1-6) to a category word and a Vestaboard color tile.
+pub fn epa_category(index: u8) -> Option<(&'static str, char)> {
+  match index {
+    1 => Some(("good", 'G')),
+    2 => Some(("moderate", 'Y')),
+    3 => Some(("unhealthy for some", 'O')),
+    4 => Some(("unhealthy", 'R')),
+    5 => Some(("very unhealthy", 'V')),
+    6 => Some(("hazardous", 'K')),
+    _ => None,
+  }
+}
+
+/// Header row naming the location, dropping characters the board can't show and cutting
+/// the name short when it won't fit beside "air quality".
+fn air_quality_header(location: &str) -> String {
+  let header = "air quality";
+  let name = location
+    .to_lowercase()
+    .chars()
+    .filter(|&c| is_valid_character(c))
+    .collect::<String>();
+  let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+  let room = MAX_MESSAGE_LENGTH - header.len() - 1;
+  let name: String = name.chars().take(room).collect();
+  match name.trim_end() {
+    "" => header.to_string(),
+    name => format!("{} {}", header, name),
+  }
+}
+
+/// Render a weatherapi.com response with an `air_quality` block into board lines.
+pub fn format_air_quality(response_text: &str) -> Result<WidgetOutput, VestaboardError> {
+  let json: AirQualityResponse = serde_json::from_str(response_text).map_err(|e| {
+    log::error!("Failed to parse air quality JSON response: {}", e);
+    VestaboardError::json_error(e, "parsing air quality API response")
+  })?;
+  let aqi = json.current.air_quality;
+
+  let category = match epa_category(aqi.us_epa_index) {
+    Some((word, tile)) => format!("{} {} {}", tile, word, tile),
+    None => "unknown".to_string(),
+  };
+
+  let lines = vec![
+    center_line(air_quality_header(&json.location.name)),
+    String::new(),
+    center_line(category),
+    center_line(format!("epa index {}", aqi.us_epa_index)),
+    center_line(format!("pm2.5 {:.1} pm10 {:.1}", aqi.pm2_5, aqi.pm10)),
+  ];
+  Ok(center_message(lines, MAX_MESSAGE_HEIGHT))
+}
+
+pub async fn get_air_quality(location: &str) -> Result<WidgetOutput, VestaboardError> {
+  let start_time = std::time::Instant::now();
+  log::info!("Air quality widget starting for {}", location);
+
+  let weather_api_key = secret("WEATHER_API_KEY").ok_or_else(|| {
+    log::error!("WEATHER_API_KEY environment variable not set");
+    VestaboardError::config_error("WEATHER_API_KEY", "Environment variable not set")
+  })?;
+
+  let client = Client::new();
+  let url = "https://api.weatherapi.com/v1/current.json";
+  let query = [("key", weather_api_key.as_str()), ("q", location), ("aqi", "yes")];
+
+  log::debug!("Making air quality API request for {}", location);
+
+  let response = send_with_retry(|| client.get(url).query(&query), &NETWORK_RETRY, MAX_RETRY_AFTER)
+    .await
+    .map_err(|e| {
+      log::error!("Air quality API request failed: {}", e);
+      let error = VestaboardError::reqwest_error(e, "requesting weather air quality");
+      print_error(&error.to_user_message());
+      error
+    })?;
+
+  let response_text = read_checked(response, "Weather API")
+    .await
+    .inspect_err(|e| print_error(&e.to_user_message()))?;
+
+  let lines = format_air_quality(&response_text).inspect_err(|e| print_error(&e.to_user_message()))?;
+
+  log::info!("Air quality widget completed successfully in {:?}", start_time.elapsed());
+  Ok(lines)
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+  use crate::widgets::airquality::airquality::{epa_category, format_air_quality};
+
+  fn sample_payload(index: u8) -> String {
+    payload_for("Austin", index)
+  }
+
+  fn payload_for(name: &str, index: u8) -> String {
+    format!(
+      r#"{{
+        "location": {{ "name": "{}", "region": "Texas", "country": "USA" }},
+        "current": {{
+          "temp_f": 81.0,
+          "air_quality": {{
+            "co": 223.6, "no2": 6.5, "o3": 82.3, "so2": 1.2,
+            "pm2_5": 12.4, "pm10": 18.05,
+            "us-epa-index": {}, "gb-defra-index": 2
+          }}
+        }}
+      }}"#,
+      name, index
+    )
+  }
+
+  #[test]
+  fn test_epa_category_mapping() {
+    assert_eq!(epa_category(1), Some(("good", 'G')));
+    assert_eq!(epa_category(2), Some(("moderate", 'Y')));
+    assert_eq!(epa_category(3), Some(("unhealthy for some", 'O')));
+    assert_eq!(epa_category(4), Some(("unhealthy", 'R')));
+    assert_eq!(epa_category(5), Some(("very unhealthy", 'V')));
+    assert_eq!(epa_category(6), Some(("hazardous", 'K')));
+    assert_eq!(epa_category(0), None);
+    assert_eq!(epa_category(7), None);
+  }
+
+  #[test]
+  fn test_format_air_quality_sample_payload() {
+    let lines = format_air_quality(&sample_payload(2)).unwrap();
+
+    let trimmed: Vec<&str> = lines.iter().map(|l| l.trim()).collect();
+    assert_eq!(
+      trimmed,
+      vec![
+        "air quality austin",
+        "",
+        "Y moderate Y",
+        "epa index 2",
+        "pm2.5 12.4 pm10 18.1",
+        ""
+      ]
+    );
+    assert!(lines.iter().all(|l| l.chars().count() <= 22));
+    assert!(crate::api_broker::validate_message_content(&lines).is_ok());
+  }
+
+  #[test]
+  fn test_format_air_quality_long_accented_location_fits() {
+    let lines = format_air_quality(&payload_for("São José dos Campos", 1)).unwrap();
+
+    assert_eq!(lines[0].trim(), "air quality so jos dos");
+    assert!(lines.iter().all(|l| l.chars().count() <= 22));
+    assert!(crate::api_broker::validate_message_content(&lines).is_ok());
+  }
+
+  #[test]
+  fn test_format_air_quality_unknown_index() {
+    let lines = format_air_quality(&sample_payload(9)).unwrap();
+    assert!(lines.iter().any(|l| l.trim() == "unknown"));
+  }
+
+  #[test]
+  fn test_format_air_quality_missing_block_is_json_error() {
+    let result = format_air_quality(r#"{"location": {"name": "Austin"}, "current": {}}"#);
+    assert!(result.is_err());
+  }
+}
@@ -0,0 +1,5 @@
+pub mod airquality;
+pub use airquality::get_air_quality;
+
+#[cfg(test)]
+pub mod airquality_tests;
@@ -0,0 +1,114 @@
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::api::common::{read_checked, send_with_retry, MAX_RETRY_AFTER};
+use crate::api::retry::NETWORK_RETRY;
+use crate::cli_display::print_error;
+use crate::errors::VestaboardError;
+use crate::secrets::secret;
+use crate::widgets::widget_utils::{center_line, center_message, full_justify_line, WidgetOutput, MAX_MESSAGE_HEIGHT};
+
+// reference: https://www.exchangerate-api.com/docs/pair-conversion-requests
+
+/// Pair conversion response. The provider reports failures as `"result": "error"`.
+#[derive(Deserialize, Debug)]
+struct RateResponse {
+  result: String,
+  #[serde(rename = "error-type")]
+  error_type: Option<String>,
+  conversion_rate: Option<f64>,
+}
+
+/// Read and validate the `{"from": "USD", "to": "EUR"}` input, returning uppercase codes.
+pub fn parse_pair(input: &Value) -> Result<(String, String), VestaboardError> {
+  let code = |field: &str| {
+    input
+      .get(field)
+      .and_then(Value::as_str)
+      .map(|s| s.trim().to_uppercase())
+      .filter(|s| s.len() == 3 && s.chars().all(|c| c.is_ascii_alphabetic()))
+      .ok_or_else(|| {
+        VestaboardError::validation_error(&format!(
+          "Forex widget needs a 3-letter '{}' currency code, e.g. {{\"from\": \"USD\", \"to\": \"EUR\"}}",
+          field
+        ))
+      })
+  };
+  Ok((code("from")?, code("to")?))
+}
+
+/// Format a rate with 4 decimals for small values and 2 for large ones.
+pub fn format_rate(rate: f64) -> String {
+  if rate < 10.0 {
+    format!("{:.4}", rate)
+  } else {
+    format!("{:.2}", rate)
+  }
+}
+
+/// Render a provider response for a currency pair into board lines.
+pub fn render_rate(response_text: &str, from: &str, to: &str) -> Result<WidgetOutput, VestaboardError> {
+  let response: RateResponse = serde_json::from_str(response_text).map_err(|e| {
+    log::error!("Failed to parse exchange rate JSON response: {}", e);
+    VestaboardError::json_error(e, "parsing exchange rate API response")
+  })?;
+
+  let rate = match (response.result.as_str(), response.conversion_rate) {
+    ("success", Some(rate)) => rate,
+    _ => {
+      let reason = response.error_type.unwrap_or_else(|| "no rate returned".to_string());
+      log::error!("Exchange rate API error for {}/{}: {}", from, to, reason);
+      return Err(VestaboardError::api_error(None, &format!("Exchange rate error: {}", reason)));
+    },
+  };
+
+  let (from, to) = (from.to_lowercase(), to.to_lowercase());
+  let lines = vec![
+    center_line("exchange rate".to_string()),
+    String::new(),
+    full_justify_line(format!("1 {} =", from), format!("{} {}", format_rate(rate), to)),
+  ];
+  Ok(center_message(lines, MAX_MESSAGE_HEIGHT))
+}
+
+/// Fill the provider URL template's `{key}`, `{from}` and `{to}` placeholders.
+pub fn build_url(template: &str, key: &str, from: &str, to: &str) -> String {
+  template
+    .replace("{key}", key)
+    .replace("{from}", from)
+    .replace("{to}", to)
+}
+
+pub async fn get_forex(input: &Value, url_template: &str) -> Result<WidgetOutput, VestaboardError> {
+  let start_time = std::time::Instant::now();
+  let (from, to) = parse_pair(input)?;
+  log::info!("Forex widget starting for {}/{}", from, to);
+
+  let api_key = secret("FOREX_API_KEY").ok_or_else(|| {
+    log::error!("FOREX_API_KEY environment variable not set");
+    VestaboardError::config_error("FOREX_API_KEY", "Environment variable not set")
+  })?;
+
+  let client = Client::new();
+  let url = build_url(url_template, &api_key, &from, &to);
+  log::debug!("Making exchange rate request to: {}", url.replace(&api_key, "***")); // Hide API key in logs
+
+  let response = send_with_retry(|| client.get(&url), &NETWORK_RETRY, MAX_RETRY_AFTER)
+    .await
+    .map_err(|e| {
+      log::error!("Exchange rate request failed: {}", e);
+      let error = VestaboardError::reqwest_error(e, "requesting exchange rate");
+      print_error(&error.to_user_message());
+      error
+    })?;
+
+  let response_text = read_checked(response, "Exchange rate API")
+    .await
+    .inspect_err(|e| print_error(&e.to_user_message()))?;
+
+  let lines = render_rate(&response_text, &from, &to).inspect_err(|e| print_error(&e.to_user_message()))?;
+
+  log::info!("Forex widget completed successfully in {:?}", start_time.elapsed());
+  Ok(lines)
+}
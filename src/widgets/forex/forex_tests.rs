@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+  use crate::errors::VestaboardError;
+  use crate::widgets::forex::forex::{build_url, format_rate, parse_pair, render_rate};
+  use serde_json::json;
+
+  #[test]
+  fn test_parse_pair_normalizes_codes() {
+    assert_eq!(parse_pair(&json!({"from": "usd", "to": " EUR "})).unwrap(), ("USD".to_string(), "EUR".to_string()));
+  }
+
+  #[test]
+  fn test_parse_pair_rejects_bad_input() {
+    assert!(parse_pair(&json!(null)).is_err());
+    assert!(parse_pair(&json!({"from": "USD"})).is_err());
+    assert!(parse_pair(&json!({"from": "US", "to": "EUR"})).is_err());
+    assert!(parse_pair(&json!({"from": "US1", "to": "EUR"})).is_err());
+  }
+
+  #[test]
+  fn test_format_rate_precision() {
+    assert_eq!(format_rate(0.9213), "0.9213");
+    assert_eq!(format_rate(151.4567), "151.46");
+  }
+
+  #[test]
+  fn test_render_rate_sample_response() {
+    let body = r#"{"result": "success", "base_code": "USD", "target_code": "EUR", "conversion_rate": 0.92}"#;
+    let lines = render_rate(body, "USD", "EUR").unwrap();
+
+    assert_eq!(lines.len(), 6);
+    assert!(lines.iter().any(|l| l.trim() == "exchange rate"));
+    assert!(lines.contains(&"1 usd =     0.9200 eur".to_string()));
+    assert!(crate::api_broker::validate_message_content(&lines).is_ok());
+  }
+
+  #[test]
+  fn test_render_rate_error_response() {
+    let body = r#"{"result": "error", "error-type": "unsupported-code"}"#;
+    match render_rate(body, "USD", "XYZ") {
+      Err(VestaboardError::ApiError { code, message }) => {
+        assert_eq!(code, None);
+        assert_eq!(message, "Exchange rate error: unsupported-code");
+      },
+      other => panic!("Expected ApiError, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_render_rate_malformed_response() {
+    assert!(matches!(render_rate("not json", "USD", "EUR"), Err(VestaboardError::JsonError { .. })));
+  }
+
+  #[test]
+  fn test_build_url_fills_placeholders() {
+    assert_eq!(
+      build_url("https://example.com/{key}/pair/{from}/{to}", "k", "USD", "EUR"),
+      "https://example.com/k/pair/USD/EUR"
+    );
+  }
+}
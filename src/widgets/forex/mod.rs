@@ -0,0 +1,5 @@
+pub mod forex;
+pub use forex::{get_forex, parse_pair};
+
+#[cfg(test)]
+pub mod forex_tests;
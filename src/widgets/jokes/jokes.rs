@@ -1,12 +1,12 @@
 use crate::errors::VestaboardError;
-use crate::widgets::widget_utils;
+use crate::widgets::widget_utils::{self, BoardSize};
 
-pub fn get_joke() -> Result<Vec<String>, VestaboardError> {
+pub fn get_joke(size: BoardSize) -> Result<Vec<String>, VestaboardError> {
   log::debug!("Jokes widget starting");
   let joke = "what did the janitor say when he jumped out of the closet? \"supplies!\"";
   log::info!("Selected joke: {}", joke);
   // Widget just formats the message - validation happens at the main level
-  let formatted = widget_utils::format_message(joke);
+  let formatted = widget_utils::format_message_for(joke, size);
   log::debug!("Jokes widget completed successfully, {} lines generated", formatted.len());
   Ok(formatted)
 }
@@ -1,10 +1,11 @@
 #[cfg(test)]
 mod tests {
   use crate::widgets::jokes::get_joke;
+  use crate::widgets::widget_utils::STANDARD_BOARD;
 
   #[test]
   fn test_get_joke_success() {
-    let result = get_joke();
+    let result = get_joke(STANDARD_BOARD);
     assert!(result.is_ok(), "get_joke should return Ok result");
 
     let lines = result.unwrap();
@@ -21,8 +22,8 @@ mod tests {
   #[test]
   fn test_get_joke_consistency() {
     // The joke should be consistent across calls
-    let result1 = get_joke();
-    let result2 = get_joke();
+    let result1 = get_joke(STANDARD_BOARD);
+    let result2 = get_joke(STANDARD_BOARD);
 
     assert!(result1.is_ok(), "First call should succeed");
     assert!(result2.is_ok(), "Second call should succeed");
@@ -35,7 +36,7 @@ mod tests {
 
   #[test]
   fn test_get_joke_format() {
-    let result = get_joke();
+    let result = get_joke(STANDARD_BOARD);
     assert!(result.is_ok(), "get_joke should return Ok result");
 
     let lines = result.unwrap();
@@ -0,0 +1,62 @@
+use serde_json::Value;
+
+use crate::errors::VestaboardError;
+use crate::widgets::widget_utils::{center_message, full_justify_line_to_width, BoardSize, WidgetOutput};
+
+/// A row's label or value as text; numbers are allowed so `72` works as well as `"72"`.
+fn cell_text(cell: &Value) -> Option<String> {
+  match cell {
+    Value::String(s) => Some(s.trim().to_lowercase()),
+    Value::Number(n) => Some(n.to_string()),
+    _ => None,
+  }
+}
+
+/// Read and validate the `{"rows": [["temp", "72f"], ["humidity", "40%"]]}` input.
+///
+/// There must be 1 to `size.rows` rows, each a `[label, value]` pair that fits on one line.
+pub fn parse_rows(input: &Value, size: BoardSize) -> Result<Vec<(String, String)>, VestaboardError> {
+  let rows = input.get("rows").and_then(Value::as_array).ok_or_else(|| {
+    VestaboardError::validation_error(r#"kv widget needs rows, e.g. {"rows": [["temp", "72f"], ["humidity", "40%"]]}"#)
+  })?;
+  if rows.is_empty() || rows.len() > size.rows {
+    return Err(VestaboardError::validation_error(&format!(
+      "kv widget takes 1 to {} rows, got {}",
+      size.rows,
+      rows.len()
+    )));
+  }
+
+  rows
+    .iter()
+    .enumerate()
+    .map(|(index, row)| {
+      let pair = match row.as_array().map(Vec::as_slice) {
+        Some([label, value]) => cell_text(label).zip(cell_text(value)),
+        _ => None,
+      };
+      let (label, value) = pair.ok_or_else(|| {
+        VestaboardError::validation_error(&format!("kv row {} must be a [label, value] pair", index + 1))
+      })?;
+      let width = label.chars().count() + value.chars().count();
+      if width > size.cols {
+        return Err(VestaboardError::validation_error(&format!(
+          "kv row {} is {} characters, more than fit on a line ({})",
+          index + 1,
+          width,
+          size.cols
+        )));
+      }
+      Ok((label, value))
+    })
+    .collect()
+}
+
+/// Render each row as `label ...... value`, centered vertically on a board of `size`.
+pub fn get_kv(input: &Value, size: BoardSize) -> Result<WidgetOutput, VestaboardError> {
+  let lines = parse_rows(input, size)?
+    .into_iter()
+    .map(|(label, value)| full_justify_line_to_width(label, value, size.cols))
+    .collect();
+  Ok(center_message(lines, size.rows))
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+  use crate::widgets::kv::kv::{get_kv, parse_rows};
+  use crate::widgets::widget_utils::{BoardSize, STANDARD_BOARD};
+  use serde_json::json;
+
+  #[test]
+  fn test_get_kv_renders_rows() {
+    let lines = get_kv(&json!({"rows": [["temp", "72F"], ["humidity", "40%"]]}), STANDARD_BOARD).unwrap();
+
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[2], "temp               72f");
+    assert_eq!(lines[3], "humidity           40%");
+    assert!(lines.iter().all(|line| line.chars().count() <= 22));
+  }
+
+  #[test]
+  fn test_get_kv_accepts_numbers() {
+    let rows = parse_rows(&json!({"rows": [["count", 3]]}), STANDARD_BOARD).unwrap();
+    assert_eq!(rows, vec![("count".to_string(), "3".to_string())]);
+  }
+
+  #[test]
+  fn test_get_kv_exact_width_row_sits_flush() {
+    let lines = get_kv(&json!({"rows": [["abcdefghijk", "lmnopqrstuv"]]}), STANDARD_BOARD).unwrap();
+    assert!(lines.contains(&"abcdefghijklmnopqrstuv".to_string()));
+  }
+
+  #[test]
+  fn test_parse_rows_rejects_row_too_wide() {
+    assert!(parse_rows(&json!({"rows": [["abcdefghijkl", "lmnopqrstuv"]]}), STANDARD_BOARD).is_err());
+  }
+
+  #[test]
+  fn test_parse_rows_rejects_too_many_rows() {
+    let rows: Vec<_> = (0..7).map(|i| json!([format!("row {}", i), "x"])).collect();
+    assert!(parse_rows(&json!({ "rows": rows }), STANDARD_BOARD).is_err());
+  }
+
+  #[test]
+  fn test_parse_rows_checks_against_board_size() {
+    let small = BoardSize { cols: 10, rows: 2 };
+    assert!(parse_rows(&json!({"rows": [["temp", "72f"], ["wind", "5"]]}), small).is_ok());
+    assert!(parse_rows(&json!({"rows": [["a", "1"], ["b", "2"], ["c", "3"]]}), small).is_err());
+    assert!(parse_rows(&json!({"rows": [["humidity", "40%"]]}), small).is_err());
+  }
+
+  #[test]
+  fn test_parse_rows_rejects_bad_input() {
+    assert!(parse_rows(&json!(null), STANDARD_BOARD).is_err());
+    assert!(parse_rows(&json!({"rows": []}), STANDARD_BOARD).is_err());
+    assert!(parse_rows(&json!({"rows": [["label"]]}), STANDARD_BOARD).is_err());
+    assert!(parse_rows(&json!({"rows": [["label", null]]}), STANDARD_BOARD).is_err());
+  }
+}
@@ -0,0 +1,5 @@
+pub mod kv;
+pub use kv::{get_kv, parse_rows};
+
+#[cfg(test)]
+pub mod kv_tests;
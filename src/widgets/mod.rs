@@ -1,4 +1,9 @@
+pub mod airquality;
+pub mod forex;
 pub mod jokes;
+pub mod kv;
+pub mod on_this_day;
+pub mod random;
 pub mod resolver;
 pub mod sat_words;
 pub mod text;
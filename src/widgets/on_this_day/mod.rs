@@ -0,0 +1,5 @@
+pub mod on_this_day;
+pub use on_this_day::get_on_this_day;
+
+#[cfg(test)]
+pub mod on_this_day_tests;
@@ -0,0 +1,80 @@
+use chrono::{Datelike, NaiveDate};
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use crate::errors::VestaboardError;
+use crate::widgets::widget_utils::{center_line, center_message, split_into_lines, WidgetOutput, MAX_MESSAGE_HEIGHT};
+
+/// Rows left for the event text below the header and blank line
+const MAX_EVENT_LINES: usize = 4;
+
+/// Events keyed by (month, day)
+pub type EventsMap = HashMap<(u32, u32), Vec<String>>;
+
+pub fn get_on_this_day(path: &Path, date: NaiveDate) -> Result<WidgetOutput, VestaboardError> {
+  log::debug!("On this day widget starting for {}", date);
+  let events_map = create_events_map(path).map_err(|e| {
+    log::error!("Failed to load on this day events from {}: {}", path.display(), e);
+    VestaboardError::io_error(e, "reading on this day events")
+  })?;
+
+  let header = center_line(format!("on this day {}", date.format("%m/%d")));
+  let events = events_map.get(&(date.month(), date.day()));
+  let event = match events {
+    Some(events) if !events.is_empty() => &events[thread_rng().gen_range(0..events.len())],
+    _ => {
+      log::info!("No events on record for {}", date.format("%m-%d"));
+      return Ok(center_message(
+        vec![header, String::new(), center_line("nothing on record".to_string())],
+        MAX_MESSAGE_HEIGHT,
+      ));
+    },
+  };
+
+  log::info!("Selected event for {}: {}", date.format("%m-%d"), event);
+  let mut message = vec![header, String::new()];
+  message.extend(
+    split_into_lines(event)
+      .into_iter()
+      .take(MAX_EVENT_LINES)
+      .map(center_line),
+  );
+  log::debug!("On this day widget completed successfully, {} lines generated", message.len());
+  Ok(message)
+}
+
+/// Parse an events file into a (month, day) -> events map.
+///
+/// Each line is `MM-DD text`; blank lines and lines starting with `#` are skipped.
+pub fn create_events_map<P>(filename: P) -> io::Result<EventsMap>
+where
+  P: AsRef<Path>,
+{
+  let file = File::open(filename)?;
+  let reader = io::BufReader::new(file);
+  let mut map: EventsMap = HashMap::new();
+
+  for (line_number, line) in reader.lines().enumerate() {
+    let line = line?;
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+      continue;
+    }
+
+    let parsed = trimmed.split_once(' ').and_then(|(date, text)| {
+      let (month, day) = date.split_once('-')?;
+      Some((month.parse::<u32>().ok()?, day.parse::<u32>().ok()?, text.trim()))
+    });
+    match parsed {
+      Some((month, day, text)) if (1..=12).contains(&month) && (1..=31).contains(&day) && !text.is_empty() => {
+        map.entry((month, day)).or_default().push(text.to_lowercase());
+      },
+      _ => log::warn!("Line {} does not follow expected pattern: {}", line_number + 1, line),
+    }
+  }
+
+  Ok(map)
+}
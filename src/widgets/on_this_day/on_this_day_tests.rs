@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+  use crate::errors::VestaboardError;
+  use crate::widgets::on_this_day::on_this_day::{create_events_map, get_on_this_day};
+  use chrono::NaiveDate;
+  use std::io::Write;
+  use std::path::Path;
+  use tempfile::NamedTempFile;
+
+  fn dataset() -> NamedTempFile {
+    let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+    writeln!(temp_file, "# comment line").unwrap();
+    writeln!(temp_file, "07-20 1969 Apollo 11 lands on the moon").unwrap();
+    writeln!(temp_file, "07-20 1976 viking 1 lands on mars").unwrap();
+    writeln!(temp_file, "11-09 1989 the berlin wall falls").unwrap();
+    writeln!(temp_file).unwrap();
+    writeln!(temp_file, "not a date line").unwrap();
+    temp_file.flush().unwrap();
+    temp_file
+  }
+
+  #[test]
+  fn test_create_events_map_groups_by_date() {
+    let file = dataset();
+    let map = create_events_map(file.path()).unwrap();
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(
+      map.get(&(7, 20)).unwrap(),
+      &vec![
+        "1969 apollo 11 lands on the moon".to_string(),
+        "1976 viking 1 lands on mars".to_string()
+      ]
+    );
+    assert_eq!(map.get(&(11, 9)).unwrap(), &vec!["1989 the berlin wall falls".to_string()]);
+  }
+
+  #[test]
+  fn test_get_on_this_day_matching_date() {
+    let file = dataset();
+    let lines = get_on_this_day(file.path(), NaiveDate::from_ymd_opt(2024, 11, 9).unwrap()).unwrap();
+
+    assert_eq!(lines[0].trim(), "on this day 11/09");
+    assert_eq!(lines[1], "");
+    assert_eq!(lines[2].trim(), "1989 the berlin wall");
+    assert_eq!(lines[3].trim(), "falls");
+    assert!(lines.len() <= 6);
+  }
+
+  #[test]
+  fn test_get_on_this_day_picks_one_of_several_events() {
+    let file = dataset();
+    let lines = get_on_this_day(file.path(), NaiveDate::from_ymd_opt(2024, 7, 20).unwrap()).unwrap();
+
+    let text = lines[2..].iter().map(|l| l.trim()).collect::<Vec<_>>().join(" ");
+    assert!(text == "1969 apollo 11 lands on the moon" || text == "1976 viking 1 lands on mars");
+  }
+
+  #[test]
+  fn test_get_on_this_day_no_entries() {
+    let file = dataset();
+    let lines = get_on_this_day(file.path(), NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()).unwrap();
+
+    assert!(lines.iter().any(|l| l.trim() == "on this day 01/02"));
+    assert!(lines.iter().any(|l| l.trim() == "nothing on record"));
+    assert!(crate::api_broker::validate_message_content(&lines).is_ok());
+  }
+
+  #[test]
+  fn test_get_on_this_day_missing_file() {
+    let result = get_on_this_day(Path::new("/nonexistent/events.txt"), NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+    match result {
+      Err(VestaboardError::IOError { context, .. }) => assert!(context.contains("on this day")),
+      other => panic!("Expected IOError, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_bundled_dataset_is_valid() {
+    let map = create_events_map("./src/widgets/on_this_day/events.txt").unwrap();
+    assert!(!map.is_empty());
+    for events in map.values() {
+      for event in events {
+        let lines = crate::widgets::widget_utils::split_into_lines(event);
+        assert!(crate::api_broker::validate_message_content(&lines).is_ok(), "invalid event: {}", event);
+      }
+    }
+  }
+}
@@ -0,0 +1,5 @@
+pub mod random;
+pub use random::{parse_choices, select_widget};
+
+#[cfg(test)]
+pub mod random_tests;
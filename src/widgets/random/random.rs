@@ -0,0 +1,52 @@
+use rand::Rng;
+use serde_json::Value;
+
+use crate::errors::VestaboardError;
+
+/// Parse and validate the `choices` list in the random widget's input.
+///
+/// Input format: `{"choices": ["weather", "sat-word", "jokes"]}`
+///
+/// Every choice must appear in `known_widgets`, the widgets that can be picked; "random"
+/// itself is rejected so the widget can't select itself.
+pub fn parse_choices(input: &Value, known_widgets: &[&str]) -> Result<Vec<String>, VestaboardError> {
+  let choices = input
+    .get("choices")
+    .and_then(|c| c.as_array())
+    .ok_or_else(|| VestaboardError::widget_error("random", "Input must include a \"choices\" list of widget names"))?;
+
+  if choices.is_empty() {
+    return Err(VestaboardError::widget_error("random", "Choices list is empty"));
+  }
+
+  let mut names = Vec::with_capacity(choices.len());
+  for choice in choices {
+    let name = choice
+      .as_str()
+      .map(|s| s.to_lowercase())
+      .ok_or_else(|| VestaboardError::widget_error("random", &format!("Invalid choice: {}", choice)))?;
+    if name == "random" || !known_widgets.contains(&name.as_str()) {
+      return Err(VestaboardError::widget_error(
+        "random",
+        &format!("Can't pick {} at random. Choose from: {}", name, known_widgets.join(", ")),
+      ));
+    }
+    names.push(name);
+  }
+
+  Ok(names)
+}
+
+/// Pick one widget name from the random widget's input.
+///
+/// The RNG is passed in so tests can use a seeded generator.
+pub fn select_widget<R: Rng + ?Sized>(
+  input: &Value,
+  known_widgets: &[&str],
+  rng: &mut R,
+) -> Result<String, VestaboardError> {
+  let mut names = parse_choices(input, known_widgets)?;
+  let selected = names.swap_remove(rng.gen_range(0..names.len()));
+  log::info!("Random widget selected '{}'", selected);
+  Ok(selected)
+}
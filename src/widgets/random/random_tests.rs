@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+  use crate::config::Config;
+  use crate::errors::VestaboardError;
+  use crate::widgets::random::select_widget;
+  use crate::widgets::resolver::{execute_widget, random_choices, validate_widget_input, WIDGET_NAMES};
+  use rand::rngs::StdRng;
+  use rand::SeedableRng;
+  use serde_json::json;
+
+  #[test]
+  fn test_select_widget_only_returns_allowed_choices() {
+    let input = json!({"choices": ["weather", "sat-word", "jokes"]});
+    let mut rng = StdRng::seed_from_u64(42);
+
+    for _ in 0..100 {
+      let selected = select_widget(&input, WIDGET_NAMES, &mut rng).unwrap();
+      assert!(["weather", "sat-word", "jokes"].contains(&selected.as_str()));
+    }
+  }
+
+  #[test]
+  fn test_select_widget_eventually_picks_every_choice() {
+    let input = json!({"choices": ["weather", "jokes"]});
+    let mut rng = StdRng::seed_from_u64(7);
+    let picks: Vec<String> = (0..50)
+      .map(|_| select_widget(&input, WIDGET_NAMES, &mut rng).unwrap())
+      .collect();
+
+    assert!(picks.iter().any(|p| p == "weather"));
+    assert!(picks.iter().any(|p| p == "jokes"));
+  }
+
+  #[test]
+  fn test_select_widget_unknown_choice_errors() {
+    let input = json!({"choices": ["weather", "horoscope"]});
+    let mut rng = StdRng::seed_from_u64(1);
+    let result = select_widget(&input, WIDGET_NAMES, &mut rng);
+
+    match result {
+      Err(VestaboardError::WidgetError { widget, message }) => {
+        assert_eq!(widget, "random");
+        assert!(message.contains("horoscope"));
+      },
+      other => panic!("Expected WidgetError, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_select_widget_rejects_itself() {
+    let input = json!({"choices": ["random"]});
+    let mut rng = StdRng::seed_from_u64(1);
+    assert!(select_widget(&input, WIDGET_NAMES, &mut rng).is_err());
+  }
+
+  #[test]
+  fn test_select_widget_missing_or_empty_choices_errors() {
+    let mut rng = StdRng::seed_from_u64(1);
+    assert!(select_widget(&json!(null), WIDGET_NAMES, &mut rng).is_err());
+    assert!(select_widget(&json!({"choices": []}), WIDGET_NAMES, &mut rng).is_err());
+  }
+
+  #[tokio::test]
+  async fn test_execute_random_widget_delegates_to_choice() {
+    let result = execute_widget("random", &json!({"choices": ["clear"]}), &Config::default()).await;
+    assert_eq!(result.unwrap(), vec![String::from("")]);
+  }
+
+  #[tokio::test]
+  async fn test_random_widget_rejects_widgets_that_need_input() {
+    for widget in ["text", "file", "forex", "kv", "colortest"] {
+      let input = json!({ "choices": [widget] });
+      assert!(!random_choices().contains(&widget));
+      assert!(validate_widget_input("random", &input).is_err(), "{} should not be a choice", widget);
+      assert!(execute_widget("random", &input, &Config::default()).await.is_err());
+    }
+    assert!(validate_widget_input("random", &json!({"choices": ["weather", "jokes", "clear"]})).is_ok());
+  }
+
+  #[tokio::test]
+  async fn test_execute_random_widget_unknown_choice_errors() {
+    let result = execute_widget("random", &json!({"choices": ["nope"]}), &Config::default()).await;
+    assert!(result.is_err());
+  }
+}
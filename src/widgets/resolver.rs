@@ -1,17 +1,438 @@
+use chrono::Local;
+use rand::thread_rng;
 use serde_json::Value;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+use crate::api::Transport;
+use crate::api_broker::{handle_message, MessageDestination};
 use crate::cli_display::{print_error, print_progress};
+use crate::config::Config;
 use crate::errors::VestaboardError;
 use crate::widgets::{
+  airquality::get_air_quality,
+  forex::{get_forex, parse_pair},
   jokes::get_joke,
+  kv::{get_kv, parse_rows},
+  on_this_day::get_on_this_day,
+  random::{parse_choices, select_widget},
   sat_words::get_sat_word,
-  text::{get_text, get_text_from_file},
-  weather::get_weather,
+  text::{
+    expand_placeholders, fit_text, fit_text_with, get_bordered_text, get_text, get_text_from_file, load_abbreviations,
+    parse_text_input, TextInput,
+  },
+  weather::{get_weather, parse_weather_mode, WeatherMode},
+  widget_utils::{
+    color_fill, error_to_display_message_with_theme, missing_key_message, Theme, WidgetOutput, STANDARD_BOARD,
+  },
 };
 use crate::{log_widget_error, log_widget_start, log_widget_success};
 
+/// How one widget's input is described (`vbl widget schema`) and checked before it is saved
+struct WidgetSpec {
+  name: &'static str,
+  input_type: &'static str,
+  required: bool,
+  description: &'static str,
+  fields: &'static [SchemaField],
+  validate: fn(&Value) -> Result<(), VestaboardError>,
+  /// Representative input, used when previewing every widget at once
+  sample: fn() -> Value,
+  /// Whether the widget lays itself out for the configured `board_cols`/`board_rows`;
+  /// widgets that don't only fit the standard board
+  any_size: bool,
+}
+
+/// One field of an object input in a widget's input schema
+struct SchemaField {
+  name: &'static str,
+  field_type: &'static str,
+  required: bool,
+  description: &'static str,
+}
+
+/// Spec for a widget that takes no input
+const fn no_input(name: &'static str) -> WidgetSpec {
+  WidgetSpec {
+    name,
+    input_type: "null",
+    required: false,
+    description: "Takes no input",
+    fields: &[],
+    validate: |_| Ok(()),
+    sample: || Value::Null,
+    any_size: false,
+  }
+}
+
+/// Every widget `execute_widget` knows how to run, in the order they are listed and previewed
+const WIDGETS: [WidgetSpec; 12] = [
+  WidgetSpec {
+    name: "text",
+    input_type: "string or object",
+    required: true,
+    description: "Message to show; {date}, {time}, and {weekday} are filled in. Use an object to add a border",
+    fields: &[
+      SchemaField {
+        name: "message",
+        field_type: "string",
+        required: true,
+        description: "Message to show",
+      },
+      SchemaField {
+        name: "border",
+        field_type: "string",
+        required: false,
+        description: "Color of a one-tile border around the board (e.g. blue)",
+      },
+    ],
+    validate: |input| match parse_text_input(input)? {
+      TextInput { message, .. } if message.trim().is_empty() => {
+        Err(VestaboardError::validation_error("Input is required for text widgets."))
+      },
+      _ => Ok(()),
+    },
+    sample: || Value::String("Hello from Vestaboard".to_string()),
+    any_size: true,
+  },
+  WidgetSpec {
+    name: "file",
+    input_type: "string",
+    required: true,
+    description: "Path of a text file whose lines are shown as-is",
+    fields: &[],
+    validate: |input| match input.as_str() {
+      Some(s) if !s.trim().is_empty() => Ok(()),
+      _ => Err(VestaboardError::validation_error("Input is required for file widgets.")),
+    },
+    sample: || Value::String("message.txt".to_string()),
+    any_size: true,
+  },
+  WidgetSpec {
+    name: "weather",
+    input_type: "object",
+    required: false,
+    description: "Omit for the standard view",
+    fields: &[
+      SchemaField {
+        name: "mode",
+        field_type: "string",
+        required: false,
+        description:
+          "standard, feels (feels like and humidity), rain (rain chance through the day), or trend (daily highs)",
+      },
+      SchemaField {
+        name: "lines",
+        field_type: "array",
+        required: false,
+        description: "Lines to show in order instead of a mode: time, temps, condition, rain, summary, feels, rain-trend, wind, trend, pressure",
+      },
+      SchemaField {
+        name: "compact",
+        field_type: "boolean",
+        required: false,
+        description: "true for a single row with the temperature, condition, and chance of rain",
+      },
+    ],
+    validate: |input| parse_weather_mode(input).map(|_| ()),
+    sample: || Value::Null,
+    any_size: false,
+  },
+  no_input("airquality"),
+  WidgetSpec {
+    name: "forex",
+    input_type: "object",
+    required: true,
+    description: "Currency pair to show the exchange rate for",
+    fields: &[
+      SchemaField {
+        name: "from",
+        field_type: "string",
+        required: true,
+        description: "3-letter currency code to convert from, e.g. USD",
+      },
+      SchemaField {
+        name: "to",
+        field_type: "string",
+        required: true,
+        description: "3-letter currency code to convert to, e.g. EUR",
+      },
+    ],
+    validate: |input| parse_pair(input).map(|_| ()),
+    sample: || serde_json::json!({ "from": "USD", "to": "EUR" }),
+    any_size: false,
+  },
+  WidgetSpec {
+    name: "kv",
+    input_type: "object",
+    required: true,
+    description: "Labels and values, one pair per row",
+    fields: &[SchemaField {
+      name: "rows",
+      field_type: "array",
+      required: true,
+      description: "[label, value] pairs, at least one and no more than the board has rows, that each fit on one line",
+    }],
+    validate: |input| parse_rows(input, STANDARD_BOARD).map(|_| ()),
+    sample: || serde_json::json!({ "rows": [["temp", "72f"], ["humidity", "40%"]] }),
+    any_size: true,
+  },
+  WidgetSpec {
+    any_size: true,
+    ..no_input("jokes")
+  },
+  no_input("sat-word"),
+  no_input("onthisday"),
+  WidgetSpec {
+    any_size: true,
+    ..no_input("clear")
+  },
+  WidgetSpec {
+    name: "colortest",
+    input_type: "string",
+    required: true,
+    description: "Color to fill the board with: red, orange, yellow, green, blue, violet, white, or black",
+    fields: &[],
+    validate: |input| color_fill(input.as_str().unwrap_or_default(), STANDARD_BOARD).map(|_| ()),
+    sample: || Value::String("red".to_string()),
+    any_size: true,
+  },
+  WidgetSpec {
+    name: "random",
+    input_type: "object",
+    required: true,
+    description: "Widgets to pick from at random",
+    fields: &[SchemaField {
+      name: "choices",
+      field_type: "array",
+      required: true,
+      description: "Names of widgets whose input is optional, since the pick runs without input",
+    }],
+    validate: validate_random_choices,
+    sample: || serde_json::json!({ "choices": ["jokes", "sat-word"] }),
+    any_size: true,
+  },
+];
+
+/// The random widget's choices must come from `random_choices`
+fn validate_random_choices(input: &Value) -> Result<(), VestaboardError> {
+  parse_choices(input, &random_choices()).map(|_| ())
+}
+
+/// Whether a registered widget can run without input
+pub fn input_optional(widget_type: &str) -> bool {
+  widget_spec(widget_type).is_some_and(|spec| !spec.required)
+}
+
+/// Widgets the random widget can pick. The pick runs without input, so widgets that
+/// require input (text, file, forex, kv, colortest, and random itself) are left out.
+pub fn random_choices() -> Vec<&'static str> {
+  WIDGET_NAMES
+    .iter()
+    .copied()
+    .filter(|widget| input_optional(widget))
+    .collect()
+}
+
+/// The widget names in `WIDGETS`, in order
+const fn widget_names<const N: usize>(widgets: &[WidgetSpec; N]) -> [&'static str; N] {
+  let mut names = [""; N];
+  let mut i = 0;
+  while i < N {
+    names[i] = widgets[i].name;
+    i += 1;
+  }
+  names
+}
+
+const NAMES: [&str; WIDGETS.len()] = widget_names(&WIDGETS);
+
+/// Names of all widgets that `execute_widget` knows how to run
+pub const WIDGET_NAMES: &[&str] = &NAMES;
+
+/// The spec for a registered widget
+fn widget_spec(widget_type: &str) -> Option<&'static WidgetSpec> {
+  WIDGETS.iter().find(|spec| spec.name == widget_type)
+}
+
+/// Widgets that call a web API when run
+pub const NETWORK_WIDGETS: &[&str] = &["weather", "airquality", "forex"];
+
+/// Build a widget's JSON input from command-line words.
+///
+/// Text, file, and colortest widgets take the words joined as a single string, the random widget
+/// takes them as its list of choices, the forex widget takes them as its from and to
+/// currencies, and all other widgets ignore input.
+pub fn input_from_args(widget_type: &str, args: &[String]) -> Value {
+  match widget_type {
+    "text" | "file" | "colortest" => Value::String(args.join(" ")),
+    "random" => serde_json::json!({ "choices": args }),
+    "forex" => serde_json::json!({
+      "from": args.first().cloned().unwrap_or_default(),
+      "to": args.get(1).cloned().unwrap_or_default(),
+    }),
+    _ => Value::Null,
+  }
+}
+
+/// Validate that an input value has the shape a widget expects, without running it.
+///
+/// This catches missing text, empty file paths, bad random choices, malformed currency
+/// codes, and kv rows that don't fit before they are saved to a playlist or schedule.
+pub fn validate_widget_input(widget_type: &str, input: &Value) -> Result<(), VestaboardError> {
+  match widget_spec(widget_type) {
+    Some(spec) => (spec.validate)(input),
+    None => Err(VestaboardError::validation_error(&format!("Unknown widget type: {}", widget_type))),
+  }
+}
+
+/// The configured `weather_lines` layout replaces the standard view when the input doesn't pick a mode or lines
+fn configured_weather_layout(mode: WeatherMode, input: &Value, config: &Config) -> WeatherMode {
+  match (mode, config.get_weather_lines()) {
+    (WeatherMode::Standard, Some(lines)) if input.get("mode").is_none() => WeatherMode::Lines(lines),
+    (mode, _) => mode,
+  }
+}
+
+/// Describe the input a widget expects as JSON: the input's type, whether it is required,
+/// and for object inputs, each field's name, type, and whether it is required.
+///
+/// Read from the same `WIDGETS` table that `validate_widget_input` checks against.
+pub fn widget_input_schema(widget_type: &str) -> Result<Value, VestaboardError> {
+  let spec = widget_spec(widget_type).ok_or_else(|| {
+    VestaboardError::validation_error(&format!(
+      "Unknown widget '{}'. Supported: {}",
+      widget_type,
+      WIDGET_NAMES.join(", ")
+    ))
+  })?;
+  let fields: Vec<Value> = spec
+    .fields
+    .iter()
+    .map(|field| {
+      serde_json::json!({
+        "name": field.name,
+        "type": field.field_type,
+        "required": field.required,
+        "description": field.description,
+      })
+    })
+    .collect();
+  Ok(serde_json::json!({
+    "widget": spec.name,
+    "input": {
+      "type": spec.input_type,
+      "required": spec.required,
+      "description": spec.description,
+      "fields": fields,
+    },
+  }))
+}
+
+/// Resolve a widget named on the command line (`vbl show --widget NAME --input JSON`)
+/// without going through a widget subcommand.
+///
+/// The name must be registered in `WIDGET_NAMES`. Input that isn't valid JSON is used
+/// as a plain string, so `--input "hello"` works for text. The input is checked with
+/// `validate_widget_input`.
+pub fn resolve_inline_widget(name: &str, input: Option<&str>) -> Result<(String, Value), VestaboardError> {
+  let input = match input {
+    Some(raw) => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    None => Value::Null,
+  };
+  resolve_widget_input(name, input)
+}
+
+/// Widget name and input for `--input-file`: the file's JSON, checked against the widget.
+///
+/// Unlike `--input`, the file must hold well-formed JSON, so plain text needs quotes
+/// (e.g. `"good morning"`).
+pub fn resolve_input_file(name: &str, path: &Path) -> Result<(String, Value), VestaboardError> {
+  let raw = fs::read_to_string(path)
+    .map_err(|e| VestaboardError::io_error(e, &format!("reading input file {}", path.display())))?;
+  let input = serde_json::from_str(&raw)
+    .map_err(|e| VestaboardError::json_error(e, &format!("parsing input file {}", path.display())))?;
+  resolve_widget_input(name, input)
+}
+
+/// Lowercase a registered widget name and validate its input
+fn resolve_widget_input(name: &str, input: Value) -> Result<(String, Value), VestaboardError> {
+  let widget = name.trim().to_lowercase();
+  if !WIDGET_NAMES.contains(&widget.as_str()) {
+    return Err(VestaboardError::validation_error(&format!(
+      "Unknown widget '{}'. Supported: {}",
+      name,
+      WIDGET_NAMES.join(", ")
+    )));
+  }
+
+  validate_widget_input(&widget, &input)?;
+  Ok((widget, input))
+}
+
+/// Representative input for a widget, used when previewing every widget at once
+pub fn sample_input(widget_type: &str) -> Value {
+  widget_spec(widget_type).map_or(Value::Null, |spec| (spec.sample)())
+}
+
+/// Render every registered widget with its sample input.
+///
+/// Widget errors are converted to their display message, so every widget in
+/// `WIDGET_NAMES` gets a rendered grid.
+pub async fn render_all_widgets(config: &Config) -> Vec<(&'static str, Vec<String>)> {
+  render_all_widgets_with(config, |_| None).await
+}
+
+/// Like `render_all_widgets`, but any widget `stub` returns a message for shows that message
+/// instead of running, e.g. to keep the `NETWORK_WIDGETS` offline.
+pub async fn render_all_widgets_with(
+  config: &Config,
+  stub: impl Fn(&str) -> Option<Vec<String>>,
+) -> Vec<(&'static str, Vec<String>)> {
+  let mut rendered = Vec::with_capacity(WIDGET_NAMES.len());
+  for &widget in WIDGET_NAMES {
+    let message = match stub(widget) {
+      Some(msg) => msg,
+      None => match execute_widget(widget, &sample_input(widget), config).await {
+        Ok(msg) => msg,
+        Err(e) => error_display(&e, config),
+      },
+    };
+    rendered.push((widget, message));
+  }
+  rendered
+}
+
+/// Print every registered widget's rendered grid to the console with a title
+pub async fn preview_all_widgets(transport: &Transport, config: &Config) {
+  let rendered = render_all_widgets(config).await;
+  let total = rendered.len();
+  println!("Previewing {} widgets:\n", total);
+
+  for (index, (widget, message)) in rendered.into_iter().enumerate() {
+    let title = format!("--- Widget {} of {}: {} ---", index + 1, total, widget);
+    if let Err(e) =
+      handle_message(message, widget, MessageDestination::ConsoleWithTitle(title), transport, config).await
+    {
+      println!("  Display error: {}", e.to_user_message());
+    }
+    println!();
+  }
+
+  println!("Preview complete.");
+}
+
+/// Display message for a widget error, styled with the configured theme and board size
+pub fn error_display(error: &VestaboardError, config: &Config) -> Vec<String> {
+  error_to_display_message_with_theme(error, &Theme::from_config(config), config.get_board_size())
+}
+
+/// The friendly "set the api key" board for a missing key, or the error itself otherwise
+fn missing_key_display(error: VestaboardError, widget: &str, config: &Config) -> Result<WidgetOutput, VestaboardError> {
+  missing_key_message(&error, widget, &Theme::from_config(config), config.get_board_size()).ok_or(error)
+}
+
 /// Execute a widget by type string with unified error handling and logging
 ///
 /// This function provides a single entry point for executing all widget types,
@@ -20,21 +441,35 @@ use crate::{log_widget_error, log_widget_start, log_widget_success};
 /// # Arguments
 /// * `widget_type` - The type of widget to execute ("text", "file", "weather", etc.)
 /// * `input` - JSON value containing widget-specific input parameters
+/// * `config` - Loaded config supplying widget options (location, units, board size, etc.)
 ///
 /// # Returns
 /// * `Ok(Vec<String>)` - The generated message lines (NOT validated)
 /// * `Err(VestaboardError)` - Widget execution error
-pub async fn execute_widget(widget_type: &str, input: &Value) -> Result<Vec<String>, VestaboardError> {
+pub async fn execute_widget(widget_type: &str, input: &Value, config: &Config) -> Result<Vec<String>, VestaboardError> {
   let start_time = Instant::now();
 
   // Extract input string for logging
   let input_str = match widget_type {
-    "text" | "file" => input.as_str().unwrap_or(""),
+    "text" | "file" => input.as_str().or_else(|| input["message"].as_str()).unwrap_or(""),
     _ => "",
   };
 
   log_widget_start!(widget_type, input_str);
 
+  let size = config.get_board_size();
+  if size != STANDARD_BOARD && widget_spec(widget_type).is_some_and(|spec| !spec.any_size) {
+    let error = VestaboardError::widget_error(
+      widget_type,
+      &format!(
+        "only fits the standard {}x{} board, not the configured {}x{}",
+        STANDARD_BOARD.cols, STANDARD_BOARD.rows, size.cols, size.rows
+      ),
+    );
+    log_widget_error!(widget_type, error, start_time.elapsed());
+    return Err(error);
+  }
+
   // Print user-facing widget start message
   match widget_type {
     "text" => print_progress("Creating message..."),
@@ -42,26 +477,87 @@ pub async fn execute_widget(widget_type: &str, input: &Value) -> Result<Vec<Stri
       let file_path = input.as_str().unwrap_or("");
       print_progress(&format!("Reading file: {}...", file_path));
     },
-    "weather" => print_progress("Fetching weather for Austin, TX..."),
+    "weather" => print_progress(&format!("Fetching weather for {}...", config.get_weather_location())),
+    "airquality" => print_progress(&format!("Fetching air quality for {}...", config.get_weather_location())),
+    "forex" => {
+      let (from, to) = parse_pair(input).unwrap_or_default();
+      print_progress(&format!("Fetching {}/{} exchange rate...", from, to));
+    },
+    "kv" => print_progress("Creating dashboard..."),
     "jokes" => print_progress("Getting joke..."),
     "sat-word" => print_progress("Selecting SAT word..."),
+    "onthisday" => print_progress("Looking up today in history..."),
     "clear" => print_progress("Clearing board..."),
+    "colortest" => print_progress("Filling board with color..."),
+    "random" => print_progress("Picking a random widget..."),
     _ => {},
   }
 
   let message_result = match widget_type {
-    "text" => {
-      let text_input = input.as_str().unwrap_or("");
-      get_text(text_input)
+    "text" => match parse_text_input(input) {
+      Ok(TextInput { message, border }) => {
+        let text_input = expand_placeholders(&message, Local::now(), config.get_locale());
+        match border {
+          Some(border) => get_bordered_text(&text_input, border, config.get_board_size()),
+          None if config.get_fit_text() => {
+            let fitted = match config
+              .get_abbreviations_file_path()
+              .map(|path| load_abbreviations(&path))
+            {
+              Some(Ok(abbreviations)) => fit_text_with(&text_input, &abbreviations),
+              Some(Err(e)) => {
+                log::warn!("Ignoring abbreviations file: {}", e);
+                fit_text(&text_input)
+              },
+              None => fit_text(&text_input),
+            };
+            get_text(&fitted, config.get_board_size())
+          },
+          None => get_text(&text_input, config.get_board_size()),
+        }
+      },
+      Err(e) => Err(e),
     },
     "file" => {
       let file_path = input.as_str().unwrap_or("");
       get_text_from_file(PathBuf::from(file_path))
     },
-    "weather" => get_weather().await,
-    "jokes" => get_joke(),
+    "weather" => match parse_weather_mode(input) {
+      Ok(mode) => match get_weather(
+        &config.get_weather_location(),
+        config.get_units(),
+        configured_weather_layout(mode, input, config),
+        config.get_weather_cache().as_ref(),
+      )
+      .await
+      {
+        Err(e) if config.get_friendly_missing_keys() => missing_key_display(e, "weather", config),
+        result => result,
+      },
+      Err(e) => Err(e),
+    },
+    "airquality" => match get_air_quality(&config.get_weather_location()).await {
+      Err(e) if config.get_friendly_missing_keys() => missing_key_display(e, "air quality", config),
+      result => result,
+    },
+    "forex" => match get_forex(input, &config.get_forex_api_url()).await {
+      Err(e) if config.get_friendly_missing_keys() => missing_key_display(e, "forex", config),
+      result => result,
+    },
+    "kv" => get_kv(input, size),
+    "jokes" => get_joke(size),
     "sat-word" => get_sat_word(),
+    "onthisday" => get_on_this_day(&config.get_on_this_day_file_path(), Local::now().date_naive()),
     "clear" => Ok(vec![String::from("")]), // Clear command
+    "colortest" => color_fill(input.as_str().unwrap_or_default(), config.get_board_size()),
+    "random" => {
+      // Delegate to the selected widget, which handles its own logging and output
+      let choice = select_widget(input, &random_choices(), &mut thread_rng());
+      match choice {
+        Ok(choice) => return Box::pin(execute_widget(&choice, &Value::Null, config)).await,
+        Err(e) => Err(e),
+      }
+    },
     _ => {
       let error = VestaboardError::widget_error(widget_type, &format!("Unknown widget type: {}", widget_type));
       return Err(error);
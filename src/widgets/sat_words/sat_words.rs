@@ -14,15 +14,28 @@ struct UsedWord {
   timestamp: String,
 }
 
+/// Bundled SAT words dictionary (lines of `word (type) definition (example)`)
+pub const SAT_WORDS_FILE_PATH: &str = "./src/widgets/sat_words/words.txt";
+
 pub fn get_sat_word() -> Result<WidgetOutput, VestaboardError> {
+  get_sat_word_from(Path::new(SAT_WORDS_FILE_PATH))
+}
+
+/// Pick a random word from the dictionary at `path`. An empty dictionary (or one with
+/// no readable entries) is a widget error rather than a panic.
+pub fn get_sat_word_from(path: &Path) -> Result<WidgetOutput, VestaboardError> {
   log::debug!("SAT word widget starting");
-  let path = "./src/widgets/sat_words/words.txt";
   let words_map = create_words_map(path).map_err(|e| {
-    log::error!("Failed to load SAT words dictionary from {}: {}", path, e);
+    log::error!("Failed to load SAT words dictionary from {}: {}", path.display(), e);
     VestaboardError::io_error(e, "reading SAT words dictionary")
   })?;
 
   log::debug!("Loaded {} words from SAT dictionary", words_map.len());
+  if words_map.is_empty() {
+    log::error!("No words available in SAT words dictionary {}", path.display());
+    return Err(VestaboardError::widget_error("sat-word", "No words available in dictionary"));
+  }
+
   let mut rng = thread_rng();
   if let Some((key, value)) = words_map.iter().nth(rng.gen_range(0..words_map.len())) {
     log::info!("Selected SAT word: {} ({})", key, value[0].0);
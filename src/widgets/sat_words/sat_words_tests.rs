@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
   use crate::errors::VestaboardError;
-  use crate::widgets::sat_words::sat_words::{create_words_map, get_sat_word};
+  use crate::widgets::sat_words::sat_words::{create_words_map, get_sat_word, get_sat_word_from};
   use std::io::Write;
   use tempfile::NamedTempFile;
 
@@ -160,4 +160,27 @@ mod tests {
     }
     // If the test passes (file exists and works), that's also fine
   }
+
+  #[test]
+  fn test_get_sat_word_from_empty_dictionary_is_widget_error() {
+    let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+
+    match get_sat_word_from(temp_file.path()) {
+      Err(VestaboardError::WidgetError { widget, message }) => {
+        assert_eq!(widget, "sat-word");
+        assert_eq!(message, "No words available in dictionary");
+      },
+      other => panic!("Expected a sat-word WidgetError, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_get_sat_word_from_dictionary_without_valid_entries_is_widget_error() {
+    let mut temp_file = NamedTempFile::new().expect("Failed to create temp file");
+    writeln!(temp_file, "truncated").expect("Failed to write");
+    temp_file.flush().expect("Failed to flush");
+
+    let result = get_sat_word_from(temp_file.path());
+    assert!(matches!(result, Err(VestaboardError::WidgetError { .. })));
+  }
 }
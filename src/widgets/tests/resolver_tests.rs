@@ -1,29 +1,34 @@
 #[cfg(test)]
 mod tests {
-  use crate::widgets::resolver::execute_widget;
+  use crate::config::Config;
+  use crate::errors::VestaboardError;
+  use crate::widgets::resolver::{
+    execute_widget, input_from_args, render_all_widgets_with, resolve_inline_widget, resolve_input_file, sample_input,
+    validate_widget_input, widget_input_schema, NETWORK_WIDGETS, WIDGET_NAMES,
+  };
 
   #[tokio::test]
   async fn test_execute_text_widget() {
-    let result = execute_widget("text", &serde_json::json!("hello world")).await;
+    let result = execute_widget("text", &serde_json::json!("hello world"), &Config::default()).await;
     assert!(result.is_ok());
   }
 
   #[tokio::test]
   async fn test_execute_unknown_widget() {
-    let result = execute_widget("unknown", &serde_json::json!(null)).await;
+    let result = execute_widget("unknown", &serde_json::json!(null), &Config::default()).await;
     assert!(result.is_err());
   }
 
   #[tokio::test]
   async fn test_execute_clear_widget() {
-    let result = execute_widget("clear", &serde_json::json!(null)).await;
+    let result = execute_widget("clear", &serde_json::json!(null), &Config::default()).await;
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), vec![String::from("")]);
   }
 
   #[tokio::test]
   async fn test_execute_text_widget_with_null_input() {
-    let result = execute_widget("text", &serde_json::json!(null)).await;
+    let result = execute_widget("text", &serde_json::json!(null), &Config::default()).await;
     assert!(result.is_ok());
     // Should handle null as empty string
     let message = result.unwrap();
@@ -33,23 +38,24 @@ mod tests {
   #[tokio::test]
   async fn test_execute_text_widget_with_invalid_json() {
     // Test with number instead of string
-    let result = execute_widget("text", &serde_json::json!(123)).await;
+    let result = execute_widget("text", &serde_json::json!(123), &Config::default()).await;
     assert!(result.is_ok());
-    // Should handle non-string as empty string
+    // Numbers are shown as written
     let message = result.unwrap();
     assert_eq!(message.len(), 6); // 6 lines for Vestaboard display
+    assert!(message.iter().any(|line| line.trim() == "123"));
   }
 
   #[tokio::test]
   async fn test_execute_file_widget_with_nonexistent_file() {
-    let result = execute_widget("file", &serde_json::json!("/path/that/does/not/exist.txt")).await;
+    let result = execute_widget("file", &serde_json::json!("/path/that/does/not/exist.txt"), &Config::default()).await;
     assert!(result.is_err());
     // Should return a VestaboardError for file not found
   }
 
   #[tokio::test]
   async fn test_execute_file_widget_with_null_input() {
-    let result = execute_widget("file", &serde_json::json!(null)).await;
+    let result = execute_widget("file", &serde_json::json!(null), &Config::default()).await;
     assert!(result.is_err());
     // Should return error for empty file path
   }
@@ -57,7 +63,7 @@ mod tests {
   #[tokio::test]
   async fn test_execute_widget_with_invalid_file_path() {
     // Test that invalid file paths return errors in normal mode
-    let result = execute_widget("file", &serde_json::json!("/invalid/path.txt")).await;
+    let result = execute_widget("file", &serde_json::json!("/invalid/path.txt"), &Config::default()).await;
     assert!(result.is_err()); // Should return error for invalid file path
     let error = result.unwrap_err();
     assert!(error.to_string().contains("not found") || error.to_string().contains("No such file"));
@@ -66,7 +72,7 @@ mod tests {
   #[tokio::test]
   async fn test_execute_unknown_widget_returns_error() {
     // Test that unknown widget type returns error
-    let result = execute_widget("nonexistent_widget", &serde_json::json!(null)).await;
+    let result = execute_widget("nonexistent_widget", &serde_json::json!(null), &Config::default()).await;
     assert!(result.is_err()); // Should return error for unknown widget
     let error = result.unwrap_err();
     assert!(
@@ -74,9 +80,39 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn test_execute_kv_widget_lays_out_to_configured_board() {
+    let config = Config {
+      board_cols: Some(15),
+      board_rows: Some(3),
+      ..Config::default()
+    };
+    let lines = execute_widget("kv", &serde_json::json!({"rows": [["temp", "72f"]]}), &config)
+      .await
+      .unwrap();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[1], "temp        72f");
+  }
+
+  #[tokio::test]
+  async fn test_execute_fixed_layout_widget_rejects_smaller_board() {
+    let config = Config {
+      board_cols: Some(15),
+      board_rows: Some(3),
+      ..Config::default()
+    };
+    let result = execute_widget("sat-word", &serde_json::json!(null), &config).await;
+
+    match result {
+      Err(VestaboardError::WidgetError { message, .. }) => assert!(message.contains("standard 22x6 board")),
+      other => panic!("expected a widget error, got {:?}", other),
+    }
+  }
+
   #[tokio::test]
   async fn test_execute_widget_with_empty_string_input() {
-    let result = execute_widget("text", &serde_json::json!("")).await;
+    let result = execute_widget("text", &serde_json::json!(""), &Config::default()).await;
     assert!(result.is_ok());
     let message = result.unwrap();
     assert_eq!(message.len(), 6); // Should still format as 6 lines
@@ -84,11 +120,179 @@ mod tests {
 
   #[tokio::test]
   async fn test_execute_widget_with_array_input() {
-    // Test with invalid input type (array instead of string)
-    let result = execute_widget("text", &serde_json::json!(["invalid", "array"])).await;
-    assert!(result.is_ok());
-    // Should handle invalid input gracefully
-    let message = result.unwrap();
-    assert_eq!(message.len(), 6);
+    // Arrays aren't a text shape, so they're reported instead of shown blank
+    let result = execute_widget("text", &serde_json::json!(["invalid", "array"]), &Config::default()).await;
+    match result {
+      Err(VestaboardError::WidgetError { widget, message }) => {
+        assert_eq!(widget, "text");
+        assert!(message.contains("not a list"));
+      },
+      other => panic!("Expected a text widget error, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_input_from_args_by_widget() {
+    let args = vec!["hello".to_string(), "world".to_string()];
+    assert_eq!(input_from_args("text", &args), serde_json::json!("hello world"));
+    assert_eq!(input_from_args("random", &args), serde_json::json!({"choices": ["hello", "world"]}));
+    assert_eq!(input_from_args("weather", &args), serde_json::json!(null));
+    let pair = vec!["usd".to_string(), "eur".to_string()];
+    assert_eq!(input_from_args("forex", &pair), serde_json::json!({"from": "usd", "to": "eur"}));
+  }
+
+  #[test]
+  fn test_validate_widget_input_accepts_valid_input() {
+    assert!(validate_widget_input("text", &serde_json::json!("hello")).is_ok());
+    assert!(validate_widget_input("file", &serde_json::json!("message.txt")).is_ok());
+    assert!(validate_widget_input("weather", &serde_json::json!(null)).is_ok());
+    assert!(validate_widget_input("random", &serde_json::json!({"choices": ["jokes", "weather"]})).is_ok());
+    assert!(validate_widget_input("forex", &serde_json::json!({"from": "usd", "to": "eur"})).is_ok());
+  }
+
+  #[test]
+  fn test_validate_widget_input_rejects_invalid_input() {
+    assert!(validate_widget_input("text", &serde_json::json!("   ")).is_err());
+    assert!(validate_widget_input("text", &serde_json::json!(null)).is_err());
+    assert!(validate_widget_input("text", &serde_json::json!({"message": "", "border": "blue"})).is_err());
+    assert!(validate_widget_input("file", &serde_json::json!("")).is_err());
+    assert!(validate_widget_input("random", &serde_json::json!({"choices": ["nope"]})).is_err());
+    assert!(validate_widget_input("forex", &serde_json::json!({"from": "usd"})).is_err());
+    assert!(validate_widget_input("unknown", &serde_json::json!(null)).is_err());
+    assert!(validate_widget_input("colortest", &serde_json::json!("purple")).is_err());
+    assert!(validate_widget_input("colortest", &serde_json::json!(null)).is_err());
+  }
+
+  #[test]
+  fn test_every_widget_has_an_input_schema() {
+    for widget in WIDGET_NAMES {
+      let schema = widget_input_schema(widget).unwrap_or_else(|e| panic!("no schema for {}: {}", widget, e));
+      assert_eq!(schema["widget"], *widget);
+      let input = &schema["input"];
+      assert!(input["type"].as_str().is_some_and(|t| !t.is_empty()), "no input type for {}", widget);
+      assert!(input["required"].is_boolean());
+      assert!(input["description"].as_str().is_some_and(|d| !d.is_empty()));
+
+      // Each field is described, and an object's sample input has every required field
+      let sample = sample_input(widget);
+      for field in input["fields"].as_array().unwrap() {
+        let name = field["name"].as_str().unwrap();
+        assert!(field["type"].is_string() && field["required"].is_boolean());
+        if field["required"] == true && sample.is_object() {
+          assert!(sample.get(name).is_some(), "{} sample input is missing {}", widget, name);
+        }
+      }
+      if input["required"] == true {
+        assert!(!sample.is_null(), "{} requires input but its sample has none", widget);
+      }
+    }
+  }
+
+  #[test]
+  fn test_input_schema_describes_forex_fields() {
+    let schema = widget_input_schema("forex").unwrap();
+    let fields: Vec<&str> = schema["input"]["fields"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|field| field["name"].as_str().unwrap())
+      .collect();
+    assert_eq!(schema["input"]["type"], "object");
+    assert_eq!(fields, vec!["from", "to"]);
+    assert_eq!(widget_input_schema("jokes").unwrap()["input"]["type"], "null");
+  }
+
+  #[test]
+  fn test_input_schema_for_unknown_widget_errors() {
+    let error = widget_input_schema("nope").unwrap_err();
+    assert!(error.to_string().contains("Unknown widget 'nope'"));
+  }
+
+  #[test]
+  fn test_sample_inputs_are_valid() {
+    for widget in WIDGET_NAMES {
+      assert!(validate_widget_input(widget, &sample_input(widget)).is_ok(), "bad sample for {}", widget);
+    }
+  }
+
+  #[tokio::test]
+  async fn test_render_all_widgets_covers_every_widget() {
+    // The network widgets are stubbed so the test doesn't call the live APIs
+    let stub = |widget: &str| {
+      NETWORK_WIDGETS
+        .contains(&widget)
+        .then(|| vec![format!("{} offline", widget)])
+    };
+    let rendered = render_all_widgets_with(&Config::default(), stub).await;
+    let names: Vec<&str> = rendered.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, WIDGET_NAMES);
+    for (name, message) in rendered {
+      assert!(!message.is_empty(), "no output for {}", name);
+      if NETWORK_WIDGETS.contains(&name) {
+        assert_eq!(message, vec![format!("{} offline", name)]);
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn test_resolve_inline_widget_runs_registered_widget() {
+    let (widget, input) = resolve_inline_widget("Text", Some(r#""hello world""#)).unwrap();
+    assert_eq!(widget, "text");
+    assert_eq!(input, serde_json::json!("hello world"));
+
+    let message = execute_widget(&widget, &input, &Config::default()).await.unwrap();
+    assert!(message.iter().any(|line| line.trim() == "hello world"));
+  }
+
+  #[test]
+  fn test_resolve_inline_widget_parses_json_and_plain_input() {
+    let (_, input) = resolve_inline_widget("forex", Some(r#"{"from": "usd", "to": "eur"}"#)).unwrap();
+    assert_eq!(input, serde_json::json!({"from": "usd", "to": "eur"}));
+
+    let (_, input) = resolve_inline_widget("text", Some("good morning")).unwrap();
+    assert_eq!(input, serde_json::json!("good morning"));
+
+    let (_, input) = resolve_inline_widget("jokes", None).unwrap();
+    assert_eq!(input, serde_json::json!(null));
+  }
+
+  #[test]
+  fn test_resolve_inline_widget_rejects_unknown_name_and_bad_input() {
+    assert!(resolve_inline_widget("nope", None).is_err());
+    assert!(resolve_inline_widget("text", None).is_err());
+    assert!(resolve_inline_widget("forex", Some(r#"{"from": "usd"}"#)).is_err());
+  }
+
+  #[test]
+  fn test_resolve_input_file_reads_valid_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("rows.json");
+    std::fs::write(&path, r#"{"rows": [["temp", "72f"], ["humidity", "40%"]]}"#).unwrap();
+
+    let (widget, input) = resolve_input_file("KV", &path).unwrap();
+    assert_eq!(widget, "kv");
+    assert_eq!(input, serde_json::json!({"rows": [["temp", "72f"], ["humidity", "40%"]]}));
+  }
+
+  #[test]
+  fn test_resolve_input_file_rejects_malformed_and_invalid_input() {
+    let dir = tempfile::tempdir().unwrap();
+    let malformed = dir.path().join("malformed.json");
+    std::fs::write(&malformed, r#"{"from": "usd", "to": "#).unwrap();
+    assert!(matches!(resolve_input_file("forex", &malformed), Err(VestaboardError::JsonError { .. })));
+
+    // Plain text is not JSON, unlike with --input
+    let plain = dir.path().join("plain.json");
+    std::fs::write(&plain, "good morning").unwrap();
+    assert!(resolve_input_file("text", &plain).is_err());
+
+    let wrong_shape = dir.path().join("forex.json");
+    std::fs::write(&wrong_shape, r#"{"from": "usd"}"#).unwrap();
+    assert!(resolve_input_file("forex", &wrong_shape).is_err());
+
+    assert!(matches!(
+      resolve_input_file("text", &dir.path().join("missing.json")),
+      Err(VestaboardError::IOError { .. })
+    ));
   }
 }
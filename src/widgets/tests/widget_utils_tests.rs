@@ -1,8 +1,14 @@
 #[path = "../widget_utils.rs"]
 mod widget_utils;
+use crate::config::Config;
 use crate::errors::VestaboardError;
 use std::io::{Error as IoError, ErrorKind};
-use widget_utils::{center_line, error_to_display_message, format_error, format_message, full_justify_line};
+use widget_utils::{
+  center_line, color_fill, color_tile, emoji_to_color_tiles, error_to_display_message,
+  error_to_display_message_with_theme, format_error, format_error_with_theme, format_message, format_message_for,
+  full_justify_line, full_justify_line_to_width, is_blank_message, missing_key_message, split_into_lines,
+  split_into_lines_to_width, Alignment, BoardSize, Theme, BLANK_PLACEHOLDER, STANDARD_BOARD,
+};
 
 #[cfg(test)]
 mod tests {
@@ -25,6 +31,37 @@ mod tests {
     assert_eq!(formatted, expected);
   }
 
+  #[test]
+  fn test_format_message_for_standard_board_matches_format_message() {
+    let message = "the quick brown fox jumps over the lazy dog";
+    assert_eq!(format_message_for(message, STANDARD_BOARD), format_message(message));
+    assert_eq!(BoardSize::default(), STANDARD_BOARD);
+  }
+
+  #[test]
+  fn test_format_message_for_small_board() {
+    let size = BoardSize { cols: 15, rows: 3 };
+    let formatted = format_message_for("meeting moved to room 204", size);
+    let expected = vec![" meeting moved ", "  to room 204  ", ""];
+    assert_eq!(formatted, expected);
+    assert!(formatted.iter().all(|line| line.chars().count() <= 15));
+  }
+
+  #[test]
+  fn test_split_into_lines_to_width_splits_long_words() {
+    let lines = split_into_lines_to_width("abcdefghijkl mn", 5);
+    assert_eq!(lines, vec!["abcde", "fghij", "kl", "mn"]);
+
+    let text = "a sentence long enough to need more than one line on the board";
+    assert_eq!(split_into_lines(text), split_into_lines_to_width(text, STANDARD_BOARD.cols));
+  }
+
+  #[test]
+  fn test_full_justify_line_to_width() {
+    assert_eq!(full_justify_line_to_width("temp".to_string(), "72".to_string(), 10), "temp    72");
+    assert_eq!(full_justify_line_to_width("humidity".to_string(), "78%".to_string(), 10), "humidity 78%");
+  }
+
   #[test]
   fn test_format_message_long_word() {
     let message = "thisisaverylongwordthatshouldwrap";
@@ -87,6 +124,213 @@ mod tests {
     assert_eq!(formatted, expected);
   }
 
+  #[test]
+  fn test_format_error_wraps_multibyte_text_by_characters() {
+    let formatted = format_error("échec de connexion à la base de données générée");
+    for line in &formatted {
+      assert!(line.chars().count() <= 22, "line too wide: {:?}", line);
+    }
+    let content: Vec<&str> = formatted[2..]
+      .iter()
+      .map(|l| l.trim())
+      .filter(|l| !l.is_empty())
+      .collect();
+    assert_eq!(content, vec!["échec de connexion à", "la base de données", "générée"]);
+
+    // 19 characters but 35 bytes: fits on one line
+    let formatted = format_error("éééé éééé éééé éééé");
+    let content: Vec<&str> = formatted[2..]
+      .iter()
+      .map(|l| l.trim())
+      .filter(|l| !l.is_empty())
+      .collect();
+    assert_eq!(content, vec!["éééé éééé éééé éééé"]);
+  }
+
+  #[test]
+  fn test_format_error_with_accent() {
+    let theme = Theme {
+      accent: 'O',
+      ..Theme::default()
+    };
+    let formatted = format_error_with_theme("disk almost full", "warning", &theme, STANDARD_BOARD);
+    let expected = vec![
+      "       warning        ".to_string(),
+      "O O O O O O O O O O O".to_string(),
+      "".to_string(),
+      "   disk almost full   ".to_string(),
+      "".to_string(),
+      "".to_string(),
+    ];
+    assert_eq!(formatted, expected);
+  }
+
+  #[test]
+  fn test_error_display_with_accent() {
+    let error = VestaboardError::widget_error("test", "test message");
+    let theme = Theme {
+      accent: 'Y',
+      ..Theme::default()
+    };
+    let display = error_to_display_message_with_theme(&error, &theme, STANDARD_BOARD);
+    assert_eq!(display[0], "     widget error     ");
+    assert_eq!(display[1], "Y Y Y Y Y Y Y Y Y Y Y");
+  }
+
+  #[test]
+  fn test_default_theme_matches_plain_format() {
+    assert_eq!(
+      format_error_with_theme("disk almost full", "error", &Theme::default(), STANDARD_BOARD),
+      format_error("disk almost full")
+    );
+    assert_eq!(
+      Theme::default().status_message("back soon", STANDARD_BOARD),
+      format_message_for("back soon", STANDARD_BOARD)
+    );
+    assert_eq!(Theme::from_config(&Config::default()), Theme::default());
+  }
+
+  #[test]
+  fn test_custom_theme_changes_header_accent_and_alignment() {
+    let theme = Theme {
+      header: "** {header} **".to_string(),
+      accent: 'B',
+      alignment: Alignment::Left,
+    };
+    let formatted = format_error_with_theme("disk almost full", "warning", &theme, STANDARD_BOARD);
+    let expected = vec![
+      "** warning **         ".to_string(),
+      "B B B B B B B B B B B".to_string(),
+      "".to_string(),
+      "disk almost full      ".to_string(),
+      "".to_string(),
+      "".to_string(),
+    ];
+    assert_eq!(formatted, expected);
+
+    let error = VestaboardError::widget_error("test", "test message");
+    let right = Theme {
+      alignment: Alignment::Right,
+      ..Theme::default()
+    };
+    let display = error_to_display_message_with_theme(&error, &right, STANDARD_BOARD);
+    assert_eq!(display[0], "          widget error");
+    assert_eq!(display[3], "            test error");
+  }
+
+  #[test]
+  fn test_status_message_follows_theme_and_board_size() {
+    let theme = Theme {
+      alignment: Alignment::Right,
+      ..Theme::default()
+    };
+    let small = BoardSize { cols: 15, rows: 3 };
+    let display = theme.status_message("board under maintenance", small);
+    assert_eq!(
+      display,
+      vec![
+        "    board under".to_string(),
+        "    maintenance".to_string(),
+        "".to_string(),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_theme_from_config() {
+    let config = Config {
+      theme_header: Some("!! {header}".to_string()),
+      theme_alignment: Some("Right".to_string()),
+      error_accent_color: Some("green".to_string()),
+      ..Config::default()
+    };
+    let theme = Theme::from_config(&config);
+    assert_eq!(theme.header, "!! {header}");
+    assert_eq!(theme.accent, 'G');
+    assert_eq!(theme.alignment, Alignment::Right);
+
+    // Unknown alignments fall back to center, and long headers are cut to the board width
+    let config = Config {
+      theme_header: Some("this header is much too long: {header}".to_string()),
+      theme_alignment: Some("diagonal".to_string()),
+      ..Config::default()
+    };
+    let theme = Theme::from_config(&config);
+    assert_eq!(theme.alignment, Alignment::Center);
+    assert_eq!(format_error_with_theme("oops", "error", &theme, STANDARD_BOARD)[0], "this header is much to");
+  }
+
+  #[test]
+  fn test_missing_key_message_uses_theme_alignment() {
+    let error = VestaboardError::config_error("WEATHER_API_KEY", "Environment variable not set");
+    let theme = Theme {
+      alignment: Alignment::Left,
+      ..Theme::default()
+    };
+    let display = missing_key_message(&error, "weather", &theme, STANDARD_BOARD).unwrap();
+    assert_eq!(display[2], "weather unavailable   ");
+  }
+
+  #[test]
+  fn test_color_tile_names_and_letters() {
+    assert_eq!(color_tile("orange"), Some('O'));
+    assert_eq!(color_tile("Violet"), Some('V'));
+    assert_eq!(color_tile("k"), Some('K'));
+    assert_eq!(color_tile("purple"), None);
+    assert_eq!(color_tile(""), None);
+  }
+
+  #[test]
+  fn test_color_fill_fills_board_with_each_color_code() {
+    let colors = [
+      ("red", 63),
+      ("orange", 64),
+      ("yellow", 65),
+      ("green", 66),
+      ("blue", 67),
+      ("violet", 68),
+      ("white", 69),
+      ("black", 70),
+    ];
+    for (color, code) in colors {
+      let message = color_fill(color, STANDARD_BOARD).unwrap();
+      assert_eq!(crate::api_broker::message_to_codes(message), [[code; 22]; 6], "{}", color);
+    }
+    assert_eq!(color_fill("B", STANDARD_BOARD).unwrap(), color_fill("blue", STANDARD_BOARD).unwrap());
+  }
+
+  #[test]
+  fn test_color_fill_rejects_unknown_color() {
+    let error = color_fill("purple", STANDARD_BOARD).unwrap_err();
+    assert!(error.to_string().contains("Unknown color 'purple'"));
+    assert!(color_fill("", STANDARD_BOARD).is_err());
+  }
+
+  #[test]
+  fn test_color_fill_matches_board_size() {
+    let message = color_fill("green", BoardSize { cols: 15, rows: 3 }).unwrap();
+    assert_eq!(message, vec!["G".repeat(15); 3]);
+  }
+
+  #[test]
+  fn test_full_justify_line_exact_fit() {
+    let justified = full_justify_line("a".repeat(10), "b".repeat(12));
+    assert_eq!(justified, format!("{}{}", "a".repeat(10), "b".repeat(12)));
+    assert_eq!(justified.chars().count(), 22);
+
+    let justified = full_justify_line("a".repeat(10), "b".repeat(11));
+    assert_eq!(justified, format!("{} {}", "a".repeat(10), "b".repeat(11)));
+    assert_eq!(justified.chars().count(), 22);
+  }
+
+  #[test]
+  fn test_full_justify_line_overflow() {
+    // Too long to fit either way; the strings stay separated by a single space
+    let justified = full_justify_line("a".repeat(12), "b".repeat(12));
+    assert_eq!(justified, format!("{} {}", "a".repeat(12), "b".repeat(12)));
+    assert_eq!(justified.chars().count(), 25);
+  }
+
   #[test]
   fn test_full_justify_line() {
     let s1 = "hello".to_string();
@@ -140,6 +384,60 @@ mod tests {
     assert_eq!(display[5], ""); // Empty padding line
   }
 
+  #[test]
+  fn test_error_to_display_message_long_path_fits_board() {
+    let io_err = IoError::new(ErrorKind::NotFound, "file not found");
+    let error = VestaboardError::io_error(io_err, "reading file /home/vbl/messages/announcement.txt");
+    let display = error_to_display_message(&error);
+
+    assert!(display.iter().all(|line| line.chars().count() <= 22));
+    assert_eq!(display.len(), 6);
+    // The path breaks after a '/', keeping the file name whole
+    assert_eq!(display[2].trim(), "'/home/vbl/messages/");
+    assert_eq!(display[3].trim(), "announcement.txt' not");
+    assert_eq!(display[4].trim(), "found");
+  }
+
+  #[test]
+  fn test_format_error_long_path_segment_is_cut_at_the_row_width() {
+    let display = format_error("/tmp/a_very_long_file_name_for_the_board.txt");
+    assert_eq!(display[2].trim(), "/tmp/");
+    assert_eq!(display[3].trim(), "a_very_long_file_name_");
+    assert_eq!(display[4].trim(), "for_the_board.txt");
+  }
+
+  #[test]
+  fn test_error_formatting_follows_board_size() {
+    let small = BoardSize { cols: 15, rows: 4 };
+    let display = format_error_with_theme("disk almost full", "warning", &Theme::default(), small);
+    assert_eq!(
+      display,
+      vec![
+        "    warning    ".to_string(),
+        "R R R R R R R R".to_string(),
+        "  disk almost  ".to_string(),
+        "     full      ".to_string(),
+      ]
+    );
+
+    let error = VestaboardError::widget_error("weather", "no data");
+    let display = error_to_display_message_with_theme(&error, &Theme::default(), small);
+    assert_eq!(display.len(), 4);
+    assert!(display.iter().all(|line| line.chars().count() <= 15));
+
+    let error = VestaboardError::config_error("WEATHER_API_KEY", "Environment variable not set");
+    let display = missing_key_message(&error, "weather", &Theme::default(), small).unwrap();
+    assert_eq!(
+      display,
+      vec![
+        "    weather    ".to_string(),
+        "  unavailable  ".to_string(),
+        "set weather api".to_string(),
+        "      key      ".to_string(),
+      ]
+    );
+  }
+
   #[test]
   fn test_error_to_display_message_json_error() {
     let json_err = serde_json::from_str::<serde_json::Value>("{invalid json").unwrap_err();
@@ -292,6 +590,44 @@ mod tests {
     assert_eq!(display[5], ""); // Empty padding line
   }
 
+  #[test]
+  fn test_emoji_to_color_tiles_each_square() {
+    assert_eq!(emoji_to_color_tiles("🟥"), "R");
+    assert_eq!(emoji_to_color_tiles("🟧"), "O");
+    assert_eq!(emoji_to_color_tiles("🟨"), "Y");
+    assert_eq!(emoji_to_color_tiles("🟩"), "G");
+    assert_eq!(emoji_to_color_tiles("🟦"), "B");
+    assert_eq!(emoji_to_color_tiles("🟪"), "V");
+    assert_eq!(emoji_to_color_tiles("⬜"), "W");
+    assert_eq!(emoji_to_color_tiles("⬛"), "K");
+  }
+
+  #[test]
+  fn test_emoji_to_color_tiles_mixed_line() {
+    assert_eq!(emoji_to_color_tiles("🟥🟥🟥 go team 🟩"), "RRR go team G");
+    // Variation selectors after a square are dropped, other emoji are left alone
+    assert_eq!(emoji_to_color_tiles("⬛\u{FE0F} hi 😀"), "K hi 😀");
+  }
+
+  #[test]
+  fn test_missing_key_message_for_api_key() {
+    let error = VestaboardError::config_error("WEATHER_API_KEY", "Environment variable not set");
+    let display = missing_key_message(&error, "weather", &Theme::default(), STANDARD_BOARD).unwrap();
+
+    assert_eq!(display.len(), 6);
+    assert_eq!(display[2], " weather unavailable  ");
+    assert_eq!(display[3], " set weather api key  ");
+  }
+
+  #[test]
+  fn test_missing_key_message_ignores_other_errors() {
+    let config = VestaboardError::config_error("schedule_file_path", "Invalid path");
+    assert!(missing_key_message(&config, "weather", &Theme::default(), STANDARD_BOARD).is_none());
+
+    let api = VestaboardError::api_error(Some(401), "Unauthorized");
+    assert!(missing_key_message(&api, "weather", &Theme::default(), STANDARD_BOARD).is_none());
+  }
+
   #[test]
   fn test_error_to_display_message_other_error_short() {
     let error = VestaboardError::other("short message");
@@ -333,4 +669,25 @@ mod tests {
     // Should have at least one content line
     assert!(display.len() >= 3);
   }
+
+  #[test]
+  fn test_is_blank_message_empty_grid() {
+    assert!(is_blank_message(&[]));
+    assert!(is_blank_message(&vec![String::new(); 6]));
+  }
+
+  #[test]
+  fn test_is_blank_message_whitespace_only_grid() {
+    let message = vec![" ".repeat(22), "   ".to_string(), String::new(), " ".repeat(22)];
+    assert!(is_blank_message(&message));
+  }
+
+  #[test]
+  fn test_is_blank_message_non_blank_grid() {
+    assert!(!is_blank_message(&format_message("hello")));
+    assert!(!is_blank_message(&format_message(BLANK_PLACEHOLDER)));
+    // A single color tile is content
+    let message = vec![String::new(), center_line("R".to_string())];
+    assert!(!is_blank_message(&message));
+  }
 }
@@ -1,7 +1,14 @@
 pub mod text;
 // this is just so main can use text::get_text instead of text::text::get_text
+pub use text::expand_placeholders;
+pub use text::fit_text;
+pub use text::fit_text_with;
+pub use text::get_bordered_text;
 pub use text::get_text;
 pub use text::get_text_from_file;
+pub use text::load_abbreviations;
+pub use text::parse_text_input;
+pub use text::TextInput;
 
 #[cfg(test)]
 pub mod text_tests;
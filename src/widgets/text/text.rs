@@ -1,15 +1,242 @@
 use crate::errors::VestaboardError;
-use crate::widgets::widget_utils;
-use std::{fs, path::PathBuf};
+use crate::locale::Locale;
+use crate::widgets::widget_utils::{self, color_tile, split_into_lines, BoardSize, MAX_MESSAGE_LENGTH};
+use chrono::{DateTime, Datelike, Local};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::{fs, path::Path, path::PathBuf};
 
-pub fn get_text(text: &str) -> Result<Vec<String>, VestaboardError> {
+/// Words that fit mode may replace with a shorter form
+const ABBREVIATIONS: &[(&str, &str)] = &[
+  ("and", "&"),
+  ("with", "w/"),
+  ("without", "w/o"),
+  ("tomorrow", "tmrw"),
+  ("tonight", "tonite"),
+  ("minutes", "min"),
+  ("minute", "min"),
+  ("hours", "hrs"),
+  ("temperature", "temp"),
+  ("degrees", "deg"),
+  ("please", "pls"),
+  ("thanks", "thx"),
+  ("weekend", "wknd"),
+];
+
+/// Only words at least this long have their vowels removed
+const MIN_VOWEL_STRIP_LENGTH: usize = 6;
+
+/// Fit mode only kicks in when the text is at most this many characters longer than one line fewer holds
+const MAX_FIT_OVERFLOW: usize = 8;
+
+/// Drop the interior vowels of a word, keeping its first and last letters.
+fn strip_vowels(word: &str) -> String {
+  let last = word.chars().count() - 1;
+  word
+    .chars()
+    .enumerate()
+    .filter(|&(i, c)| i == 0 || i == last || !"aeiou".contains(c))
+    .map(|(_, c)| c)
+    .collect()
+}
+
+/// Parse an abbreviation dictionary: one `word=abbreviation` pair per line.
+///
+/// Words are matched case-insensitively, so they're stored lowercase. Blank lines and lines
+/// starting with `#` are skipped, as are lines without an `=` or with an empty side.
+pub fn parse_abbreviations(content: &str) -> HashMap<String, String> {
+  content
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .filter_map(|line| {
+      let Some((word, short)) = line.split_once('=') else {
+        log::warn!("Skipping abbreviation line without '=': {}", line);
+        return None;
+      };
+      let (word, short) = (word.trim(), short.trim());
+      (!word.is_empty() && !short.is_empty()).then(|| (word.to_lowercase(), short.to_string()))
+    })
+    .collect()
+}
+
+/// Read the abbreviation dictionary at `path` (see `parse_abbreviations` for the format)
+pub fn load_abbreviations(path: &Path) -> Result<HashMap<String, String>, VestaboardError> {
+  let content = fs::read_to_string(path)
+    .map_err(|e| VestaboardError::io_error(e, &format!("reading abbreviations file {}", path.display())))?;
+  let abbreviations = parse_abbreviations(&content);
+  log::debug!("Loaded {} abbreviations from {}", abbreviations.len(), path.display());
+  Ok(abbreviations)
+}
+
+/// Abbreviate text that spills a few characters onto an extra line so it wraps to one line fewer.
+///
+/// Common words are swapped for short forms first, then vowels are removed from the longest
+/// words, one change at a time until the text fits. Text that already fits, overflows by more
+/// than a few characters, or can't be saved a line is returned unchanged.
+pub fn fit_text(text: &str) -> String {
+  fit_text_with(text, &HashMap::new())
+}
+
+/// Like `fit_text`, but tries the user's `abbreviations` (lowercase word to short form)
+/// before the built-in ones and the vowel-stripping fallback.
+pub fn fit_text_with(text: &str, abbreviations: &HashMap<String, String>) -> String {
+  let lines = split_into_lines(text).len();
+  if lines <= 1 {
+    return text.to_string();
+  }
+
+  let target = lines - 1;
+  let length = text.split_whitespace().collect::<Vec<_>>().join(" ").chars().count();
+  if length > target * MAX_MESSAGE_LENGTH + MAX_FIT_OVERFLOW {
+    return text.to_string();
+  }
+
+  let fits = |words: &[String]| split_into_lines(&words.join(" ")).len() <= target;
+  let mut words: Vec<String> = text.split_whitespace().map(String::from).collect();
+
+  for i in 0..words.len() {
+    let custom = abbreviations.get(&words[i].to_lowercase()).map(String::as_str);
+    let built_in = || {
+      ABBREVIATIONS
+        .iter()
+        .find(|(long, _)| *long == words[i])
+        .map(|(_, short)| *short)
+    };
+    if let Some(short) = custom.or_else(built_in) {
+      words[i] = short.to_string();
+      if fits(&words) {
+        return words.join(" ");
+      }
+    }
+  }
+
+  let mut long_words: Vec<usize> = (0..words.len())
+    .filter(|&i| words[i].len() >= MIN_VOWEL_STRIP_LENGTH && words[i].chars().all(|c| c.is_ascii_alphabetic()))
+    .collect();
+  long_words.sort_by_key(|&i| Reverse(words[i].len()));
+  for i in long_words {
+    words[i] = strip_vowels(&words[i]);
+    if fits(&words) {
+      return words.join(" ");
+    }
+  }
+
+  text.to_string()
+}
+
+/// Replace `{date}`, `{time}` and `{weekday}` in text with values for `now`
+/// (e.g. "may 5", "8:30 am", "monday"), with day and month names in `locale`.
+/// Other braces are left as they are.
+pub fn expand_placeholders(text: &str, now: DateTime<Local>, locale: Locale) -> String {
+  let placeholders = [
+    ("{date}", locale.format_date(&now)),
+    ("{time}", now.format("%-I:%M %p").to_string()),
+    ("{weekday}", locale.weekday_name(now.weekday()).to_string()),
+  ];
+  placeholders
+    .iter()
+    .fold(text.to_string(), |text, (placeholder, value)| text.replace(placeholder, &value.to_lowercase()))
+}
+
+/// Text widget output wrapped and centered for a board of the given size
+pub fn get_text(text: &str, size: BoardSize) -> Result<Vec<String>, VestaboardError> {
   log::debug!("Text widget starting with {} characters", text.len());
   // Widget just formats the message - validation happens at the main level
-  let formatted = widget_utils::format_message(text);
+  let formatted = widget_utils::format_message_for(&widget_utils::emoji_to_color_tiles(text), size);
   log::debug!("Text widget completed successfully, {} lines generated", formatted.len());
   Ok(formatted)
 }
 
+/// Text widget input: a plain string, or `{"message": "...", "border": "blue"}` to frame it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextInput {
+  pub message: String,
+  /// Color tile for a one-tile border around the board, if any
+  pub border: Option<char>,
+}
+
+/// Read the text widget's input, checking that the border (if any) is a color name.
+///
+/// The message can be a string, a number (shown as written, e.g. `42`), or either of those
+/// in a `{"message": ...}` object. Arrays, booleans, and objects without a message are
+/// rejected with a widget error naming what was given. A blank message is allowed here;
+/// `validate_widget_input` rejects it before it is saved.
+pub fn parse_text_input(input: &Value) -> Result<TextInput, VestaboardError> {
+  let (message, border) = match input {
+    Value::Object(fields) => match fields.get("message") {
+      Some(message) => (message, fields.get("border")),
+      None => {
+        return Err(VestaboardError::widget_error(
+          "text",
+          r#"Text input objects need a "message", e.g. {"message": "hello"}"#,
+        ))
+      },
+    },
+    _ => (input, None),
+  };
+  let message = match message {
+    Value::String(text) => text.clone(),
+    Value::Number(number) => number.to_string(),
+    Value::Null => String::new(),
+    other => {
+      let kind = match other {
+        Value::Array(_) => "a list",
+        Value::Object(_) => "an object",
+        _ => "true or false",
+      };
+      return Err(VestaboardError::widget_error(
+        "text",
+        &format!("Text input should be a string, a number, or {{\"message\": ...}}, not {}", kind),
+      ));
+    },
+  };
+  let border = match border {
+    None => None,
+    Some(name) => {
+      let name = name.as_str().unwrap_or_default();
+      Some(color_tile(name).ok_or_else(|| {
+        VestaboardError::validation_error(&format!(
+          "Unknown border color '{}'. Use red, orange, yellow, green, blue, violet, white, or black",
+          name
+        ))
+      })?)
+    },
+  };
+  Ok(TextInput { message, border })
+}
+
+/// Text framed by a one-tile `border` of color tiles, wrapped and centered in the rows and
+/// columns inside it (4x20 on a standard board).
+pub fn get_bordered_text(text: &str, border: char, size: BoardSize) -> Result<Vec<String>, VestaboardError> {
+  if size.rows < 3 || size.cols < 3 {
+    return Err(VestaboardError::validation_error("The board is too small for a border"));
+  }
+  let interior = BoardSize {
+    cols: size.cols - 2,
+    rows: size.rows - 2,
+  };
+  let lines = widget_utils::format_message_for(&widget_utils::emoji_to_color_tiles(text), interior);
+  if lines.len() > interior.rows {
+    return Err(VestaboardError::validation_error(&format!(
+      "Text needs {} lines, but only {} fit inside the border",
+      lines.len(),
+      interior.rows
+    )));
+  }
+
+  let edge = border.to_string().repeat(size.cols);
+  let mut bordered = vec![edge.clone()];
+  bordered.extend(
+    lines
+      .iter()
+      .map(|line| format!("{}{:^width$}{}", border, line, border, width = interior.cols)),
+  );
+  bordered.push(edge);
+  Ok(bordered)
+}
+
 pub fn get_text_from_file(file: PathBuf) -> Result<Vec<String>, VestaboardError> {
   log::debug!("File widget starting, reading from: {}", file.display());
   match fs::read_to_string(&file) {
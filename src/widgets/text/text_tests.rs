@@ -1,7 +1,14 @@
 #[cfg(test)]
 mod tests {
   use crate::errors::VestaboardError;
-  use crate::widgets::text::{get_text, get_text_from_file};
+  use crate::locale::Locale;
+  use crate::widgets::text::text::parse_abbreviations;
+  use crate::widgets::text::{
+    expand_placeholders, fit_text, fit_text_with, get_bordered_text, get_text, get_text_from_file, load_abbreviations,
+    parse_text_input, TextInput,
+  };
+  use crate::widgets::widget_utils::{BoardSize, STANDARD_BOARD};
+  use chrono::{Local, TimeZone};
   use std::io::Write;
   use std::path::PathBuf;
   use tempfile::NamedTempFile;
@@ -9,7 +16,7 @@ mod tests {
   #[test]
   fn test_get_text_success() {
     let text = "hello world"; // Use lowercase which is valid for Vestaboard
-    let result = get_text(text);
+    let result = get_text(text, STANDARD_BOARD);
 
     assert!(result.is_ok());
     let lines = result.unwrap();
@@ -25,7 +32,7 @@ mod tests {
     // Test with uppercase letters which are invalid for Vestaboard
     // The widget should format the message successfully - validation happens at main level
     let text = "Hello World"; // "H" and "W" are invalid uppercase characters
-    let result = get_text(text);
+    let result = get_text(text, STANDARD_BOARD);
 
     assert!(result.is_ok());
     let lines = result.unwrap();
@@ -88,7 +95,7 @@ mod tests {
   fn test_get_text_with_long_lines() {
     // Test text that will need to be wrapped (using lowercase)
     let long_text = "this is a very long line that should be wrapped because it exceeds the maximum length";
-    let result = get_text(long_text);
+    let result = get_text(long_text, STANDARD_BOARD);
 
     assert!(result.is_ok());
     let lines = result.unwrap();
@@ -99,4 +106,200 @@ mod tests {
       assert!(line.len() <= 22); // MAX_MESSAGE_LENGTH
     }
   }
+
+  #[test]
+  fn test_fit_text_squeezes_slight_overflow_onto_one_line() {
+    let text = "see you tomorrow at noon";
+    assert_eq!(text.len(), 24);
+    assert_eq!(
+      get_text(text, STANDARD_BOARD)
+        .unwrap()
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .count(),
+      2
+    );
+
+    let fitted = fit_text(text);
+    assert_eq!(fitted, "see you tmrw at noon");
+    assert_eq!(
+      get_text(&fitted, STANDARD_BOARD)
+        .unwrap()
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .count(),
+      1
+    );
+  }
+
+  #[test]
+  fn test_fit_text_strips_vowels_when_no_abbreviation_applies() {
+    assert_eq!(fit_text("remember the groceries ok"), "remember the grcrs ok");
+  }
+
+  #[test]
+  fn test_parse_abbreviations_skips_comments_and_malformed_lines() {
+    let abbreviations = parse_abbreviations("# units\nKilometers = km\n\nno separator\nempty=\n");
+    assert_eq!(abbreviations.len(), 1);
+    assert_eq!(abbreviations.get("kilometers").map(String::as_str), Some("km"));
+  }
+
+  #[test]
+  fn test_fit_text_with_abbreviations_file() {
+    let mut file = NamedTempFile::new().unwrap();
+    writeln!(file, "thermostat=tstat\ntomorrow=tmw").unwrap();
+    let abbreviations = load_abbreviations(file.path()).unwrap();
+
+    // Listed words use the file's short forms, ahead of the built-in ones
+    assert_eq!(fit_text_with("set the thermostat tonight", &abbreviations), "set the tstat tonight");
+    assert_eq!(fit_text_with("see you tomorrow at noon", &abbreviations), "see you tmw at noon");
+    // Unlisted words fall through to the default behavior
+    assert_eq!(fit_text_with("remember the groceries ok", &abbreviations), fit_text("remember the groceries ok"));
+  }
+
+  #[test]
+  fn test_load_abbreviations_missing_file() {
+    let result = load_abbreviations(&PathBuf::from("no/such/abbreviations.txt"));
+    assert!(matches!(result, Err(VestaboardError::IOError { .. })));
+  }
+
+  #[test]
+  fn test_fit_text_leaves_fitting_text_alone() {
+    assert_eq!(fit_text("see you tomorrow"), "see you tomorrow");
+  }
+
+  #[test]
+  fn test_fit_text_leaves_long_text_alone() {
+    let text = "the quick brown fox jumps over the lazy dog while the farmer watches from the porch \
+                and the cat sleeps on the warm windowsill all afternoon long";
+    assert_eq!(fit_text(text), text);
+  }
+
+  #[test]
+  fn test_get_text_renders_colored_squares_as_tiles() {
+    let lines = get_text("🟥🟥🟥", STANDARD_BOARD).unwrap();
+    assert!(lines.iter().any(|line| line.trim() == "RRR"));
+    assert!(crate::api_broker::validate_message_content(&lines).is_ok());
+  }
+
+  #[test]
+  fn test_get_text_small_board() {
+    let size = BoardSize { cols: 10, rows: 4 };
+    let lines = get_text("see you at the park", size).unwrap();
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines.iter().all(|line| line.chars().count() <= 10));
+    assert_eq!(lines.join(" ").split_whitespace().collect::<Vec<_>>(), vec!["see", "you", "at", "the", "park"]);
+  }
+
+  #[test]
+  fn test_expand_placeholders() {
+    let now = Local.with_ymd_and_hms(2025, 5, 5, 8, 30, 0).unwrap();
+    assert_eq!(expand_placeholders("today is {date}", now, Locale::English), "today is may 5");
+    assert_eq!(expand_placeholders("it is {time}", now, Locale::English), "it is 8:30 am");
+    assert_eq!(expand_placeholders("happy {weekday}!", now, Locale::English), "happy monday!");
+    assert_eq!(expand_placeholders("{weekday} {date} {time}", now, Locale::English), "monday may 5 8:30 am");
+  }
+
+  #[test]
+  fn test_expand_placeholders_passes_other_text_through() {
+    let now = Local.with_ymd_and_hms(2025, 5, 5, 20, 5, 0).unwrap();
+    assert_eq!(expand_placeholders("hello world", now, Locale::English), "hello world");
+    assert_eq!(expand_placeholders("see you {tomorrow}", now, Locale::English), "see you {tomorrow}");
+    assert_eq!(expand_placeholders("{time}", now, Locale::English), "8:05 pm");
+  }
+
+  #[test]
+  fn test_expand_placeholders_uses_locale_names() {
+    let now = Local.with_ymd_and_hms(2025, 5, 5, 8, 30, 0).unwrap();
+    assert_eq!(expand_placeholders("{weekday} {date}", now, Locale::Spanish), "lunes 5 may");
+    assert_eq!(expand_placeholders("{weekday} {date}", now, Locale::French), "lundi 5 mai");
+  }
+
+  #[test]
+  fn test_parse_text_input_with_border() {
+    assert_eq!(
+      parse_text_input(&serde_json::json!("hello")).unwrap(),
+      TextInput {
+        message: "hello".to_string(),
+        border: None
+      }
+    );
+    assert_eq!(
+      parse_text_input(&serde_json::json!({"message": "hello", "border": "Blue"})).unwrap(),
+      TextInput {
+        message: "hello".to_string(),
+        border: Some('B')
+      }
+    );
+
+    let error = parse_text_input(&serde_json::json!({"message": "hello", "border": "plaid"})).unwrap_err();
+    assert!(error.to_string().contains("Unknown border color 'plaid'"));
+    assert!(parse_text_input(&serde_json::json!({"border": "blue"})).is_err());
+  }
+
+  #[test]
+  fn test_parse_text_input_coerces_numbers_and_message_objects() {
+    let message = |input: serde_json::Value| parse_text_input(&input).unwrap().message;
+    assert_eq!(message(serde_json::json!("hello")), "hello");
+    assert_eq!(message(serde_json::json!(42)), "42");
+    assert_eq!(message(serde_json::json!(-3.5)), "-3.5");
+    assert_eq!(message(serde_json::json!({"message": "hello"})), "hello");
+    assert_eq!(message(serde_json::json!({"message": 7})), "7");
+  }
+
+  #[test]
+  fn test_parse_text_input_rejects_unsupported_shapes() {
+    let widget_error = |input: serde_json::Value| match parse_text_input(&input) {
+      Err(VestaboardError::WidgetError { widget, message }) => {
+        assert_eq!(widget, "text");
+        message
+      },
+      other => panic!("Expected a widget error for {}, got {:?}", input, other),
+    };
+    assert!(widget_error(serde_json::json!(["a", "b"])).contains("not a list"));
+    assert!(widget_error(serde_json::json!(true)).contains("not true or false"));
+    assert!(widget_error(serde_json::json!({"message": {"text": "hi"}})).contains("not an object"));
+    assert!(widget_error(serde_json::json!({"text": "hi"})).contains(r#"need a "message""#));
+
+    // Missing input still renders as a blank message; validate_widget_input rejects it
+    // before it is saved
+    for input in [
+      serde_json::json!(null),
+      serde_json::json!(""),
+      serde_json::json!({"message": null}),
+    ] {
+      assert_eq!(parse_text_input(&input).unwrap().message, "");
+    }
+  }
+
+  #[test]
+  fn test_bordered_text_frames_the_board() {
+    let lines = get_bordered_text("happy birthday", 'B', STANDARD_BOARD).unwrap();
+
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[0], "B".repeat(22));
+    assert_eq!(lines[5], "B".repeat(22));
+    for line in &lines[1..5] {
+      assert_eq!(line.chars().count(), 22);
+      assert!(line.starts_with('B') && line.ends_with('B'));
+    }
+    // One line of text, centered in the 4x20 interior
+    assert_eq!(lines[1], format!("B{}B", " ".repeat(20)));
+    assert_eq!(lines[2], "B   happy birthday   B");
+    assert!(crate::api_broker::validate_message_content(&lines).is_ok());
+  }
+
+  #[test]
+  fn test_bordered_text_wraps_to_the_interior_width() {
+    // 21 characters would fit one row without a border, but not in the 20-tile interior
+    let lines = get_bordered_text("congratulations class", 'G', STANDARD_BOARD).unwrap();
+    assert_eq!(lines[2], "G  congratulations   G");
+    assert_eq!(lines[3], "G       class        G");
+
+    // Wraps to 5 rows of at most 20 characters, one more than the border leaves room for
+    let too_long = "happy birthday to the best friend anyone could ask for, have a wonderful day";
+    let error = get_bordered_text(too_long, 'G', STANDARD_BOARD).unwrap_err();
+    assert_eq!(error.to_user_message(), "Text needs 5 lines, but only 4 fit inside the border");
+  }
 }
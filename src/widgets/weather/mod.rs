@@ -1,2 +1,7 @@
 pub mod weather;
-pub use weather::get_weather;
+pub use weather::{
+  get_weather, parse_weather_lines, parse_weather_mode, Units, WeatherCache, WeatherLine, WeatherMode,
+};
+
+#[cfg(test)]
+pub mod weather_tests;
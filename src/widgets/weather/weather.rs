@@ -1,12 +1,20 @@
-use dotenv::dotenv;
+use chrono::{DateTime, Duration, NaiveDateTime, Timelike, Utc};
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json;
-use std::env;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 
+use crate::api::common::{read_checked, send_with_retry, MAX_RETRY_AFTER};
+use crate::api::retry::NETWORK_RETRY;
 use crate::cli_display::print_error;
 use crate::errors::VestaboardError;
-use crate::widgets::widget_utils::{center_line, center_message, full_justify_line, split_into_lines, WidgetOutput};
+use crate::secrets::secret;
+use crate::widgets::widget_utils::{
+  center_line, center_message, full_justify_line, split_into_lines, WidgetOutput, MAX_MESSAGE_HEIGHT,
+  MAX_MESSAGE_LENGTH,
+};
 
 // reference: https://www.weatherapi.com/api-explorer.aspx#forecast
 
@@ -17,90 +25,37 @@ struct WeatherResponse {
   forecast: Forecast,
 }
 
+/// Reply from the current-conditions endpoint, used when the forecast is unavailable
+#[derive(Deserialize, Debug)]
+struct CurrentResponse {
+  current: Current,
+  location: Location,
+}
+
 #[derive(Deserialize, Debug)]
 struct Location {
-  #[allow(dead_code)]
-  name: String,
-  #[allow(dead_code)]
-  region: String,
-  #[allow(dead_code)]
   country: String,
-  #[allow(dead_code)]
-  lat: f64,
-  #[allow(dead_code)]
-  lon: f64,
-  #[allow(dead_code)]
-  tz_id: String,
-  #[allow(dead_code)]
-  localtime_epoch: i64,
   localtime: String,
 }
 
 #[derive(Deserialize, Debug)]
-struct Current {
-  #[allow(dead_code)]
-  last_updated_epoch: i64,
-  #[allow(dead_code)]
+pub(crate) struct Current {
   last_updated: String,
-  #[allow(dead_code)]
   temp_c: f64,
   temp_f: f64,
-  #[allow(dead_code)]
-  is_day: i32,
   condition: Condition,
-  #[allow(dead_code)]
   wind_kph: f64,
-  #[allow(dead_code)]
   wind_mph: f64,
-  #[allow(dead_code)]
-  wind_degree: i32,
-  #[allow(dead_code)]
   wind_dir: String,
   pressure_in: f64,
-  #[allow(dead_code)]
-  pressure_mb: f64,
-  #[allow(dead_code)]
-  precip_in: f64,
-  #[allow(dead_code)]
-  precip_mm: f64,
-  #[allow(dead_code)]
   humidity: i32,
-  #[allow(dead_code)]
-  cloud: i32,
-  #[allow(dead_code)]
   feelslike_c: f64,
-  #[allow(dead_code)]
   feelslike_f: f64,
-  #[allow(dead_code)]
-  windchill_c: f64,
-  #[allow(dead_code)]
-  windchill_f: f64,
-  #[allow(dead_code)]
-  heatindex_c: f64,
-  #[allow(dead_code)]
-  heatindex_f: f64,
-  #[allow(dead_code)]
-  dewpoint_c: f64,
-  #[allow(dead_code)]
-  dewpoint_f: f64,
-  #[allow(dead_code)]
-  vis_km: f64,
-  #[allow(dead_code)]
-  vis_miles: f64,
-  #[allow(dead_code)]
-  uv: f64,
-  #[allow(dead_code)]
-  gust_kph: f64,
-  #[allow(dead_code)]
-  gust_mph: f64,
 }
 
 #[derive(Deserialize, Debug)]
 struct Condition {
   text: String,
-  #[allow(dead_code)]
-  icon: String,
-  #[allow(dead_code)]
   code: i32,
 }
 
@@ -111,275 +66,856 @@ struct Forecast {
 
 #[derive(Deserialize, Debug)]
 struct ForecastDay {
-  #[allow(dead_code)]
-  astro: Astro,
-  #[allow(dead_code)]
-  date: String,
-  #[allow(dead_code)]
-  date_epoch: i64,
   day: Day,
-  #[allow(dead_code)]
   hour: Vec<Hour>,
 }
 
-#[derive(Deserialize, Debug)]
-struct Astro {
-  #[allow(dead_code)]
-  sunrise: String,
-  #[allow(dead_code)]
-  sunset: String,
-  #[allow(dead_code)]
-  moonrise: String,
-  #[allow(dead_code)]
-  moonset: String,
-  #[allow(dead_code)]
-  moon_phase: String,
-  #[allow(dead_code)]
-  moon_illumination: i32,
-  #[allow(dead_code)]
-  is_moon_up: i32,
-  #[allow(dead_code)]
-  is_sun_up: i32,
-}
-
 #[derive(Deserialize, Debug)]
 struct Day {
-  #[allow(dead_code)]
   maxtemp_c: f64,
   maxtemp_f: f64,
-  #[allow(dead_code)]
   mintemp_c: f64,
   mintemp_f: f64,
-  #[allow(dead_code)]
-  avgtemp_c: f64,
-  #[allow(dead_code)]
-  avgtemp_f: f64,
-  #[allow(dead_code)]
-  maxwind_kph: f64,
-  #[allow(dead_code)]
-  maxwind_mph: f64,
   totalprecip_in: f64,
-  #[allow(dead_code)]
   totalprecip_mm: f64,
-  #[allow(dead_code)]
-  totalsnow_cm: f64,
-  #[allow(dead_code)]
-  avgvis_km: f64,
-  #[allow(dead_code)]
-  avgvis_miles: f64,
-  #[allow(dead_code)]
-  avghumidity: i32,
-  #[allow(dead_code)]
-  daily_will_it_rain: i32,
-  #[allow(dead_code)]
   daily_chance_of_rain: i32,
-  #[allow(dead_code)]
-  daily_will_it_snow: i32,
-  #[allow(dead_code)]
-  daily_chance_of_snow: i32,
-  #[allow(dead_code)]
-  condition: Condition,
-  #[allow(dead_code)]
-  uv: f64,
 }
 
 #[derive(Deserialize, Debug)]
-struct Hour {
-  #[allow(dead_code)]
-  time_epoch: i64,
-  #[allow(dead_code)]
+pub(crate) struct Hour {
   time: String,
-  #[allow(dead_code)]
-  temp_c: f64,
-  #[allow(dead_code)]
-  temp_f: f64,
-  #[allow(dead_code)]
-  is_day: i32,
-  #[allow(dead_code)]
-  condition: Condition,
-  #[allow(dead_code)]
-  wind_kph: f64,
-  #[allow(dead_code)]
-  wind_mph: f64,
-  #[allow(dead_code)]
-  wind_degree: i32,
-  #[allow(dead_code)]
-  wind_dir: String,
-  #[allow(dead_code)]
   pressure_in: f64,
-  #[allow(dead_code)]
-  pressure_mb: f64,
-  #[allow(dead_code)]
-  precip_in: f64,
-  #[allow(dead_code)]
-  precip_mm: f64,
-  #[allow(dead_code)]
-  snow_cm: f64,
-  #[allow(dead_code)]
-  humidity: i32,
-  #[allow(dead_code)]
-  cloud: i32,
-  #[allow(dead_code)]
-  feelslike_c: f64,
-  #[allow(dead_code)]
-  feelslike_f: f64,
-  #[allow(dead_code)]
-  windchill_c: f64,
-  #[allow(dead_code)]
-  windchill_f: f64,
-  #[allow(dead_code)]
-  heatindex_c: f64,
-  #[allow(dead_code)]
-  heatindex_f: f64,
-  #[allow(dead_code)]
-  dewpoint_c: f64,
-  #[allow(dead_code)]
-  dewpoint_f: f64,
-  #[allow(dead_code)]
-  will_it_rain: i32,
-  #[allow(dead_code)]
   chance_of_rain: i32,
-  #[allow(dead_code)]
-  will_it_snow: i32,
-  #[allow(dead_code)]
-  chance_of_snow: i32,
-  #[allow(dead_code)]
-  vis_km: f64,
-  #[allow(dead_code)]
-  vis_miles: f64,
-  #[allow(dead_code)]
-  gust_kph: f64,
-  #[allow(dead_code)]
-  gust_mph: f64,
-  #[allow(dead_code)]
-  uv: f64,
-}
-
-pub async fn get_weather() -> Result<WidgetOutput, VestaboardError> {
-  let start_time = std::time::Instant::now();
-  log::info!("Weather widget starting");
+}
+
+/// Map a weatherapi.com condition code to a Vestaboard color tile.
+///
+/// Returns `None` for conditions without an obvious color (clouds, fog, unknown codes).
+/// Reference: https://www.weatherapi.com/docs/weather_conditions.json
+pub fn condition_accent(code: i32) -> Option<char> {
+  match code {
+    1000 => Some('Y'),                                                         // sunny / clear
+    1063 | 1150..=1201 | 1240..=1246 => Some('B'),                             // drizzle and rain
+    1066 | 1069 | 1072 | 1114 | 1117 | 1204..=1237 | 1249..=1264 => Some('W'), // snow, sleet and ice
+    1087 | 1273..=1282 => Some('V'),                                           // thunder
+    _ => None,
+  }
+}
+
+/// Measurement system used for weather temperatures and rainfall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+  /// Fahrenheit and inches
+  Imperial,
+  /// Celsius and millimeters
+  Metric,
+  /// Fahrenheit and Celsius together (e.g. `72f/22c`), with other measurements imperial
+  Both,
+}
+
+/// Default units for a country name as returned by weatherapi.com (`location.country`).
+///
+/// The US and the few other countries that still use Fahrenheit get imperial; everyone else metric.
+pub fn units_for_country(country: &str) -> Units {
+  match country.trim().to_lowercase().as_str() {
+    "united states of america" | "united states" | "usa" | "us" | "liberia" | "myanmar" => Units::Imperial,
+    _ => Units::Metric,
+  }
+}
+
+/// Pick the units to display: an explicit setting wins, otherwise go by the location's country.
+pub fn resolve_units(configured: Option<Units>, country: &str) -> Units {
+  configured.unwrap_or_else(|| units_for_country(country))
+}
+
+/// What the weather widget shows, picked with its input (`{"mode": "feels"}` or `{"lines": [...]}`).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum WeatherMode {
+  /// Conditions, temperatures, rain and pressure
+  #[default]
+  Standard,
+  /// Standard, with a "feels like" temperature and humidity line in place of one summary line
+  FeelsLike,
+  /// Standard, with the chance of rain for the morning, afternoon, and evening in place of one summary line
+  RainTrend,
+  /// Standard, with a sparkline of the forecast's daily highs and their labels in place of a
+  /// summary line and the pressure
+  Trend,
+  /// The named lines in order, trimmed to the board's 6 rows
+  Lines(Vec<WeatherLine>),
+  /// A single centered row with the temperature, condition, and chance of rain, to sit alongside
+  /// other content
+  Compact,
+}
+
+/// One named line (or group of lines) in a custom weather layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherLine {
+  /// Local time, or the age of cached data
+  Time,
+  /// Current, low, and high temperatures
+  Temps,
+  /// Current conditions, with a color tile when there is one
+  Condition,
+  /// Chance and amount of rain today (no rows when it's dry)
+  Rain,
+  /// Condition and rain together, as in the standard view
+  Summary,
+  /// "Feels like" temperature and humidity
+  Feels,
+  /// Chance of rain in the morning, afternoon, and evening
+  RainTrend,
+  /// Wind speed and direction
+  Wind,
+  /// Sparkline of the forecast's daily highs, with their temperatures on the row below
+  Trend,
+  /// Current pressure and the pressure forecast
+  Pressure,
+}
+
+/// Names accepted for each `WeatherLine`, in the order they're listed in errors
+pub const WEATHER_LINE_NAMES: &[&str] = &[
+  "time",
+  "temps",
+  "condition",
+  "rain",
+  "summary",
+  "feels",
+  "rain-trend",
+  "wind",
+  "trend",
+  "pressure",
+];
+
+impl WeatherLine {
+  /// Parse a line name (case-insensitive), or None if it isn't one of `WEATHER_LINE_NAMES`.
+  pub fn parse(name: &str) -> Option<Self> {
+    match name.trim().to_lowercase().as_str() {
+      "time" => Some(WeatherLine::Time),
+      "temps" => Some(WeatherLine::Temps),
+      "condition" => Some(WeatherLine::Condition),
+      "rain" => Some(WeatherLine::Rain),
+      "summary" => Some(WeatherLine::Summary),
+      "feels" => Some(WeatherLine::Feels),
+      "rain-trend" => Some(WeatherLine::RainTrend),
+      "wind" => Some(WeatherLine::Wind),
+      "trend" => Some(WeatherLine::Trend),
+      "pressure" => Some(WeatherLine::Pressure),
+      _ => None,
+    }
+  }
+}
+
+/// Parse a list of line names, naming the first one that isn't recognized.
+pub fn parse_weather_lines<S: AsRef<str>>(names: &[S]) -> Result<Vec<WeatherLine>, VestaboardError> {
+  names
+    .iter()
+    .map(|name| {
+      WeatherLine::parse(name.as_ref()).ok_or_else(|| {
+        VestaboardError::validation_error(&format!(
+          "Unknown weather line '{}'. Use any of: {}",
+          name.as_ref(),
+          WEATHER_LINE_NAMES.join(", ")
+        ))
+      })
+    })
+    .collect()
+}
+
+/// Read the weather widget's input: null (or no mode) is the standard view, a `lines` list picks
+/// a custom layout, and `{"compact": true}` picks the one-row summary.
+pub fn parse_weather_mode(input: &serde_json::Value) -> Result<WeatherMode, VestaboardError> {
+  if let Some(compact) = input.get("compact") {
+    if input.get("mode").is_some() || input.get("lines").is_some() {
+      return Err(VestaboardError::validation_error(
+        "Weather input can have compact, a mode, or lines, not more than one",
+      ));
+    }
+    match compact.as_bool() {
+      Some(true) => return Ok(WeatherMode::Compact),
+      Some(false) => return Ok(WeatherMode::Standard),
+      None => return Err(VestaboardError::validation_error("Weather compact should be true or false")),
+    }
+  }
+  if let Some(lines) = input.get("lines") {
+    if input.get("mode").is_some() {
+      return Err(VestaboardError::validation_error("Weather input can have a mode or lines, not both"));
+    }
+    let names = lines
+      .as_array()
+      .and_then(|names| names.iter().map(serde_json::Value::as_str).collect::<Option<Vec<_>>>())
+      .ok_or_else(|| {
+        VestaboardError::validation_error(r#"Weather lines should be a list of names, e.g. ["time", "temps"]"#)
+      })?;
+    return parse_weather_lines(&names).map(WeatherMode::Lines);
+  }
+  let mode = match input {
+    serde_json::Value::Null => None,
+    serde_json::Value::Object(fields) => fields.get("mode").and_then(serde_json::Value::as_str),
+    _ => Some(""),
+  };
+  match mode.map(|m| m.trim().to_lowercase()).as_deref() {
+    None | Some("standard") => Ok(WeatherMode::Standard),
+    Some("feels") | Some("feels-like") => Ok(WeatherMode::FeelsLike),
+    Some("rain") | Some("rain-trend") => Ok(WeatherMode::RainTrend),
+    Some("trend") | Some("sparkline") => Ok(WeatherMode::Trend),
+    _ => Err(VestaboardError::validation_error(
+      r#"Weather input should be empty, {"mode": "feels"} to add feels like and humidity, {"mode": "rain"} to add the chance of rain through the day, or {"mode": "trend"} to add the trend of daily highs"#,
+    )),
+  }
+}
+
+/// One row of weather: temperature, condition, and chance of rain (e.g. `72f sunny 60%rain`).
+///
+/// The rain is left out when the chance is zero. When the row would run past the board, the
+/// condition keeps as many whole words as fit, then is cut short, then dropped.
+pub fn compact_weather_line(temp: &str, condition: &str, rain_chance: i32) -> String {
+  let rain = (rain_chance > 0).then(|| format!("{}%rain", rain_chance));
+  let fixed = [Some(temp), rain.as_deref()]
+    .into_iter()
+    .flatten()
+    .map(|part| part.chars().count() + 1)
+    .sum::<usize>();
+  let room = MAX_MESSAGE_LENGTH.saturating_sub(fixed);
+
+  let mut short = String::new();
+  for word in condition.split_whitespace() {
+    let candidate = if short.is_empty() {
+      word.to_string()
+    } else {
+      format!("{} {}", short, word)
+    };
+    if candidate.chars().count() > room {
+      if short.is_empty() {
+        short = word.chars().take(room).collect();
+      }
+      break;
+    }
+    short = candidate;
+  }
+
+  [
+    Some(temp),
+    Some(short.as_str()).filter(|s| !s.is_empty()),
+    rain.as_deref(),
+  ]
+  .into_iter()
+  .flatten()
+  .collect::<Vec<_>>()
+  .join(" ")
+  .chars()
+  .take(MAX_MESSAGE_LENGTH)
+  .collect()
+}
+
+/// The "feels like" temperature and humidity, justified across one line (e.g. `feels 72D     humid 40%`).
+pub(crate) fn feels_like_line(current: &Current, units: Units) -> String {
+  let feels = match units {
+    Units::Imperial => current.feelslike_f,
+    Units::Metric => current.feelslike_c,
+    Units::Both => {
+      // Both scales only when they leave a gap before the humidity
+      let feels = format!("feels {}", dual_temp(current.feelslike_f, current.feelslike_c));
+      let humidity = format!("humid {}%", current.humidity);
+      if feels.chars().count() + humidity.chars().count() < MAX_MESSAGE_LENGTH {
+        return full_justify_line(feels, humidity);
+      }
+      current.feelslike_f
+    },
+  };
+  full_justify_line(format!("feels {:.0}D", feels), format!("humid {}%", current.humidity))
+}
+
+/// Wind speed and direction, justified across one line (e.g. `wind 12mph          nnw`).
+pub(crate) fn wind_line(current: &Current, units: Units) -> String {
+  let speed = match units {
+    Units::Imperial | Units::Both => format!("wind {:.0}mph", current.wind_mph),
+    Units::Metric => format!("wind {:.0}kph", current.wind_kph),
+  };
+  full_justify_line(speed, current.wind_dir.to_lowercase())
+}
+
+/// Highest hourly chance of rain (percent) in each part of a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RainTrend {
+  /// 6am to noon
+  pub morning: i32,
+  /// Noon to 6pm
+  pub afternoon: i32,
+  /// 6pm to midnight
+  pub evening: i32,
+}
+
+/// Bucket a day's hourly forecast into morning, afternoon, and evening, keeping the highest
+/// chance of rain in each. Hours before 6am, or with an unreadable time, are left out.
+pub(crate) fn rain_trend(hours: &[Hour]) -> RainTrend {
+  let mut trend = RainTrend::default();
+  for hour in hours {
+    let Ok(time) = NaiveDateTime::parse_from_str(&hour.time, "%Y-%m-%d %H:%M") else {
+      continue;
+    };
+    let bucket = match time.hour() {
+      6..=11 => &mut trend.morning,
+      12..=17 => &mut trend.afternoon,
+      18..=23 => &mut trend.evening,
+      _ => continue,
+    };
+    *bucket = (*bucket).max(hour.chance_of_rain);
+  }
+  trend
+}
+
+/// The rain trend on one line (e.g. `am 10% pm 60% eve 30%`), dropping the spaces after
+/// the labels if it would otherwise be too wide.
+pub fn rain_trend_line(trend: &RainTrend) -> String {
+  let line = format!("am {}% pm {}% eve {}%", trend.morning, trend.afternoon, trend.evening);
+  if line.chars().count() <= MAX_MESSAGE_LENGTH {
+    return center_line(line);
+  }
+  full_justify_line(format!("am{}% pm{}%", trend.morning, trend.afternoon), format!("eve{}%", trend.evening))
+}
+
+/// Color tiles for the temperature sparkline, from the coolest high to the warmest
+const SPARKLINE_TILES: [char; 6] = ['V', 'B', 'G', 'Y', 'O', 'R'];
+
+/// Spread daily highs across `width` columns as color tiles, coolest to warmest.
+///
+/// Columns between two days are interpolated, and the lowest and highest values map to the
+/// first and last tiles. When every high is the same (or there is only one day), the whole
+/// row is the middle tile.
+pub fn temperature_sparkline(highs: &[f64], width: usize) -> String {
+  if highs.is_empty() {
+    return String::new();
+  }
+  let low = highs.iter().copied().fold(f64::INFINITY, f64::min);
+  let high = highs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+  let steps = (SPARKLINE_TILES.len() - 1) as f64;
+  (0..width)
+    .map(|column| {
+      if high <= low {
+        return SPARKLINE_TILES[SPARKLINE_TILES.len() / 2];
+      }
+      // Position of this column along the days, from 0 to the last day
+      let position = match width {
+        1 => 0.0,
+        _ => column as f64 * (highs.len() - 1) as f64 / (width - 1) as f64,
+      };
+      let day = (position.floor() as usize).min(highs.len() - 1);
+      let next = (day + 1).min(highs.len() - 1);
+      let value = highs[day] + (highs[next] - highs[day]) * (position - day as f64);
+      SPARKLINE_TILES[((value - low) / (high - low) * steps).round() as usize]
+    })
+    .collect()
+}
+
+/// Each day's high (e.g. `84D`) under its place in the sparkline: the first at the left edge,
+/// the last at the right, and the rest in between. A label that would run into the one
+/// before it is left out.
+pub fn sparkline_labels(highs: &[f64], width: usize) -> String {
+  let mut row = vec![' '; width];
+  let mut next_free = 0;
+  for (i, high) in highs.iter().enumerate() {
+    let label: Vec<char> = format!("{:.0}D", high).chars().collect();
+    if label.len() > width {
+      continue;
+    }
+    let anchor = match highs.len() {
+      1 => width / 2,
+      n => i * (width - 1) / (n - 1),
+    };
+    let start = anchor.saturating_sub(label.len() / 2).min(width - label.len());
+    if start < next_free {
+      continue;
+    }
+    row[start..start + label.len()].copy_from_slice(&label);
+    next_free = start + label.len() + 1;
+  }
+  row.into_iter().collect()
+}
+
+/// The forecast's daily highs in `units`, today first
+fn daily_highs(json: &WeatherResponse, units: Units) -> Vec<f64> {
+  json
+    .forecast
+    .forecastday
+    .iter()
+    .map(|day| match units {
+      Units::Imperial | Units::Both => day.day.maxtemp_f,
+      Units::Metric => day.day.maxtemp_c,
+    })
+    .collect()
+}
+
+/// The sparkline row and its label row for the forecast's daily highs
+fn trend_lines(json: &WeatherResponse, units: Units) -> Vec<String> {
+  let highs = daily_highs(json, units);
+  vec![
+    temperature_sparkline(&highs, MAX_MESSAGE_LENGTH),
+    sparkline_labels(&highs, MAX_MESSAGE_LENGTH),
+  ]
+}
+
+/// Weather cache settings: a response younger than `max_age` is reused instead of calling
+/// the API, and one older than `stale_after` is marked on the board with its age.
+#[derive(Debug, Clone)]
+pub struct WeatherCache {
+  pub path: PathBuf,
+  pub max_age: Duration,
+  pub stale_after: Duration,
+}
+
+/// A weather API response saved to the cache file
+#[derive(Serialize, Deserialize, Debug)]
+struct CachedWeather {
+  location: String,
+  fetched_at: DateTime<Utc>,
+  response: String,
+}
 
-  dotenv().ok();
-  let weather_api_key = env::var("WEATHER_API_KEY").map_err(|e| {
-    log::error!("WEATHER_API_KEY environment variable not set: {}", e);
+/// Load the cached response for `location`, if there is one.
+fn load_cached(path: &Path, location: &str) -> Option<CachedWeather> {
+  let content = fs::read_to_string(path).ok()?;
+  serde_json::from_str::<CachedWeather>(&content)
+    .ok()
+    .filter(|cached| cached.location == location)
+}
+
+/// Save a response to the cache (best-effort; a failed write only costs an API call later).
+fn save_cached(path: &Path, location: &str, response: &str) {
+  let cached = CachedWeather {
+    location: location.to_string(),
+    fetched_at: Utc::now(),
+    response: response.to_string(),
+  };
+  let written = path
+    .parent()
+    .map_or(Ok(()), fs::create_dir_all)
+    .map_err(|e| e.to_string())
+    .and_then(|_| serde_json::to_string(&cached).map_err(|e| e.to_string()))
+    .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+  if let Err(e) = written {
+    log::warn!("Failed to save weather cache to {}: {}", path.display(), e);
+  }
+}
+
+/// How long ago something happened, for the board: "~15m ago", "~3h ago", "~2d ago".
+pub fn format_age(age: Duration) -> String {
+  let minutes = age.num_minutes().max(1);
+  if minutes < 60 {
+    format!("~{}m ago", minutes)
+  } else if minutes < 48 * 60 {
+    format!("~{}h ago", minutes / 60)
+  } else {
+    format!("~{}d ago", minutes / (24 * 60))
+  }
+}
+
+/// Top line for weather that is older than `stale_after`: the response's `last_updated`
+/// clock time and the cache age (e.g. "2:05 pm ~15m ago"). `None` while the data is fresh.
+pub fn staleness_line(last_updated: &str, age: Duration, stale_after: Duration) -> Option<String> {
+  if age < stale_after {
+    return None;
+  }
+  match NaiveDateTime::parse_from_str(last_updated, "%Y-%m-%d %H:%M") {
+    Ok(updated) => Some(format!("{} {}", updated.format("%-I:%M %p").to_string().to_lowercase(), format_age(age))),
+    Err(_) => Some(format!("as of {}", format_age(age))),
+  }
+}
+
+/// Which weatherapi.com endpoint to call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WeatherEndpoint {
+  /// Current conditions plus a 3-day forecast
+  Forecast,
+  /// Current conditions only
+  Current,
+}
+
+/// Fetch the response text from one weatherapi.com endpoint: the 3-day forecast, or current
+/// conditions only.
+async fn fetch_weather(location: &str, endpoint: WeatherEndpoint) -> Result<String, VestaboardError> {
+  let weather_api_key = secret("WEATHER_API_KEY").ok_or_else(|| {
+    log::error!("WEATHER_API_KEY environment variable not set");
     VestaboardError::config_error("WEATHER_API_KEY", "Environment variable not set")
   })?;
 
   log::debug!("Weather API key found");
 
   let client = Client::new();
-  #[allow(unused_variables)]
-  let url_current = format!("https://api.weatherapi.com/v1/current.json?key={}&q=austin", weather_api_key,);
-  let url_forecast = format!(
-    "https://api.weatherapi.com/v1/forecast.json?key={}&q=austin&days=3&aqi=no&alerts=no",
-    weather_api_key,
-  );
+  let key = weather_api_key.as_str();
+  // Let reqwest encode the query, so locations with spaces or commas ("new york, ny") survive
+  let (url, query, context) = match endpoint {
+    WeatherEndpoint::Forecast => (
+      "https://api.weatherapi.com/v1/forecast.json",
+      vec![
+        ("key", key),
+        ("q", location),
+        ("days", "3"),
+        ("aqi", "no"),
+        ("alerts", "no"),
+      ],
+      "requesting weather forecast",
+    ),
+    WeatherEndpoint::Current => (
+      "https://api.weatherapi.com/v1/current.json",
+      vec![("key", key), ("q", location), ("aqi", "no")],
+      "requesting current weather",
+    ),
+  };
+
+  log::debug!("Making weather API request to {} for {}", url, location);
+
+  let response = send_with_retry(|| client.get(url).query(&query), &NETWORK_RETRY, MAX_RETRY_AFTER)
+    .await
+    .map_err(|e| {
+      log::error!("Weather API request failed: {}", e);
+      VestaboardError::reqwest_error(e, context)
+    })?;
+
+  log::debug!("Weather API response status: {}", response.status());
+
+  read_checked(response, "Weather API").await
+}
+
+/// A weather API reply: the full forecast, or only current conditions when the forecast failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WeatherData {
+  Forecast(String),
+  CurrentOnly(String),
+}
 
-  log::debug!("Making weather API request to: {}", url_forecast.replace(&weather_api_key, "***"),); // Hide API key in logs
+/// Run `forecast`, and if it fails, `current` instead. When both fail, the forecast's error
+/// is returned.
+pub(crate) async fn fetch_with_fallback<F, FFut, C, CFut>(
+  forecast: F,
+  current: C,
+) -> Result<WeatherData, VestaboardError>
+where
+  F: FnOnce() -> FFut,
+  FFut: Future<Output = Result<String, VestaboardError>>,
+  C: FnOnce() -> CFut,
+  CFut: Future<Output = Result<String, VestaboardError>>,
+{
+  let forecast_error = match forecast().await {
+    Ok(text) => return Ok(WeatherData::Forecast(text)),
+    Err(e) => e,
+  };
+  log::warn!("Weather forecast failed ({}), trying current conditions only", forecast_error);
+  match current().await {
+    Ok(text) => {
+      log::warn!("Showing current weather without the forecast");
+      Ok(WeatherData::CurrentOnly(text))
+    },
+    Err(e) => {
+      log::error!("Current weather failed too: {}", e);
+      Err(forecast_error)
+    },
+  }
+}
 
-  let response = client.get(&url_forecast).send().await.map_err(|e| {
-    log::error!("Weather API request failed: {}", e);
-    let error = VestaboardError::reqwest_error(e, "requesting weather forecast");
+/// Get the weather for `location`, using the cache when one is configured.
+///
+/// A fresh cached response is used without calling the API. If the API call fails,
+/// an older cached response is shown instead, marked with its age.
+pub async fn get_weather(
+  location: &str,
+  units: Option<Units>,
+  mode: WeatherMode,
+  cache: Option<&WeatherCache>,
+) -> Result<WidgetOutput, VestaboardError> {
+  get_weather_with(location, units, mode, cache, || {
+    fetch_with_fallback(
+      || fetch_weather(location, WeatherEndpoint::Forecast),
+      || fetch_weather(location, WeatherEndpoint::Current),
+    )
+  })
+  .await
+}
+
+/// `get_weather` with the API call made by `fetch`
+pub(crate) async fn get_weather_with<F, Fut>(
+  location: &str,
+  units: Option<Units>,
+  mode: WeatherMode,
+  cache: Option<&WeatherCache>,
+  fetch: F,
+) -> Result<WidgetOutput, VestaboardError>
+where
+  F: FnOnce() -> Fut,
+  Fut: Future<Output = Result<WeatherData, VestaboardError>>,
+{
+  let start_time = std::time::Instant::now();
+  log::info!("Weather widget starting");
+
+  let now = Utc::now();
+  let cached = cache.and_then(|c| load_cached(&c.path, location).map(|cached| (c, cached)));
+  if let Some((settings, cached)) = &cached {
+    let age = now - cached.fetched_at;
+    if age < settings.max_age {
+      log::info!("Using cached weather from {:?} ago", age.to_std().unwrap_or_default());
+      return render_weather(&cached.response, units, mode, age, settings.stale_after);
+    }
+  }
+
+  let fetched = fetch().await;
+  let response_text = match fetched {
+    Ok(WeatherData::Forecast(text)) => text,
+    Ok(WeatherData::CurrentOnly(text)) => return render_current_weather(&text, units, mode),
+    Err(e) => match cached {
+      Some((settings, cached)) => {
+        log::warn!("Weather API failed, showing cached weather instead: {}", e);
+        return render_weather(&cached.response, units, mode, now - cached.fetched_at, settings.stale_after);
+      },
+      None => {
+        print_error(&e.to_user_message());
+        return Err(e);
+      },
+    },
+  };
+  if let Some(settings) = cache {
+    save_cached(&settings.path, location, &response_text);
+  }
+
+  let message = render_weather(&response_text, units, mode, Duration::zero(), Duration::MAX)?;
+  let duration = start_time.elapsed();
+  log::info!("Weather widget completed successfully in {:?}", duration);
+  Ok(message)
+}
+
+/// Render a current-conditions response when the forecast is unavailable: the time, the
+/// current temperature, the conditions, feels like, and wind. There is no low or high, rain
+/// chance, or pressure forecast, so `mode` only matters for the compact row.
+pub(crate) fn render_current_weather(
+  response_text: &str,
+  units: Option<Units>,
+  mode: WeatherMode,
+) -> Result<WidgetOutput, VestaboardError> {
+  let json: CurrentResponse = serde_json::from_str(response_text).map_err(|e| {
+    log::error!("Failed to parse current weather JSON response: {}", e);
+    let error = VestaboardError::json_error(e, "parsing current weather API response");
     print_error(&error.to_user_message());
     error
   })?;
+  let units = resolve_units(units, &json.location.country);
+
+  if mode == WeatherMode::Compact {
+    let condition = json.current.condition.text.replace("\"", "").to_lowercase();
+    return Ok(vec![center_line(compact_weather_line(
+      &current_temp(&json.current, units),
+      &condition,
+      0,
+    ))]);
+  }
+
+  let mut lines = vec![
+    center_line(json.location.localtime.to_lowercase()),
+    center_line(format!("W{}", current_temp(&json.current, units))),
+  ];
+  lines.extend(
+    split_into_lines(&condition_text(&json.current))
+      .into_iter()
+      .take(2)
+      .map(center_line),
+  );
+  lines.push(feels_like_line(&json.current, units));
+  lines.push(wind_line(&json.current, units));
+  log::debug!("Current-only weather output: {} lines", lines.len());
+  Ok(center_message(lines, MAX_MESSAGE_HEIGHT))
+}
 
-  let status_code = response.status().as_u16();
-  log::debug!("Weather API response status: {}", status_code);
+/// The current temperature alone (e.g. `70.0D`, or `70f/21c` for both scales)
+fn current_temp(current: &Current, units: Units) -> String {
+  match units {
+    Units::Imperial => format!("{:.1}D", current.temp_f),
+    Units::Metric => format!("{:.1}D", current.temp_c),
+    Units::Both => dual_temp(current.temp_f, current.temp_c),
+  }
+}
 
-  let response_text = response.text().await.map_err(|e| {
-    log::error!("Failed to read weather response: {}", e);
-    VestaboardError::reqwest_error(e, "reading weather response")
+/// Render a forecast response. Data at least `stale_after` old (`age`) shows its age on the top line.
+fn render_weather(
+  response_text: &str,
+  units: Option<Units>,
+  mode: WeatherMode,
+  age: Duration,
+  stale_after: Duration,
+) -> Result<WidgetOutput, VestaboardError> {
+  log::debug!("Processing successful weather response");
+  let json: WeatherResponse = serde_json::from_str(response_text).map_err(|e| {
+    log::error!("Failed to parse weather JSON response: {}", e);
+    let error = VestaboardError::json_error(e, "parsing weather API response");
+    print_error(&error.to_user_message());
+    error
   })?;
 
-  match status_code {
-    200 => {
-      log::debug!("Processing successful weather response");
-      // Try to parse the text as JSON
-      let json: WeatherResponse = serde_json::from_str(&response_text).map_err(|e| {
-        log::error!("Failed to parse weather JSON response: {}", e);
-        let error = VestaboardError::json_error(e, "parsing weather API response");
-        print_error(&error.to_user_message());
-        error
-      })?;
+  log::debug!("Weather data parsed successfully");
 
-      log::debug!("Weather data parsed successfully");
+  let units = resolve_units(units, &json.location.country);
+  log::debug!("Using {:?} units for {}", units, json.location.country);
 
-      let localtime = json.location.localtime.to_lowercase();
-      let temps = format!(
-        "W{:>3.1}D B{:>3.1}D R{:>3.1}D",
-        json.current.temp_f, json.forecast.forecastday[0].day.mintemp_f, json.forecast.forecastday[0].day.maxtemp_f,
+  let localtime = staleness_line(&json.current.last_updated, age, stale_after)
+    .unwrap_or_else(|| json.location.localtime.to_lowercase());
+
+  let mut weather_description = Vec::new();
+  match mode {
+    WeatherMode::Lines(lines) => {
+      weather_description.extend(
+        lines
+          .iter()
+          .flat_map(|line| weather_line(*line, &json, units, &localtime)),
       );
-      let condition = json.current.condition.text.replace("\"", "").to_lowercase();
-      let chance_precip = json.forecast.forecastday[0].day.daily_chance_of_rain;
-      let totalprecip_in = json.forecast.forecastday[0].day.totalprecip_in;
-      let rain_chance = if chance_precip > 0 {
-        format!("w/ {}% chance", chance_precip)
-      } else {
-        "".to_string()
-      };
-      let rain_amount = if totalprecip_in > 0.0 {
-        format!("{}\" of rain", totalprecip_in)
-      } else {
-        "".to_string()
+      if weather_description.len() > 6 {
+        log::warn!("Weather layout has {} rows, showing the first 6", weather_description.len());
+        weather_description.truncate(6);
+      }
+    },
+    WeatherMode::Compact => {
+      let temp = match units {
+        Units::Imperial => format!("{:.0}f", json.current.temp_f),
+        Units::Metric => format!("{:.0}c", json.current.temp_c),
+        Units::Both => dual_temp(json.current.temp_f, json.current.temp_c),
       };
-      let weather_summary = format!("{} {} {}", condition, rain_chance, rain_amount);
-      let pressure_in = format!(" {}", json.current.pressure_in);
-      let future_pressure_in = json
-        .forecast
-        .forecastday
-        .iter()
-        .take(2)
-        .map(|day| format!("{:>.2}", day.hour[0].pressure_in))
-        .collect::<Vec<String>>()
-        .join(" ")
-        + " ";
-
-      let mut weather_description = Vec::new();
+      let condition = json.current.condition.text.replace("\"", "").to_lowercase();
+      let rain_chance = json.forecast.forecastday[0].day.daily_chance_of_rain;
+      weather_description.push(center_line(compact_weather_line(&temp, &condition, rain_chance)));
+    },
+    WeatherMode::Trend => {
+      // The sparkline and its labels take the place of a summary row and the pressure
       weather_description.push(center_line(localtime));
-      weather_description.push(center_line(temps));
-
-      for line in center_message(split_into_lines(&weather_summary), 3) {
+      weather_description.push(center_line(temps_text(&json, units)));
+      for line in center_message(split_into_lines(&summary_text(&json, units)), 2)
+        .into_iter()
+        .take(2)
+      {
         weather_description.push(center_line(line.to_string()));
       }
-      weather_description.push(full_justify_line(pressure_in, future_pressure_in));
+      weather_description.extend(trend_lines(&json, units));
+    },
+    mode => {
+      let detail_line = match mode {
+        WeatherMode::FeelsLike => Some(feels_like_line(&json.current, units)),
+        WeatherMode::RainTrend => Some(rain_trend_line(&rain_trend(&json.forecast.forecastday[0].hour))),
+        _ => None,
+      };
+      weather_description.push(center_line(localtime));
+      weather_description.push(center_line(temps_text(&json, units)));
 
-      let duration = start_time.elapsed();
-      log::info!("Weather widget completed successfully in {:?}", duration);
-      log::debug!("Weather output: {} lines", weather_description.len());
-      Ok(weather_description)
+      match detail_line {
+        None => {
+          for line in center_message(split_into_lines(&summary_text(&json, units)), 3) {
+            weather_description.push(center_line(line.to_string()));
+          }
+        },
+        Some(detail_line) => {
+          // The summary gives up a row so the board stays at 6 lines
+          for line in center_message(split_into_lines(&summary_text(&json, units)), 2)
+            .into_iter()
+            .take(2)
+          {
+            weather_description.push(center_line(line.to_string()));
+          }
+          weather_description.push(detail_line);
+        },
+      }
+      weather_description.push(pressure_line(&json));
     },
-    400 | 401 | 403 => {
-      log::warn!("Weather API authentication/authorization error ({})", status_code);
-      // Parse error response
-      let error: serde_json::Value = serde_json::from_str(&response_text).map_err(|_| {
-        log::error!("Invalid error response format from weather API");
-        let api_error = VestaboardError::api_error(Some(status_code), "Invalid API response format");
-        print_error(&api_error.to_user_message());
-        api_error
-      })?;
+  }
 
-      let error_code = error["error"]["code"].as_i64();
-      let error_message = error["error"]["message"].as_str().unwrap_or("Unknown error");
+  log::debug!("Weather output: {} lines", weather_description.len());
+  Ok(weather_description)
+}
 
-      log::error!("Weather API error {}: {}", error_code.unwrap_or(-1), error_message);
-      let api_error =
-        VestaboardError::api_error(error_code.map(|c| c as u16), &format!("Weather API error: {}", error_message));
-      print_error(&api_error.to_user_message());
-      Err(api_error)
-    },
-    502 | 504 => {
-      log::warn!("Weather service temporarily unavailable ({})", status_code);
-      let error = VestaboardError::api_error(Some(status_code), "Weather service temporarily unavailable");
-      print_error(&error.to_user_message());
-      Err(error)
-    },
-    _ => {
-      log::error!("Unexpected weather API response status: {}", status_code);
-      let error =
-        VestaboardError::api_error(Some(status_code), &format!("Unexpected response status: {}", status_code));
-      print_error(&error.to_user_message());
-      Err(error)
+/// Render one line of a custom layout. Text that may not fit (condition, rain, summary) wraps
+/// onto as many centered rows as it needs.
+fn weather_line(line: WeatherLine, json: &WeatherResponse, units: Units, localtime: &str) -> Vec<String> {
+  let wrapped = |text: String| split_into_lines(&text).into_iter().map(center_line).collect::<Vec<_>>();
+  match line {
+    WeatherLine::Time => vec![center_line(localtime.to_string())],
+    WeatherLine::Temps => vec![center_line(temps_text(json, units))],
+    WeatherLine::Condition => wrapped(condition_text(&json.current)),
+    WeatherLine::Rain => wrapped(rain_text(json, units)),
+    WeatherLine::Summary => wrapped(summary_text(json, units)),
+    WeatherLine::Feels => vec![feels_like_line(&json.current, units)],
+    WeatherLine::RainTrend => vec![rain_trend_line(&rain_trend(&json.forecast.forecastday[0].hour))],
+    WeatherLine::Wind => vec![wind_line(&json.current, units)],
+    WeatherLine::Trend => trend_lines(json, units),
+    WeatherLine::Pressure => vec![pressure_line(json)],
+  }
+}
+
+/// Current, low, and high temperatures with their color tiles
+fn temps_text(json: &WeatherResponse, units: Units) -> String {
+  let today = &json.forecast.forecastday[0].day;
+  let (temp, low, high) = match units {
+    Units::Imperial => (json.current.temp_f, today.mintemp_f, today.maxtemp_f),
+    Units::Metric => (json.current.temp_c, today.mintemp_c, today.maxtemp_c),
+    Units::Both => {
+      return dual_temps_text((json.current.temp_f, json.current.temp_c), today.mintemp_f, today.maxtemp_f)
     },
+  };
+  format!("W{:>3.1}D B{:>3.1}D R{:>3.1}D", temp, low, high)
+}
+
+/// A temperature in both scales, rounded (e.g. `72f/22c`)
+fn dual_temp(fahrenheit: f64, celsius: f64) -> String {
+  format!("{:.0}f/{:.0}c", fahrenheit, celsius)
+}
+
+/// Temperatures line for `Units::Both`: the current temperature in both scales, and the low and
+/// high in Fahrenheit, since all three in both scales can't fit (e.g. `W72f/22c B60f R80f`).
+/// If even that overflows, only the current temperature is kept.
+pub(crate) fn dual_temps_text(current: (f64, f64), low_f: f64, high_f: f64) -> String {
+  let current = format!("W{}", dual_temp(current.0, current.1));
+  let line = format!("{} B{:.0}f R{:.0}f", current, low_f, high_f);
+  if line.chars().count() <= MAX_MESSAGE_LENGTH {
+    line
+  } else {
+    current
   }
 }
+
+/// The current condition, framed by its color tile when it has one
+fn condition_text(current: &Current) -> String {
+  let condition = current.condition.text.replace("\"", "").to_lowercase();
+  match condition_accent(current.condition.code) {
+    Some(tile) => format!("{} {} {}", tile, condition, tile),
+    None => condition,
+  }
+}
+
+/// Today's chance and amount of rain, each left out when zero
+fn rain_text(json: &WeatherResponse, units: Units) -> String {
+  let today = &json.forecast.forecastday[0].day;
+  let rain_chance = if today.daily_chance_of_rain > 0 {
+    format!("w/ {}% chance", today.daily_chance_of_rain)
+  } else {
+    "".to_string()
+  };
+  let rain_amount = match units {
+    _ if today.totalprecip_in <= 0.0 => "".to_string(),
+    Units::Imperial | Units::Both => format!("{}\" of rain", today.totalprecip_in),
+    Units::Metric => format!("{}mm of rain", today.totalprecip_mm),
+  };
+  format!("{} {}", rain_chance, rain_amount).trim().to_string()
+}
+
+/// Condition followed by the rain, as the standard view shows them
+fn summary_text(json: &WeatherResponse, units: Units) -> String {
+  format!("{} {}", condition_text(&json.current), rain_text(json, units))
+}
+
+/// Current pressure on the left, the next two days' forecast on the right
+fn pressure_line(json: &WeatherResponse) -> String {
+  let pressure_in = format!(" {}", json.current.pressure_in);
+  let future_pressure_in = json
+    .forecast
+    .forecastday
+    .iter()
+    .take(2)
+    .map(|day| format!("{:>.2}", day.hour[0].pressure_in))
+    .collect::<Vec<String>>()
+    .join(" ")
+    + " ";
+  full_justify_line(pressure_in, future_pressure_in)
+}
@@ -0,0 +1,528 @@
+#[cfg(test)]
+mod tests {
+  use crate::errors::VestaboardError;
+  use crate::widgets::weather::weather::{
+    compact_weather_line, condition_accent, dual_temps_text, feels_like_line, fetch_with_fallback, format_age,
+    get_weather, get_weather_with, parse_weather_lines, parse_weather_mode, rain_trend, rain_trend_line,
+    render_current_weather, resolve_units, sparkline_labels, staleness_line, temperature_sparkline, units_for_country,
+    wind_line, Current, Hour, RainTrend, Units, WeatherCache, WeatherData, WeatherLine, WeatherMode,
+  };
+  use crate::widgets::widget_utils::{missing_key_message, Theme, STANDARD_BOARD};
+  use chrono::{Duration, Utc};
+  use serial_test::serial;
+  use tempfile::tempdir;
+
+  /// A saved weatherapi.com forecast response for Austin, trimmed to the fields the widget reads
+  const FORECAST: &str = include_str!("fixtures/forecast.json");
+
+  #[test]
+  fn test_condition_accent_sunny_is_yellow() {
+    assert_eq!(condition_accent(1000), Some('Y'));
+  }
+
+  #[test]
+  fn test_condition_accent_rain_is_blue() {
+    assert_eq!(condition_accent(1063), Some('B')); // patchy rain possible
+    assert_eq!(condition_accent(1183), Some('B')); // light rain
+    assert_eq!(condition_accent(1195), Some('B')); // heavy rain
+    assert_eq!(condition_accent(1243), Some('B')); // moderate or heavy rain shower
+  }
+
+  #[test]
+  fn test_condition_accent_snow_is_white() {
+    assert_eq!(condition_accent(1066), Some('W')); // patchy snow possible
+    assert_eq!(condition_accent(1117), Some('W')); // blizzard
+    assert_eq!(condition_accent(1219), Some('W')); // moderate snow
+    assert_eq!(condition_accent(1258), Some('W')); // moderate or heavy snow showers
+  }
+
+  #[test]
+  fn test_condition_accent_thunder_is_violet() {
+    assert_eq!(condition_accent(1087), Some('V'));
+    assert_eq!(condition_accent(1276), Some('V'));
+  }
+
+  #[test]
+  fn test_condition_accent_clouds_have_no_accent() {
+    assert_eq!(condition_accent(1003), None);
+    assert_eq!(condition_accent(1009), None);
+    assert_eq!(condition_accent(1135), None);
+  }
+
+  #[test]
+  fn test_condition_accent_unknown_code_has_no_accent() {
+    assert_eq!(condition_accent(0), None);
+    assert_eq!(condition_accent(9999), None);
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_missing_api_key_renders_friendly_message() {
+    std::env::remove_var("WEATHER_API_KEY");
+    std::env::remove_var("WEATHER_API_KEY_FILE");
+
+    let error = get_weather("austin", None, WeatherMode::Standard, None)
+      .await
+      .unwrap_err();
+    let display = missing_key_message(&error, "weather", &Theme::default(), STANDARD_BOARD)
+      .expect("missing key should have a friendly message");
+
+    assert!(display.iter().any(|line| line.trim() == "weather unavailable"));
+    assert!(display.iter().any(|line| line.trim() == "set weather api key"));
+  }
+
+  #[test]
+  fn test_units_for_us_is_imperial() {
+    assert_eq!(units_for_country("United States of America"), Units::Imperial);
+    assert_eq!(units_for_country("USA"), Units::Imperial);
+  }
+
+  #[test]
+  fn test_units_for_uk_is_metric() {
+    assert_eq!(units_for_country("United Kingdom"), Units::Metric);
+  }
+
+  #[test]
+  fn test_units_default_to_metric() {
+    assert_eq!(units_for_country("France"), Units::Metric);
+    assert_eq!(units_for_country(""), Units::Metric);
+  }
+
+  #[test]
+  fn test_configured_units_override_country() {
+    assert_eq!(resolve_units(Some(Units::Metric), "United States of America"), Units::Metric);
+    assert_eq!(resolve_units(Some(Units::Imperial), "United Kingdom"), Units::Imperial);
+    assert_eq!(resolve_units(None, "United Kingdom"), Units::Metric);
+  }
+
+  #[test]
+  fn test_format_age() {
+    assert_eq!(format_age(Duration::seconds(20)), "~1m ago");
+    assert_eq!(format_age(Duration::minutes(15)), "~15m ago");
+    assert_eq!(format_age(Duration::minutes(150)), "~2h ago");
+    assert_eq!(format_age(Duration::days(3)), "~3d ago");
+  }
+
+  #[test]
+  fn test_staleness_line_hidden_while_fresh() {
+    assert_eq!(staleness_line("2025-05-05 14:05", Duration::minutes(3), Duration::minutes(10)), None);
+  }
+
+  #[test]
+  fn test_staleness_line_slightly_stale() {
+    assert_eq!(
+      staleness_line("2025-05-05 14:05", Duration::minutes(15), Duration::minutes(10)),
+      Some("2:05 pm ~15m ago".to_string())
+    );
+  }
+
+  #[test]
+  fn test_staleness_line_very_stale() {
+    assert_eq!(
+      staleness_line("2025-05-05 09:30", Duration::hours(30), Duration::minutes(10)),
+      Some("9:30 am ~30h ago".to_string())
+    );
+    assert_eq!(
+      staleness_line("not a time", Duration::days(4), Duration::minutes(10)),
+      Some("as of ~4d ago".to_string())
+    );
+  }
+
+  /// Cache settings backed by a cache file written `age` ago
+  fn cache_with(dir: &std::path::Path, age: Duration) -> WeatherCache {
+    let path = dir.join("weather_cache.json");
+    let cached = serde_json::json!({
+      "location": "austin",
+      "fetched_at": Utc::now() - age,
+      "response": FORECAST,
+    });
+    std::fs::write(&path, cached.to_string()).unwrap();
+    WeatherCache {
+      path,
+      max_age: Duration::minutes(30),
+      stale_after: Duration::minutes(10),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_fresh_cache_is_used_without_api_call() {
+    let dir = tempdir().unwrap();
+    let cache = cache_with(dir.path(), Duration::minutes(2));
+
+    let message = get_weather("austin", Some(Units::Imperial), WeatherMode::Standard, Some(&cache))
+      .await
+      .unwrap();
+
+    assert_eq!(message[0].trim(), "2025-05-05 06:55");
+    assert!(message[1].contains("70.0D"));
+  }
+
+  #[tokio::test]
+  async fn test_stale_cache_shows_its_age() {
+    let dir = tempdir().unwrap();
+    let cache = cache_with(dir.path(), Duration::minutes(15));
+
+    let message = get_weather("austin", Some(Units::Imperial), WeatherMode::Standard, Some(&cache))
+      .await
+      .unwrap();
+
+    assert_eq!(message[0].trim(), "6:45 am ~15m ago");
+  }
+
+  #[tokio::test]
+  async fn test_expired_cache_is_shown_when_api_fails() {
+    let dir = tempdir().unwrap();
+    // Older than max_age, so the API is tried first
+    let cache = cache_with(dir.path(), Duration::minutes(45));
+
+    let message = get_weather_with("austin", Some(Units::Imperial), WeatherMode::Standard, Some(&cache), || async {
+      Err(VestaboardError::api_error(Some(503), "weather unavailable"))
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(message[0].trim(), "6:45 am ~45m ago");
+  }
+
+  /// The `current` block of the saved forecast (feels like 70.0F / 21.1C, humidity 78%)
+  fn sample_current() -> Current {
+    let forecast: serde_json::Value = serde_json::from_str(FORECAST).unwrap();
+    serde_json::from_value(forecast["current"].clone()).unwrap()
+  }
+
+  #[test]
+  fn test_feels_like_line_imperial() {
+    assert_eq!(feels_like_line(&sample_current(), Units::Imperial), "feels 70D    humid 78%");
+  }
+
+  #[test]
+  fn test_feels_like_line_metric() {
+    assert_eq!(feels_like_line(&sample_current(), Units::Metric), "feels 21D    humid 78%");
+  }
+
+  #[test]
+  fn test_parse_weather_mode() {
+    assert_eq!(parse_weather_mode(&serde_json::json!(null)).unwrap(), WeatherMode::Standard);
+    assert_eq!(parse_weather_mode(&serde_json::json!({})).unwrap(), WeatherMode::Standard);
+    assert_eq!(parse_weather_mode(&serde_json::json!({"mode": "feels"})).unwrap(), WeatherMode::FeelsLike);
+    assert_eq!(parse_weather_mode(&serde_json::json!({"mode": "rain"})).unwrap(), WeatherMode::RainTrend);
+    assert_eq!(parse_weather_mode(&serde_json::json!({"mode": "trend"})).unwrap(), WeatherMode::Trend);
+    assert!(parse_weather_mode(&serde_json::json!({"mode": "wind"})).is_err());
+    assert!(parse_weather_mode(&serde_json::json!("feels")).is_err());
+  }
+
+  #[tokio::test]
+  async fn test_feels_like_mode_stays_within_six_rows() {
+    let dir = tempdir().unwrap();
+    let cache = cache_with(dir.path(), Duration::minutes(2));
+
+    let message = get_weather("austin", Some(Units::Metric), WeatherMode::FeelsLike, Some(&cache))
+      .await
+      .unwrap();
+
+    assert_eq!(message.len(), 6);
+    assert_eq!(message[4], "feels 21D    humid 78%");
+    assert!(message.iter().all(|line| line.chars().count() <= 22));
+  }
+
+  /// Today's hourly forecast from the saved response, with the chance of rain for each hour
+  /// replaced by `chances` when given
+  fn sample_hours(chances: Option<[i32; 24]>) -> Vec<Hour> {
+    let forecast: serde_json::Value = serde_json::from_str(FORECAST).unwrap();
+    let mut hours = forecast["forecast"]["forecastday"][0]["hour"].clone();
+    if let Some(chances) = chances {
+      for (hour, chance) in hours.as_array_mut().unwrap().iter_mut().zip(chances) {
+        hour["chance_of_rain"] = serde_json::json!(chance);
+      }
+    }
+    serde_json::from_value(hours).unwrap()
+  }
+
+  #[test]
+  fn test_rain_trend_takes_highest_chance_in_each_period() {
+    let trend = rain_trend(&sample_hours(None));
+    assert_eq!(
+      trend,
+      RainTrend {
+        morning: 20,
+        afternoon: 70,
+        evening: 10,
+      }
+    );
+  }
+
+  #[test]
+  fn test_rain_trend_buckets_hours_into_periods() {
+    let mut chances = [0; 24];
+    chances[3] = 90; // overnight hours aren't counted
+    chances[6] = 15;
+    chances[11] = 25;
+    chances[12] = 50;
+    chances[17] = 35;
+    chances[18] = 80;
+    chances[23] = 5;
+
+    let trend = rain_trend(&sample_hours(Some(chances)));
+    assert_eq!(
+      trend,
+      RainTrend {
+        morning: 25,
+        afternoon: 50,
+        evening: 80,
+      }
+    );
+  }
+
+  #[test]
+  fn test_rain_trend_line() {
+    let trend = RainTrend {
+      morning: 10,
+      afternoon: 60,
+      evening: 30,
+    };
+    assert_eq!(rain_trend_line(&trend), "am 10% pm 60% eve 30% ");
+
+    let wet = RainTrend {
+      morning: 100,
+      afternoon: 100,
+      evening: 100,
+    };
+    let line = rain_trend_line(&wet);
+    assert_eq!(line, "am100% pm100%  eve100%");
+  }
+
+  #[tokio::test]
+  async fn test_rain_trend_mode_stays_within_six_rows() {
+    let dir = tempdir().unwrap();
+    let cache = cache_with(dir.path(), Duration::minutes(2));
+
+    let message = get_weather("austin", Some(Units::Imperial), WeatherMode::RainTrend, Some(&cache))
+      .await
+      .unwrap();
+
+    assert_eq!(message.len(), 6);
+    assert_eq!(message[4].trim(), "am 20% pm 70% eve 10%");
+    assert!(message.iter().all(|line| line.chars().count() <= 22));
+  }
+
+  #[test]
+  fn test_parse_weather_lines_input() {
+    let mode = parse_weather_mode(&serde_json::json!({"lines": ["time", "Temps", "wind"]})).unwrap();
+    assert_eq!(mode, WeatherMode::Lines(vec![WeatherLine::Time, WeatherLine::Temps, WeatherLine::Wind]));
+
+    let unknown = parse_weather_lines(&["time", "tides"]).unwrap_err();
+    assert!(unknown.to_string().contains("Unknown weather line 'tides'"));
+    assert!(parse_weather_mode(&serde_json::json!({"lines": "time"})).is_err());
+    assert!(parse_weather_mode(&serde_json::json!({"lines": ["time"], "mode": "feels"})).is_err());
+  }
+
+  #[test]
+  fn test_parse_compact_input() {
+    assert_eq!(parse_weather_mode(&serde_json::json!({"compact": true})).unwrap(), WeatherMode::Compact);
+    assert_eq!(parse_weather_mode(&serde_json::json!({"compact": false})).unwrap(), WeatherMode::Standard);
+    assert!(parse_weather_mode(&serde_json::json!({"compact": "yes"})).is_err());
+    assert!(parse_weather_mode(&serde_json::json!({"compact": true, "mode": "feels"})).is_err());
+    assert!(parse_weather_mode(&serde_json::json!({"compact": true, "lines": ["time"]})).is_err());
+  }
+
+  #[test]
+  fn test_compact_weather_line() {
+    assert_eq!(compact_weather_line("72f", "sunny", 60), "72f sunny 60%rain");
+    assert_eq!(compact_weather_line("72f", "sunny", 0), "72f sunny");
+    assert_eq!(compact_weather_line("72f/22c", "clear", 100), "72f/22c clear 100%rain");
+  }
+
+  #[test]
+  fn test_compact_weather_line_abbreviates_long_conditions() {
+    // Whole words are kept while they fit
+    assert_eq!(compact_weather_line("70f", "partly cloudy", 70), "70f partly 70%rain");
+    // A single word that doesn't fit is cut short
+    assert_eq!(compact_weather_line("72f/22c", "thundery outbreaks possible", 100), "72f/22c thund 100%rain");
+    assert_eq!(compact_weather_line("72f", "patchy light rain with thunder", 0), "72f patchy light rain");
+
+    for condition in ["moderate or heavy rain with thunder", "blizzard", ""] {
+      let line = compact_weather_line("-12f/-24c", condition, 100);
+      assert!(line.chars().count() <= 22, "too long: {}", line);
+      assert!(line.starts_with("-12f/-24c") && line.ends_with("100%rain"));
+    }
+  }
+
+  #[tokio::test]
+  async fn test_compact_mode_is_one_centered_row() {
+    let dir = tempdir().unwrap();
+    let cache = cache_with(dir.path(), Duration::minutes(2));
+
+    let message = get_weather("austin", Some(Units::Imperial), WeatherMode::Compact, Some(&cache))
+      .await
+      .unwrap();
+
+    assert_eq!(message, vec!["  70f partly 70%rain  ".to_string()]);
+  }
+
+  #[test]
+  fn test_wind_line() {
+    // The saved forecast has 6.9 mph / 11.2 kph from the SSE
+    assert_eq!(wind_line(&sample_current(), Units::Imperial), "wind 7mph          sse");
+    assert_eq!(wind_line(&sample_current(), Units::Metric), "wind 11kph         sse");
+  }
+
+  /// Render the saved forecast with a custom layout
+  async fn render_lines(names: &[&str]) -> Vec<String> {
+    let dir = tempdir().unwrap();
+    let cache = cache_with(dir.path(), Duration::minutes(2));
+    let mode = WeatherMode::Lines(parse_weather_lines(names).unwrap());
+    get_weather("austin", Some(Units::Imperial), mode, Some(&cache))
+      .await
+      .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_weather_lines_follow_the_given_order() {
+    let message = render_lines(&["temps", "time"]).await;
+    assert_eq!(message.len(), 2);
+    assert!(message[0].contains("70.0D"));
+    assert_eq!(message[1].trim(), "2025-05-05 06:55");
+
+    let message = render_lines(&["wind", "rain-trend", "time"]).await;
+    assert_eq!(
+      message,
+      vec![
+        "wind 7mph          sse".to_string(),
+        "am 20% pm 70% eve 10% ".to_string(),
+        "   2025-05-05 06:55   ".to_string(),
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_weather_lines_are_capped_at_six_rows() {
+    let message = render_lines(&["time", "temps", "feels", "wind", "pressure", "rain-trend", "time"]).await;
+    assert_eq!(message.len(), 6);
+    assert_eq!(message[0].trim(), "2025-05-05 06:55");
+    assert_eq!(message[5].trim(), "am 20% pm 70% eve 10%");
+    assert!(message.iter().all(|line| line.chars().count() <= 22));
+  }
+
+  #[test]
+  fn test_temperature_sparkline_rises_from_coolest_to_warmest() {
+    assert_eq!(temperature_sparkline(&[60.0, 70.0, 80.0], 22), "VVVBBBBGGGGYYYYOOOORRR");
+    assert_eq!(temperature_sparkline(&[80.0, 60.0, 80.0], 22), "RROOYYGGBBVVBBGGYYOORR");
+  }
+
+  #[test]
+  fn test_temperature_sparkline_edge_cases() {
+    assert_eq!(temperature_sparkline(&[72.0, 72.0, 72.0], 22), "Y".repeat(22));
+    assert_eq!(temperature_sparkline(&[72.0], 22), "Y".repeat(22));
+    assert_eq!(temperature_sparkline(&[], 22), "");
+    assert_eq!(temperature_sparkline(&[60.0, 80.0], 2), "VR");
+  }
+
+  #[test]
+  fn test_sparkline_labels() {
+    assert_eq!(sparkline_labels(&[82.4, 84.2, 86.0], 22), "82D      84D       86D");
+    assert_eq!(sparkline_labels(&[72.0], 22), "          72D         ");
+    // Labels that would overlap the one before are dropped
+    assert_eq!(sparkline_labels(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], 10), "1D  5D  8D");
+  }
+
+  #[tokio::test]
+  async fn test_trend_mode_stays_within_six_rows() {
+    let dir = tempdir().unwrap();
+    let cache = cache_with(dir.path(), Duration::minutes(2));
+
+    let message = get_weather("austin", Some(Units::Imperial), WeatherMode::Trend, Some(&cache))
+      .await
+      .unwrap();
+
+    // The saved forecast's highs are 82.4, 84.2, and 86.0F
+    assert_eq!(message.len(), 6);
+    assert_eq!(message[4], "VVVBBBBGGGGYYYYOOOORRR");
+    assert_eq!(message[5], "82D      84D       86D");
+  }
+
+  #[test]
+  fn test_dual_temps_text() {
+    assert_eq!(dual_temps_text((72.0, 22.2), 60.1, 79.8), "W72f/22c B60f R80f");
+    // Extreme temperatures still fit 22 columns
+    assert_eq!(dual_temps_text((-40.0, -40.0), -45.0, -35.0), "W-40f/-40c B-45f R-35f");
+    assert_eq!(dual_temps_text((120.2, 49.0), 101.0, 125.6), "W120f/49c B101f R126f");
+    // Past that, only the current temperature is kept
+    assert_eq!(dual_temps_text((-130.0, -90.0), -140.0, -120.0), "W-130f/-90c");
+  }
+
+  #[test]
+  fn test_units_both_parses_from_config() {
+    let units: Units = serde_json::from_str(r#""both""#).unwrap();
+    assert_eq!(units, Units::Both);
+  }
+
+  #[tokio::test]
+  async fn test_both_units_show_fahrenheit_and_celsius() {
+    let dir = tempdir().unwrap();
+    let cache = cache_with(dir.path(), Duration::minutes(2));
+
+    let message = get_weather("austin", Some(Units::Both), WeatherMode::FeelsLike, Some(&cache))
+      .await
+      .unwrap();
+
+    assert_eq!(message[1].trim(), "W70f/21c B66f R82f");
+    // Both scales would run into the humidity, so feels like falls back to Fahrenheit
+    assert_eq!(message[4], "feels 70D    humid 78%");
+    assert!(message.iter().all(|line| line.chars().count() <= 22));
+  }
+
+  /// The saved forecast with its `forecast` block removed, as the current-only endpoint returns
+  fn current_only_response() -> String {
+    let mut response: serde_json::Value = serde_json::from_str(FORECAST).unwrap();
+    response.as_object_mut().unwrap().remove("forecast");
+    response.to_string()
+  }
+
+  #[tokio::test]
+  async fn test_forecast_failure_falls_back_to_current_only() {
+    let current = current_only_response();
+    let data = fetch_with_fallback(
+      || async { Err(VestaboardError::api_error(Some(500), "forecast unavailable")) },
+      || async { Ok(current.clone()) },
+    )
+    .await
+    .unwrap();
+    assert_eq!(data, WeatherData::CurrentOnly(current.clone()));
+
+    let message = render_current_weather(&current, Some(Units::Imperial), WeatherMode::Standard).unwrap();
+    // Only the current temperature: no low/high or rain chance without a forecast
+    assert_eq!(
+      message,
+      vec![
+        "   2025-05-05 06:55   ",
+        "        W70.0D        ",
+        "    partly cloudy     ",
+        "feels 70D    humid 78%",
+        "wind 7mph          sse",
+        "",
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_forecast_success_skips_current_request() {
+    let data = fetch_with_fallback(
+      || async { Ok("forecast".to_string()) },
+      || async { panic!("current conditions should not be requested") },
+    )
+    .await
+    .unwrap();
+    assert_eq!(data, WeatherData::Forecast("forecast".to_string()));
+  }
+
+  #[tokio::test]
+  async fn test_both_failures_return_forecast_error() {
+    let error = fetch_with_fallback(
+      || async { Err(VestaboardError::api_error(Some(500), "forecast unavailable")) },
+      || async { Err(VestaboardError::api_error(Some(503), "current unavailable")) },
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error, VestaboardError::api_error(Some(500), "forecast unavailable"));
+  }
+}
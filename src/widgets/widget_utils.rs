@@ -1,31 +1,239 @@
+use crate::config::Config;
 use crate::errors::VestaboardError;
 
 pub type WidgetOutput = Vec<String>;
 pub const MAX_MESSAGE_LENGTH: usize = 22;
 pub const MAX_MESSAGE_HEIGHT: usize = 6;
 
+/// Tiles across and down on a board. Character codes are always sent as a standard
+/// 22x6 grid; a smaller board only uses its top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardSize {
+  pub cols: usize,
+  pub rows: usize,
+}
+
+/// The standard Vestaboard: 22 columns by 6 rows
+pub const STANDARD_BOARD: BoardSize = BoardSize {
+  cols: MAX_MESSAGE_LENGTH,
+  rows: MAX_MESSAGE_HEIGHT,
+};
+
+impl Default for BoardSize {
+  fn default() -> Self {
+    STANDARD_BOARD
+  }
+}
+/// Color tile used for the row under error headers unless configured otherwise
+pub const DEFAULT_ACCENT_COLOR: char = 'R';
+/// Shown instead of a blank message when `blank_placeholder` is on
+pub const BLANK_PLACEHOLDER: &str = "nothing to show";
+/// Placeholder in a theme's header template for the message's own header
+pub const HEADER_PLACEHOLDER: &str = "{header}";
+
+/// Where the lines of error and status messages sit within a row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+  Left,
+  #[default]
+  Center,
+  Right,
+}
+
+impl Alignment {
+  /// Parse a config value: left, center, or right
+  pub fn parse(name: &str) -> Option<Self> {
+    match name.trim().to_lowercase().as_str() {
+      "left" => Some(Alignment::Left),
+      "center" | "centre" => Some(Alignment::Center),
+      "right" => Some(Alignment::Right),
+      _ => None,
+    }
+  }
+
+  /// Pad `line` out to `width` tiles with this alignment
+  pub fn apply(self, line: String, width: usize) -> String {
+    match self {
+      Alignment::Left => format!("{:<1$}", line, width),
+      Alignment::Center => center_line_to_width(line, width),
+      Alignment::Right => format!("{:>1$}", line, width),
+    }
+  }
+}
+
+/// Look of error and status messages: the header, the accent row under it, and alignment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+  /// Header template; `{header}` is replaced by the message's header (e.g. "file error")
+  pub header: String,
+  /// Color tile for the row under the header
+  pub accent: char,
+  /// Alignment of the header and message lines
+  pub alignment: Alignment,
+}
+
+impl Default for Theme {
+  fn default() -> Self {
+    Self {
+      header: HEADER_PLACEHOLDER.to_string(),
+      accent: DEFAULT_ACCENT_COLOR,
+      alignment: Alignment::Center,
+    }
+  }
+}
+
+impl Theme {
+  /// The theme from `theme_header`, `error_accent_color`, and `theme_alignment` in the config
+  pub fn from_config(config: &Config) -> Self {
+    let alignment = match config.theme_alignment.as_deref() {
+      Some(name) => Alignment::parse(name).unwrap_or_else(|| {
+        log::warn!("Unknown theme_alignment '{}', using center", name);
+        Alignment::Center
+      }),
+      None => Alignment::Center,
+    };
+    Self {
+      header: config
+        .theme_header
+        .clone()
+        .unwrap_or_else(|| HEADER_PLACEHOLDER.to_string()),
+      accent: config.get_error_accent_color(),
+      alignment,
+    }
+  }
+
+  /// The header row for a message whose own header is `header`, cut to `width` tiles
+  fn header_line(&self, header: &str, width: usize) -> String {
+    let text: String = self
+      .header
+      .replace(HEADER_PLACEHOLDER, header)
+      .to_lowercase()
+      .chars()
+      .take(width)
+      .collect();
+    self.alignment.apply(text, width)
+  }
+
+  /// The accent row under a header: color tiles alternating with spaces across `width`
+  fn accent_line(&self, width: usize) -> String {
+    vec![self.accent.to_string(); width.div_ceil(2).max(1)].join(" ")
+  }
+
+  /// A status message such as "down for maintenance", wrapped and aligned to the board
+  /// and centered vertically
+  pub fn status_message(&self, text: &str, size: BoardSize) -> WidgetOutput {
+    let lines = split_into_lines_to_width(text, size.cols)
+      .into_iter()
+      .map(|line| self.alignment.apply(line, size.cols))
+      .collect();
+    center_message(lines, size.rows)
+  }
+}
+
 pub fn full_justify_line(s1: String, s2: String) -> String {
+  full_justify_line_to_width(s1, s2, MAX_MESSAGE_LENGTH)
+}
+
+/// Put `s1` at the start and `s2` at the end of a line `width` tiles wide
+pub fn full_justify_line_to_width(s1: String, s2: String, width: usize) -> String {
   let len1 = s1.chars().count();
   let len2 = s2.chars().count();
+  // Strings that exactly fill the line sit flush; ones that overflow keep a single space
   let mut padding = 1;
-  if len1 + len2 < MAX_MESSAGE_LENGTH {
-    padding = MAX_MESSAGE_LENGTH - len1 - len2;
+  if len1 + len2 <= width {
+    padding = width - len1 - len2;
   }
   return format!("{}{:padding$}{}", s1, "", s2);
 }
 
+/// Colored square emoji and the Vestaboard color tile each one stands for
+const EMOJI_COLOR_TILES: &[(char, char)] = &[
+  ('🟥', 'R'),
+  ('🟧', 'O'),
+  ('🟨', 'Y'),
+  ('🟩', 'G'),
+  ('🟦', 'B'),
+  ('🟪', 'V'),
+  ('⬜', 'W'),
+  ('⬛', 'K'),
+];
+
+/// Color names and the Vestaboard color tile each one stands for
+const COLOR_NAMES: &[(&str, char)] = &[
+  ("red", 'R'),
+  ("orange", 'O'),
+  ("yellow", 'Y'),
+  ("green", 'G'),
+  ("blue", 'B'),
+  ("violet", 'V'),
+  ("white", 'W'),
+  ("black", 'K'),
+];
+
+/// Look up a color tile by name ("orange") or tile letter ("O").
+pub fn color_tile(name: &str) -> Option<char> {
+  let name = name.trim();
+  COLOR_NAMES.iter().find_map(|(color, tile)| {
+    (name.eq_ignore_ascii_case(color) || (name.len() == 1 && name.eq_ignore_ascii_case(&tile.to_string())))
+      .then_some(*tile)
+  })
+}
+
+/// Fill the whole board with one color (by name or tile letter), for checking how a color
+/// tile looks on the board.
+pub fn color_fill(color: &str, size: BoardSize) -> Result<WidgetOutput, VestaboardError> {
+  let tile = color_tile(color).ok_or_else(|| {
+    VestaboardError::validation_error(&format!(
+      "Unknown color '{}'. Use red, orange, yellow, green, blue, violet, white, or black",
+      color
+    ))
+  })?;
+  Ok(vec![tile.to_string().repeat(size.cols); size.rows])
+}
+
+/// Replace colored square emoji with the matching color tile characters.
+///
+/// A variation selector following a square is dropped with it; all other characters
+/// (including other emoji) are left for validation to handle.
+pub fn emoji_to_color_tiles(text: &str) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut after_tile = false;
+  for c in text.chars() {
+    if after_tile && c == '\u{FE0F}' {
+      after_tile = false;
+      continue;
+    }
+    match EMOJI_COLOR_TILES.iter().find(|(emoji, _)| *emoji == c) {
+      Some((_, tile)) => {
+        result.push(*tile);
+        after_tile = true;
+      },
+      None => {
+        result.push(c);
+        after_tile = false;
+      },
+    }
+  }
+  result
+}
+
 pub fn split_into_lines(text: &str) -> WidgetOutput {
+  split_into_lines_to_width(text, MAX_MESSAGE_LENGTH)
+}
+
+/// Word-wrap text into lines at most `width` tiles long, splitting words that don't fit on a line
+pub fn split_into_lines_to_width(text: &str, width: usize) -> WidgetOutput {
   let mut formatted_message: Vec<String> = Vec::new();
   let words: Vec<&str> = text.split_whitespace().collect();
   let mut current_line = String::new();
 
   for word in words {
-    if word.len() > MAX_MESSAGE_LENGTH {
+    if word.len() > width {
       let mut split_word = word.to_string();
       while !split_word.is_empty() {
         let split_index = split_word
           .char_indices()
-          .nth(MAX_MESSAGE_LENGTH)
+          .nth(width)
           .map(|(i, _)| i)
           .unwrap_or(split_word.len());
         let split = split_word.split_off(split_index);
@@ -34,7 +242,7 @@ pub fn split_into_lines(text: &str) -> WidgetOutput {
       }
       continue;
     }
-    if current_line.len() + word.len() + 1 > MAX_MESSAGE_LENGTH {
+    if current_line.len() + word.len() + 1 > width {
       // if next word doesn't fit, add to formatted_message
       formatted_message.push(current_line);
       current_line = String::new();
@@ -54,8 +262,18 @@ pub fn split_into_lines(text: &str) -> WidgetOutput {
   formatted_message
 }
 
+/// Whether a message would leave the board empty: no lines, or lines of only spaces.
+pub fn is_blank_message(message: &[String]) -> bool {
+  message.iter().all(|line| line.trim().is_empty())
+}
+
 pub fn center_line(line: String) -> String {
-  format!("{:^1$}", line, MAX_MESSAGE_LENGTH)
+  center_line_to_width(line, MAX_MESSAGE_LENGTH)
+}
+
+/// Center a line within `width` tiles
+pub fn center_line_to_width(line: String, width: usize) -> String {
+  format!("{:^1$}", line, width)
   // let half_padding = (22 - line.len()) / 2;
   // if half_padding > 0 {
   //     format!("{}{}", " ".repeat(half_padding), line)
@@ -78,56 +296,122 @@ pub fn center_message(mut message: Vec<String>, height: usize) -> WidgetOutput {
 }
 
 pub fn format_message(message: &str) -> WidgetOutput {
+  format_message_for(message, STANDARD_BOARD)
+}
+
+/// Wrap and center text on a board of the given size
+pub fn format_message_for(message: &str, size: BoardSize) -> WidgetOutput {
   // Widget just formats the message - validation happens at the main level
   let mut formatted_message: Vec<String> = Vec::new();
-  split_into_lines(message).iter().for_each(|line| {
-    formatted_message.push(center_line(line.to_string()));
+  split_into_lines_to_width(message, size.cols).iter().for_each(|line| {
+    formatted_message.push(center_line_to_width(line.to_string(), size.cols));
   });
-  center_message(formatted_message, MAX_MESSAGE_HEIGHT)
+  center_message(formatted_message, size.rows)
 }
 
 // There is only room for 4 lines of error message on the Vestaboard
 pub fn format_error(error: &str) -> WidgetOutput {
-  format_error_with_header(error, "error")
+  format_error_with_theme(error, "error", &Theme::default(), STANDARD_BOARD)
+}
+
+/// Split a word too long for a row of `width` tiles into pieces that fit, breaking
+/// after a '/' where possible so file paths split between directories.
+fn break_long_word(word: &str, width: usize) -> Vec<String> {
+  if word.chars().count() <= width {
+    return vec![word.to_string()];
+  }
+  let mut pieces = Vec::new();
+  let mut current = String::new();
+  for part in word.split_inclusive('/') {
+    if !current.is_empty() && current.chars().count() + part.chars().count() > width {
+      pieces.push(std::mem::take(&mut current));
+    }
+    current.push_str(part);
+    // A single path segment can still be too long; cut it at the row width
+    while current.chars().count() > width {
+      pieces.push(current.chars().take(width).collect());
+      current = current.chars().skip(width).collect();
+    }
+  }
+  if !current.is_empty() {
+    pieces.push(current);
+  }
+  pieces
 }
 
-pub fn format_error_with_header(error: &str, header: &str) -> WidgetOutput {
+/// Format an error for a board of `size` under a themed header, a row of accent color
+/// tiles, and alignment (e.g. an 'O' accent for warnings instead of the default red).
+pub fn format_error_with_theme(error: &str, header: &str, theme: &Theme, size: BoardSize) -> WidgetOutput {
   let mut formatted_message: Vec<String> = Vec::new();
   let lowercase_error = error.to_lowercase();
-  let words: Vec<&str> = lowercase_error.split_whitespace().collect();
+  // Break up words too long for one row (e.g. file paths) so the message fits the board
+  let words: Vec<String> = lowercase_error
+    .split_whitespace()
+    .flat_map(|word| break_long_word(word, size.cols))
+    .collect();
   let mut current_line = String::new();
   let mut content_lines: Vec<String> = Vec::new();
 
   // Build content lines first
   for word in words {
-    if current_line.len() + word.len() + 1 > MAX_MESSAGE_LENGTH {
-      let padded_line = center_line(current_line);
+    if !current_line.is_empty() && current_line.chars().count() + word.chars().count() + 1 > size.cols {
+      let padded_line = theme.alignment.apply(current_line, size.cols);
       content_lines.push(padded_line);
       current_line = String::new();
     }
     if !current_line.is_empty() {
       current_line.push(' ');
     }
-    current_line.push_str(word);
+    current_line.push_str(&word);
   }
 
   if !current_line.is_empty() {
-    content_lines.push(center_line(current_line));
+    content_lines.push(theme.alignment.apply(current_line, size.cols));
   }
 
-  // Center content within 4 available lines (6 total - 2 header lines)
-  let centered_content = center_message(content_lines, 4);
+  // Center content within the rows left under the 2 header lines
+  let centered_content = center_message(content_lines, size.rows.saturating_sub(2));
 
-  // Create final message: header + red line + centered content
-  formatted_message.push(center_line(header.to_lowercase()));
-  formatted_message.push("R R R R R R R R R R R".to_string());
+  // Create final message: header + accent line + centered content
+  formatted_message.push(theme.header_line(header, size.cols));
+  formatted_message.push(theme.accent_line(size.cols));
   formatted_message.extend(centered_content);
 
   formatted_message
 }
 
-/// Converts a VestaboardError to a display message for the Vestaboard
+/// Board message for a widget whose API key is not configured, e.g. "weather unavailable / set weather api key".
+///
+/// Returns `None` for any error other than a missing `*_API_KEY` setting.
+pub fn missing_key_message(
+  error: &VestaboardError,
+  widget: &str,
+  theme: &Theme,
+  size: BoardSize,
+) -> Option<WidgetOutput> {
+  match error {
+    VestaboardError::ConfigError { field, .. } if field.ends_with("_API_KEY") => {
+      let key = field.replace('_', " ").to_lowercase();
+      let lines = [format!("{} unavailable", widget), format!("set {}", key)]
+        .iter()
+        .flat_map(|line| split_into_lines_to_width(line, size.cols))
+        .map(|line| theme.alignment.apply(line, size.cols))
+        .collect();
+      Some(center_message(lines, size.rows))
+    },
+    _ => None,
+  }
+}
+
+/// Converts a VestaboardError to a display message for a standard board in the default theme
 pub fn error_to_display_message(error: &VestaboardError) -> Vec<String> {
+  error_to_display_message_with_theme(error, &Theme::default(), STANDARD_BOARD)
+}
+
+/// Converts a VestaboardError to a display message for a board of `size`, styled with `theme`
+pub fn error_to_display_message_with_theme(error: &VestaboardError, theme: &Theme, size: BoardSize) -> Vec<String> {
+  let format_error_with_theme =
+    |error: &str, header: &str, theme: &Theme| format_error_with_theme(error, header, theme, size);
   match error {
     VestaboardError::IOError { context, .. } => {
       // Extract more meaningful info from the context
@@ -135,45 +419,45 @@ pub fn error_to_display_message(error: &VestaboardError) -> Vec<String> {
         // Try to extract filename
         let parts: Vec<&str> = context.split(' ').collect();
         if let Some(filename) = parts.last() {
-          format_error_with_header(&format!("'{}' not found", filename), "file error")
+          format_error_with_theme(&format!("'{}' not found", filename), "file error", theme)
         } else {
-          format_error_with_header("File not found", "file error")
+          format_error_with_theme("File not found", "file error", theme)
         }
       } else if context.contains("creating") || context.contains("writing") {
-        format_error_with_header("Cannot write file", "file error")
+        format_error_with_theme("Cannot write file", "file error", theme)
       } else {
-        format_error_with_header("File operation failed", "file error")
+        format_error_with_theme("File operation failed", "file error", theme)
       }
     },
     VestaboardError::JsonError { context, .. } => {
       if context.contains("parsing") {
-        format_error_with_header("Invalid data format", "data error")
+        format_error_with_theme("Invalid data format", "data error", theme)
       } else {
-        format_error_with_header("Data processing error", "data error")
+        format_error_with_theme("Data processing error", "data error", theme)
       }
     },
     VestaboardError::ReqwestError { context, .. } => {
       if context.contains("weather") {
-        format_error_with_header("Weather service unavailable", "network error")
+        format_error_with_theme("Weather service unavailable", "network error", theme)
       } else {
-        format_error_with_header("Network error", "network error")
+        format_error_with_theme("Network error", "network error", theme)
       }
     },
     VestaboardError::WidgetError { widget, message: _ } => match widget.as_str() {
-      "weather" => format_error_with_header("Weather data unavailable", "widget error"),
-      "text" => format_error_with_header("Text processing error", "widget error"),
-      "sat-word" => format_error_with_header("Dictionary unavailable", "widget error"),
-      _ => format_error_with_header(&format!("{} error", widget), "widget error"),
+      "weather" => format_error_with_theme("Weather data unavailable", "widget error", theme),
+      "text" => format_error_with_theme("Text processing error", "widget error", theme),
+      "sat-word" => format_error_with_theme("Dictionary unavailable", "widget error", theme),
+      _ => format_error_with_theme(&format!("{} error", widget), "widget error", theme),
     },
-    VestaboardError::ScheduleError { .. } => format_error_with_header("Schedule error", "schedule error"),
+    VestaboardError::ScheduleError { .. } => format_error_with_theme("Schedule error", "schedule error", theme),
     VestaboardError::ApiError { code, .. } => match code {
-      Some(404) => format_error_with_header("Service not found", "api error"),
-      Some(401) | Some(403) => format_error_with_header("Access denied", "api error"),
-      Some(500..=599) => format_error_with_header("Service temporarily down", "api error"),
-      _ => format_error_with_header("Service error", "api error"),
+      Some(404) => format_error_with_theme("Service not found", "api error", theme),
+      Some(401) | Some(403) => format_error_with_theme("Access denied", "api error", theme),
+      Some(500..=599) => format_error_with_theme("Service temporarily down", "api error", theme),
+      _ => format_error_with_theme("Service error", "api error", theme),
     },
     VestaboardError::ConfigError { field, .. } => {
-      format_error_with_header(&format!("Config: {} missing", field), "config error")
+      format_error_with_theme(&format!("Config: {} missing", field), "config error", theme)
     },
     VestaboardError::Other { message } => {
       // Truncate long messages for display, but be more generous
@@ -182,10 +466,10 @@ pub fn error_to_display_message(error: &VestaboardError) -> Vec<String> {
       } else {
         message.clone()
       };
-      format_error_with_header(&display_msg, "error")
+      format_error_with_theme(&display_msg, "error", theme)
     },
-    VestaboardError::LockError { message } => format_error_with_header(message, "lock error"),
-    VestaboardError::InputError { message } => format_error_with_header(message, "input error"),
-    VestaboardError::ValidationError { message } => format_error_with_header(message, "validation error"),
+    VestaboardError::LockError { message } => format_error_with_theme(message, "lock error", theme),
+    VestaboardError::InputError { message } => format_error_with_theme(message, "input error", theme),
+    VestaboardError::ValidationError { message } => format_error_with_theme(message, "validation error", theme),
   }
 }